@@ -0,0 +1,192 @@
+//! Autostart registration
+//!
+//! Keeps the "Start at Login" entry pointing at the running executable.
+//!
+//! ## Design Intent
+//! After an update or move, an enabled autostart entry may point at a stale
+//! path. Rewriting it unconditionally on every launch costs a registry or
+//! LaunchAgent write and can leave autostart disabled if the re-enable
+//! fails, so the entry is only rewritten when its target actually differs.
+//!
+//! The `Autostart` trait abstracts the plugin so that decision can be tested
+//! without touching the real login items.
+//!
+//! ## Side Effects
+//! - Reads the registered autostart entry
+//! - May disable and re-enable autostart
+
+use crate::platform;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, State};
+use tauri_plugin_autostart::{AutoLaunchManager, ManagerExt};
+
+/// Operations needed to keep an autostart entry current
+pub trait Autostart {
+    /// Whether autostart is currently enabled
+    fn is_enabled(&self) -> Result<bool, String>;
+    /// Executable the entry currently launches, if it can be read
+    fn registered_path(&self) -> Option<PathBuf>;
+    /// Register autostart for the running executable
+    fn enable(&self) -> Result<(), String>;
+    /// Remove the autostart entry
+    fn disable(&self) -> Result<(), String>;
+}
+
+/// `Autostart` backed by tauri-plugin-autostart
+pub struct PluginAutostart<'a> {
+    manager: State<'a, AutoLaunchManager>,
+    app_name: String,
+}
+
+impl<'a> PluginAutostart<'a> {
+    /// Wrap the plugin's manager for `app`
+    pub fn new(app: &'a AppHandle) -> Self {
+        Self {
+            manager: app.autolaunch(),
+            app_name: app.package_info().name.clone(),
+        }
+    }
+}
+
+impl Autostart for PluginAutostart<'_> {
+    fn is_enabled(&self) -> Result<bool, String> {
+        self.manager.is_enabled().map_err(|e| e.to_string())
+    }
+
+    fn registered_path(&self) -> Option<PathBuf> {
+        // The plugin registers "<path> <args>" and Tea passes no args
+        platform::registered_autostart_command(&self.app_name)
+            .map(|command| PathBuf::from(command.trim()))
+    }
+
+    fn enable(&self) -> Result<(), String> {
+        self.manager.enable().map_err(|e| e.to_string())
+    }
+
+    fn disable(&self) -> Result<(), String> {
+        self.manager.disable().map_err(|e| e.to_string())
+    }
+}
+
+/// Path the autostart plugin registers for this process
+///
+/// ## Platform Behavior
+/// Mirrors the plugin: the canonical executable on macOS, the AppImage on
+/// Linux when running from one, otherwise `current_exe()`.
+///
+/// ## Returns
+/// The expected target, or None if the executable path is unavailable
+pub fn expected_path() -> Option<PathBuf> {
+    let exe = match std::env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => {
+            log::warn!("Failed to resolve current executable: {}", e);
+            return None;
+        }
+    };
+
+    #[cfg(target_os = "macos")]
+    let exe = exe.canonicalize().unwrap_or(exe);
+
+    #[cfg(target_os = "linux")]
+    let exe = std::env::var_os("APPIMAGE").map_or(exe, PathBuf::from);
+
+    Some(exe)
+}
+
+/// Rewrite an enabled autostart entry if it points somewhere else
+///
+/// ## Arguments
+/// * `autostart` - Autostart backend; the caller has checked it is enabled
+/// * `current` - Executable the entry should launch
+///
+/// ## Design Intent
+/// An unreadable entry is rewritten, matching the previous behavior, since
+/// there is no way to tell whether it is current.
+///
+/// ## Returns
+/// True if the entry was rewritten
+pub fn refresh_path(autostart: &dyn Autostart, current: &Path) -> bool {
+    match autostart.registered_path() {
+        Some(registered) if registered == current => {
+            log::debug!("Autostart path is current: {}", current.display());
+            return false;
+        }
+        Some(registered) => log::info!(
+            "Autostart path changed: {} -> {}",
+            registered.display(),
+            current.display()
+        ),
+        None => log::info!("Autostart path unknown, re-registering"),
+    }
+
+    if let Err(e) = autostart.disable() {
+        log::error!("Failed to update autostart path: {}", e);
+        return false;
+    }
+    if let Err(e) = autostart.enable() {
+        log::error!("Failed to update autostart path: {}", e);
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    struct MockAutostart {
+        registered: Option<PathBuf>,
+        calls: RefCell<Vec<&'static str>>,
+    }
+
+    impl MockAutostart {
+        fn new(registered: Option<&str>) -> Self {
+            Self {
+                registered: registered.map(PathBuf::from),
+                calls: RefCell::new(Vec::new()),
+            }
+        }
+    }
+
+    impl Autostart for MockAutostart {
+        fn is_enabled(&self) -> Result<bool, String> {
+            Ok(true)
+        }
+
+        fn registered_path(&self) -> Option<PathBuf> {
+            self.registered.clone()
+        }
+
+        fn enable(&self) -> Result<(), String> {
+            self.calls.borrow_mut().push("enable");
+            Ok(())
+        }
+
+        fn disable(&self) -> Result<(), String> {
+            self.calls.borrow_mut().push("disable");
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_matching_path_is_not_rewritten() {
+        let mock = MockAutostart::new(Some("/opt/tea/tea"));
+        assert!(!refresh_path(&mock, Path::new("/opt/tea/tea")));
+        assert!(mock.calls.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_changed_path_is_rewritten() {
+        let mock = MockAutostart::new(Some("/old/tea"));
+        assert!(refresh_path(&mock, Path::new("/opt/tea/tea")));
+        assert_eq!(*mock.calls.borrow(), vec!["disable", "enable"]);
+    }
+
+    #[test]
+    fn test_unknown_path_is_rewritten() {
+        let mock = MockAutostart::new(None);
+        assert!(refresh_path(&mock, Path::new("/opt/tea/tea")));
+        assert_eq!(*mock.calls.borrow(), vec!["disable", "enable"]);
+    }
+}
@@ -0,0 +1,58 @@
+//! Maximum awake duration monitor
+//!
+//! Enforces `max_session_secs`, turning wake off once it has been on
+//! continuously for longer than the cap.
+//!
+//! ## Design Intent
+//! Runs independently of timers and schedules, so it still catches an app
+//! left on indefinitely. The continuous-awake clock lives in
+//! `AppStateManager::awake_since` and restarts on every enable; the check
+//! itself is `commands::enforce_awake_cap_impl`. The monitor sleeps until
+//! the cap is due when that is sooner than the next regular check, so wake
+//! goes off when the tray countdown says it will.
+//!
+//! ## Side Effects
+//! - Spawns a Tokio task for the application lifetime
+//! - May disable wake, persist state and refresh the tray
+
+use crate::commands::{self, AppStateManager};
+use crate::notify;
+use crate::tray;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// How often the awake duration is checked against the cap
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Shortest wait between checks, so a failing check cannot spin
+const MIN_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Start the awake cap monitor in the background
+///
+/// ## Side Effects
+/// Spawns a Tokio task that runs for the lifetime of the application.
+pub fn spawn_awake_cap_monitor(app: AppHandle) {
+    tokio::spawn(async move {
+        loop {
+            let due = commands::awake_cap_remaining_impl(&app.state::<AppStateManager>())
+                .unwrap_or_else(|e| {
+                    log::error!("Awake cap check failed: {}", e);
+                    None
+                });
+            let wait = due.map_or(CHECK_INTERVAL, |left| {
+                left.clamp(MIN_CHECK_INTERVAL, CHECK_INTERVAL)
+            });
+            tokio::time::sleep(wait).await;
+
+            let state = app.state::<AppStateManager>();
+            match commands::enforce_awake_cap_impl(&state) {
+                Ok(true) => {
+                    tray::refresh(&app);
+                    notify::notify_state_change(&app, false, Some("awake time limit reached"));
+                }
+                Ok(false) => {}
+                Err(e) => log::error!("Awake cap check failed: {}", e),
+            }
+        }
+    });
+}
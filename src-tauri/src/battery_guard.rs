@@ -0,0 +1,64 @@
+//! Battery floor monitor
+//!
+//! Enforces `battery_floor_percent`, turning wake off when a laptop on
+//! battery drops below the chosen charge.
+//!
+//! ## Design Intent
+//! Unlike the `only_on_ac` power pause, this is a hard stop: wake is
+//! disabled as if by the user and stays off after AC returns. The check
+//! itself is `commands::enforce_battery_floor_impl`; this task only feeds
+//! it the platform readings.
+//!
+//! ## Side Effects
+//! - Spawns a Tokio task for the application lifetime
+//! - May disable wake, persist state, refresh the tray and notify
+//!
+//! ## Failure Modes
+//! - No battery or unreadable charge: Never disables (no-op on desktops)
+
+use crate::commands::{self, AppStateManager};
+use crate::notify;
+use crate::platform;
+use crate::tray;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// How often the battery charge is checked against the floor
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Start the battery floor monitor in the background
+///
+/// ## Side Effects
+/// Spawns a Tokio task that runs for the lifetime of the application.
+pub fn spawn_battery_guard(app: AppHandle) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(CHECK_INTERVAL).await;
+
+            let state = app.state::<AppStateManager>();
+            // Skip the platform queries while no floor is set
+            let floor = match state.power.battery_floor_percent.lock() {
+                Ok(floor) => *floor,
+                Err(e) => {
+                    log::error!("Mutex poisoned during battery floor check: {}", e);
+                    continue;
+                }
+            };
+            let Some(floor) = floor else {
+                continue;
+            };
+
+            let source = platform::power_source();
+            let percent = platform::battery_percent();
+            match commands::enforce_battery_floor_impl(&state, source, percent) {
+                Ok(true) => {
+                    tray::refresh(&app);
+                    let reason = format!("battery below {}%", floor);
+                    notify::notify_state_change(&app, false, Some(&reason));
+                }
+                Ok(false) => {}
+                Err(e) => log::error!("Battery floor check failed: {}", e),
+            }
+        }
+    });
+}
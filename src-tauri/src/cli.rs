@@ -0,0 +1,140 @@
+//! Headless state control
+//!
+//! Implements `tea --enable`, `tea --disable` and `tea --status` for
+//! machines without a desktop, such as build servers driven by scripts.
+//!
+//! ## Design Intent
+//! Commands go to the running tray app over its control endpoint (see
+//! `ipc::send_request`), so `--enable` takes effect at once rather than on
+//! the next start. Every command prints the resulting `sleep_disabled` and
+//! `screen_mode` as JSON so scripts can check what they got. With no
+//! instance to ask, `--status` falls back to the saved state, while
+//! `--enable` / `--disable` fail: nothing would keep the machine awake, and
+//! `--once` is the headless way to do that.
+//!
+//! ## Exit Codes
+//! - `once::EXIT_OK` (0): Status printed
+//! - `once::EXIT_CONFIG_INVALID` (3): Config unreadable in strict mode
+//! - `EXIT_NOT_RUNNING` (4): `--enable` / `--disable` with no instance
+//!   listening
+//! - `EXIT_REQUEST_FAILED` (5): The running instance refused the request
+//!   or could not be reached
+//!
+//! ## Side Effects
+//! - Prints JSON to stdout
+//! - `--enable` / `--disable` change the running instance's wake state
+
+use crate::core::ipc::IpcRequest;
+use crate::core::{CliAction, ScreenMode};
+use crate::ipc;
+use crate::once::{EXIT_CONFIG_INVALID, EXIT_OK};
+use crate::persistence::{read_startup_state, strict_config_from_env, AppState};
+use serde::Serialize;
+
+/// No running instance answered `--enable` / `--disable`
+pub const EXIT_NOT_RUNNING: i32 = 4;
+
+/// The running instance refused the request or could not be reached
+pub const EXIT_REQUEST_FAILED: i32 = 5;
+
+/// What `--status` reports
+#[derive(Serialize, Debug, Clone, PartialEq)]
+struct StatusReport {
+    sleep_disabled: bool,
+    screen_mode: ScreenMode,
+}
+
+impl From<&AppState> for StatusReport {
+    fn from(state: &AppState) -> Self {
+        Self {
+            sleep_disabled: state.sleep_disabled,
+            screen_mode: state.screen_mode,
+        }
+    }
+}
+
+/// Run one state control command
+///
+/// ## Arguments
+/// * `action` - `Enable`, `Disable` or `Status`; anything else only
+///   reports the status
+///
+/// ## Returns
+/// Process exit code (see module docs)
+pub fn run(action: CliAction) -> i32 {
+    let request = match action {
+        CliAction::Enable => IpcRequest::Enable,
+        CliAction::Disable => IpcRequest::Disable,
+        _ => IpcRequest::Status,
+    };
+
+    let report = match ipc::send_request(request) {
+        Some(Ok((sleep_disabled, screen_mode))) => StatusReport {
+            sleep_disabled,
+            screen_mode,
+        },
+        Some(Err(e)) => {
+            log::error!("{}", e);
+            return EXIT_REQUEST_FAILED;
+        }
+        None if request == IpcRequest::Status => match read_startup_state(strict_config_from_env())
+        {
+            Ok(state) => StatusReport::from(&state),
+            Err(e) => {
+                log::error!("Config invalid: {}", e);
+                return EXIT_CONFIG_INVALID;
+            }
+        },
+        None => {
+            log::error!(
+                "Tea is not running, or Command Line Control is off in its tray menu. \
+                 To keep the system awake without the tray, use \
+                 `tea --once --for <duration>`"
+            );
+            return EXIT_NOT_RUNNING;
+        }
+    };
+
+    match serde_json::to_string(&report) {
+        Ok(json) => println!("{}", json),
+        Err(e) => log::error!("Failed to serialize status: {}", e),
+    }
+    EXIT_OK
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::{read_state, set_test_config_dir, update_state};
+
+    #[test]
+    fn test_without_instance_only_status_succeeds() {
+        let dir = tempfile::tempdir().unwrap();
+        set_test_config_dir(Some(dir.path().to_path_buf()));
+        update_state(|s| s.screen_mode = ScreenMode::AllowScreenOff).unwrap();
+
+        assert_eq!(run(CliAction::Enable), EXIT_NOT_RUNNING);
+        assert_eq!(run(CliAction::Disable), EXIT_NOT_RUNNING);
+        // Nothing is written for a tray app that is not there to apply it
+        let state = read_state();
+        assert!(!state.sleep_disabled);
+        assert_eq!(state.screen_mode, ScreenMode::AllowScreenOff);
+
+        assert_eq!(run(CliAction::Status), EXIT_OK);
+
+        set_test_config_dir(None);
+    }
+
+    #[test]
+    fn test_status_report_json() {
+        let state = AppState {
+            sleep_disabled: true,
+            screen_mode: ScreenMode::KeepScreenOn,
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_string(&StatusReport::from(&state)).unwrap(),
+            r#"{"sleep_disabled":true,"screen_mode":"KeepScreenOn"}"#
+        );
+    }
+}
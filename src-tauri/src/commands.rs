@@ -12,18 +12,210 @@
 //! Commands orchestrate core logic, persistence, and wake service.
 //! UI handlers simply delegate to these commands.
 
-use crate::core::ScreenMode;
-use crate::persistence::{write_state, AppState};
+use crate::autostart::{Autostart, PluginAutostart};
+use crate::core::awake_cap::{awake_cap_reached, awake_cap_remaining, max_session_duration};
+use crate::core::diagnostics::{self, DiagnosticsInput};
+use crate::core::ipc::IpcRequest;
+use crate::core::net_watch::NetTarget;
+use crate::core::override_guard::{decide_manual_disable, OverrideDecision};
+use crate::core::power::{
+    below_battery_floor, refuses_enable_on_battery, validate_battery_floor, PowerSource,
+};
+use crate::core::profile::validate_profile_name;
+use crate::core::schedule::{override_expired, schedule_request, Schedule};
+use crate::core::timed_awake::{deadline_after, validate_timed_awake};
+use crate::core::{
+    self, format_duration_human, resolve_desired_state, resolve_wake_interval, sleep_risk,
+    validate_wake_interval, AutomationInputs, AwakeReason, BehaviorRow, ConditionEndAction,
+    ConditionEndPlan, ProfileSettings, ScreenMode, SleepRisk, StartupCheck, WakeKey, WakeStrategy,
+};
+use crate::error::ErrorReport;
+use crate::hotkey;
+use crate::ipc;
+use crate::logging;
+use crate::notify;
+use crate::persistence::{ensure_default_profile, read_state, update_state, write_state, AppState};
 use crate::platform;
-use crate::wake_service::WakeService;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::{Arc, Mutex};
-use tauri::State;
+use crate::power_monitor;
+use crate::scheduler::{ScheduledAction, ScheduledActions};
+use crate::service_manager::ServiceManager;
+use crate::settings::{
+    AutomationSettings, ControlSettings, PowerSettings, TraySettings, WakeSettings,
+};
+use crate::tray;
+use crate::wake_service;
+use chrono::NaiveDateTime;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, State};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+use tokio::time::Instant;
 
 /// Shared application state managed by Tauri
+///
+/// Every field is shared, so a clone can be moved into a timer. Settings
+/// are grouped by feature (see `settings`); the rest is the running wake
+/// session.
+#[derive(Clone)]
 pub struct AppStateManager {
     pub is_awake: Arc<AtomicBool>,
-    pub screen_mode: Arc<Mutex<ScreenMode>>,
+    /// When wake was last enabled, None while sleep is allowed
+    pub awake_since: Arc<Mutex<Option<Instant>>>,
+    /// Pending auto-disable of a timed wake session
+    pub auto_disable_timer: Arc<Mutex<Option<String>>>,
+    /// Pending resume at the end of a pause
+    pub pause_timer: Arc<Mutex<Option<String>>>,
+    pub scheduled_actions: Arc<ScheduledActions>,
+    /// Owner of the wake service lifecycle (mocked backend in tests)
+    pub service: Arc<ServiceManager>,
+    /// Toggles not yet persisted or applied to the wake service
+    pub toggle_commit: Arc<ToggleCommit>,
+    pub wake: WakeSettings,
+    pub power: PowerSettings,
+    pub automation: AutomationSettings,
+    pub tray: TraySettings,
+    pub control: ControlSettings,
+}
+
+/// How long a toggle waits for another before it is committed
+pub const TOGGLE_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Pending commit of a burst of toggles
+///
+/// ## Design Intent
+/// Rapid clicks or a bouncing hotkey would otherwise start and stop the
+/// wake service and rewrite the state file on every toggle. Each toggle
+/// bumps `generation`; only the commit scheduled by the last one in a
+/// burst runs, and it applies whatever state the burst ended in.
+#[derive(Default)]
+pub struct ToggleCommit {
+    generation: AtomicU64,
+    pending: AtomicBool,
+}
+
+impl ToggleCommit {
+    /// Mark a commit pending, returning the generation that may run it
+    fn schedule(&self) -> u64 {
+        self.pending.store(true, Ordering::SeqCst);
+        self.generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Claim the pending commit, if it is still `generation`'s (or any, for None)
+    fn take(&self, generation: Option<u64>) -> bool {
+        if generation.is_some_and(|g| g != self.generation.load(Ordering::SeqCst)) {
+            return false;
+        }
+        self.pending.swap(false, Ordering::SeqCst)
+    }
+}
+
+/// Lock the shared screen mode, recovering from a poisoned mutex
+///
+/// ## Design Intent
+/// `ScreenMode` is a plain `Copy` value, so a holder that panicked cannot
+/// have left it half-written. Recovering the guard keeps every later
+/// toggle working instead of failing (or panicking) from then on. The
+/// poison flag is cleared so the warning is logged once per panic.
+///
+/// ## Arguments
+/// * `screen_mode` - Shared screen mode
+/// * `context` - Operation name for the warning
+pub fn lock_screen_mode<'a>(
+    screen_mode: &'a Mutex<ScreenMode>,
+    context: &str,
+) -> MutexGuard<'a, ScreenMode> {
+    screen_mode.lock().unwrap_or_else(|e| {
+        log::warn!(
+            "Mutex poisoned during {}, recovering screen mode: {}",
+            context,
+            e
+        );
+        screen_mode.clear_poison();
+        e.into_inner()
+    })
+}
+
+/// Wake state reported to listeners after every change
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateChanged {
+    pub is_awake: bool,
+    pub screen_mode: ScreenMode,
+}
+
+/// Callback receiving the wake state after every change
+pub type StateListener = Arc<dyn Fn(StateChanged) + Send + Sync>;
+
+/// Listener registered by `set_state_listener`
+static STATE_LISTENER: OnceLock<StateListener> = OnceLock::new();
+
+/// Register the process-wide state change listener
+///
+/// ## Design Intent
+/// The `*_impl` functions have no `AppHandle`; `main` registers a listener
+/// that emits the change to every window. Without one (e.g. in unit
+/// tests) changes are not reported anywhere.
+///
+/// ## Failure Modes
+/// - Already registered: Logs a warning, the first listener is kept
+pub fn set_state_listener(listener: StateListener) {
+    if STATE_LISTENER.set(listener).is_err() {
+        log::warn!("State change listener already registered, ignoring");
+    }
+}
+
+/// Report a state change to the listener, if any
+fn notify_state_changed(is_awake: bool, screen_mode: ScreenMode) {
+    if let Some(listener) = STATE_LISTENER.get() {
+        listener(StateChanged {
+            is_awake,
+            screen_mode,
+        });
+    }
+}
+
+/// Result of a manual toggle request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ToggleOutcome {
+    /// Wake state changed to the given state and mode
+    Toggled(bool, ScreenMode),
+    /// Disabling would override an active watcher; ask first
+    NeedsConfirmation(String),
+}
+
+/// Result of the `toggle_sleep` command
+///
+/// ## Design Intent
+/// A confirmation prompt is not a failure, so it is returned alongside the
+/// (unchanged) state rather than as an error; errors stay for toggles that
+/// were refused or failed, such as master off or refuse on battery.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ToggleResponse {
+    pub is_awake: bool,
+    pub screen_mode: ScreenMode,
+    /// Prompt to confirm before retrying with `confirm`, None once toggled
+    pub needs_confirmation: Option<String>,
+}
+
+impl ToggleResponse {
+    /// Response for a manual toggle outcome, with the current state when
+    /// nothing was toggled
+    pub fn from_outcome(state: &AppStateManager, outcome: ToggleOutcome) -> Self {
+        match outcome {
+            ToggleOutcome::Toggled(is_awake, screen_mode) => Self {
+                is_awake,
+                screen_mode,
+                needs_confirmation: None,
+            },
+            ToggleOutcome::NeedsConfirmation(prompt) => Self {
+                is_awake: state.is_awake.load(Ordering::SeqCst),
+                screen_mode: *lock_screen_mode(&state.wake.screen_mode, "toggle_sleep"),
+                needs_confirmation: Some(prompt),
+            },
+        }
+    }
 }
 
 /// Internal business logic for toggling sleep state
@@ -33,39 +225,561 @@ pub struct AppStateManager {
 /// Keeps business logic in one place.
 ///
 /// ## Arguments
-/// * `is_awake` - Shared atomic flag
-/// * `screen_mode` - Shared mutex with screen mode
+/// * `state` - Shared application state
+///
+/// ## Returns
+/// New awake state and screen mode, or error string (including an enable
+/// refused on battery, see `check_refuse_on_battery`)
+pub fn toggle_sleep_impl(state: &AppStateManager) -> Result<(bool, ScreenMode), String> {
+    let target = !state.is_awake.load(Ordering::SeqCst);
+    if target {
+        check_refuse_on_battery(state, platform::power_source)?;
+    }
+    let (_, current_mode) = apply_awake(state, target)?;
+    schedule_toggle_commit(state);
+    notify_state_changed(target, current_mode);
+    Ok((target, current_mode))
+}
+
+/// Commit the toggled state once no further toggle follows
+///
+/// ## Design Intent
+/// The in-memory state, and with it the tray, changes at once; only the
+/// state file write and the wake service sync wait for `TOGGLE_DEBOUNCE`
+/// (see `ToggleCommit`).
+///
+/// ## Side Effects
+/// Spawns a Tokio task that persists the state and syncs the service
+fn schedule_toggle_commit(state: &AppStateManager) {
+    let generation = state.toggle_commit.schedule();
+    let commit = state.toggle_commit.clone();
+    let is_awake = state.is_awake.clone();
+    let screen_mode = state.wake.screen_mode.clone();
+    let service = state.service.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(TOGGLE_DEBOUNCE).await;
+        if !commit.take(Some(generation)) {
+            return;
+        }
+        let awake = is_awake.load(Ordering::SeqCst);
+        let current_mode = *lock_screen_mode(&screen_mode, "toggle commit");
+        if let Err(e) = persist_awake(awake, current_mode) {
+            log::error!("{}", e);
+        }
+        service.request_sync();
+    });
+}
+
+/// Persist a toggle still waiting for its commit
+///
+/// ## Design Intent
+/// Called on quit, so the last toggle before exiting is not lost. The
+/// service is being stopped anyway, so only the state file is written.
+pub fn flush_toggle(state: &AppStateManager) {
+    if !state.toggle_commit.take(None) {
+        return;
+    }
+    let awake = state.is_awake.load(Ordering::SeqCst);
+    let current_mode = *lock_screen_mode(&state.wake.screen_mode, "toggle flush");
+    if let Err(e) = persist_awake(awake, current_mode) {
+        log::error!("{}", e);
+    }
+}
+
+/// Refuse a manual enable while on battery, if the user asked for that
+///
+/// ## Design Intent
+/// A hard rule complementing the battery floor: with `refuse_on_battery`
+/// set, wake cannot be turned on while unplugged at all. It does not turn
+/// an active session off when the power is pulled; `only_on_ac` and the
+/// battery floor cover that. `source` is only queried when the rule is on,
+/// since it can mean running a system tool.
+///
+/// ## Returns
+/// Ok(()) if enabling may go ahead, or an error string saying why not
+pub fn check_refuse_on_battery<F>(state: &AppStateManager, source: F) -> Result<(), String>
+where
+    F: FnOnce() -> PowerSource,
+{
+    if state.power.refuse_on_battery.load(Ordering::SeqCst)
+        && refuses_enable_on_battery(true, source())
+    {
+        return Err(
+            "Not enabling sleep prevention on battery (refuse on battery is set); plug in first"
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// Internal business logic for a manual toggle by the user
+///
+/// ## Design Intent
+/// Everything the tray item and the `toggle_sleep` command do, callable
+/// with the shared state alone so it can be tested without a tray.
+///
+/// ## Arguments
+/// * `state` - Shared application state
+/// * `confirmed` - The user already confirmed overriding a watcher
+///
+/// ## Side Effects
+/// - Takes the session over from any watcher
+/// - Persists state and starts or stops the wake service once no further
+///   toggle follows within `TOGGLE_DEBOUNCE`
+///
+/// ## Returns
+/// The outcome, or error string (including while master is off, or for an
+/// enable refused on battery)
+pub fn manual_toggle_impl(
+    state: &AppStateManager,
+    confirmed: bool,
+) -> Result<ToggleOutcome, String> {
+    if state.automation.master_off.load(Ordering::SeqCst) {
+        return Err(format!("{} is paused (master off)", core::APP_NAME));
+    }
+    if !state.is_awake.load(Ordering::SeqCst) {
+        // Checked again by `toggle_sleep_impl`, but before taking the
+        // session over here so a refusal changes nothing
+        check_refuse_on_battery(state, platform::power_source)?;
+    }
+    if let Err(prompt) = guard_manual_disable(state, confirmed) {
+        return Ok(ToggleOutcome::NeedsConfirmation(prompt));
+    }
+
+    // A manual toggle takes the session over from any watcher and ends a
+    // pause without resuming later
+    state
+        .automation
+        .enabled_by_watcher
+        .store(false, Ordering::SeqCst);
+    cancel_pause(state)?;
+    start_schedule_override(state)?;
+    let (awake, mode) = toggle_sleep_impl(state)?;
+    Ok(ToggleOutcome::Toggled(awake, mode))
+}
+
+/// Internal business logic for setting sleep prevention to a given state
+///
+/// ## Design Intent
+/// Used where the desired state is known (watchers, timers) rather than
+/// flipped. The wake service is brought in line through the
+/// `ServiceManager`, so a repeated enable never spawns a second service.
+///
+/// ## Arguments
+/// * `state` - Shared application state
+/// * `awake` - Desired wake state
 ///
 /// ## Returns
 /// New awake state and screen mode, or error string
-pub fn toggle_sleep_impl(
-    is_awake: &Arc<AtomicBool>,
-    screen_mode: &Arc<Mutex<ScreenMode>>,
-) -> Result<(bool, ScreenMode), String> {
-    let was_awake = is_awake.load(Ordering::SeqCst);
-    let new_awake = !was_awake;
-    is_awake.store(new_awake, Ordering::SeqCst);
+pub fn set_awake_impl(state: &AppStateManager, awake: bool) -> Result<(bool, ScreenMode), String> {
+    let (was_awake, current_mode) = apply_awake(state, awake)?;
+
+    // Persisted and synced here, so a pending toggle commit has nothing left
+    state.toggle_commit.take(None);
+    persist_awake(awake, current_mode)?;
+
+    // Start or stop the service on a change
+    if awake != was_awake {
+        state.service.request_sync();
+    }
+
+    notify_state_changed(awake, current_mode);
+    Ok((awake, current_mode))
+}
+
+/// Change the in-memory wake state
+///
+/// ## Side Effects
+/// Restarts the continuous-awake clock and cancels a pending auto-disable
+/// on a change
+///
+/// ## Returns
+/// The previous wake state and the current screen mode, or error string
+fn apply_awake(state: &AppStateManager, awake: bool) -> Result<(bool, ScreenMode), String> {
+    let was_awake = state.is_awake.swap(awake, Ordering::SeqCst);
+
+    log::info!("Set sleep prevention: {} -> {}", was_awake, awake);
+
+    let current_mode = *lock_screen_mode(&state.wake.screen_mode, "set_awake");
+
+    // The continuous-awake clock for max_session_secs restarts on any change
+    if awake != was_awake {
+        *state
+            .awake_since
+            .lock()
+            .map_err(|e| format!("Mutex poisoned during set_awake: {}", e))? =
+            awake.then(Instant::now);
+        if !awake {
+            cancel_auto_disable(state)?;
+        }
+    }
+    Ok((was_awake, current_mode))
+}
+
+/// Persist the wake state and screen mode
+fn persist_awake(awake: bool, current_mode: ScreenMode) -> Result<(), String> {
+    update_state(|s| {
+        s.sleep_disabled = awake;
+        s.screen_mode = current_mode;
+        if !awake {
+            s.disable_at = None;
+        }
+    })
+    .map(|_| ())
+    .map_err(|e| format!("Failed to persist state: {}", e))
+}
 
-    log::info!("Toggle sleep: {} -> {}", was_awake, new_awake);
+/// Current wall clock as a UNIX timestamp, None before 1970
+pub fn unix_now() -> Option<i64> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .ok()
+        .and_then(|since| i64::try_from(since.as_secs()).ok())
+}
 
-    // Get current screen mode with proper poisoning handling
-    let current_mode = *screen_mode
+/// Cancel a pending auto-disable, if any
+fn cancel_auto_disable(state: &AppStateManager) -> Result<(), String> {
+    let id = state
+        .auto_disable_timer
         .lock()
-        .map_err(|e| format!("Mutex poisoned during toggle_sleep: {}", e))?;
+        .map_err(|e| format!("Mutex poisoned during auto-disable: {}", e))?
+        .take();
+    if let Some(id) = id {
+        // Already fired timers are gone from the registry; nothing to cancel
+        let _ = state.scheduled_actions.cancel(&id);
+    }
+    Ok(())
+}
 
-    // Persist state
-    let new_state = AppState {
-        sleep_disabled: new_awake,
-        screen_mode: current_mode,
+/// Internal business logic for enabling wake for a limited time
+///
+/// ## Design Intent
+/// Enables wake like a manual toggle and schedules the auto-disable in the
+/// shared `ScheduledActions`, so it can be listed and cancelled like any
+/// other timer. A new choice replaces a pending auto-disable, and any
+/// disable cancels it. The deadline is persisted as `disable_at` so a
+/// restart resumes the remaining time (see `main`).
+///
+/// ## Arguments
+/// * `state` - Shared application state
+/// * `duration` - Time until wake turns off again, None for no limit
+/// * `on_expire` - Called after the auto-disable (re-renders the tray)
+///
+/// ## Returns
+/// New awake state and screen mode, or error string (including while
+/// master is off)
+pub fn toggle_sleep_for_impl<F>(
+    state: &AppStateManager,
+    duration: Option<Duration>,
+    on_expire: F,
+) -> Result<(bool, ScreenMode), String>
+where
+    F: FnOnce() + Send + 'static,
+{
+    if state.automation.master_off.load(Ordering::SeqCst) {
+        return Err(format!("{} is paused (master off)", core::APP_NAME));
+    }
+    if let Some(duration) = duration {
+        validate_timed_awake(duration)?;
+    }
+
+    cancel_auto_disable(state)?;
+    cancel_pause(state)?;
+    start_schedule_override(state)?;
+    state
+        .automation
+        .enabled_by_watcher
+        .store(false, Ordering::SeqCst);
+    let result = set_awake_impl(state, true)?;
+
+    // Without a readable clock the timer still runs; only a restart loses it
+    let disable_at = duration
+        .zip(unix_now())
+        .map(|(d, now)| deadline_after(now, d));
+    update_state(|s| s.disable_at = disable_at)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+
+    let Some(duration) = duration else {
+        return Ok(result);
     };
-    write_state(&new_state).map_err(|e| format!("Failed to persist state: {}", e))?;
+    let timer_state = state.clone();
+    let id = state
+        .scheduled_actions
+        .schedule("Auto-disable", duration, async move {
+            log::info!("Timed sleep prevention ended");
+            if let Err(e) = set_awake_impl(&timer_state, false) {
+                log::error!("Auto-disable failed: {}", e);
+            }
+            on_expire();
+        });
+    *state
+        .auto_disable_timer
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during auto-disable: {}", e))? = Some(id);
 
-    // Start service if needed
-    if new_awake {
-        start_wake_service(is_awake.clone(), current_mode);
+    Ok(result)
+}
+
+/// Disable sleep for a limited time (Tauri command for frontend)
+///
+/// ## Arguments
+/// * `app` - Application handle, used to refresh the tray
+/// * `state` - Managed application state
+/// * `duration_secs` - Seconds until wake turns off again, None for no
+///   limit
+///
+/// ## Returns
+/// New awake state and screen mode, or error string
+#[tauri::command]
+pub fn toggle_sleep_for(
+    app: AppHandle,
+    state: State<AppStateManager>,
+    duration_secs: Option<u64>,
+) -> Result<(bool, ScreenMode), String> {
+    let result = toggle_sleep_for_impl(
+        &state,
+        duration_secs.map(Duration::from_secs),
+        notify::on_timer_expired(app.clone()),
+    )?;
+    tray::refresh(&app);
+    Ok(result)
+}
+
+/// Internal business logic for the time left before auto-disable
+///
+/// ## Design Intent
+/// Reads the pending auto-disable from the scheduler, the same entry that
+/// will turn wake off, so the countdown can never disagree with it. The
+/// awake cap (`max_session_secs`) turns wake off too, so whichever of the
+/// two comes first is reported; the cap monitor wakes up for its deadline
+/// (see `awake_cap`).
+///
+/// ## Returns
+/// Remaining time, None when wake is off or on without a limit
+pub fn get_remaining_time_impl(state: &AppStateManager) -> Result<Option<Duration>, String> {
+    let timer = state
+        .auto_disable_timer
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_remaining_time: {}", e))?
+        .as_deref()
+        .and_then(|id| state.scheduled_actions.remaining(id));
+    let cap = awake_cap_remaining_impl(state)?;
+    Ok(match (timer, cap) {
+        (Some(timer), Some(cap)) => Some(timer.min(cap)),
+        (timer, cap) => timer.or(cap),
+    })
+}
+
+/// Get the seconds left before auto-disable (Tauri command for frontend)
+///
+/// ## Returns
+/// Whole seconds left, rounded up, or None when wake is off or on without a
+/// limit
+#[tauri::command]
+pub fn get_remaining_time(state: State<AppStateManager>) -> Result<Option<u64>, String> {
+    Ok(get_remaining_time_impl(&state)?.map(ceil_secs))
+}
+
+/// Cancel a pending resume after a pause, if any
+///
+/// ## Side Effects
+/// Clears the persisted `resume_at` when a resume was pending
+fn cancel_pause(state: &AppStateManager) -> Result<(), String> {
+    let id = state
+        .pause_timer
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during pause: {}", e))?
+        .take();
+    if let Some(id) = id {
+        // Already fired timers are gone from the registry; nothing to cancel
+        let _ = state.scheduled_actions.cancel(&id);
+        update_state(|s| s.resume_at = None)
+            .map_err(|e| format!("Failed to persist state: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Schedule wake to turn back on at the end of a pause
+///
+/// ## Design Intent
+/// Split from `pause_for_impl` so startup can resume a pause persisted
+/// before a restart with the time it has left. The resume is a scheduled
+/// action like the auto-disable, so it can be listed and cancelled. With
+/// master off when it fires, wake stays off.
+///
+/// ## Arguments
+/// * `state` - Shared application state
+/// * `after` - Time until wake turns back on
+/// * `on_resume` - Called after wake turned back on (re-renders the tray)
+///
+/// ## Returns
+/// Ok(()), or error string if the timer slot is poisoned
+pub fn schedule_resume<F>(
+    state: &AppStateManager,
+    after: Duration,
+    on_resume: F,
+) -> Result<(), String>
+where
+    F: FnOnce() + Send + 'static,
+{
+    let timer_state = state.clone();
+    let id = state
+        .scheduled_actions
+        .schedule("Resume after pause", after, async move {
+            if let Ok(mut timer) = timer_state.pause_timer.lock() {
+                *timer = None;
+            }
+            if let Err(e) = update_state(|s| s.resume_at = None) {
+                log::warn!("Failed to clear persisted pause: {}", e);
+            }
+            if timer_state.automation.master_off.load(Ordering::SeqCst) {
+                log::info!("Pause ended while master is off, staying off");
+                return;
+            }
+            log::info!("Pause ended, resuming sleep prevention");
+            if let Err(e) = set_awake_impl(&timer_state, true) {
+                log::error!("Resume after pause failed: {}", e);
+            }
+            on_resume();
+        });
+    *state
+        .pause_timer
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during pause: {}", e))? = Some(id);
+    Ok(())
+}
+
+/// Internal business logic for pausing sleep prevention for a while
+///
+/// ## Design Intent
+/// The reverse of a timed wake: wake turns off now and back on by itself
+/// after `duration`, in whatever screen mode is then selected (the
+/// previous one unless changed meanwhile). A pending auto-disable is
+/// dropped, as with any disable. The resume time is persisted as
+/// `resume_at` so a restart keeps it (see `main`). A manual toggle or a
+/// timed wake during the pause cancels the resume.
+///
+/// ## Arguments
+/// * `state` - Shared application state
+/// * `duration` - How long to allow sleep
+/// * `on_resume` - Called after wake turned back on (re-renders the tray)
+///
+/// ## Returns
+/// New awake state and screen mode, or error string (while master is
+/// off, while wake is already off, or for an invalid duration)
+pub fn pause_for_impl<F>(
+    state: &AppStateManager,
+    duration: Duration,
+    on_resume: F,
+) -> Result<(bool, ScreenMode), String>
+where
+    F: FnOnce() + Send + 'static,
+{
+    if state.automation.master_off.load(Ordering::SeqCst) {
+        return Err(format!("{} is paused (master off)", core::APP_NAME));
+    }
+    validate_timed_awake(duration)?;
+    if !state.is_awake.load(Ordering::SeqCst) {
+        return Err("Sleep prevention is off, nothing to pause".to_string());
+    }
+
+    log::info!("Pausing sleep prevention for {:?}", duration);
+    cancel_pause(state)?;
+    start_schedule_override(state)?;
+    state
+        .automation
+        .enabled_by_watcher
+        .store(false, Ordering::SeqCst);
+    let result = set_awake_impl(state, false)?;
+
+    // Without a readable clock the pause still ends; only a restart loses it
+    let resume_at = unix_now().map(|now| deadline_after(now, duration));
+    update_state(|s| s.resume_at = resume_at)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+    schedule_resume(state, duration, on_resume)?;
+    Ok(result)
+}
+
+/// Allow sleep for a while, then resume (Tauri command for frontend)
+///
+/// ## Arguments
+/// * `app` - Application handle, used to refresh the tray
+/// * `state` - Managed application state
+/// * `minutes` - How long to allow sleep
+///
+/// ## Returns
+/// New awake state and screen mode, or error string
+#[tauri::command]
+pub fn pause_for(
+    app: AppHandle,
+    state: State<AppStateManager>,
+    minutes: u32,
+) -> Result<(bool, ScreenMode), String> {
+    let result = pause_for_impl(
+        &state,
+        Duration::from_secs(u64::from(minutes) * 60),
+        notify::on_pause_ended(app.clone()),
+    )?;
+    tray::refresh(&app);
+    Ok(result)
+}
+
+/// Internal business logic for the time left before a pause ends
+///
+/// ## Returns
+/// Remaining time, None when no pause is pending
+pub fn get_pause_remaining_impl(state: &AppStateManager) -> Result<Option<Duration>, String> {
+    let timer = state
+        .pause_timer
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_pause_remaining: {}", e))?;
+    Ok(timer
+        .as_deref()
+        .and_then(|id| state.scheduled_actions.remaining(id)))
+}
+
+/// Check a manual toggle against active watchers
+///
+/// ## Design Intent
+/// Only a disable can override a watcher. It is always allowed with a
+/// warning, unless `confirm_override_watchers` asks for a second
+/// confirmation first.
+///
+/// ## Arguments
+/// * `state` - Shared application state
+/// * `confirmed` - The user already confirmed the override
+///
+/// ## Returns
+/// Ok(()) to proceed, or the confirmation prompt as error string
+pub fn guard_manual_disable(state: &AppStateManager, confirmed: bool) -> Result<(), String> {
+    if !state.is_awake.load(Ordering::SeqCst) {
+        return Ok(());
     }
+    let active_watch = state
+        .automation
+        .active_watch
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during guard_manual_disable: {}", e))?
+        .clone();
 
-    Ok((new_awake, current_mode))
+    match decide_manual_disable(
+        active_watch.as_deref(),
+        state
+            .automation
+            .confirm_override_watchers
+            .load(Ordering::SeqCst),
+        confirmed,
+    ) {
+        OverrideDecision::Proceed => Ok(()),
+        OverrideDecision::Warn(message) => {
+            log::warn!("{}", message);
+            Ok(())
+        }
+        OverrideDecision::Confirm(message) => {
+            log::info!("Manual disable needs confirmation: {}", message);
+            Err(message)
+        }
+    }
 }
 
 /// Toggle system sleep prevention (Tauri command for frontend)
@@ -75,12 +789,99 @@ pub fn toggle_sleep_impl(
 ///
 /// ## Arguments
 /// * `state` - Managed application state
+/// * `confirm` - Confirms disabling while a watched condition is active
+///
+/// ## Side Effects
+/// Refreshes the tray (and the status window) after a toggle
 ///
 /// ## Returns
-/// New awake state and screen mode, or error string
+/// `{ is_awake, screen_mode, needs_confirmation }`, where a prompt means
+/// nothing changed yet, or error string when the toggle was refused
+#[tauri::command]
+pub fn toggle_sleep(
+    app: AppHandle,
+    state: State<AppStateManager>,
+    confirm: Option<bool>,
+) -> Result<ToggleResponse, String> {
+    let outcome = manual_toggle_impl(&state, confirm.unwrap_or(false))?;
+    if matches!(outcome, ToggleOutcome::Toggled(..)) {
+        tray::refresh(&app);
+    }
+    Ok(ToggleResponse::from_outcome(&state, outcome))
+}
+
+/// Internal business logic for a request on the local control endpoint
+///
+/// ## Design Intent
+/// `ENABLE` and `DISABLE` name the state wanted, so they only toggle when
+/// wake is not in it already; the toggle is the manual one, with the same
+/// master switch and battery checks as a click. Sending the request is
+/// deliberate, so overriding a watcher needs no confirmation (as with a
+/// second launch).
+///
+/// ## Returns
+/// Wake state and screen mode after the request, or error string
+pub fn control_request_impl(
+    state: &AppStateManager,
+    request: IpcRequest,
+) -> Result<(bool, ScreenMode), String> {
+    let wanted = match request {
+        IpcRequest::Enable => true,
+        IpcRequest::Disable => false,
+        IpcRequest::Status => {
+            let mode = *lock_screen_mode(&state.wake.screen_mode, "control_request");
+            return Ok((state.is_awake.load(Ordering::SeqCst), mode));
+        }
+    };
+
+    log::info!("Control request: {:?}", request);
+    if state.is_awake.load(Ordering::SeqCst) == wanted {
+        let mode = *lock_screen_mode(&state.wake.screen_mode, "control_request");
+        return Ok((wanted, mode));
+    }
+    match manual_toggle_impl(state, true)? {
+        ToggleOutcome::Toggled(awake, mode) => Ok((awake, mode)),
+        ToggleOutcome::NeedsConfirmation(prompt) => Err(prompt),
+    }
+}
+
+/// Internal business logic for listing why wake is on
+///
+/// ## Design Intent
+/// Feeds the current state through `resolve_desired_state`, which reports
+/// the reasons as a byproduct, so the list always matches the precedence
+/// rules.
+///
+/// ## Returns
+/// Active reasons, most significant first (empty when wake is off or
+/// paused), or error string
+pub fn get_awake_reasons_impl(state: &AppStateManager) -> Result<Vec<AwakeReason>, String> {
+    let active_watch = state
+        .automation
+        .active_watch
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_awake_reasons: {}", e))?
+        .clone();
+
+    let desired = resolve_desired_state(AutomationInputs {
+        master_off: state.automation.master_off.load(Ordering::SeqCst),
+        current_awake: state.is_awake.load(Ordering::SeqCst),
+        muted: state.automation.muted.load(Ordering::SeqCst),
+        power_pause: state.power.paused.is_paused(),
+        watcher_request: None,
+        enabled_by_watcher: state.automation.enabled_by_watcher.load(Ordering::SeqCst),
+        active_watch: active_watch.as_deref(),
+    });
+    Ok(desired.reasons)
+}
+
+/// List the reasons the machine is currently kept awake
+///
+/// ## Returns
+/// Active reasons, most significant first, or error string
 #[tauri::command]
-pub fn toggle_sleep(state: State<AppStateManager>) -> Result<(bool, ScreenMode), String> {
-    toggle_sleep_impl(&state.is_awake, &state.screen_mode)
+pub fn get_awake_reasons(state: State<AppStateManager>) -> Result<Vec<AwakeReason>, String> {
+    get_awake_reasons_impl(&state)
 }
 
 /// Internal business logic for changing screen mode
@@ -90,44 +891,34 @@ pub fn toggle_sleep(state: State<AppStateManager>) -> Result<(bool, ScreenMode),
 /// Keeps business logic in one place.
 ///
 /// ## Arguments
-/// * `is_awake` - Shared atomic flag
-/// * `screen_mode` - Shared mutex with screen mode
+/// * `state` - Shared application state
 /// * `new_mode` - Desired screen mode
 ///
 /// ## Returns
 /// New screen mode, or error string
 pub fn change_screen_mode_impl(
-    is_awake: &Arc<AtomicBool>,
-    screen_mode: &Arc<Mutex<ScreenMode>>,
+    state: &AppStateManager,
     new_mode: ScreenMode,
 ) -> Result<ScreenMode, String> {
     log::info!("Change screen mode to {:?}", new_mode);
 
-    // Update screen mode with proper poisoning handling
-    {
-        let mut mode = screen_mode
-            .lock()
-            .map_err(|e| format!("Mutex poisoned during change_screen_mode: {}", e))?;
-        *mode = new_mode;
-    }
+    *lock_screen_mode(&state.wake.screen_mode, "change_screen_mode") = new_mode;
 
     // Persist state
-    let awake = is_awake.load(Ordering::SeqCst);
-    let new_state = AppState {
-        sleep_disabled: awake,
-        screen_mode: new_mode,
-    };
-    write_state(&new_state).map_err(|e| format!("Failed to persist state: {}", e))?;
+    let awake = state.is_awake.load(Ordering::SeqCst);
+    update_state(|s| {
+        s.sleep_disabled = awake;
+        s.screen_mode = new_mode;
+    })
+    .map_err(|e| format!("Failed to persist state: {}", e))?;
 
     // Restart service if currently awake
     if awake {
         log::info!("Restarting wake service with new screen mode");
-        is_awake.store(false, Ordering::SeqCst);
-        std::thread::sleep(std::time::Duration::from_millis(100));
-        is_awake.store(true, Ordering::SeqCst);
-        start_wake_service(is_awake.clone(), new_mode);
+        state.service.request_sync();
     }
 
+    notify_state_changed(awake, new_mode);
     Ok(new_mode)
 }
 
@@ -140,70 +931,4080 @@ pub fn change_screen_mode_impl(
 /// * `state` - Managed application state
 /// * `new_mode` - Desired screen mode
 ///
+/// ## Side Effects
+/// Refreshes the tray (and the status window)
+///
 /// ## Returns
 /// New screen mode, or error string
 #[tauri::command]
 pub fn change_screen_mode(
+    app: AppHandle,
     state: State<AppStateManager>,
     new_mode: ScreenMode,
 ) -> Result<ScreenMode, String> {
-    change_screen_mode_impl(&state.is_awake, &state.screen_mode, new_mode)
+    let mode = change_screen_mode_impl(&state, new_mode)?;
+    tray::refresh(&app);
+    Ok(mode)
 }
 
-/// Get current application state
+/// Live settings and wake state, as a status window renders them
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct StateSnapshot {
+    pub is_awake: bool,
+    pub screen_mode: ScreenMode,
+    pub wake_key: WakeKey,
+    pub wake_strategy: WakeStrategy,
+    /// Wake interval in effect for `screen_mode`, before jitter
+    pub wake_interval_secs: u64,
+    /// Shorter interval of an active `boost`, which takes precedence
+    pub boost_interval_secs: Option<u64>,
+    pub interval_jitter: bool,
+    /// Whole seconds before wake turns off by itself, rounded up
+    pub remaining_secs: Option<u64>,
+    /// Whole seconds before a pause ends, rounded up
+    pub pause_remaining_secs: Option<u64>,
+    pub power_paused: bool,
+    pub automation_muted: bool,
+    pub master_enabled: bool,
+    pub active_profile: Option<String>,
+    /// Watched condition keeping wake on, if any
+    pub active_watch: Option<String>,
+}
+
+/// Whole seconds in `duration`, rounded up
+fn ceil_secs(duration: Duration) -> u64 {
+    duration.as_secs() + u64::from(duration.subsec_nanos() > 0)
+}
+
+/// Internal business logic for the state snapshot
 ///
 /// ## Design Intent
-/// Provides UI with current state for rendering.
+/// One read of every live setting, so a frontend renders from a single
+/// source instead of a getter per setting that may interleave with a
+/// change.
 ///
 /// ## Returns
-/// Current awake state and screen mode, or error string
-#[tauri::command]
-pub fn get_state(state: State<AppStateManager>) -> Result<(bool, ScreenMode), String> {
-    let awake = state.is_awake.load(Ordering::SeqCst);
-    let mode = *state
-        .screen_mode
+/// The snapshot, or error string if a lock is poisoned
+pub fn get_state_snapshot_impl(state: &AppStateManager) -> Result<StateSnapshot, String> {
+    let screen_mode = *lock_screen_mode(&state.wake.screen_mode, "get_state");
+    let wake_key = *state
+        .wake
+        .wake_key
         .lock()
         .map_err(|e| format!("Mutex poisoned during get_state: {}", e))?;
+    let wake_strategy = *state
+        .wake
+        .wake_strategy
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_state: {}", e))?;
+    let active_profile = state
+        .tray
+        .active_profile
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_state: {}", e))?
+        .clone();
+    let active_watch = state
+        .automation
+        .active_watch
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_state: {}", e))?
+        .clone();
+
+    Ok(StateSnapshot {
+        is_awake: state.is_awake.load(Ordering::SeqCst),
+        screen_mode,
+        wake_key,
+        wake_strategy,
+        wake_interval_secs: configured_wake_interval(screen_mode).as_secs(),
+        boost_interval_secs: state.service.boost_interval().map(|boost| boost.as_secs()),
+        interval_jitter: read_state().interval_jitter,
+        remaining_secs: get_remaining_time_impl(state)?.map(ceil_secs),
+        pause_remaining_secs: get_pause_remaining_impl(state)?.map(ceil_secs),
+        power_paused: state.power.paused.is_paused(),
+        automation_muted: state.automation.muted.load(Ordering::SeqCst),
+        master_enabled: !state.automation.master_off.load(Ordering::SeqCst),
+        active_profile,
+        active_watch,
+    })
+}
+
+/// Get current application state
+///
+/// ## Design Intent
+/// Provides UI with current state for rendering.
+///
+/// ## Returns
+/// Snapshot of the wake state and live settings, or error string
+#[tauri::command]
+pub fn get_state(state: State<AppStateManager>) -> Result<StateSnapshot, String> {
+    get_state_snapshot_impl(&state)
+}
+
+/// Get the awake state and screen mode only
+///
+/// ## Design Intent
+/// What `get_state` returned before it became a `StateSnapshot`, kept for
+/// frontends and scripts that expect the tuple.
+///
+/// ## Returns
+/// Current awake state and screen mode, or error string
+#[tauri::command]
+pub fn get_state_tuple(state: State<AppStateManager>) -> Result<(bool, ScreenMode), String> {
+    let awake = state.is_awake.load(Ordering::SeqCst);
+    let mode = *lock_screen_mode(&state.wake.screen_mode, "get_state_tuple");
+
+    Ok((awake, mode))
+}
 
-    Ok((awake, mode))
-}
-
-/// Start wake service in background
+/// Internal business logic for reloading wake state from disk
 ///
 /// ## Design Intent
-/// Spawns asynchronous wake service task. Used by both business logic
-/// and startup initialization.
+/// Lets the state file be edited by hand or by a script and applied
+/// without a restart. Only the wake preference and screen mode are
+/// applied; master off still overrides a saved wake preference, as at
+/// startup.
+///
+/// ## Concurrency
+/// A menu event or command can change the state while the file is being
+/// applied. The `screen_mode` lock is held from reading the file until
+/// both values are applied, so a concurrent screen mode change lands
+/// entirely before or after the reload. A concurrent toggle flips the wake
+/// state before it takes that lock, so it can still land on either side:
+/// whichever applies last decides the wake state. A toggle's file write is
+/// debounced (see `ToggleCommit`), so for up to `TOGGLE_DEBOUNCE` after it
+/// the file can still hold the reloaded state. The pending commit is not
+/// flushed first, as that would overwrite the edit being reloaded; when it
+/// runs it persists whichever state applied last, so the file matches
+/// again.
 ///
 /// ## Side Effects
-/// - Spawns Tokio task
-/// - Starts F15 simulation
-/// - Sets platform display flags
-pub fn start_wake_service(is_awake: Arc<AtomicBool>, screen_mode: ScreenMode) {
-    let display_controller = platform::get_display_controller();
-    let service = WakeService::new(is_awake, display_controller);
+/// - Starts, restarts or stops the wake service as needed
+/// - Cancels a pending auto-disable when wake turns off
+///
+/// ## Returns
+/// Applied awake state and screen mode, or error string
+pub fn reload_state_impl(state: &AppStateManager) -> Result<(bool, ScreenMode), String> {
+    let (awake, was_awake, mode_changed, new_mode) = {
+        let mut mode = lock_screen_mode(&state.wake.screen_mode, "reload_state");
+        let loaded = read_state();
+        let awake = loaded.sleep_disabled && !state.automation.master_off.load(Ordering::SeqCst);
+        let was_awake = state.is_awake.swap(awake, Ordering::SeqCst);
+        let mode_changed = *mode != loaded.screen_mode;
+        *mode = loaded.screen_mode;
+        (awake, was_awake, mode_changed, loaded.screen_mode)
+    };
 
-    tokio::spawn(async move {
-        if let Err(e) = service.run(screen_mode).await {
-            log::error!("Wake service error: {}", e);
+    log::info!(
+        "Reloaded state: sleep prevention {} -> {}, screen mode {:?}",
+        was_awake,
+        awake,
+        new_mode
+    );
+
+    if awake != was_awake {
+        *state
+            .awake_since
+            .lock()
+            .map_err(|e| format!("Mutex poisoned during reload_state: {}", e))? =
+            awake.then(Instant::now);
+        if !awake {
+            cancel_auto_disable(state)?;
         }
-    });
+    }
+
+    if awake != was_awake || (awake && mode_changed) {
+        state.service.request_sync();
+    }
+
+    notify_state_changed(awake, new_mode);
+    Ok((awake, new_mode))
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Reload wake state from the state file (Tauri command)
+///
+/// ## Side Effects
+/// Refreshes the tray (and the status window)
+///
+/// ## Returns
+/// Applied awake state and screen mode, or error string
+#[tauri::command]
+pub fn reload_state(
+    app: AppHandle,
+    state: State<AppStateManager>,
+) -> Result<(bool, ScreenMode), String> {
+    let result = reload_state_impl(&state)?;
+    tray::refresh(&app);
+    Ok(result)
+}
 
-    #[test]
-    fn test_app_state_manager_creation() {
-        let manager = AppStateManager {
-            is_awake: Arc::new(AtomicBool::new(false)),
-            screen_mode: Arc::new(Mutex::new(ScreenMode::default())),
-        };
+/// Internal business logic for writing the in-memory wake state to disk
+///
+/// ## Design Intent
+/// The counterpart of `reload_state_impl`: persists the wake preference
+/// and screen mode as they are now, e.g. after a failed write or before
+/// editing the file by hand. Other persisted fields are kept.
+///
+/// ## Concurrency
+/// Holds the `screen_mode` lock while writing, so a concurrent screen
+/// mode change or reload cannot interleave with the write.
+///
+/// ## Returns
+/// Persisted awake state and screen mode, or a structured error whose code
+/// tells a permissions problem from a full disk
+pub fn flush_state_impl(state: &AppStateManager) -> Result<(bool, ScreenMode), ErrorReport> {
+    let mode = lock_screen_mode(&state.wake.screen_mode, "flush_state");
+    let awake = state.is_awake.load(Ordering::SeqCst);
+    update_state(|s| {
+        s.sleep_disabled = awake;
+        s.screen_mode = *mode;
+    })?;
 
-        assert!(!manager.is_awake.load(Ordering::SeqCst));
-        assert_eq!(
-            *manager.screen_mode.lock().unwrap(),
-            ScreenMode::AllowScreenOff
+    log::info!(
+        "Flushed state: sleep prevention {}, screen mode {:?}",
+        awake,
+        *mode
+    );
+    Ok((awake, *mode))
+}
+
+/// Write the in-memory wake state to the state file (Tauri command)
+///
+/// ## Returns
+/// Persisted awake state and screen mode, or `{ code, message, hint }`
+#[tauri::command]
+pub fn flush_state(state: State<AppStateManager>) -> Result<(bool, ScreenMode), ErrorReport> {
+    flush_state_impl(&state)
+}
+
+/// Internal business logic for resetting every setting to its default
+///
+/// ## Design Intent
+/// Overwrites the state file with `AppState::default()` rather than
+/// resetting fields one by one, so settings added later are reset too.
+/// The file gets the same "Default" profile a fresh install would (see
+/// `ensure_default_profile`). The shared state is then brought in line
+/// with the file, as `main` does at startup; it is destructured without
+/// `..`, so a new field fails to compile until it is reset or listed as
+/// surviving. Runtime-only state such as the busy indicator is cleared.
+///
+/// ## Side Effects
+/// - Stops the wake service and cancels pending auto-disable, pause and
+///   unmute timers
+/// - Overwrites the state file
+/// - Turns the master switch back on and resumes scheduled actions
+///
+/// ## Returns
+/// The toggle shortcut in effect before the reset, so the caller can swap
+/// its registration, or error string
+pub fn reset_state_impl(state: &AppStateManager) -> Result<String, String> {
+    log::info!("Resetting all settings to defaults");
+
+    set_awake_impl(state, false)?;
+    cancel_pause(state)?;
+    set_automation_muted_impl(state, false, None)?;
+
+    let mut defaults = AppState::default();
+    ensure_default_profile(&mut defaults);
+    write_state(&defaults).map_err(|e| format!("Failed to persist state: {}", e))?;
+
+    let previous_shortcut = hotkey::effective_shortcut(
+        state
+            .control
+            .toggle_shortcut
+            .lock()
+            .map_err(|e| format!("Mutex poisoned during reset_state: {}", e))?
+            .as_deref(),
+    )
+    .to_string();
+
+    // No `..`: a field added to the manager must be handled here
+    let AppStateManager {
+        // Already reset above, or shared handles that outlive the settings
+        is_awake: _,
+        awake_since: _,
+        auto_disable_timer: _,
+        pause_timer: _,
+        service: _,
+        toggle_commit: _,
+        scheduled_actions,
+        wake,
+        power,
+        automation,
+        tray,
+        control,
+    } = state;
+
+    wake.reset_from(&defaults)?;
+    power.reset_from(&defaults)?;
+    automation.reset_from(&defaults)?;
+    scheduled_actions.set_suspended(defaults.master_off);
+    tray.reset_from(&defaults)?;
+    control.reset_from(&defaults)?;
+    power_monitor::refresh(&power.only_on_ac, &automation.muted, &power.paused);
+
+    notify_state_changed(false, defaults.screen_mode);
+    Ok(previous_shortcut)
+}
+
+/// Reset every setting to its default (Tauri command)
+///
+/// ## Side Effects
+/// - Swaps the global shortcut back to the default
+/// - Hides the advanced menu, resets the tray's left click and profile
+///   entries, and refreshes the tray
+///
+/// ## Returns
+/// Error string if the reset or the shortcut registration failed
+#[tauri::command]
+pub fn reset_state(app: AppHandle, state: State<AppStateManager>) -> Result<(), String> {
+    let previous_shortcut = reset_state_impl(&state)?;
+    ipc::sync_server(&app);
+    tray::on_reset(&app);
+    let next_shortcut = hotkey::effective_shortcut(None);
+    if previous_shortcut != next_shortcut {
+        hotkey::unregister(&app, &previous_shortcut);
+        hotkey::register(&app, next_shortcut)?;
+    }
+    Ok(())
+}
+
+/// Internal business logic for reading the autostart registration
+///
+/// ## Returns
+/// Whether Tea starts at login, or error string
+pub fn get_autostart_impl(autostart: &dyn Autostart) -> Result<bool, String> {
+    autostart
+        .is_enabled()
+        .map_err(|e| format!("Failed to check autostart status: {}", e))
+}
+
+/// Internal business logic for turning autostart on or off
+///
+/// ## Side Effects
+/// Registers or removes the login item (registry entry, LaunchAgent or
+/// .desktop file, see `handle_toggle_autostart`)
+///
+/// ## Design Intent
+/// The OS can accept the change and still not apply it (e.g. group policy
+/// reverting a registry write), so the setting is read back afterwards and
+/// a change that did not stick is an error too.
+///
+/// ## Returns
+/// The new autostart setting, or error string
+pub fn set_autostart_impl(autostart: &dyn Autostart, enabled: bool) -> Result<bool, String> {
+    let action = if enabled { "enable" } else { "disable" };
+    let result = if enabled {
+        autostart.enable()
+    } else {
+        autostart.disable()
+    };
+    result.map_err(|e| format!("Failed to {} autostart: {}", action, e))?;
+
+    if get_autostart_impl(autostart)? != enabled {
+        return Err(format!(
+            "Failed to {} autostart: the change did not take effect, it may be blocked by policy",
+            action
+        ));
+    }
+    log::info!("Autostart {}d", action);
+    Ok(enabled)
+}
+
+/// Whether Tea starts at login (Tauri command)
+///
+/// ## Returns
+/// Autostart setting, or error string
+#[tauri::command]
+pub fn get_autostart(app: AppHandle) -> Result<bool, String> {
+    get_autostart_impl(&PluginAutostart::new(&app))
+}
+
+/// Turn autostart on or off (Tauri command)
+///
+/// ## Side Effects
+/// Updates the Start at Login checkmark to the setting actually in effect,
+/// re-read after a failure
+///
+/// ## Returns
+/// The new autostart setting, or error string
+#[tauri::command]
+pub fn set_autostart(app: AppHandle, enabled: bool) -> Result<bool, String> {
+    let autostart = PluginAutostart::new(&app);
+    let result = set_autostart_impl(&autostart, enabled);
+    let actual = match &result {
+        Ok(enabled) => Ok(*enabled),
+        Err(_) => get_autostart_impl(&autostart),
+    };
+    match actual {
+        Ok(actual) => tray::show_autostart(&app, actual),
+        Err(e) => log::warn!("{}", e),
+    }
+    result
+}
+
+/// Internal business logic for setting the instance label
+///
+/// ## Design Intent
+/// Normalizes the label (trimmed, empty means none) so the tooltip never
+/// shows an empty `[]` prefix, then stores and persists it.
+///
+/// ## Arguments
+/// * `instance_label` - Shared label slot
+/// * `label` - Desired label, or None to clear
+///
+/// ## Returns
+/// The label as stored, or error string
+pub fn set_instance_label_impl(
+    instance_label: &Arc<Mutex<Option<String>>>,
+    label: Option<String>,
+) -> Result<Option<String>, String> {
+    let label = label
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty());
+
+    log::info!("Set instance label: {:?}", label);
+
+    {
+        let mut current = instance_label
+            .lock()
+            .map_err(|e| format!("Mutex poisoned during set_instance_label: {}", e))?;
+        current.clone_from(&label);
+    }
+
+    let persisted = label.clone();
+    update_state(move |state| state.instance_label = persisted)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+
+    Ok(label)
+}
+
+/// Set the instance label shown in the tooltip (Tauri command for frontend)
+///
+/// ## Design Intent
+/// Lets multi-instance setups (e.g. one per VM) tell their trays apart.
+///
+/// ## Side Effects
+/// - Persists the label
+/// - Refreshes the tray tooltip
+///
+/// ## Returns
+/// The label as stored, or error string
+#[tauri::command]
+pub fn set_instance_label(
+    app: AppHandle,
+    state: State<AppStateManager>,
+    label: Option<String>,
+) -> Result<Option<String>, String> {
+    let label = set_instance_label_impl(&state.tray.instance_label, label)?;
+    tray::refresh(&app);
+    Ok(label)
+}
+
+/// Get the current instance label
+///
+/// ## Returns
+/// The label, or None when unset
+#[tauri::command]
+pub fn get_instance_label(state: State<AppStateManager>) -> Result<Option<String>, String> {
+    let label = state
+        .tray
+        .instance_label
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_instance_label: {}", e))?;
+    Ok(label.clone())
+}
+
+/// Internal business logic for setting the tooltip template
+///
+/// ## Design Intent
+/// An empty template means none, so clearing the field in a settings UI
+/// brings back the built-in wording. Placeholders are not validated:
+/// unknown ones stay literal in the tooltip (see `core::tooltip`).
+///
+/// ## Returns
+/// The template as stored, or error string
+pub fn set_tooltip_template_impl(
+    tooltip_template: &Arc<Mutex<Option<String>>>,
+    template: Option<String>,
+) -> Result<Option<String>, String> {
+    let template = template
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+
+    log::info!("Set tooltip template: {:?}", template);
+
+    tooltip_template
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during set_tooltip_template: {}", e))?
+        .clone_from(&template);
+
+    let persisted = template.clone();
+    update_state(move |state| state.tooltip_template = persisted)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+
+    Ok(template)
+}
+
+/// Set the tooltip template (Tauri command for frontend)
+///
+/// ## Side Effects
+/// - Persists the template
+/// - Refreshes the tray tooltip
+///
+/// ## Returns
+/// The template as stored, or error string
+#[tauri::command]
+pub fn set_tooltip_template(
+    app: AppHandle,
+    state: State<AppStateManager>,
+    template: Option<String>,
+) -> Result<Option<String>, String> {
+    let template = set_tooltip_template_impl(&state.tray.tooltip_template, template)?;
+    tray::refresh(&app);
+    Ok(template)
+}
+
+/// Get the tooltip template
+///
+/// ## Returns
+/// The template, or None when the built-in wording is used
+#[tauri::command]
+pub fn get_tooltip_template(state: State<AppStateManager>) -> Result<Option<String>, String> {
+    let template = state
+        .tray
+        .tooltip_template
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_tooltip_template: {}", e))?;
+    Ok(template.clone())
+}
+
+/// Set whether wake is only kept while on external power (Tauri command)
+///
+/// ## Design Intent
+/// Persists the preference and re-evaluates the power pause immediately so
+/// the change takes effect without waiting for the next monitor poll.
+///
+/// ## Arguments
+/// * `state` - Managed application state
+/// * `enabled` - Whether to pause while on battery
+///
+/// ## Returns
+/// The stored preference, or error string
+#[tauri::command]
+pub fn set_only_on_ac(state: State<AppStateManager>, enabled: bool) -> Result<bool, String> {
+    log::info!("Set only-on-AC: {}", enabled);
+    update_state(|s| s.only_on_ac = enabled)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+
+    state.power.only_on_ac.store(enabled, Ordering::SeqCst);
+    power_monitor::refresh(
+        &state.power.only_on_ac,
+        &state.automation.muted,
+        &state.power.paused,
+    );
+    Ok(enabled)
+}
+
+/// Get whether wake is only kept while on external power
+#[tauri::command]
+pub fn get_only_on_ac(state: State<AppStateManager>) -> bool {
+    state.power.only_on_ac.load(Ordering::SeqCst)
+}
+
+/// Set whether manual enables are refused on battery (Tauri command)
+///
+/// ## Returns
+/// The stored preference, or error string
+#[tauri::command]
+pub fn set_refuse_on_battery(state: State<AppStateManager>, enabled: bool) -> Result<bool, String> {
+    log::info!("Set refuse on battery: {}", enabled);
+    update_state(|s| s.refuse_on_battery = enabled)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+    state
+        .power
+        .refuse_on_battery
+        .store(enabled, Ordering::SeqCst);
+    Ok(enabled)
+}
+
+/// Get whether manual enables are refused on battery
+#[tauri::command]
+pub fn get_refuse_on_battery(state: State<AppStateManager>) -> bool {
+    state.power.refuse_on_battery.load(Ordering::SeqCst)
+}
+
+/// Set whether the tray icon blinks on every F15 press (Tauri command)
+///
+/// ## Arguments
+/// * `state` - Managed application state
+/// * `enabled` - Whether to blink
+///
+/// ## Returns
+/// The stored preference, or error string
+#[tauri::command]
+pub fn set_blink_on_press(state: State<AppStateManager>, enabled: bool) -> Result<bool, String> {
+    log::info!("Set blink on press: {}", enabled);
+    update_state(|s| s.blink_on_press = enabled)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+
+    state.wake.blink_on_press.store(enabled, Ordering::SeqCst);
+    Ok(enabled)
+}
+
+/// Set whether every wake cycle logs a heartbeat (Tauri command)
+///
+/// ## Design Intent
+/// For diagnosing a system that slept hours into a session: each cycle
+/// logs its iteration, press result and next due time at info level.
+/// Takes effect on the next cycle, without restarting the service.
+///
+/// ## Returns
+/// The stored preference, or error string
+#[tauri::command]
+pub fn set_log_each_iteration(
+    state: State<AppStateManager>,
+    enabled: bool,
+) -> Result<bool, String> {
+    log::info!("Set log each iteration: {}", enabled);
+    update_state(|s| s.log_each_iteration = enabled)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+
+    state
+        .wake
+        .log_each_iteration
+        .store(enabled, Ordering::SeqCst);
+    Ok(enabled)
+}
+
+/// Get whether every wake cycle logs a heartbeat
+#[tauri::command]
+pub fn get_log_each_iteration(state: State<AppStateManager>) -> bool {
+    state.wake.log_each_iteration.load(Ordering::SeqCst)
+}
+
+/// Internal business logic for choosing the simulated wake key
+///
+/// ## Design Intent
+/// The running service reads the shared key every cycle, so the choice
+/// applies from the next press without restarting the service.
+///
+/// ## Returns
+/// The stored key, or error string (including keys this platform lacks)
+pub fn set_wake_key_impl(state: &AppStateManager, key: WakeKey) -> Result<WakeKey, String> {
+    if !key.is_supported() {
+        return Err(format!("{} is not available on this platform", key.label()));
+    }
+    log::info!("Set wake key: {}", key.label());
+
+    update_state(|s| s.wake_key = key).map_err(|e| format!("Failed to persist state: {}", e))?;
+    *state
+        .wake
+        .wake_key
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during set_wake_key: {}", e))? = key;
+    Ok(key)
+}
+
+/// Set the key pressed every wake cycle (Tauri command)
+///
+/// ## Returns
+/// The stored key, or error string
+#[tauri::command]
+pub fn set_wake_key(state: State<AppStateManager>, key: WakeKey) -> Result<WakeKey, String> {
+    set_wake_key_impl(&state, key)
+}
+
+/// Get the key pressed every wake cycle
+#[tauri::command]
+pub fn get_wake_key(state: State<AppStateManager>) -> Result<WakeKey, String> {
+    let key = state
+        .wake
+        .wake_key
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_wake_key: {}", e))?;
+    Ok(*key)
+}
+
+/// Internal business logic for choosing key press or mouse jiggle
+///
+/// ## Design Intent
+/// Like the wake key, the running service reads the shared strategy every
+/// cycle, so the choice applies from the next cycle without a restart.
+///
+/// ## Returns
+/// The stored strategy, or error string
+pub fn set_wake_strategy_impl(
+    state: &AppStateManager,
+    strategy: WakeStrategy,
+) -> Result<WakeStrategy, String> {
+    log::info!("Set wake strategy: {}", strategy.label());
+
+    update_state(|s| s.wake_strategy = strategy)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+    *state
+        .wake
+        .wake_strategy
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during set_wake_strategy: {}", e))? = strategy;
+    Ok(strategy)
+}
+
+/// Set what the wake service simulates every cycle (Tauri command)
+///
+/// ## Returns
+/// The stored strategy, or error string
+#[tauri::command]
+pub fn set_wake_strategy(
+    state: State<AppStateManager>,
+    strategy: WakeStrategy,
+) -> Result<WakeStrategy, String> {
+    set_wake_strategy_impl(&state, strategy)
+}
+
+/// Get what the wake service simulates every cycle
+#[tauri::command]
+pub fn get_wake_strategy(state: State<AppStateManager>) -> Result<WakeStrategy, String> {
+    let strategy = state
+        .wake
+        .wake_strategy
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_wake_strategy: {}", e))?;
+    Ok(*strategy)
+}
+
+/// List the wake keys this platform supports, in menu order
+#[tauri::command]
+pub fn list_wake_keys() -> Vec<WakeKey> {
+    WakeKey::ALL
+        .into_iter()
+        .filter(|key| key.is_supported())
+        .collect()
+}
+
+/// Set what happens when a watched condition ends (Tauri command)
+///
+/// ## Arguments
+/// * `action` - Follow-up once the watcher releases wake
+/// * `delay_secs` - Grace period before a system action (None uses the
+///   default)
+///
+/// ## Returns
+/// The stored action, or error string
+#[tauri::command]
+pub fn set_on_condition_end(
+    action: ConditionEndAction,
+    delay_secs: Option<u64>,
+) -> Result<ConditionEndAction, String> {
+    log::info!(
+        "Set on condition end: {:?} (delay {:?})",
+        action,
+        delay_secs
+    );
+    update_state(|s| {
+        s.on_condition_end = action;
+        s.condition_end_delay_secs = delay_secs;
+    })
+    .map_err(|e| format!("Failed to persist state: {}", e))?;
+    Ok(action)
+}
+
+/// Get what happens when a watched condition ends
+#[tauri::command]
+pub fn get_on_condition_end() -> ConditionEndAction {
+    read_state().on_condition_end
+}
+
+/// Schedule the follow-up for a watched condition that ended
+///
+/// ## Design Intent
+/// System actions go through the shared timer registry, so the pending
+/// action shows up in `list_scheduled_actions` under its announcement and
+/// can be cancelled with `cancel_scheduled_action` until it fires.
+///
+/// ## Arguments
+/// * `actions` - Timer registry
+/// * `plan` - Result of `plan_condition_end`
+/// * `perform` - Runs the system action (`ServiceManager::power_action`)
+///
+/// ## Returns
+/// Id of the scheduled action, or None if nothing beyond disabling is due
+pub fn schedule_condition_end<F>(
+    actions: &Arc<ScheduledActions>,
+    plan: ConditionEndPlan,
+    perform: F,
+) -> Option<String>
+where
+    F: FnOnce(ConditionEndAction) -> Result<(), String> + Send + 'static,
+{
+    let ConditionEndPlan::Delayed {
+        action,
+        delay,
+        notice,
+    } = plan
+    else {
+        return None;
+    };
+
+    log::warn!("{}, cancel the scheduled action to stay on", notice);
+    Some(actions.schedule(&notice, delay, async move {
+        if let Err(e) = perform(action) {
+            log::error!("Condition end action failed: {}", e);
+        }
+    }))
+}
+
+/// Internal business logic for the notifications preference
+///
+/// ## Returns
+/// The stored preference, or error string
+pub fn set_notifications_enabled_impl(
+    state: &AppStateManager,
+    enabled: bool,
+) -> Result<bool, String> {
+    log::info!("Set notifications: {}", enabled);
+    update_state(|s| s.notifications_enabled = enabled)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+
+    state
+        .tray
+        .notifications_enabled
+        .store(enabled, Ordering::SeqCst);
+    Ok(enabled)
+}
+
+/// Set whether wake changes show a desktop notification (Tauri command)
+///
+/// ## Returns
+/// The stored preference, or error string
+#[tauri::command]
+pub fn set_notifications_enabled(
+    app: AppHandle,
+    state: State<AppStateManager>,
+    enabled: bool,
+) -> Result<bool, String> {
+    let result = set_notifications_enabled_impl(&state, enabled);
+    tray::refresh(&app);
+    result
+}
+
+/// Get whether wake changes show a desktop notification
+#[tauri::command]
+pub fn get_notifications_enabled(state: State<AppStateManager>) -> bool {
+    state.tray.notifications_enabled.load(Ordering::SeqCst)
+}
+
+/// Internal business logic for the control endpoint switch
+///
+/// ## Design Intent
+/// The endpoint lets any process of the user change wake, so it stays off
+/// until asked for; `tea --enable`, `--disable` and `--status` need it to
+/// reach the running instance.
+///
+/// ## Returns
+/// The stored setting, or error string
+pub fn set_enable_ipc_impl(state: &AppStateManager, enabled: bool) -> Result<bool, String> {
+    log::info!("Set control endpoint: {}", enabled);
+    update_state(|s| s.enable_ipc = enabled)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+
+    state.control.enable_ipc.store(enabled, Ordering::SeqCst);
+    Ok(enabled)
+}
+
+/// Turn the local control endpoint on or off (Tauri command)
+///
+/// ## Side Effects
+/// Starts or stops the endpoint at once and updates the tray checkmark
+///
+/// ## Returns
+/// The stored setting, or error string
+#[tauri::command]
+pub fn set_enable_ipc(
+    app: AppHandle,
+    state: State<AppStateManager>,
+    enabled: bool,
+) -> Result<bool, String> {
+    let result = set_enable_ipc_impl(&state, enabled);
+    ipc::sync_server(&app);
+    tray::refresh(&app);
+    result
+}
+
+/// Get whether the local control endpoint is on (Tauri command)
+#[tauri::command]
+pub fn get_enable_ipc(state: State<AppStateManager>) -> bool {
+    state.control.enable_ipc.load(Ordering::SeqCst)
+}
+
+/// Internal business logic for the session only flag
+///
+/// ## Design Intent
+/// Only the next startup reads the flag: it starts with sleep allowed
+/// whatever was saved, then clears the flag. The running session is not
+/// affected.
+///
+/// ## Returns
+/// The stored flag, or error string
+pub fn set_session_only_impl(state: &AppStateManager, enabled: bool) -> Result<bool, String> {
+    log::info!("Set session only: {}", enabled);
+    update_state(|s| s.session_only = enabled)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+
+    state.wake.session_only.store(enabled, Ordering::SeqCst);
+    Ok(enabled)
+}
+
+/// Set whether the next launch starts with sleep allowed (Tauri command)
+///
+/// ## Returns
+/// The stored flag, or error string
+#[tauri::command]
+pub fn set_session_only(
+    app: AppHandle,
+    state: State<AppStateManager>,
+    enabled: bool,
+) -> Result<bool, String> {
+    let result = set_session_only_impl(&state, enabled);
+    tray::refresh(&app);
+    result
+}
+
+/// Get whether the next launch starts with sleep allowed
+#[tauri::command]
+pub fn get_session_only(state: State<AppStateManager>) -> bool {
+    state.wake.session_only.load(Ordering::SeqCst)
+}
+
+/// Get whether the tray icon blinks on every F15 press
+#[tauri::command]
+pub fn get_blink_on_press(state: State<AppStateManager>) -> bool {
+    state.wake.blink_on_press.load(Ordering::SeqCst)
+}
+
+/// Internal business logic for the left click preference
+///
+/// ## Returns
+/// The stored preference, or error string
+pub fn set_left_click_toggles_impl(state: &AppStateManager, enabled: bool) -> Result<bool, String> {
+    log::info!("Set left click toggles: {}", enabled);
+    update_state(|s| s.left_click_toggles = Some(enabled))
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+
+    state
+        .tray
+        .left_click_toggles
+        .store(enabled, Ordering::SeqCst);
+    Ok(enabled)
+}
+
+/// Set whether a left click on the tray icon toggles wake (Tauri command)
+///
+/// ## Side Effects
+/// Switches the tray between toggling and opening the menu on left click
+///
+/// ## Returns
+/// The stored preference, or error string
+#[tauri::command]
+pub fn set_left_click_toggles(
+    app: AppHandle,
+    state: State<AppStateManager>,
+    enabled: bool,
+) -> Result<bool, String> {
+    let enabled = set_left_click_toggles_impl(&state, enabled)?;
+    tray::show_left_click(&app, enabled);
+    Ok(enabled)
+}
+
+/// Get whether a left click on the tray icon toggles wake
+#[tauri::command]
+pub fn get_left_click_toggles(state: State<AppStateManager>) -> bool {
+    state.tray.left_click_toggles.load(Ordering::SeqCst)
+}
+
+/// Set whether overriding an active watcher needs confirmation (Tauri command)
+///
+/// ## Returns
+/// The stored preference, or error string
+#[tauri::command]
+pub fn set_confirm_override_watchers(
+    state: State<AppStateManager>,
+    enabled: bool,
+) -> Result<bool, String> {
+    log::info!("Set confirm override watchers: {}", enabled);
+    update_state(|s| s.confirm_override_watchers = enabled)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+
+    state
+        .automation
+        .confirm_override_watchers
+        .store(enabled, Ordering::SeqCst);
+    Ok(enabled)
+}
+
+/// Get whether overriding an active watcher needs confirmation
+#[tauri::command]
+pub fn get_confirm_override_watchers(state: State<AppStateManager>) -> bool {
+    state
+        .automation
+        .confirm_override_watchers
+        .load(Ordering::SeqCst)
+}
+
+/// Internal business logic for muting automation
+///
+/// ## Design Intent
+/// While muted, watchers and power policy stop influencing wake and only the
+/// manual toggle is effective. Muting with a duration schedules an automatic
+/// unmute through the shared timer registry, replacing any earlier one.
+///
+/// ## Arguments
+/// * `state` - Shared application state
+/// * `muted` - Whether automation should be muted
+/// * `duration` - Optional time after which automation unmutes itself
+///
+/// ## Side Effects
+/// - Re-evaluates the power pause immediately
+/// - Schedules or cancels the auto-unmute timer
+///
+/// ## Returns
+/// The new mute state, or error string
+pub fn set_automation_muted_impl(
+    state: &AppStateManager,
+    muted: bool,
+    duration: Option<Duration>,
+) -> Result<bool, String> {
+    log::info!("Set automation muted: {} (for {:?})", muted, duration);
+
+    let mut unmute_timer = state
+        .automation
+        .unmute_timer
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during set_automation_muted: {}", e))?;
+
+    if let Some(id) = unmute_timer.take() {
+        // Already fired timers are gone from the registry; nothing to cancel
+        let _ = state.scheduled_actions.cancel(&id);
+    }
+
+    state.automation.muted.store(muted, Ordering::SeqCst);
+    power_monitor::refresh(
+        &state.power.only_on_ac,
+        &state.automation.muted,
+        &state.power.paused,
+    );
+
+    if let (true, Some(duration)) = (muted, duration) {
+        let automation_muted = state.automation.muted.clone();
+        let only_on_ac = state.power.only_on_ac.clone();
+        let power_paused = state.power.paused.clone();
+        let id = state
+            .scheduled_actions
+            .schedule("Unmute automation", duration, async move {
+                log::info!("Automation mute expired");
+                automation_muted.store(false, Ordering::SeqCst);
+                power_monitor::refresh(&only_on_ac, &automation_muted, &power_paused);
+            });
+        *unmute_timer = Some(id);
+    }
+
+    Ok(muted)
+}
+
+/// Mute or unmute all conditional automation (Tauri command for frontend)
+///
+/// ## Arguments
+/// * `state` - Managed application state
+/// * `muted` - Whether automation should be muted
+/// * `minutes` - Optional auto-unmute delay
+///
+/// ## Returns
+/// The new mute state, or error string
+#[tauri::command]
+pub fn set_automation_muted(
+    state: State<AppStateManager>,
+    muted: bool,
+    minutes: Option<u32>,
+) -> Result<bool, String> {
+    let duration = minutes.map(|m| Duration::from_secs(u64::from(m) * 60));
+    set_automation_muted_impl(&state, muted, duration)
+}
+
+/// Get whether conditional automation is muted
+#[tauri::command]
+pub fn get_automation_muted(state: State<AppStateManager>) -> bool {
+    state.automation.muted.load(Ordering::SeqCst)
+}
+
+/// Internal business logic for the master switch
+///
+/// ## Design Intent
+/// Top of the automation precedence chain, above muting. Turning the master
+/// switch off disables wake and suspends every watcher and scheduled
+/// action; turning it back on only lifts the suspension, wake stays off
+/// until the user enables it.
+///
+/// ## Arguments
+/// * `state` - Shared application state
+/// * `enabled` - Whether the app should be active
+///
+/// ## Side Effects
+/// - Stops the wake service and restores normal power behavior when disabling
+/// - Suspends or resumes scheduled actions
+/// - Persists the master flag
+///
+/// ## Returns
+/// The new master state, or error string
+pub fn set_master_enabled_impl(state: &AppStateManager, enabled: bool) -> Result<bool, String> {
+    log::info!("Set master enabled: {}", enabled);
+
+    state
+        .automation
+        .master_off
+        .store(!enabled, Ordering::SeqCst);
+    state.scheduled_actions.set_suspended(!enabled);
+
+    if !enabled {
+        set_busy_indicator_impl(&state.tray.busy_label, false, None)?;
+        set_awake_impl(state, false)?;
+    }
+
+    update_state(|s| s.master_off = !enabled)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+
+    Ok(enabled)
+}
+
+/// Turn the whole app on or off (Tauri command for frontend)
+///
+/// ## Arguments
+/// * `app` - Application handle, used to refresh the tray
+/// * `state` - Managed application state
+/// * `enabled` - Whether the app should be active
+///
+/// ## Returns
+/// The new master state, or error string
+#[tauri::command]
+pub fn set_master_enabled(
+    app: AppHandle,
+    state: State<AppStateManager>,
+    enabled: bool,
+) -> Result<bool, String> {
+    let result = set_master_enabled_impl(&state, enabled);
+    tray::refresh(&app);
+    result
+}
+
+/// Get whether the master switch is on
+#[tauri::command]
+pub fn get_master_enabled(state: State<AppStateManager>) -> bool {
+    !state.automation.master_off.load(Ordering::SeqCst)
+}
+
+/// Wake interval configured for a screen mode
+///
+/// ## Side Effects
+/// Reads persisted config
+pub fn configured_wake_interval(screen_mode: ScreenMode) -> Duration {
+    let config = read_state();
+    resolve_wake_interval(
+        screen_mode,
+        &config.interval_per_mode,
+        config.wake_interval_secs,
+    )
+}
+
+/// Compute the sleep risk for a screen mode
+///
+/// ## Design Intent
+/// Compares the configured wake interval for `screen_mode` with the OS
+/// idle-sleep timeout. Where a platform API keeps the system awake between
+/// presses, the interval cannot lose the race.
+///
+/// ## Side Effects
+/// Reads persisted config and may query OS power settings.
+pub fn compute_sleep_risk(screen_mode: ScreenMode) -> SleepRisk {
+    let interval = configured_wake_interval(screen_mode);
+    let risk = sleep_risk::assess_sleep_risk(
+        interval,
+        platform::idle_sleep_timeout(),
+        platform::native_wake_available(),
+    );
+    log::info!("Sleep risk with {:?} interval: {:?}", interval, risk);
+    risk
+}
+
+/// Assess whether the system could sleep despite wake (Tauri command)
+///
+/// ## Side Effects
+/// Updates the cached risk shown in the tray tooltip.
+///
+/// ## Returns
+/// Current sleep risk, or error string
+#[tauri::command]
+pub fn assess_sleep_risk(
+    app: AppHandle,
+    state: State<AppStateManager>,
+) -> Result<SleepRisk, String> {
+    let mode = *lock_screen_mode(&state.wake.screen_mode, "assess_sleep_risk");
+    let risk = compute_sleep_risk(mode);
+
+    *state
+        .wake
+        .sleep_risk
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during assess_sleep_risk: {}", e))? = risk;
+    tray::refresh(&app);
+    Ok(risk)
+}
+
+/// Internal business logic for the busy indicator
+///
+/// ## Design Intent
+/// Purely cosmetic overlay for streamers and presenters: the tray shows a
+/// distinct icon and the label until cleared, whatever the wake state.
+/// An empty or missing label falls back to "Busy".
+///
+/// ## Arguments
+/// * `busy_label` - Shared busy indicator slot
+/// * `on` - Whether to show the indicator
+/// * `label` - Text for tooltip and title
+///
+/// ## Returns
+/// The active label, or None when cleared
+pub fn set_busy_indicator_impl(
+    busy_label: &Arc<Mutex<Option<String>>>,
+    on: bool,
+    label: Option<String>,
+) -> Result<Option<String>, String> {
+    let label = on.then(|| {
+        label
+            .map(|label| label.trim().to_string())
+            .filter(|label| !label.is_empty())
+            .unwrap_or_else(|| "Busy".to_string())
+    });
+    log::info!("Set busy indicator: {:?}", label);
+
+    let mut busy = busy_label
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during set_busy_indicator: {}", e))?;
+    *busy = label.clone();
+    Ok(label)
+}
+
+/// Show or clear the busy indicator (Tauri command for frontend)
+///
+/// ## Returns
+/// The active label, or None when cleared
+#[tauri::command]
+pub fn set_busy_indicator(
+    app: AppHandle,
+    state: State<AppStateManager>,
+    on: bool,
+    label: Option<String>,
+) -> Result<Option<String>, String> {
+    let result = set_busy_indicator_impl(&state.tray.busy_label, on, label);
+    tray::refresh(&app);
+    result
+}
+
+/// Get how long the user has been idle (Tauri command for frontend)
+///
+/// ## Design Intent
+/// Lets a settings window show the live idle time while tuning
+/// idle-related thresholds.
+///
+/// ## Returns
+/// Idle seconds, or error string where unsupported
+#[tauri::command]
+pub fn get_system_idle_secs() -> Result<u64, String> {
+    platform::system_idle_secs()
+}
+
+/// Get the result of the startup self-check
+#[tauri::command]
+pub fn get_startup_check(check: State<StartupCheck>) -> StartupCheck {
+    check.inner().clone()
+}
+
+/// Whether the tray menu shows advanced entries
+#[tauri::command]
+pub fn get_show_advanced_menu() -> bool {
+    read_state().show_advanced_menu
+}
+
+/// Show or hide advanced tray menu entries (Tauri command)
+///
+/// ## Design Intent
+/// Casual users get the simple menu by default; power users can expand
+/// it with diagnostics. Applied to the live menu immediately.
+///
+/// ## Returns
+/// The new setting, or error string if it could not be persisted
+#[tauri::command]
+pub fn set_show_advanced_menu(app: AppHandle, show: bool) -> Result<bool, String> {
+    log::info!("Set show advanced menu: {}", show);
+    update_state(|s| s.show_advanced_menu = show)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+    tray::set_advanced_menu(&app, show);
+    Ok(show)
+}
+
+/// Build information for the running binary
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct BuildInfo {
+    pub version: &'static str,
+    pub target_os: &'static str,
+    pub target_arch: &'static str,
+    pub debug: bool,
+}
+
+/// What the current platform supports
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Capabilities {
+    pub screen_modes: Vec<ScreenMode>,
+    pub wake_methods: Vec<platform::WakeMethod>,
+    pub window_watch: bool,
+    pub device_watch: bool,
+    pub remote_session_watch: bool,
+    pub process_watch: bool,
+    pub network_watch: bool,
+}
+
+/// Options a frontend should offer on this platform
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct PlatformCapabilities {
+    /// Whether Allow Screen Off does anything here
+    pub allow_screen_off_supported: bool,
+    /// Whether Away Mode is offered here (Windows only)
+    pub away_mode_supported: bool,
+    /// Operating system, as in `std::env::consts::OS`
+    pub os: String,
+}
+
+/// User configuration currently in effect
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct EffectiveConfig {
+    pub instance_label: Option<String>,
+    pub only_on_ac: bool,
+    pub watch_window_title: Option<String>,
+    pub watch_device: Option<String>,
+    pub watch_process: Option<String>,
+    pub watch_network: Option<String>,
+    pub keep_awake_on_remote_session: bool,
+    pub automation_muted: bool,
+    pub master_enabled: bool,
+}
+
+/// Everything the frontend needs on boot, in one call
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Bootstrap {
+    pub build: BuildInfo,
+    pub capabilities: Capabilities,
+    pub is_awake: bool,
+    pub screen_mode: ScreenMode,
+    pub power_paused: bool,
+    pub config: EffectiveConfig,
+    pub startup_check: StartupCheck,
+}
+
+/// Internal business logic for the diagnostics report
+///
+/// ## Design Intent
+/// Collects what support asks for first when wake "isn't working"; the
+/// report format and what is left out live in `core::diagnostics`.
+///
+/// ## Returns
+/// The formatted report, or error string on a poisoned mutex
+pub fn collect_diagnostics_impl(
+    state: &AppStateManager,
+    autostart: &dyn Autostart,
+) -> Result<String, String> {
+    let screen_mode = *lock_screen_mode(&state.wake.screen_mode, "collect_diagnostics");
+    let wake_strategy = *state
+        .wake
+        .wake_strategy
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during collect_diagnostics: {}", e))?;
+    let wake_key = *state
+        .wake
+        .wake_key
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during collect_diagnostics: {}", e))?;
+    let log_lines = logging::recent_log_lines(diagnostics::LOG_TAIL_LINES);
+    let home_var = if cfg!(windows) { "USERPROFILE" } else { "HOME" };
+    let home_dir = std::env::var(home_var).ok();
+
+    Ok(diagnostics::format_report(&DiagnosticsInput {
+        version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        is_awake: state.is_awake.load(Ordering::SeqCst),
+        screen_mode,
+        input_simulation: wake_service::uses_input_simulation(screen_mode),
+        wake_strategy,
+        wake_key,
+        autostart: get_autostart_impl(autostart),
+        log_lines: &log_lines,
+        home_dir: home_dir.as_deref(),
+    }))
+}
+
+/// Collect a diagnostics report and copy it to the clipboard (Tauri command)
+///
+/// ## Side Effects
+/// Replaces the clipboard contents with the report
+///
+/// ## Returns
+/// The report, or error string if it could not be collected or copied
+#[tauri::command]
+pub fn collect_diagnostics(
+    app: AppHandle,
+    state: State<AppStateManager>,
+) -> Result<String, String> {
+    let report = collect_diagnostics_impl(&state, &PluginAutostart::new(&app))?;
+    app.clipboard()
+        .write_text(report.clone())
+        .map_err(|e| format!("Failed to copy diagnostics to clipboard: {}", e))?;
+    Ok(report)
+}
+
+/// Record a trace-level log for the next `seconds` (Tauri command)
+///
+/// ## Design Intent
+/// Lets a user reproduce an intermittent problem with full logging and
+/// hand over only that window, without restarting under `RUST_LOG`. The
+/// log level in effect before is restored afterwards.
+///
+/// ## Returns
+/// Path of the captured log file, or error string if `seconds` is out of
+/// range, a capture is already running or the file cannot be written
+#[tauri::command]
+pub async fn capture_debug_window(seconds: u64) -> Result<String, String> {
+    if seconds == 0 || seconds > logging::MAX_CAPTURE_SECS {
+        return Err(format!(
+            "Capture window must be between 1 and {} seconds",
+            logging::MAX_CAPTURE_SECS
+        ));
+    }
+    let path = logging::capture_file_path();
+    logging::capture_to(&path, Duration::from_secs(seconds)).await?;
+    log::info!("Debug capture saved to {}", path.display());
+    Ok(path.display().to_string())
+}
+
+/// Get build information (Tauri command for frontend)
+#[tauri::command]
+pub fn get_build_info() -> BuildInfo {
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION"),
+        target_os: std::env::consts::OS,
+        target_arch: std::env::consts::ARCH,
+        debug: cfg!(debug_assertions),
+    }
+}
+
+/// Get platform capabilities (Tauri command for frontend)
+#[tauri::command]
+pub fn get_capabilities() -> Capabilities {
+    Capabilities {
+        screen_modes: ScreenMode::supported(),
+        wake_methods: platform::wake_methods(),
+        window_watch: platform::supports_window_enumeration(),
+        device_watch: platform::supports_device_enumeration(),
+        remote_session_watch: platform::supports_session_enumeration(),
+        process_watch: platform::supports_process_enumeration(),
+        network_watch: platform::supports_interface_enumeration(),
+    }
+}
+
+/// Get which options to offer on this platform (Tauri command)
+///
+/// ## Design Intent
+/// Lets a frontend hide Allow Screen Off where it would be a dead toggle,
+/// using the same `ScreenMode::is_supported` check as the tray menu.
+#[tauri::command]
+pub fn get_platform_capabilities() -> PlatformCapabilities {
+    PlatformCapabilities {
+        allow_screen_off_supported: ScreenMode::AllowScreenOff.is_supported(),
+        away_mode_supported: ScreenMode::AwayMode.is_supported(),
+        os: std::env::consts::OS.to_string(),
+    }
+}
+
+/// Get what every screen mode and wake method does here (Tauri command)
+///
+/// ## Design Intent
+/// Drives the in-app help table and generated documentation, so the
+/// description always matches the platform the app runs on.
+///
+/// ## Returns
+/// One row per supported screen mode and available wake method
+#[tauri::command]
+pub fn behavior_matrix() -> Vec<BehaviorRow> {
+    core::behavior::behavior_matrix(
+        std::env::consts::OS,
+        &ScreenMode::supported(),
+        &platform::wake_methods(),
+    )
+}
+
+/// Internal business logic for the boot snapshot
+///
+/// ## Design Intent
+/// Reads every value the separate getters return in one pass, so the
+/// frontend never renders a mix of states from before and after a change.
+///
+/// ## Returns
+/// Aggregated snapshot, or error string if a lock is poisoned
+pub fn bootstrap_impl(
+    state: &AppStateManager,
+    startup_check: &StartupCheck,
+) -> Result<Bootstrap, String> {
+    let screen_mode = *lock_screen_mode(&state.wake.screen_mode, "get_bootstrap");
+    let instance_label = state
+        .tray
+        .instance_label
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_bootstrap: {}", e))?
+        .clone();
+    let watch_window_title = state
+        .automation
+        .watch_window_title
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_bootstrap: {}", e))?
+        .clone();
+    let watch_device = state
+        .automation
+        .watch_device
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_bootstrap: {}", e))?
+        .clone();
+    let watch_process = state
+        .automation
+        .watch_process
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_bootstrap: {}", e))?
+        .clone();
+    let watch_network = state
+        .automation
+        .watch_network
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_bootstrap: {}", e))?
+        .clone();
+
+    Ok(Bootstrap {
+        build: get_build_info(),
+        capabilities: get_capabilities(),
+        is_awake: state.is_awake.load(Ordering::SeqCst),
+        screen_mode,
+        power_paused: state.power.paused.is_paused(),
+        config: EffectiveConfig {
+            instance_label,
+            only_on_ac: state.power.only_on_ac.load(Ordering::SeqCst),
+            watch_window_title,
+            watch_device,
+            watch_process,
+            watch_network,
+            keep_awake_on_remote_session: state
+                .automation
+                .keep_awake_on_remote_session
+                .load(Ordering::SeqCst),
+            automation_muted: state.automation.muted.load(Ordering::SeqCst),
+            master_enabled: !state.automation.master_off.load(Ordering::SeqCst),
+        },
+        startup_check: startup_check.clone(),
+    })
+}
+
+/// Get the boot snapshot (Tauri command for frontend)
+///
+/// ## Design Intent
+/// Replaces separate round-trips for build info, capabilities, state and
+/// config when the settings window opens.
+#[tauri::command]
+pub fn get_bootstrap(
+    state: State<AppStateManager>,
+    startup_check: State<StartupCheck>,
+) -> Result<Bootstrap, String> {
+    bootstrap_impl(&state, &startup_check)
+}
+
+/// List pending one-shot timers
+///
+/// ## Returns
+/// Every scheduled action that has not yet fired, soonest first
+#[tauri::command]
+pub fn list_scheduled_actions(state: State<AppStateManager>) -> Vec<ScheduledAction> {
+    state.scheduled_actions.list()
+}
+
+/// Cancel a pending one-shot timer
+///
+/// ## Arguments
+/// * `state` - Managed application state
+/// * `id` - Id returned by `list_scheduled_actions`
+///
+/// ## Returns
+/// Ok(()) if cancelled, or error string if no such action is pending
+#[tauri::command]
+pub fn cancel_scheduled_action(state: State<AppStateManager>, id: String) -> Result<(), String> {
+    state.scheduled_actions.cancel(&id)
+}
+
+/// Set the window title pattern that keeps the system awake (Tauri command)
+///
+/// ## Design Intent
+/// While a visible window title contains the pattern (case-insensitive),
+/// the window watcher keeps wake on. Empty or None clears the watch.
+///
+/// ## Platform Behavior
+/// Window enumeration is Windows-only for now; elsewhere the pattern is
+/// stored but never matches.
+///
+/// ## Returns
+/// The pattern as stored, or error string
+#[tauri::command]
+pub fn set_watch_window_title(
+    state: State<AppStateManager>,
+    pattern: Option<String>,
+) -> Result<Option<String>, String> {
+    let pattern = pattern
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+
+    log::info!("Set window title watch: {:?}", pattern);
+    if pattern.is_some() && cfg!(not(windows)) {
+        log::warn!("Window title watch is not supported on this platform yet");
+    }
+
+    state
+        .automation
+        .watch_window_title
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during set_watch_window_title: {}", e))?
+        .clone_from(&pattern);
+
+    let persisted = pattern.clone();
+    update_state(move |s| s.watch_window_title = persisted)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+
+    Ok(pattern)
+}
+
+/// Get the window title pattern that keeps the system awake
+#[tauri::command]
+pub fn get_watch_window_title(state: State<AppStateManager>) -> Result<Option<String>, String> {
+    let pattern = state
+        .automation
+        .watch_window_title
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_watch_window_title: {}", e))?;
+    Ok(pattern.clone())
+}
+
+/// Set the device that keeps the system awake while connected (Tauri command)
+///
+/// ## Design Intent
+/// While a present device matches the pattern (name substring, or a
+/// `VID:PID` pair such as `17EF:A396`), the device watcher keeps wake on.
+/// Empty or None clears the watch.
+///
+/// ## Platform Behavior
+/// Device enumeration is Windows-only for now; elsewhere the pattern is
+/// stored but never matches.
+///
+/// ## Returns
+/// The pattern as stored, or error string
+#[tauri::command]
+pub fn set_watch_device(
+    state: State<AppStateManager>,
+    pattern: Option<String>,
+) -> Result<Option<String>, String> {
+    let pattern = pattern
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+
+    log::info!("Set device watch: {:?}", pattern);
+    if pattern.is_some() && !platform::supports_device_enumeration() {
+        log::warn!("Device watch is not supported on this platform yet");
+    }
+
+    state
+        .automation
+        .watch_device
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during set_watch_device: {}", e))?
+        .clone_from(&pattern);
+
+    let persisted = pattern.clone();
+    update_state(move |s| s.watch_device = persisted)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+
+    Ok(pattern)
+}
+
+/// Get the device pattern that keeps the system awake
+#[tauri::command]
+pub fn get_watch_device(state: State<AppStateManager>) -> Result<Option<String>, String> {
+    let pattern = state
+        .automation
+        .watch_device
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_watch_device: {}", e))?;
+    Ok(pattern.clone())
+}
+
+/// Internal business logic for the watched process
+///
+/// ## Design Intent
+/// While a process with this executable name runs, the process watcher
+/// keeps wake on; several matching processes count as one trigger. Empty
+/// or None clears the watch.
+///
+/// ## Returns
+/// The name as stored, or error string
+pub fn set_watch_process_impl(
+    state: &AppStateManager,
+    name: Option<String>,
+) -> Result<Option<String>, String> {
+    let name = name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty());
+
+    log::info!("Set process watch: {:?}", name);
+    if name.is_some() && !platform::supports_process_enumeration() {
+        log::warn!("Process watch is not supported on this platform yet");
+    }
+
+    state
+        .automation
+        .watch_process
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during set_watch_process: {}", e))?
+        .clone_from(&name);
+
+    let persisted = name.clone();
+    update_state(move |s| s.watch_process = persisted)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+
+    Ok(name)
+}
+
+/// Set the process that keeps the system awake while running (Tauri command)
+///
+/// ## Platform Behavior
+/// - Windows, Linux, macOS: Matched against running executable names
+/// - Other: Stored but never matches
+///
+/// ## Returns
+/// The name as stored, or error string
+#[tauri::command]
+pub fn set_watch_process(
+    app: AppHandle,
+    state: State<AppStateManager>,
+    name: Option<String>,
+) -> Result<Option<String>, String> {
+    let result = set_watch_process_impl(&state, name);
+    tray::refresh(&app);
+    result
+}
+
+/// Get the process name that keeps the system awake
+#[tauri::command]
+pub fn get_watch_process(state: State<AppStateManager>) -> Result<Option<String>, String> {
+    let name = state
+        .automation
+        .watch_process
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_watch_process: {}", e))?;
+    Ok(name.clone())
+}
+
+/// Internal business logic for the Windows wake API choice
+///
+/// ## Design Intent
+/// Power requests show up in `powercfg /requests` with Tea's reason, so an
+/// administrator can see why a machine won't sleep. A running session
+/// restarts with the chosen API.
+///
+/// ## Platform Behavior
+/// - Windows: Power requests when enabled, else `SetThreadExecutionState`
+/// - Other: Stored but has no effect
+///
+/// ## Returns
+/// The stored setting, or error string
+pub fn set_use_power_requests_impl(state: &AppStateManager, enabled: bool) -> Result<bool, String> {
+    log::info!("Set use power requests: {}", enabled);
+    update_state(|s| s.use_power_requests = enabled)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+    state.service.request_sync();
+    Ok(enabled)
+}
+
+/// Choose power requests over `SetThreadExecutionState` (Tauri command)
+///
+/// ## Returns
+/// The stored setting, or error string
+#[tauri::command]
+pub fn set_use_power_requests(
+    state: State<AppStateManager>,
+    enabled: bool,
+) -> Result<bool, String> {
+    set_use_power_requests_impl(&state, enabled)
+}
+
+/// Get whether Windows power requests are used (Tauri command)
+#[tauri::command]
+pub fn get_use_power_requests() -> bool {
+    read_state().use_power_requests
+}
+
+/// Internal business logic for the watched network
+///
+/// ## Design Intent
+/// While the interface is up (e.g. `wg0` for a VPN) or the `host:port`
+/// accepts connections, the network watcher keeps wake on. Empty or None
+/// clears the watch.
+///
+/// ## Returns
+/// The target as stored, or error string
+pub fn set_watch_network_impl(
+    state: &AppStateManager,
+    target: Option<String>,
+) -> Result<Option<String>, String> {
+    let target = target
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+
+    log::info!("Set network watch: {:?}", target);
+    let is_interface = matches!(
+        target.as_deref().and_then(NetTarget::parse),
+        Some(NetTarget::Interface(_))
+    );
+    if is_interface && !platform::supports_interface_enumeration() {
+        log::warn!("Interface watch is not supported on this platform yet");
+    }
+
+    state
+        .automation
+        .watch_network
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during set_watch_network: {}", e))?
+        .clone_from(&target);
+
+    let persisted = target.clone();
+    update_state(move |s| s.watch_network = persisted)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+
+    Ok(target)
+}
+
+/// Set the network that keeps the system awake while connected (Tauri command)
+///
+/// ## Arguments
+/// * `target` - Interface name, or `host:port` to reach over TCP
+///
+/// ## Platform Behavior
+/// - Windows, Linux, macOS: Interfaces and hosts
+/// - Other: Hosts only; an interface is stored but never matches
+///
+/// ## Returns
+/// The target as stored, or error string
+#[tauri::command]
+pub fn set_watch_network(
+    app: AppHandle,
+    state: State<AppStateManager>,
+    target: Option<String>,
+) -> Result<Option<String>, String> {
+    let result = set_watch_network_impl(&state, target);
+    tray::refresh(&app);
+    result
+}
+
+/// Get the network target that keeps the system awake
+#[tauri::command]
+pub fn get_watch_network(state: State<AppStateManager>) -> Result<Option<String>, String> {
+    let target = state
+        .automation
+        .watch_network
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_watch_network: {}", e))?;
+    Ok(target.clone())
+}
+
+/// Internal business logic for the global toggle shortcut
+///
+/// ## Design Intent
+/// Validates and stores the shortcut; registering it with the OS is left
+/// to the command so this can be tested without an app. Empty or None
+/// restores the built-in default.
+///
+/// ## Returns
+/// The previous and new effective shortcut, or error string
+pub fn set_toggle_shortcut_impl(
+    state: &AppStateManager,
+    shortcut: Option<String>,
+) -> Result<(String, String), String> {
+    let shortcut = hotkey::normalize_shortcut(shortcut.as_deref())?;
+
+    log::info!("Set toggle shortcut: {:?}", shortcut);
+
+    let mut current = state
+        .control
+        .toggle_shortcut
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during set_toggle_shortcut: {}", e))?;
+    let previous = hotkey::effective_shortcut(current.as_deref()).to_string();
+    current.clone_from(&shortcut);
+    let next = hotkey::effective_shortcut(current.as_deref()).to_string();
+    drop(current);
+
+    update_state(move |s| s.toggle_shortcut = shortcut)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+
+    Ok((previous, next))
+}
+
+/// Change the global shortcut that toggles wake (Tauri command)
+///
+/// ## Side Effects
+/// - Persists the shortcut, even if registering it fails
+/// - Swaps the OS registration from the previous shortcut
+///
+/// ## Returns
+/// Error string if the shortcut is invalid or taken by another application
+#[tauri::command]
+pub fn set_toggle_shortcut(
+    app: AppHandle,
+    state: State<AppStateManager>,
+    shortcut: Option<String>,
+) -> Result<(), String> {
+    let (previous, next) = set_toggle_shortcut_impl(&state, shortcut)?;
+    if previous != next {
+        hotkey::unregister(&app, &previous);
+        hotkey::register(&app, &next)?;
+    }
+    Ok(())
+}
+
+/// Get the global toggle shortcut in effect
+#[tauri::command]
+pub fn get_toggle_shortcut(state: State<AppStateManager>) -> Result<String, String> {
+    let shortcut = state
+        .control
+        .toggle_shortcut
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_toggle_shortcut: {}", e))?;
+    Ok(hotkey::effective_shortcut(shortcut.as_deref()).to_string())
+}
+
+/// How long wake has been on without interruption
+///
+/// ## Returns
+/// Elapsed time since the last enable, None when wake is off, or error
+/// string
+pub fn get_awake_elapsed_impl(state: &AppStateManager) -> Result<Option<Duration>, String> {
+    let since = *state
+        .awake_since
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during awake elapsed read: {}", e))?;
+    Ok(since.map(|since| since.elapsed()))
+}
+
+/// Time left before the awake cap turns wake off
+///
+/// ## Returns
+/// Remaining time, None when wake is off or no cap is set, or error string
+pub fn awake_cap_remaining_impl(state: &AppStateManager) -> Result<Option<Duration>, String> {
+    let secs = *state
+        .wake
+        .max_session_secs
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during awake cap check: {}", e))?;
+    let since = *state
+        .awake_since
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during awake cap check: {}", e))?;
+    match since {
+        Some(since) => Ok(awake_cap_remaining(
+            since.elapsed(),
+            max_session_duration(secs)?,
+        )),
+        None => Ok(None),
+    }
+}
+
+/// Internal business logic for the maximum awake duration
+///
+/// ## Design Intent
+/// Safety backstop for an app left on indefinitely. Once wake has been on
+/// continuously for `max_session_secs` it is turned off like a manual
+/// disable, and stays off until the user enables it again.
+///
+/// ## Returns
+/// True if the cap was reached and wake turned off, or error string
+pub fn enforce_awake_cap_impl(state: &AppStateManager) -> Result<bool, String> {
+    let secs = *state
+        .wake
+        .max_session_secs
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during awake cap check: {}", e))?;
+    let Some(since) = *state
+        .awake_since
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during awake cap check: {}", e))?
+    else {
+        return Ok(false);
+    };
+    let cap = max_session_duration(secs)?;
+    if !awake_cap_reached(since.elapsed(), cap) {
+        return Ok(false);
+    }
+
+    log::warn!(
+        "Awake for {}, the configured maximum; disabling sleep prevention until re-enabled",
+        format_duration_human(since.elapsed())
+    );
+    state
+        .automation
+        .enabled_by_watcher
+        .store(false, Ordering::SeqCst);
+    start_schedule_override(state)?;
+    set_awake_impl(state, false)?;
+    Ok(true)
+}
+
+/// Internal business logic for the battery floor
+///
+/// ## Design Intent
+/// Safety stop for a laptop left awake on battery. Below the floor wake
+/// is turned off like a manual disable; plugging back in does not turn it
+/// on again, the user has to.
+///
+/// ## Arguments
+/// * `state` - Shared application state
+/// * `source` - Current power source
+/// * `percent` - Current battery charge, None without a battery
+///
+/// ## Returns
+/// True if wake was turned off, or error string
+pub fn enforce_battery_floor_impl(
+    state: &AppStateManager,
+    source: PowerSource,
+    percent: Option<u8>,
+) -> Result<bool, String> {
+    let floor = *state
+        .power
+        .battery_floor_percent
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during battery floor check: {}", e))?;
+    if !state.is_awake.load(Ordering::SeqCst) || !below_battery_floor(floor, source, percent) {
+        return Ok(false);
+    }
+
+    log::warn!(
+        "Battery at {}%, below the {}% floor; disabling sleep prevention until re-enabled",
+        percent.unwrap_or_default(),
+        floor.unwrap_or_default()
+    );
+    state
+        .automation
+        .enabled_by_watcher
+        .store(false, Ordering::SeqCst);
+    start_schedule_override(state)?;
+    set_awake_impl(state, false)?;
+    Ok(true)
+}
+
+/// Set the battery floor (Tauri command)
+///
+/// ## Arguments
+/// * `state` - Managed application state
+/// * `percent` - Charge in percent, None to turn the floor off
+///
+/// ## Returns
+/// The stored floor, or error string if `percent` is not 1-100
+#[tauri::command]
+pub fn set_battery_floor_percent(
+    state: State<AppStateManager>,
+    percent: Option<u8>,
+) -> Result<Option<u8>, String> {
+    validate_battery_floor(percent)?;
+    log::info!("Set battery floor: {:?}", percent);
+
+    update_state(|s| s.battery_floor_percent = percent)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+    *state
+        .power
+        .battery_floor_percent
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during set_battery_floor_percent: {}", e))? = percent;
+    Ok(percent)
+}
+
+/// Get the battery floor in percent
+#[tauri::command]
+pub fn get_battery_floor_percent(state: State<AppStateManager>) -> Result<Option<u8>, String> {
+    let floor = state
+        .power
+        .battery_floor_percent
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_battery_floor_percent: {}", e))?;
+    Ok(*floor)
+}
+
+/// Current local wall-clock time, as the schedule reads it
+pub fn local_now() -> NaiveDateTime {
+    chrono::Local::now().naive_local()
+}
+
+/// Hold a change the schedule did not make until its next boundary
+///
+/// ## Design Intent
+/// Called by every manual change and safety stop, so the schedule does not
+/// undo it on the next check. A schedule that never flips holds the change
+/// for good. Without a schedule nothing is recorded.
+fn start_schedule_override(state: &AppStateManager) -> Result<(), String> {
+    let schedule = state
+        .automation
+        .schedule
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during schedule override: {}", e))?;
+    let until = (!schedule.is_empty()).then(|| {
+        schedule
+            .next_boundary(local_now())
+            .unwrap_or(NaiveDateTime::MAX)
+    });
+    if let Some(until) = until {
+        log::info!("Manual change holds against the schedule until {}", until);
+    }
+    *state
+        .automation
+        .schedule_override
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during schedule override: {}", e))? = until;
+    Ok(())
+}
+
+/// Internal business logic for one schedule check
+///
+/// ## Design Intent
+/// Brings wake in line with the schedule unless something else has the
+/// say: master off, muted automation, a pending pause, a manual override
+/// that has not yet reached its boundary, or a watcher that turned wake
+/// on (the schedule does not end a watcher's session).
+///
+/// ## Arguments
+/// * `state` - Shared application state
+/// * `now` - Current local time
+///
+/// ## Returns
+/// The wake state set, None if nothing changed, or error string
+pub fn apply_schedule_impl(
+    state: &AppStateManager,
+    now: NaiveDateTime,
+) -> Result<Option<bool>, String> {
+    if state.automation.master_off.load(Ordering::SeqCst)
+        || state.automation.muted.load(Ordering::SeqCst)
+        || get_pause_remaining_impl(state)?.is_some()
+    {
+        return Ok(None);
+    }
+    let schedule = state
+        .automation
+        .schedule
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during schedule check: {}", e))?
+        .clone();
+    let override_until = {
+        let mut slot = state
+            .automation
+            .schedule_override
+            .lock()
+            .map_err(|e| format!("Mutex poisoned during schedule check: {}", e))?;
+        if slot.is_some_and(|until| override_expired(until, now)) {
+            log::info!("Schedule boundary reached, manual override ended");
+            *slot = None;
+        }
+        *slot
+    };
+
+    let Some(desired) = schedule_request(&schedule, now, override_until) else {
+        return Ok(None);
+    };
+    if desired == state.is_awake.load(Ordering::SeqCst)
+        || (!desired && state.automation.enabled_by_watcher.load(Ordering::SeqCst))
+    {
+        return Ok(None);
+    }
+
+    log::info!(
+        "Schedule {}, {} sleep prevention",
+        if desired { "active" } else { "inactive" },
+        if desired { "enabling" } else { "disabling" }
+    );
+    set_awake_impl(state, desired)?;
+    Ok(Some(desired))
+}
+
+/// Internal business logic for replacing the schedule
+///
+/// ## Side Effects
+/// - Persists the schedule
+/// - Drops a manual override, so the new schedule applies on the next check
+///
+/// ## Returns
+/// The stored schedule, or error string for an invalid window
+pub fn set_schedule_impl(state: &AppStateManager, schedule: Schedule) -> Result<Schedule, String> {
+    schedule.validate()?;
+    log::info!("Set schedule: {} window(s)", schedule.windows.len());
+
+    update_state(|s| s.schedule = schedule.clone())
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+    *state
+        .automation
+        .schedule
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during set_schedule: {}", e))? = schedule.clone();
+    *state
+        .automation
+        .schedule_override
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during set_schedule: {}", e))? = None;
+    Ok(schedule)
+}
+
+/// Set the wake schedule (Tauri command)
+///
+/// ## Arguments
+/// * `state` - Managed application state
+/// * `schedule` - New schedule, no windows to turn it off
+///
+/// ## Returns
+/// The stored schedule, or error string
+#[tauri::command]
+pub fn set_schedule(state: State<AppStateManager>, schedule: Schedule) -> Result<Schedule, String> {
+    set_schedule_impl(&state, schedule)
+}
+
+/// Get the wake schedule
+#[tauri::command]
+pub fn get_schedule(state: State<AppStateManager>) -> Result<Schedule, String> {
+    let schedule = state
+        .automation
+        .schedule
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_schedule: {}", e))?;
+    Ok(schedule.clone())
+}
+
+/// Internal business logic for setting the maximum continuous awake duration
+///
+/// ## Design Intent
+/// Applies to the running session too: the cap monitor checks the new cap
+/// against the current continuous-awake clock on its next pass. A timed
+/// wake keeps its own deadline, so whichever of the two is shorter ends it.
+///
+/// ## Arguments
+/// * `state` - Shared application state
+/// * `secs` - Cap in seconds, None to remove it
+///
+/// ## Returns
+/// The stored cap, or error string if `secs` is zero
+pub fn set_max_session_secs_impl(
+    state: &AppStateManager,
+    secs: Option<u64>,
+) -> Result<Option<u64>, String> {
+    max_session_duration(secs)?;
+    log::info!("Set max session length: {:?} seconds", secs);
+
+    update_state(|s| s.max_session_secs = secs)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+    *state
+        .wake
+        .max_session_secs
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during set_max_session_secs: {}", e))? = secs;
+    Ok(secs)
+}
+
+/// Set the maximum continuous awake duration in seconds (Tauri command)
+///
+/// ## Returns
+/// The stored cap, or error string if `secs` is zero
+#[tauri::command]
+pub fn set_max_session_secs(
+    state: State<AppStateManager>,
+    secs: Option<u64>,
+) -> Result<Option<u64>, String> {
+    set_max_session_secs_impl(&state, secs)
+}
+
+/// Get the maximum continuous awake duration in seconds
+#[tauri::command]
+pub fn get_max_session_secs(state: State<AppStateManager>) -> Result<Option<u64>, String> {
+    let secs = state
+        .wake
+        .max_session_secs
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_max_session_secs: {}", e))?;
+    Ok(*secs)
+}
+
+/// Internal business logic for setting the global wake interval
+///
+/// ## Design Intent
+/// Persists the interval, then syncs the service manager, which restarts a
+/// running loop so the new interval applies without restarting the app.
+///
+/// ## Arguments
+/// * `state` - Shared application state
+/// * `secs` - Interval in seconds, None for the built-in default
+///
+/// ## Returns
+/// The stored interval, or error string (including below the minimum)
+pub fn set_wake_interval_impl(
+    state: &AppStateManager,
+    secs: Option<u64>,
+) -> Result<Option<u64>, String> {
+    if let Some(secs) = secs {
+        validate_wake_interval(secs)?;
+    }
+    log::info!("Set wake interval: {:?}", secs);
+
+    update_state(|s| s.wake_interval_secs = secs)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+    state.service.request_sync();
+    Ok(secs)
+}
+
+/// Set the global wake interval in seconds (Tauri command)
+///
+/// ## Returns
+/// The stored interval, or error string
+#[tauri::command]
+pub fn set_wake_interval(
+    state: State<AppStateManager>,
+    secs: Option<u64>,
+) -> Result<Option<u64>, String> {
+    set_wake_interval_impl(&state, secs)
+}
+
+/// Get the configured global wake interval in seconds
+#[tauri::command]
+pub fn get_wake_interval() -> Option<u64> {
+    read_state().wake_interval_secs
+}
+
+/// Internal business logic for boosting the wake interval
+///
+/// ## Design Intent
+/// A temporary override for critical tasks, neither persisted nor shown
+/// as the configured interval (see `ServiceManager::boost`). It does not
+/// turn wake on; a boost set while off applies if wake is turned on
+/// before it ends.
+///
+/// ## Arguments
+/// * `state` - Shared application state
+/// * `duration_secs` - How long the boost lasts
+/// * `interval_secs` - Wake interval during the boost
+///
+/// ## Returns
+/// Ok(()), or error string for a zero duration or an interval below the
+/// minimum
+pub fn boost_impl(
+    state: &AppStateManager,
+    duration_secs: u64,
+    interval_secs: u64,
+) -> Result<(), String> {
+    validate_wake_interval(interval_secs)?;
+    if duration_secs == 0 {
+        return Err("Boost duration must be at least 1 second".to_string());
+    }
+    state.service.boost(
+        Duration::from_secs(interval_secs),
+        Duration::from_secs(duration_secs),
+    );
+    Ok(())
+}
+
+/// Shorten the wake interval for a while (Tauri command)
+///
+/// ## Returns
+/// Ok(()), or error string
+#[tauri::command]
+pub fn boost(
+    state: State<AppStateManager>,
+    duration_secs: u64,
+    interval_secs: u64,
+) -> Result<(), String> {
+    boost_impl(&state, duration_secs, interval_secs)
+}
+
+/// Internal business logic for turning wake interval jitter on or off
+///
+/// ## Design Intent
+/// Like the interval itself, persisted and then applied by syncing the
+/// service manager, which restarts a running loop with or without jitter.
+///
+/// ## Returns
+/// The stored setting, or error string
+pub fn set_interval_jitter_impl(state: &AppStateManager, enabled: bool) -> Result<bool, String> {
+    log::info!("Set interval jitter: {}", enabled);
+
+    update_state(|s| s.interval_jitter = enabled)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+    state.service.request_sync();
+    Ok(enabled)
+}
+
+/// Turn wake interval jitter on or off (Tauri command)
+///
+/// ## Returns
+/// The stored setting, or error string
+#[tauri::command]
+pub fn set_interval_jitter(state: State<AppStateManager>, enabled: bool) -> Result<bool, String> {
+    set_interval_jitter_impl(&state, enabled)
+}
+
+/// Get whether the wake interval is jittered
+#[tauri::command]
+pub fn get_interval_jitter() -> bool {
+    read_state().interval_jitter
+}
+
+/// Saved profiles, with "Default" for a state file from before profiles
+fn read_profiles() -> HashMap<String, ProfileSettings> {
+    let mut config = read_state();
+    ensure_default_profile(&mut config);
+    config.profiles
+}
+
+/// Internal business logic for switching to a profile
+///
+/// ## Design Intent
+/// Applies the profile through the same logic as the individual settings,
+/// so persistence and the service restart work exactly as if each had
+/// been changed from the menu. The wake key is applied first and the
+/// interval persisted before the screen mode change syncs the service, so
+/// a restarted loop starts with all three. A screen mode the platform
+/// cannot honor is coerced, as at startup.
+///
+/// ## Arguments
+/// * `state` - Shared application state
+/// * `name` - Profile to apply
+///
+/// ## Returns
+/// The applied settings, or error string for an unknown or invalid profile
+pub fn select_profile_impl(state: &AppStateManager, name: &str) -> Result<ProfileSettings, String> {
+    let mut settings = read_profiles()
+        .get(name)
+        .copied()
+        .ok_or_else(|| format!("No profile named \"{}\"", name))?;
+    settings.screen_mode = settings.screen_mode.coerce_to(&ScreenMode::supported());
+    if let Some(secs) = settings.wake_interval_secs {
+        validate_wake_interval(secs)?;
+    }
+    log::info!("Switch to profile \"{}\": {:?}", name, settings);
+
+    set_wake_key_impl(state, settings.wake_key)?;
+    update_state(|s| {
+        ensure_default_profile(s);
+        s.wake_interval_secs = settings.wake_interval_secs;
+        s.active_profile = Some(name.to_string());
+    })
+    .map_err(|e| format!("Failed to persist state: {}", e))?;
+    change_screen_mode_impl(state, settings.screen_mode)?;
+    // Same mode but a new interval still needs a restart
+    state.service.request_sync();
+
+    *state
+        .tray
+        .active_profile
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during select_profile: {}", e))? =
+        Some(name.to_string());
+    Ok(settings)
+}
+
+/// Switch to a saved profile (Tauri command)
+///
+/// ## Side Effects
+/// Refreshes the tray (and the status window)
+///
+/// ## Returns
+/// The applied settings, or error string
+#[tauri::command]
+pub fn select_profile(
+    app: AppHandle,
+    state: State<AppStateManager>,
+    name: String,
+) -> Result<ProfileSettings, String> {
+    let settings = select_profile_impl(&state, &name)?;
+    tray::refresh(&app);
+    Ok(settings)
+}
+
+/// Internal business logic for saving the current settings as a profile
+///
+/// ## Design Intent
+/// Profiles are created from whatever is currently set, so the menu stays
+/// the one place settings are edited. Saving under an existing name
+/// replaces it, and the saved profile becomes the active one.
+///
+/// ## Returns
+/// The saved name (trimmed) and settings, or error string
+pub fn save_profile_impl(
+    state: &AppStateManager,
+    name: &str,
+) -> Result<(String, ProfileSettings), String> {
+    let name = validate_profile_name(name)?;
+    let settings = ProfileSettings {
+        screen_mode: *lock_screen_mode(&state.wake.screen_mode, "save_profile"),
+        wake_interval_secs: read_state().wake_interval_secs,
+        wake_key: *state
+            .wake
+            .wake_key
+            .lock()
+            .map_err(|e| format!("Mutex poisoned during save_profile: {}", e))?,
+    };
+    log::info!("Save profile \"{}\": {:?}", name, settings);
+
+    update_state(|s| {
+        ensure_default_profile(s);
+        s.profiles.insert(name.clone(), settings);
+        s.active_profile = Some(name.clone());
+    })
+    .map_err(|e| format!("Failed to persist state: {}", e))?;
+    *state
+        .tray
+        .active_profile
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during save_profile: {}", e))? = Some(name.clone());
+    Ok((name, settings))
+}
+
+/// Save the current settings as a profile (Tauri command)
+///
+/// ## Side Effects
+/// Adds the profile to the tray submenu
+///
+/// ## Returns
+/// The saved settings, or error string
+#[tauri::command]
+pub fn save_profile(
+    app: AppHandle,
+    state: State<AppStateManager>,
+    name: String,
+) -> Result<ProfileSettings, String> {
+    let (name, settings) = save_profile_impl(&state, &name)?;
+    tray::add_profile_item(&app, &name);
+    tray::refresh(&app);
+    Ok(settings)
+}
+
+/// Get every saved profile by name
+#[tauri::command]
+pub fn get_profiles() -> HashMap<String, ProfileSettings> {
+    read_profiles()
+}
+
+/// Get the profile last selected or saved
+#[tauri::command]
+pub fn get_active_profile(state: State<AppStateManager>) -> Result<Option<String>, String> {
+    let active = state
+        .tray
+        .active_profile
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during get_active_profile: {}", e))?;
+    Ok(active.clone())
+}
+
+/// Set whether remote sessions keep the system awake (Tauri command)
+///
+/// ## Design Intent
+/// For machines worked on over SSH or Remote Desktop: the remote session
+/// watcher keeps wake on while anyone is connected interactively.
+///
+/// ## Platform Behavior
+/// - Windows: Remote Desktop sessions
+/// - Linux: Remote logind sessions (SSH)
+/// - Other: Stored but never matches
+///
+/// ## Returns
+/// The stored preference, or error string
+#[tauri::command]
+pub fn set_keep_awake_on_remote_session(
+    state: State<AppStateManager>,
+    enabled: bool,
+) -> Result<bool, String> {
+    log::info!("Set keep awake on remote session: {}", enabled);
+    if enabled && !platform::supports_session_enumeration() {
+        log::warn!("Remote session watch is not supported on this platform yet");
+    }
+    update_state(|s| s.keep_awake_on_remote_session = enabled)
+        .map_err(|e| format!("Failed to persist state: {}", e))?;
+
+    state
+        .automation
+        .keep_awake_on_remote_session
+        .store(enabled, Ordering::SeqCst);
+    Ok(enabled)
+}
+
+/// Get whether remote sessions keep the system awake
+#[tauri::command]
+pub fn get_keep_awake_on_remote_session(state: State<AppStateManager>) -> bool {
+    state
+        .automation
+        .keep_awake_on_remote_session
+        .load(Ordering::SeqCst)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::{set_test_config_dir, set_test_store, MemoryStateStore};
+    use crate::test_support::recording_backend;
+    use crate::wake_service::WakeBackend;
+
+    fn test_manager() -> AppStateManager {
+        test_manager_with(recording_backend().0)
+    }
+
+    fn test_manager_with(backend: WakeBackend) -> AppStateManager {
+        let is_awake = Arc::new(AtomicBool::new(false));
+        let state = AppState {
+            notifications_enabled: false,
+            ..AppState::default()
+        };
+        let wake = WakeSettings::from_state(&state, SleepRisk::Unknown);
+        let power = PowerSettings::from_state(&state);
+        AppStateManager {
+            service: Arc::new(ServiceManager::new(
+                backend,
+                is_awake.clone(),
+                wake.screen_mode.clone(),
+                power.paused.clone(),
+                wake.log_each_iteration.clone(),
+                wake.wake_key.clone(),
+                wake.wake_strategy.clone(),
+            )),
+            is_awake,
+            awake_since: Arc::new(Mutex::new(None)),
+            auto_disable_timer: Arc::new(Mutex::new(None)),
+            pause_timer: Arc::new(Mutex::new(None)),
+            scheduled_actions: Arc::new(ScheduledActions::new()),
+            toggle_commit: Arc::new(ToggleCommit::default()),
+            wake,
+            power,
+            automation: AutomationSettings::from_state(&state),
+            tray: TraySettings::from_state(&state),
+            control: ControlSettings::from_state(&state),
+        }
+    }
+
+    #[test]
+    fn test_app_state_manager_creation() {
+        let manager = test_manager();
+
+        assert!(!manager.is_awake.load(Ordering::SeqCst));
+        assert_eq!(
+            *manager.wake.screen_mode.lock().unwrap(),
+            ScreenMode::AllowScreenOff
+        );
+        assert_eq!(*manager.tray.instance_label.lock().unwrap(), None);
+        assert!(!manager.power.paused.is_paused());
+        assert!(manager.scheduled_actions.list().is_empty());
+        assert!(!manager.automation.muted.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_mute_with_duration_schedules_unmute() {
+        let manager = test_manager();
+
+        set_automation_muted_impl(&manager, true, Some(Duration::from_secs(600))).unwrap();
+        assert!(manager.automation.muted.load(Ordering::SeqCst));
+        let actions = manager.scheduled_actions.list();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].label, "Unmute automation");
+
+        // Unmuting cancels the pending auto-unmute
+        set_automation_muted_impl(&manager, false, None).unwrap();
+        assert!(!manager.automation.muted.load(Ordering::SeqCst));
+        assert!(manager.scheduled_actions.list().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_mute_expires_automatically() {
+        let manager = test_manager();
+
+        set_automation_muted_impl(&manager, true, Some(Duration::from_millis(20))).unwrap();
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!manager.automation.muted.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_mute_lifts_power_pause() {
+        let manager = test_manager();
+        manager.power.paused.set(true);
+
+        set_automation_muted_impl(&manager, true, None).unwrap();
+        assert!(!manager.power.paused.is_paused());
+    }
+
+    /// Wait until pending toggles are persisted and applied
+    async fn settle_toggles() {
+        tokio::time::sleep(TOGGLE_DEBOUNCE + Duration::from_millis(50)).await;
+    }
+
+    #[tokio::test]
+    async fn test_manual_toggle_persists_and_runs_service() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let (backend, log) = recording_backend();
+        let manager = test_manager_with(backend);
+        *manager.wake.screen_mode.lock().unwrap() = ScreenMode::KeepScreenOn;
+
+        let outcome = manual_toggle_impl(&manager, false).unwrap();
+        assert_eq!(
+            outcome,
+            ToggleOutcome::Toggled(true, ScreenMode::KeepScreenOn)
+        );
+        settle_toggles().await;
+        assert!(read_state().sleep_disabled);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(log.presses.load(Ordering::SeqCst), 1);
+        assert_eq!(
+            *log.display.lock().unwrap(),
+            vec!["set_display_mode(KeepScreenOn)"]
+        );
+
+        let outcome = manual_toggle_impl(&manager, false).unwrap();
+        assert_eq!(
+            outcome,
+            ToggleOutcome::Toggled(false, ScreenMode::KeepScreenOn)
+        );
+        settle_toggles().await;
+        assert!(!read_state().sleep_disabled);
+
+        // The loop notices the stop within one poll interval
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert_eq!(
+            log.display.lock().unwrap().last().map(String::as_str),
+            Some("restore_normal_mode")
+        );
+        set_test_store(None);
+    }
+
+    #[tokio::test]
+    async fn test_state_snapshot_reflects_managed_state() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+        update_state(|s| {
+            s.interval_per_mode = HashMap::from([(ScreenMode::KeepScreenOn, 45)]);
+            s.interval_jitter = true;
+        })
+        .unwrap();
+        *manager.wake.screen_mode.lock().unwrap() = ScreenMode::KeepScreenOn;
+        *manager.wake.wake_key.lock().unwrap() = WakeKey::F13;
+        *manager.wake.wake_strategy.lock().unwrap() = WakeStrategy::MouseJiggle;
+        *manager.tray.active_profile.lock().unwrap() = Some("Work".to_string());
+        manager.automation.muted.store(true, Ordering::SeqCst);
+        toggle_sleep_for_impl(&manager, Some(Duration::from_secs(600)), || {}).unwrap();
+
+        let snapshot = get_state_snapshot_impl(&manager).unwrap();
+        assert_eq!(
+            snapshot,
+            StateSnapshot {
+                is_awake: true,
+                screen_mode: ScreenMode::KeepScreenOn,
+                wake_key: WakeKey::F13,
+                wake_strategy: WakeStrategy::MouseJiggle,
+                wake_interval_secs: 45,
+                boost_interval_secs: None,
+                interval_jitter: true,
+                remaining_secs: snapshot.remaining_secs,
+                pause_remaining_secs: None,
+                power_paused: false,
+                automation_muted: true,
+                master_enabled: true,
+                active_profile: Some("Work".to_string()),
+                active_watch: None,
+            }
+        );
+        assert!(snapshot.remaining_secs.is_some_and(|secs| secs <= 600));
+
+        set_awake_impl(&manager, false).unwrap();
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rapid_toggles_coalesce() {
+        let store = Arc::new(MemoryStateStore::default());
+        set_test_store(Some(store.clone()));
+        let (backend, log) = recording_backend();
+        let manager = test_manager_with(backend);
+        let starts = || {
+            log.display
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|call| call.starts_with("set_display_mode"))
+                .count()
+        };
+
+        // Ten toggles within 100ms cancel out
+        for _ in 0..10 {
+            toggle_sleep_impl(&manager).unwrap();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(!manager.is_awake.load(Ordering::SeqCst));
+        settle_toggles().await;
+        assert_eq!(store.writes(), 1);
+        assert!(!read_state().sleep_disabled);
+        assert_eq!(starts(), 0);
+
+        // An odd burst ends awake, with a single wake task
+        for _ in 0..9 {
+            toggle_sleep_impl(&manager).unwrap();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(manager.is_awake.load(Ordering::SeqCst));
+        settle_toggles().await;
+        assert_eq!(store.writes(), 2);
+        assert!(read_state().sleep_disabled);
+        assert_eq!(starts(), 1);
+
+        set_awake_impl(&manager, false).unwrap();
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    #[tokio::test]
+    async fn test_manual_toggle_asks_before_overriding_watcher() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+        manager.is_awake.store(true, Ordering::SeqCst);
+        manager
+            .automation
+            .enabled_by_watcher
+            .store(true, Ordering::SeqCst);
+        manager
+            .automation
+            .confirm_override_watchers
+            .store(true, Ordering::SeqCst);
+        *manager.automation.active_watch.lock().unwrap() = Some("window \"Render\"".to_string());
+
+        let outcome = manual_toggle_impl(&manager, false).unwrap();
+        assert!(matches!(outcome, ToggleOutcome::NeedsConfirmation(_)));
+        assert!(manager.is_awake.load(Ordering::SeqCst));
+        assert!(manager.automation.enabled_by_watcher.load(Ordering::SeqCst));
+        let response = ToggleResponse::from_outcome(&manager, outcome);
+        assert!(response.is_awake);
+        assert!(response.needs_confirmation.is_some());
+
+        let outcome = manual_toggle_impl(&manager, true).unwrap();
+        assert!(matches!(outcome, ToggleOutcome::Toggled(false, _)));
+        let response = ToggleResponse::from_outcome(&manager, outcome);
+        assert!(!response.is_awake);
+        assert_eq!(response.needs_confirmation, None);
+        assert!(!manager.automation.enabled_by_watcher.load(Ordering::SeqCst));
+        assert!(!read_state().sleep_disabled);
+        set_test_store(None);
+    }
+
+    #[test]
+    fn test_manual_toggle_rejected_while_master_off() {
+        let manager = test_manager();
+        manager.automation.master_off.store(true, Ordering::SeqCst);
+
+        assert!(manual_toggle_impl(&manager, false).is_err());
+        assert!(!manager.is_awake.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_refuse_on_battery_guard() {
+        let manager = test_manager();
+        manager
+            .power
+            .refuse_on_battery
+            .store(true, Ordering::SeqCst);
+
+        assert!(check_refuse_on_battery(&manager, || PowerSource::Ac).is_ok());
+        let error = check_refuse_on_battery(&manager, || PowerSource::Battery).unwrap_err();
+        assert!(error.contains("battery"));
+        // Desktops without a battery report an unknown source
+        assert!(check_refuse_on_battery(&manager, || PowerSource::Unknown).is_ok());
+
+        manager
+            .power
+            .refuse_on_battery
+            .store(false, Ordering::SeqCst);
+        assert!(check_refuse_on_battery(&manager, || unreachable!()).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_screen_mode_change_persists_and_restarts_service() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let (backend, log) = recording_backend();
+        let manager = test_manager_with(backend);
+        set_awake_impl(&manager, true).unwrap();
+
+        let mode = change_screen_mode_impl(&manager, ScreenMode::KeepScreenOn).unwrap();
+        assert_eq!(mode, ScreenMode::KeepScreenOn);
+        assert_eq!(read_state().screen_mode, ScreenMode::KeepScreenOn);
+        assert!(read_state().sleep_disabled);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(log
+            .display
+            .lock()
+            .unwrap()
+            .contains(&"set_display_mode(KeepScreenOn)".to_string()));
+
+        manager.is_awake.store(false, Ordering::SeqCst);
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    #[tokio::test]
+    async fn test_toggle_and_screen_mode_survive_restart() {
+        // Startup reads the file itself, so this test stays on disk
+        let dir = tempfile::tempdir().unwrap();
+        set_test_config_dir(Some(dir.path().to_path_buf()));
+        let (backend, log) = recording_backend();
+        let manager = test_manager_with(backend);
+
+        assert_eq!(
+            toggle_sleep_impl(&manager),
+            Ok((true, ScreenMode::default()))
+        );
+        change_screen_mode_impl(&manager, ScreenMode::KeepScreenOn).unwrap();
+        settle_toggles().await;
+
+        // What the next launch loads, straight from the file on disk
+        let loaded = crate::persistence::read_startup_state(true).unwrap();
+        assert!(loaded.sleep_disabled);
+        assert_eq!(loaded.screen_mode, ScreenMode::KeepScreenOn);
+
+        // The session runs against the mock backend, never the OS
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(log
+            .display
+            .lock()
+            .unwrap()
+            .contains(&"set_display_mode(KeepScreenOn)".to_string()));
+
+        assert_eq!(
+            toggle_sleep_impl(&manager),
+            Ok((false, ScreenMode::KeepScreenOn))
+        );
+        settle_toggles().await;
+        let loaded = crate::persistence::read_startup_state(true).unwrap();
+        assert!(!loaded.sleep_disabled);
+        assert_eq!(loaded.screen_mode, ScreenMode::KeepScreenOn);
+
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert_eq!(
+            log.display.lock().unwrap().last().map(String::as_str),
+            Some("restore_normal_mode")
+        );
+        set_test_config_dir(None);
+    }
+
+    #[tokio::test]
+    async fn test_state_changes_reach_listener() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        // Other tests run in parallel and report here too
+        static CHANGES: Mutex<Vec<StateChanged>> = Mutex::new(Vec::new());
+        set_state_listener(Arc::new(|change| CHANGES.lock().unwrap().push(change)));
+        let manager = test_manager();
+
+        set_awake_impl(&manager, true).unwrap();
+        assert!(CHANGES.lock().unwrap().contains(&StateChanged {
+            is_awake: true,
+            screen_mode: ScreenMode::default(),
+        }));
+        change_screen_mode_impl(&manager, ScreenMode::KeepScreenOn).unwrap();
+        assert!(CHANGES.lock().unwrap().contains(&StateChanged {
+            is_awake: true,
+            screen_mode: ScreenMode::KeepScreenOn,
+        }));
+
+        manager.is_awake.store(false, Ordering::SeqCst);
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    #[tokio::test]
+    async fn test_poisoned_screen_mode_keeps_toggling() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+
+        let screen_mode = manager.wake.screen_mode.clone();
+        let panicked = std::thread::spawn(move || {
+            let _guard = screen_mode.lock().unwrap();
+            panic!("handler panicked while holding the screen mode");
+        })
+        .join();
+        assert!(panicked.is_err());
+        assert!(manager.wake.screen_mode.is_poisoned());
+
+        assert_eq!(
+            toggle_sleep_impl(&manager),
+            Ok((true, ScreenMode::AllowScreenOff))
+        );
+        assert!(!manager.wake.screen_mode.is_poisoned());
+        assert_eq!(
+            change_screen_mode_impl(&manager, ScreenMode::KeepScreenOn),
+            Ok(ScreenMode::KeepScreenOn)
+        );
+        assert_eq!(
+            toggle_sleep_impl(&manager),
+            Ok((false, ScreenMode::KeepScreenOn))
+        );
+
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    #[tokio::test]
+    async fn test_reload_state_applies_external_edit() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+
+        update_state(|s| {
+            s.sleep_disabled = true;
+            s.screen_mode = ScreenMode::KeepScreenOn;
+        })
+        .unwrap();
+        assert_eq!(
+            reload_state_impl(&manager),
+            Ok((true, ScreenMode::KeepScreenOn))
+        );
+        assert!(manager.is_awake.load(Ordering::SeqCst));
+        assert_eq!(
+            *manager.wake.screen_mode.lock().unwrap(),
+            ScreenMode::KeepScreenOn
+        );
+        assert!(manager.awake_since.lock().unwrap().is_some());
+
+        // Master off still wins over a saved wake preference
+        manager.automation.master_off.store(true, Ordering::SeqCst);
+        assert_eq!(
+            reload_state_impl(&manager),
+            Ok((false, ScreenMode::KeepScreenOn))
+        );
+        assert!(manager.awake_since.lock().unwrap().is_none());
+
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    /// Autostart backend recording its setting in memory
+    struct FakeAutostart {
+        enabled: std::cell::Cell<bool>,
+        /// Enabling returns an error
+        fail: bool,
+        /// Enabling reports success but changes nothing
+        blocked: bool,
+    }
+
+    impl Autostart for FakeAutostart {
+        fn is_enabled(&self) -> Result<bool, String> {
+            Ok(self.enabled.get())
+        }
+
+        fn registered_path(&self) -> Option<std::path::PathBuf> {
+            None
+        }
+
+        fn enable(&self) -> Result<(), String> {
+            if self.fail {
+                return Err("access denied".to_string());
+            }
+            self.enabled.set(!self.blocked);
+            Ok(())
+        }
+
+        fn disable(&self) -> Result<(), String> {
+            self.enabled.set(false);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_get_and_set_autostart() {
+        let autostart = FakeAutostart {
+            enabled: std::cell::Cell::new(false),
+            fail: false,
+            blocked: false,
+        };
+        assert_eq!(get_autostart_impl(&autostart), Ok(false));
+        assert_eq!(set_autostart_impl(&autostart, true), Ok(true));
+        assert_eq!(get_autostart_impl(&autostart), Ok(true));
+        assert_eq!(set_autostart_impl(&autostart, false), Ok(false));
+        assert_eq!(get_autostart_impl(&autostart), Ok(false));
+
+        let failing = FakeAutostart {
+            enabled: std::cell::Cell::new(false),
+            fail: true,
+            blocked: false,
+        };
+        let error = set_autostart_impl(&failing, true).unwrap_err();
+        assert!(error.contains("access denied"));
+        assert_eq!(get_autostart_impl(&failing), Ok(false));
+    }
+
+    #[test]
+    fn test_diagnostics_report_current_state() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+        manager.is_awake.store(true, Ordering::SeqCst);
+        *manager.wake.screen_mode.lock().unwrap() = ScreenMode::KeepScreenOn;
+        let autostart = FakeAutostart {
+            enabled: std::cell::Cell::new(true),
+            fail: false,
+            blocked: false,
+        };
+
+        let report = collect_diagnostics_impl(&manager, &autostart).unwrap();
+        assert!(report.contains("Awake: yes\n"));
+        assert!(report.contains("Screen mode: KeepScreenOn\n"));
+        assert!(report.contains("Input simulation: yes (F15 key press)\n"));
+        assert!(report.contains("Start at login: yes\n"));
+        set_test_store(None);
+    }
+
+    #[test]
+    fn test_set_autostart_detects_change_that_did_not_stick() {
+        let blocked = FakeAutostart {
+            enabled: std::cell::Cell::new(false),
+            fail: false,
+            blocked: true,
+        };
+        let error = set_autostart_impl(&blocked, true).unwrap_err();
+        assert!(error.contains("did not take effect"));
+        assert_eq!(get_autostart_impl(&blocked), Ok(false));
+    }
+
+    #[test]
+    fn test_flush_state_persists_memory() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+        update_state(|s| s.notifications_enabled = true).unwrap();
+
+        manager.is_awake.store(true, Ordering::SeqCst);
+        *manager.wake.screen_mode.lock().unwrap() = ScreenMode::KeepScreenOn;
+        assert_eq!(
+            flush_state_impl(&manager),
+            Ok((true, ScreenMode::KeepScreenOn))
+        );
+        let saved = read_state();
+        assert!(saved.sleep_disabled);
+        assert_eq!(saved.screen_mode, ScreenMode::KeepScreenOn);
+        assert!(saved.notifications_enabled);
+
+        set_test_store(None);
+    }
+
+    #[tokio::test]
+    async fn test_rapid_screen_mode_changes_keep_one_service() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let (backend, log) = recording_backend();
+        let manager = test_manager_with(backend);
+        set_awake_impl(&manager, true).unwrap();
+
+        let modes = [ScreenMode::KeepScreenOn, ScreenMode::AllowScreenOff];
+        for i in 0..20 {
+            change_screen_mode_impl(&manager, modes[i % 2]).unwrap();
+        }
+        // Queued restarts all read the latest mode
+        manager.service.sync().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // Every service restores the display before the next one sets it
+        let calls = log.display.lock().unwrap().clone();
+        let mut active = 0usize;
+        for call in &calls {
+            if call.starts_with("set_display_mode") {
+                active += 1;
+            } else {
+                active -= 1;
+            }
+            assert!(active <= 1, "two wake services overlapped: {:?}", calls);
+        }
+        assert_eq!(active, 1);
+        assert_eq!(
+            calls.last().map(String::as_str),
+            Some("set_display_mode(AllowScreenOff)")
+        );
+
+        manager.is_awake.store(false, Ordering::SeqCst);
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    #[tokio::test]
+    async fn test_master_off_stops_service_and_persists() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let (backend, log) = recording_backend();
+        let manager = test_manager_with(backend);
+        set_awake_impl(&manager, true).unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        set_master_enabled_impl(&manager, false).unwrap();
+        assert!(!manager.is_awake.load(Ordering::SeqCst));
+        let persisted = read_state();
+        assert!(persisted.master_off);
+        assert!(!persisted.sleep_disabled);
+
+        tokio::time::sleep(Duration::from_millis(400)).await;
+        assert_eq!(
+            log.display.lock().unwrap().last().map(String::as_str),
+            Some("restore_normal_mode")
+        );
+        set_test_store(None);
+    }
+
+    #[test]
+    fn test_set_watch_network_trims_and_clears() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+
+        let stored = set_watch_network_impl(&manager, Some(" vpn.corp:443 ".to_string()));
+        assert_eq!(stored, Ok(Some("vpn.corp:443".to_string())));
+        assert_eq!(read_state().watch_network.as_deref(), Some("vpn.corp:443"));
+        assert_eq!(
+            manager.automation.watch_network.lock().unwrap().as_deref(),
+            Some("vpn.corp:443")
+        );
+
+        assert_eq!(
+            set_watch_network_impl(&manager, Some(" ".to_string())),
+            Ok(None)
+        );
+        assert_eq!(read_state().watch_network, None);
+
+        set_test_store(None);
+    }
+
+    #[test]
+    fn test_set_watch_process_trims_and_clears() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+
+        let stored = set_watch_process_impl(&manager, Some("  blender ".to_string()));
+        assert_eq!(stored, Ok(Some("blender".to_string())));
+        assert_eq!(read_state().watch_process.as_deref(), Some("blender"));
+        assert_eq!(
+            manager.automation.watch_process.lock().unwrap().as_deref(),
+            Some("blender")
+        );
+
+        assert_eq!(
+            set_watch_process_impl(&manager, Some(" ".to_string())),
+            Ok(None)
+        );
+        assert_eq!(read_state().watch_process, None);
+
+        set_test_store(None);
+    }
+
+    #[test]
+    fn test_set_toggle_shortcut_validates_and_persists() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+
+        let changed = set_toggle_shortcut_impl(&manager, Some(" Ctrl+Shift+F12 ".to_string()));
+        assert_eq!(
+            changed,
+            Ok((
+                hotkey::DEFAULT_TOGGLE_SHORTCUT.to_string(),
+                "Ctrl+Shift+F12".to_string()
+            ))
+        );
+        assert_eq!(
+            read_state().toggle_shortcut.as_deref(),
+            Some("Ctrl+Shift+F12")
+        );
+
+        // An invalid shortcut leaves the stored one alone
+        assert!(set_toggle_shortcut_impl(&manager, Some("Ctrl+Nope".to_string())).is_err());
+        assert_eq!(
+            manager.control.toggle_shortcut.lock().unwrap().as_deref(),
+            Some("Ctrl+Shift+F12")
+        );
+
+        let (_, next) = set_toggle_shortcut_impl(&manager, None).unwrap();
+        assert_eq!(next, hotkey::DEFAULT_TOGGLE_SHORTCUT);
+        assert_eq!(read_state().toggle_shortcut, None);
+
+        set_test_store(None);
+    }
+
+    #[test]
+    fn test_notifications_default_off_and_persist() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+        assert!(!read_state().notifications_enabled);
+        assert!(!manager.tray.notifications_enabled.load(Ordering::SeqCst));
+
+        assert_eq!(set_notifications_enabled_impl(&manager, true), Ok(true));
+        assert!(read_state().notifications_enabled);
+        assert!(manager.tray.notifications_enabled.load(Ordering::SeqCst));
+
+        set_test_store(None);
+    }
+
+    #[test]
+    fn test_control_endpoint_switch_persists_and_resets() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+        assert!(!read_state().enable_ipc);
+
+        assert_eq!(set_enable_ipc_impl(&manager, true), Ok(true));
+        assert!(read_state().enable_ipc);
+        assert!(manager.control.enable_ipc.load(Ordering::SeqCst));
+
+        reset_state_impl(&manager).unwrap();
+        assert!(!read_state().enable_ipc);
+        assert!(!manager.control.enable_ipc.load(Ordering::SeqCst));
+
+        set_test_store(None);
+    }
+
+    #[test]
+    fn test_tooltip_template_persists_and_clears() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+
+        let stored = set_tooltip_template_impl(
+            &manager.tray.tooltip_template,
+            Some(" {label}: {state} ".to_string()),
+        );
+        assert_eq!(stored, Ok(Some("{label}: {state}".to_string())));
+        assert_eq!(
+            read_state().tooltip_template.as_deref(),
+            Some("{label}: {state}")
+        );
+
+        let cleared =
+            set_tooltip_template_impl(&manager.tray.tooltip_template, Some("  ".to_string()));
+        assert_eq!(cleared, Ok(None));
+        assert_eq!(*manager.tray.tooltip_template.lock().unwrap(), None);
+        assert_eq!(read_state().tooltip_template, None);
+        set_test_store(None);
+    }
+
+    #[test]
+    fn test_left_click_toggles_defaults_on_and_persists() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+        assert_eq!(read_state().left_click_toggles, None);
+        assert!(manager.tray.left_click_toggles.load(Ordering::SeqCst));
+
+        assert_eq!(set_left_click_toggles_impl(&manager, false), Ok(false));
+        assert!(!manager.tray.left_click_toggles.load(Ordering::SeqCst));
+        assert_eq!(read_state().left_click_toggles, Some(false));
+        set_test_store(None);
+    }
+
+    #[tokio::test]
+    async fn test_control_requests_set_wake_state() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+        let mode = ScreenMode::default();
+
+        assert_eq!(
+            control_request_impl(&manager, IpcRequest::Status),
+            Ok((false, mode))
+        );
+        assert_eq!(
+            control_request_impl(&manager, IpcRequest::Enable),
+            Ok((true, mode))
+        );
+        // Repeating a request leaves the state as it is
+        assert_eq!(
+            control_request_impl(&manager, IpcRequest::Enable),
+            Ok((true, mode))
+        );
+        settle_toggles().await;
+        assert!(read_state().sleep_disabled);
+        assert_eq!(
+            control_request_impl(&manager, IpcRequest::Disable),
+            Ok((false, mode))
+        );
+        settle_toggles().await;
+        assert!(!read_state().sleep_disabled);
+
+        set_master_enabled_impl(&manager, false).unwrap();
+        assert!(control_request_impl(&manager, IpcRequest::Enable).is_err());
+        set_test_store(None);
+    }
+
+    #[tokio::test]
+    async fn test_reset_state_restores_defaults() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+        set_awake_impl(&manager, true).unwrap();
+        set_master_enabled_impl(&manager, false).unwrap();
+        set_session_only_impl(&manager, true).unwrap();
+        set_left_click_toggles_impl(&manager, false).unwrap();
+        set_watch_process_impl(&manager, Some("blender".to_string())).unwrap();
+        set_toggle_shortcut_impl(&manager, Some("CmdOrCtrl+Alt+W".to_string())).unwrap();
+        update_state(|s| s.show_advanced_menu = true).unwrap();
+        set_busy_indicator_impl(&manager.tray.busy_label, true, Some("Live".to_string())).unwrap();
+
+        let previous = reset_state_impl(&manager).unwrap();
+        assert_eq!(previous, "CmdOrCtrl+Alt+W");
+
+        let mut defaults = AppState::default();
+        ensure_default_profile(&mut defaults);
+        assert_eq!(read_state(), defaults);
+        assert!(!manager.is_awake.load(Ordering::SeqCst));
+        assert!(!manager.automation.master_off.load(Ordering::SeqCst));
+        assert!(!manager.wake.session_only.load(Ordering::SeqCst));
+        assert!(manager.tray.left_click_toggles.load(Ordering::SeqCst));
+        assert_eq!(*manager.automation.watch_process.lock().unwrap(), None);
+        assert_eq!(*manager.control.toggle_shortcut.lock().unwrap(), None);
+        assert_eq!(*manager.tray.busy_label.lock().unwrap(), None);
+        assert_eq!(
+            manager.tray.active_profile.lock().unwrap().as_deref(),
+            Some(crate::core::profile::DEFAULT_PROFILE)
+        );
+        set_test_store(None);
+    }
+
+    #[test]
+    fn test_session_only_persists_without_touching_wake() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+
+        assert_eq!(set_session_only_impl(&manager, true), Ok(true));
+        assert!(read_state().session_only);
+        assert!(manager.wake.session_only.load(Ordering::SeqCst));
+        assert!(!manager.is_awake.load(Ordering::SeqCst));
+
+        set_test_store(None);
+    }
+
+    #[test]
+    fn test_set_wake_strategy_persists_and_shares() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+
+        assert_eq!(
+            set_wake_strategy_impl(&manager, WakeStrategy::MouseJiggle),
+            Ok(WakeStrategy::MouseJiggle)
+        );
+        assert_eq!(read_state().wake_strategy, WakeStrategy::MouseJiggle);
+        assert_eq!(
+            *manager.wake.wake_strategy.lock().unwrap(),
+            WakeStrategy::MouseJiggle
+        );
+
+        set_test_store(None);
+    }
+
+    #[test]
+    fn test_set_wake_key_persists_and_shares() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+
+        assert_eq!(set_wake_key_impl(&manager, WakeKey::F13), Ok(WakeKey::F13));
+        assert_eq!(read_state().wake_key, WakeKey::F13);
+        assert_eq!(*manager.wake.wake_key.lock().unwrap(), WakeKey::F13);
+        assert!(list_wake_keys().contains(&WakeKey::F15));
+
+        set_test_store(None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_set_wake_interval_validates_and_persists() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+
+        assert!(set_wake_interval_impl(&manager, Some(2)).is_err());
+        assert_eq!(read_state().wake_interval_secs, None);
+        assert_eq!(set_wake_interval_impl(&manager, Some(30)), Ok(Some(30)));
+        assert_eq!(read_state().wake_interval_secs, Some(30));
+        assert_eq!(set_wake_interval_impl(&manager, None), Ok(None));
+        assert_eq!(read_state().wake_interval_secs, None);
+
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_boost_validates_and_ends() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+
+        assert!(boost_impl(&manager, 60, 2).is_err());
+        assert!(boost_impl(&manager, 0, 10).is_err());
+        assert_eq!(manager.service.boost_interval(), None);
+
+        assert_eq!(boost_impl(&manager, 60, 10), Ok(()));
+        assert_eq!(
+            get_state_snapshot_impl(&manager)
+                .unwrap()
+                .boost_interval_secs,
+            Some(10)
+        );
+        tokio::time::sleep(Duration::from_secs(61)).await;
+        assert_eq!(manager.service.boost_interval(), None);
+        set_test_store(None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_profiles_save_and_select() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+
+        change_screen_mode_impl(&manager, ScreenMode::KeepScreenOn).unwrap();
+        set_wake_interval_impl(&manager, Some(30)).unwrap();
+        set_wake_key_impl(&manager, WakeKey::F13).unwrap();
+        let (name, work) = save_profile_impl(&manager, " Work ").unwrap();
+        assert_eq!(name, "Work");
+        assert_eq!(work.wake_interval_secs, Some(30));
+
+        set_wake_interval_impl(&manager, Some(120)).unwrap();
+        set_wake_key_impl(&manager, WakeKey::F14).unwrap();
+        save_profile_impl(&manager, "Home").unwrap();
+        let mut names: Vec<String> = read_state().profiles.into_keys().collect();
+        names.sort();
+        assert_eq!(names, vec!["Default", "Home", "Work"]);
+
+        assert_eq!(select_profile_impl(&manager, "Work"), Ok(work));
+        let config = read_state();
+        assert_eq!(config.wake_interval_secs, Some(30));
+        assert_eq!(config.wake_key, WakeKey::F13);
+        assert_eq!(config.screen_mode, ScreenMode::KeepScreenOn);
+        assert_eq!(config.active_profile.as_deref(), Some("Work"));
+        assert_eq!(*manager.wake.wake_key.lock().unwrap(), WakeKey::F13);
+        assert_eq!(
+            manager.tray.active_profile.lock().unwrap().as_deref(),
+            Some("Work")
+        );
+
+        assert!(select_profile_impl(&manager, "Gym").is_err());
+        assert!(save_profile_impl(&manager, "  ").is_err());
+
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_timed_wake_disables_after_duration() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+        let expired = Arc::new(AtomicBool::new(false));
+        let flag = expired.clone();
+
+        let (awake, _) =
+            toggle_sleep_for_impl(&manager, Some(Duration::from_secs(900)), move || {
+                flag.store(true, Ordering::SeqCst)
+            })
+            .unwrap();
+        assert!(awake);
+        assert_eq!(manager.scheduled_actions.list().len(), 1);
+        assert_eq!(
+            get_remaining_time_impl(&manager),
+            Ok(Some(Duration::from_secs(900)))
+        );
+        let disable_at = read_state().disable_at.unwrap();
+        assert!((disable_at - unix_now().unwrap() - 900).abs() <= 1);
+
+        tokio::time::sleep(Duration::from_secs(899)).await;
+        assert!(manager.is_awake.load(Ordering::SeqCst));
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(!manager.is_awake.load(Ordering::SeqCst));
+        assert!(expired.load(Ordering::SeqCst));
+        assert!(!read_state().sleep_disabled);
+        assert_eq!(read_state().disable_at, None);
+        assert_eq!(*manager.auto_disable_timer.lock().unwrap(), None);
+        assert_eq!(get_remaining_time_impl(&manager), Ok(None));
+
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_pause_resumes_after_duration() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+        let resumed = Arc::new(AtomicBool::new(false));
+        let flag = resumed.clone();
+
+        assert!(pause_for_impl(&manager, Duration::from_secs(600), || {}).is_err());
+        change_screen_mode_impl(&manager, ScreenMode::KeepScreenOn).unwrap();
+        set_awake_impl(&manager, true).unwrap();
+        assert_eq!(
+            pause_for_impl(&manager, Duration::from_secs(600), move || {
+                flag.store(true, Ordering::SeqCst)
+            }),
+            Ok((false, ScreenMode::KeepScreenOn))
+        );
+        assert!(!read_state().sleep_disabled);
+        let resume_at = read_state().resume_at.unwrap();
+        assert!((resume_at - unix_now().unwrap() - 600).abs() <= 1);
+        assert_eq!(
+            get_pause_remaining_impl(&manager),
+            Ok(Some(Duration::from_secs(600)))
+        );
+
+        tokio::time::sleep(Duration::from_secs(599)).await;
+        assert!(!manager.is_awake.load(Ordering::SeqCst));
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(manager.is_awake.load(Ordering::SeqCst));
+        assert!(resumed.load(Ordering::SeqCst));
+        assert_eq!(
+            *manager.wake.screen_mode.lock().unwrap(),
+            ScreenMode::KeepScreenOn
+        );
+        assert!(read_state().sleep_disabled);
+        assert_eq!(read_state().resume_at, None);
+        assert_eq!(get_pause_remaining_impl(&manager), Ok(None));
+
+        set_awake_impl(&manager, false).unwrap();
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_manual_toggle_cancels_pause() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+
+        set_awake_impl(&manager, true).unwrap();
+        pause_for_impl(&manager, Duration::from_secs(600), || {}).unwrap();
+        assert!(matches!(
+            manual_toggle_impl(&manager, false),
+            Ok(ToggleOutcome::Toggled(true, _))
+        ));
+        assert!(manager.scheduled_actions.list().is_empty());
+        assert_eq!(read_state().resume_at, None);
+
+        // Turned off again, it stays off past the old resume time
+        manual_toggle_impl(&manager, false).unwrap();
+        tokio::time::sleep(Duration::from_secs(700)).await;
+        assert!(!manager.is_awake.load(Ordering::SeqCst));
+
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    #[tokio::test]
+    async fn test_schedule_drives_wake_until_manual_override() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+        let schedule: Schedule =
+            serde_json::from_str(r#"{"windows":[{"days":127,"start":"09:00","end":"18:00"}]}"#)
+                .unwrap();
+        set_schedule_impl(&manager, schedule.clone()).unwrap();
+        assert_eq!(read_state().schedule, schedule);
+
+        let noon = local_now().date().and_hms_opt(12, 0, 0).unwrap();
+        assert_eq!(apply_schedule_impl(&manager, noon), Ok(Some(true)));
+        assert_eq!(apply_schedule_impl(&manager, noon), Ok(None));
+
+        // A manual disable holds until the next boundary
+        manual_toggle_impl(&manager, false).unwrap();
+        let until = manager
+            .automation
+            .schedule_override
+            .lock()
+            .unwrap()
+            .unwrap();
+        assert_eq!(until, schedule.next_boundary(local_now()).unwrap());
+        assert_eq!(apply_schedule_impl(&manager, noon), Ok(None));
+        assert!(!manager.is_awake.load(Ordering::SeqCst));
+
+        // From the boundary on the schedule drives wake again
+        let expected = schedule.is_active(until);
+        let applied = apply_schedule_impl(&manager, until).unwrap();
+        assert_eq!(applied, expected.then_some(true));
+        assert_eq!(*manager.automation.schedule_override.lock().unwrap(), None);
+        assert_eq!(manager.is_awake.load(Ordering::SeqCst), expected);
+
+        // Master off keeps the schedule out
+        set_master_enabled_impl(&manager, false).unwrap();
+        assert_eq!(apply_schedule_impl(&manager, noon), Ok(None));
+        assert!(!manager.is_awake.load(Ordering::SeqCst));
+
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_timed_wake_replaced_or_cancelled() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+
+        // A new choice replaces the pending auto-disable
+        toggle_sleep_for_impl(&manager, Some(Duration::from_secs(900)), || {}).unwrap();
+        toggle_sleep_for_impl(&manager, None, || {}).unwrap();
+        assert!(manager.scheduled_actions.list().is_empty());
+        assert_eq!(read_state().disable_at, None);
+        tokio::time::sleep(Duration::from_secs(1000)).await;
+        assert!(manager.is_awake.load(Ordering::SeqCst));
+
+        // A manual disable cancels it
+        toggle_sleep_for_impl(&manager, Some(Duration::from_secs(900)), || {}).unwrap();
+        set_awake_impl(&manager, false).unwrap();
+        assert!(manager.scheduled_actions.list().is_empty());
+        assert_eq!(read_state().disable_at, None);
+        set_awake_impl(&manager, true).unwrap();
+        tokio::time::sleep(Duration::from_secs(1000)).await;
+        assert!(manager.is_awake.load(Ordering::SeqCst));
+
+        assert!(toggle_sleep_for_impl(&manager, Some(Duration::ZERO), || {}).is_err());
+        assert!(
+            toggle_sleep_for_impl(&manager, Some(Duration::from_secs(25 * 3600)), || {}).is_err()
+        );
+        set_awake_impl(&manager, false).unwrap();
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    #[tokio::test]
+    async fn test_battery_floor_disables_and_stays_off() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+        *manager.power.battery_floor_percent.lock().unwrap() = Some(20);
+        set_awake_impl(&manager, true).unwrap();
+
+        // Desktops and charging laptops are left alone
+        assert!(!enforce_battery_floor_impl(&manager, PowerSource::Unknown, None).unwrap());
+        assert!(!enforce_battery_floor_impl(&manager, PowerSource::Ac, Some(10)).unwrap());
+        assert!(!enforce_battery_floor_impl(&manager, PowerSource::Battery, Some(25)).unwrap());
+        assert!(manager.is_awake.load(Ordering::SeqCst));
+
+        assert!(enforce_battery_floor_impl(&manager, PowerSource::Battery, Some(19)).unwrap());
+        assert!(!manager.is_awake.load(Ordering::SeqCst));
+        assert!(!read_state().sleep_disabled);
+
+        // Plugging back in does not re-enable
+        assert!(!enforce_battery_floor_impl(&manager, PowerSource::Ac, Some(19)).unwrap());
+        assert!(!manager.is_awake.load(Ordering::SeqCst));
+
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_awake_cap_disables_after_duration() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+        *manager.wake.max_session_secs.lock().unwrap() = Some(2 * 3600);
+        set_awake_impl(&manager, true).unwrap();
+
+        tokio::time::advance(Duration::from_secs(3600)).await;
+        assert!(!enforce_awake_cap_impl(&manager).unwrap());
+        assert!(manager.is_awake.load(Ordering::SeqCst));
+
+        tokio::time::advance(Duration::from_secs(3600)).await;
+        assert!(enforce_awake_cap_impl(&manager).unwrap());
+        assert!(!manager.is_awake.load(Ordering::SeqCst));
+        assert!(!read_state().sleep_disabled);
+
+        // Stays off: nothing re-enables it on the next check
+        tokio::time::advance(Duration::from_secs(3600)).await;
+        assert!(!enforce_awake_cap_impl(&manager).unwrap());
+        assert!(!manager.is_awake.load(Ordering::SeqCst));
+
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_session_cap_in_seconds_persists_and_fires() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+        assert!(set_max_session_secs_impl(&manager, Some(0)).is_err());
+        assert_eq!(
+            set_max_session_secs_impl(&manager, Some(900)),
+            Ok(Some(900))
+        );
+        assert_eq!(read_state().max_session_secs, Some(900));
+
+        // Well under an hour
+        set_awake_impl(&manager, true).unwrap();
+        tokio::time::advance(Duration::from_secs(899)).await;
+        assert!(!enforce_awake_cap_impl(&manager).unwrap());
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert!(enforce_awake_cap_impl(&manager).unwrap());
+        assert!(!manager.is_awake.load(Ordering::SeqCst));
+
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_awake_cap_fires_before_longer_timer() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+        *manager.wake.max_session_secs.lock().unwrap() = Some(2 * 3600);
+        let expired = Arc::new(AtomicBool::new(false));
+        let flag = expired.clone();
+
+        toggle_sleep_for_impl(&manager, Some(Duration::from_secs(3 * 3600)), move || {
+            flag.store(true, Ordering::SeqCst)
+        })
+        .unwrap();
+        // The shorter of the timer and the cap wins the countdown
+        assert_eq!(
+            get_remaining_time_impl(&manager),
+            Ok(Some(Duration::from_secs(2 * 3600)))
+        );
+
+        tokio::time::advance(Duration::from_secs(2 * 3600)).await;
+        assert!(enforce_awake_cap_impl(&manager).unwrap());
+        assert!(!manager.is_awake.load(Ordering::SeqCst));
+        assert_eq!(*manager.auto_disable_timer.lock().unwrap(), None);
+        assert_eq!(get_remaining_time_impl(&manager), Ok(None));
+
+        // The user's timer was cancelled, not left to fire later
+        tokio::time::advance(Duration::from_secs(3600)).await;
+        assert!(!expired.load(Ordering::SeqCst));
+
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_awake_cap_clock_resets_on_disable() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+        *manager.wake.max_session_secs.lock().unwrap() = Some(2 * 3600);
+
+        set_awake_impl(&manager, true).unwrap();
+        tokio::time::advance(Duration::from_secs(5400)).await;
+        set_awake_impl(&manager, false).unwrap();
+        set_awake_impl(&manager, true).unwrap();
+
+        tokio::time::advance(Duration::from_secs(5400)).await;
+        assert!(!enforce_awake_cap_impl(&manager).unwrap());
+        assert!(manager.is_awake.load(Ordering::SeqCst));
+
+        tokio::time::advance(Duration::from_secs(1800)).await;
+        assert!(enforce_awake_cap_impl(&manager).unwrap());
+
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_awake_elapsed_counts_from_last_enable() {
+        set_test_store(Some(Arc::new(MemoryStateStore::default())));
+        let manager = test_manager();
+        assert_eq!(get_awake_elapsed_impl(&manager), Ok(None));
+
+        set_awake_impl(&manager, true).unwrap();
+        tokio::time::advance(Duration::from_secs(83 * 60)).await;
+        assert_eq!(
+            get_awake_elapsed_impl(&manager),
+            Ok(Some(Duration::from_secs(83 * 60)))
+        );
+
+        set_awake_impl(&manager, false).unwrap();
+        assert_eq!(get_awake_elapsed_impl(&manager), Ok(None));
+
+        manager.service.sync().await.unwrap();
+        set_test_store(None);
+    }
+
+    #[test]
+    fn test_platform_capabilities_match_supported_modes() {
+        let capabilities = get_platform_capabilities();
+        assert_eq!(
+            capabilities.allow_screen_off_supported,
+            get_capabilities()
+                .screen_modes
+                .contains(&ScreenMode::AllowScreenOff)
+        );
+        assert_eq!(
+            capabilities.away_mode_supported,
+            get_capabilities()
+                .screen_modes
+                .contains(&ScreenMode::AwayMode)
+        );
+        assert_eq!(capabilities.os, std::env::consts::OS);
+    }
+
+    #[test]
+    fn test_bootstrap_matches_individual_queries() {
+        let manager = test_manager();
+        manager.is_awake.store(true, Ordering::SeqCst);
+        *manager.tray.instance_label.lock().unwrap() = Some("Laptop".to_string());
+        manager.power.only_on_ac.store(true, Ordering::SeqCst);
+        let check = StartupCheck::from_results(Vec::new());
+
+        let bootstrap = bootstrap_impl(&manager, &check).unwrap();
+
+        assert_eq!(bootstrap.build, get_build_info());
+        assert_eq!(bootstrap.capabilities, get_capabilities());
+        assert!(bootstrap.is_awake);
+        assert_eq!(
+            bootstrap.screen_mode,
+            *manager.wake.screen_mode.lock().unwrap()
+        );
+        assert!(!bootstrap.power_paused);
+        assert_eq!(bootstrap.config.instance_label.as_deref(), Some("Laptop"));
+        assert!(bootstrap.config.only_on_ac);
+        assert_eq!(bootstrap.config.watch_window_title, None);
+        assert_eq!(bootstrap.config.watch_device, None);
+        assert!(!bootstrap.config.automation_muted);
+        assert!(bootstrap.config.master_enabled);
+        assert_eq!(bootstrap.startup_check, check);
+    }
+
+    #[test]
+    fn test_behavior_matrix_covers_every_supported_pair() {
+        let matrix = behavior_matrix();
+        let modes = ScreenMode::supported();
+        let methods = platform::wake_methods();
+        assert_eq!(matrix.len(), modes.len() * methods.len());
+
+        for mode in &modes {
+            for method in &methods {
+                let rows: Vec<_> = matrix
+                    .iter()
+                    .filter(|row| row.screen_mode == *mode && row.wake_method == *method)
+                    .collect();
+                assert_eq!(rows.len(), 1, "{:?}/{:?}", mode, method);
+            }
+        }
+
+        for row in &matrix {
+            assert_eq!(row.platform, std::env::consts::OS);
+            assert!(row.keeps_system_awake);
+            assert!(!row.summary.is_empty());
+            // Every method of one mode agrees on what the mode does
+            assert!(matrix
+                .iter()
+                .filter(|other| other.screen_mode == row.screen_mode)
+                .all(|other| other.keeps_display_on == row.keeps_display_on));
+        }
+    }
+
+    #[test]
+    fn test_busy_indicator_set_and_clear() {
+        let busy = Arc::new(Mutex::new(None));
+
+        let label = set_busy_indicator_impl(&busy, true, Some(" Recording ".to_string()));
+        assert_eq!(label.unwrap().as_deref(), Some("Recording"));
+
+        let label = set_busy_indicator_impl(&busy, true, None);
+        assert_eq!(label.unwrap().as_deref(), Some("Busy"));
+
+        set_busy_indicator_impl(&busy, false, Some("ignored".to_string())).unwrap();
+        assert_eq!(*busy.lock().unwrap(), None);
+    }
+
+    #[test]
+    fn test_guard_requires_confirmation_for_active_watcher() {
+        let manager = test_manager();
+        manager.is_awake.store(true, Ordering::SeqCst);
+        *manager.automation.active_watch.lock().unwrap() = Some("window \"Backup\"".to_string());
+
+        assert!(guard_manual_disable(&manager, false).is_ok());
+
+        manager
+            .automation
+            .confirm_override_watchers
+            .store(true, Ordering::SeqCst);
+        assert!(guard_manual_disable(&manager, false).is_err());
+        assert!(guard_manual_disable(&manager, true).is_ok());
+    }
+
+    #[test]
+    fn test_guard_ignores_enabling() {
+        let manager = test_manager();
+        *manager.automation.active_watch.lock().unwrap() = Some("window \"Backup\"".to_string());
+        manager
+            .automation
+            .confirm_override_watchers
+            .store(true, Ordering::SeqCst);
+
+        assert!(guard_manual_disable(&manager, false).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_condition_end_action_runs_after_delay() {
+        let actions = Arc::new(ScheduledActions::new());
+        let performed = Arc::new(Mutex::new(None));
+        let recorder = performed.clone();
+
+        let plan = ConditionEndPlan::Delayed {
+            action: ConditionEndAction::Sleep,
+            delay: Duration::from_millis(20),
+            notice: "Sleeping in 0s".to_string(),
+        };
+        let id = schedule_condition_end(&actions, plan, move |action| {
+            *recorder.lock().unwrap() = Some(action);
+            Ok(())
+        });
+        assert!(id.is_some());
+        assert_eq!(actions.list()[0].label, "Sleeping in 0s");
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(*performed.lock().unwrap(), Some(ConditionEndAction::Sleep));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_condition_end_action_does_not_run() {
+        let actions = Arc::new(ScheduledActions::new());
+        let performed = Arc::new(AtomicBool::new(false));
+        let recorder = performed.clone();
+
+        let plan = ConditionEndPlan::Delayed {
+            action: ConditionEndAction::Shutdown,
+            delay: Duration::from_millis(50),
+            notice: "Shutting down in 0s".to_string(),
+        };
+        let id = schedule_condition_end(&actions, plan, move |_| {
+            recorder.store(true, Ordering::SeqCst);
+            Ok(())
+        })
+        .unwrap();
+        actions.cancel(&id).unwrap();
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!performed.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_disable_only_schedules_nothing() {
+        let actions = Arc::new(ScheduledActions::new());
+        let id = schedule_condition_end(&actions, ConditionEndPlan::DisableOnly, |_| Ok(()));
+        assert!(id.is_none());
+        assert!(actions.list().is_empty());
+    }
+
+    #[test]
+    fn test_awake_reasons_report_all_active_sources() {
+        let manager = test_manager();
+        assert!(get_awake_reasons_impl(&manager).unwrap().is_empty());
+
+        manager.is_awake.store(true, Ordering::SeqCst);
+        *manager.automation.active_watch.lock().unwrap() = Some("window \"Backup\"".to_string());
+        assert_eq!(
+            get_awake_reasons_impl(&manager).unwrap(),
+            vec![
+                AwakeReason::Manual,
+                AwakeReason::Watcher("window \"Backup\"".to_string())
+            ]
+        );
+
+        manager
+            .automation
+            .enabled_by_watcher
+            .store(true, Ordering::SeqCst);
+        assert_eq!(
+            get_awake_reasons_impl(&manager).unwrap(),
+            vec![AwakeReason::Watcher("window \"Backup\"".to_string())]
         );
     }
 }
@@ -0,0 +1,899 @@
+//! Automation precedence
+//!
+//! Pure resolution of what wake should do given the manual state and every
+//! automation source.
+//!
+//! ## Design Intent
+//! Automation sources (power monitor, watchers) never write wake state on
+//! their own authority. They describe what they want and this function
+//! decides, so precedence rules live in one tested place.
+//!
+//! ## Precedence
+//! 1. Master off: wake is off, nothing can turn it on
+//! 2. Muted automation: manual state wins outright, nothing is paused
+//! 3. A watcher request (edge) sets the wake state
+//! 4. Otherwise the current wake state is kept
+//!
+//! Power pausing is orthogonal to the wake state: it suspends a running
+//! session without changing whether the user wants wake on.
+//!
+//! ## Reasons
+//! The resolution also reports which sources keep wake on, so the user can
+//! see why the machine is awake. A paused session reports no reasons.
+//!
+//! ## Conditions
+//! Keep-awake triggers (manual toggle, master switch, window title, device,
+//! remote session) are `Condition` trait objects owned by one `Supervisor`.
+//! Each evaluates to KeepAwake, Neutral or ForceSleep; the supervisor
+//! debounces each one and `combine` merges them with a fixed precedence
+//! (ForceSleep > KeepAwake > Neutral), so two watchers can never race each
+//! other into `resolve_desired_state`. The supervisor itself does no I/O;
+//! sampling is up to each condition and applying the verdict is up to
+//! `watcher`. New triggers only need a `Condition` implementation.
+//!
+//! ## Watchers
+//! Watchers that look for a configured pattern (window title, device,
+//! process, network) are a `PatternCondition` over a `PatternProbe` that
+//! does the platform sampling. Every watcher is debounced with
+//! `DEBOUNCE_SAMPLES`, so a source that briefly disappears (a window
+//! recreated, a dock re-enumerating, a VPN reconnecting) doesn't flap wake.
+
+use crate::core::Debouncer;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+
+/// Consecutive agreeing polls a watcher needs before its edge counts
+pub const DEBOUNCE_SAMPLES: u32 = 2;
+
+/// A source currently keeping the machine awake
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub enum AwakeReason {
+    /// The user turned wake on
+    Manual,
+    /// A watched condition holds (e.g. `window "Render"`)
+    Watcher(String),
+}
+
+impl AwakeReason {
+    /// Short description for the tray tooltip
+    pub fn describe(&self) -> String {
+        match self {
+            AwakeReason::Manual => "Enabled manually".to_string(),
+            AwakeReason::Watcher(condition) => format!("Watching {}", condition),
+        }
+    }
+}
+
+/// Everything `resolve_desired_state` needs to decide
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AutomationInputs<'a> {
+    /// The master switch is off; the whole app is paused
+    pub master_off: bool,
+    /// Current wake state (as last set manually or by automation)
+    pub current_awake: bool,
+    /// All automation is muted; only manual control is effective
+    pub muted: bool,
+    /// Power policy asks to pause (e.g. on battery with only-on-AC)
+    pub power_pause: bool,
+    /// A watcher edge asks for wake on (`Some(true)`) or off (`Some(false)`)
+    pub watcher_request: Option<bool>,
+    /// The current wake session was turned on by a watcher, not the user
+    pub enabled_by_watcher: bool,
+    /// Watched condition that currently holds, if any
+    pub active_watch: Option<&'a str>,
+}
+
+/// Resolved wake behavior
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DesiredState {
+    /// Whether sleep prevention should be enabled
+    pub awake: bool,
+    /// Whether an enabled session should be temporarily paused
+    pub paused: bool,
+    /// Every source keeping wake on, most significant first
+    pub reasons: Vec<AwakeReason>,
+}
+
+/// Resolve the desired wake state from manual and automation inputs
+pub fn resolve_desired_state(inputs: AutomationInputs) -> DesiredState {
+    if inputs.master_off {
+        return DesiredState {
+            awake: false,
+            paused: false,
+            reasons: Vec::new(),
+        };
+    }
+
+    if inputs.muted {
+        let reasons = if inputs.current_awake {
+            vec![AwakeReason::Manual]
+        } else {
+            Vec::new()
+        };
+        return DesiredState {
+            awake: inputs.current_awake,
+            paused: false,
+            reasons,
+        };
+    }
+
+    let awake = inputs.watcher_request.unwrap_or(inputs.current_awake);
+    let paused = inputs.power_pause;
+    let mut reasons = Vec::new();
+    if awake && !paused {
+        let watcher_enabling = inputs.watcher_request == Some(true) && !inputs.current_awake;
+        if !inputs.enabled_by_watcher && !watcher_enabling {
+            reasons.push(AwakeReason::Manual);
+        }
+        if let Some(condition) = inputs.active_watch {
+            reasons.push(AwakeReason::Watcher(condition.to_string()));
+        }
+    }
+
+    DesiredState {
+        awake,
+        paused,
+        reasons,
+    }
+}
+
+/// Turn a debounced watcher edge into a wake request
+///
+/// ## Design Intent
+/// A watcher asks for wake when its condition starts holding and wake is
+/// off, and only asks for it to end if the watcher itself turned it on, so
+/// a manually enabled session survives the condition ending.
+///
+/// ## Arguments
+/// * `matched` - The condition now holds (e.g. device connected)
+/// * `awake` - Current wake state
+/// * `enabled_by_watcher` - The current session was started by a watcher
+///
+/// ## Returns
+/// The `watcher_request` for `AutomationInputs`
+pub fn watcher_request(matched: bool, awake: bool, enabled_by_watcher: bool) -> Option<bool> {
+    if matched && !awake {
+        Some(true)
+    } else if !matched && enabled_by_watcher {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+/// What a condition asks of wake right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConditionResult {
+    /// Keep the machine awake
+    KeepAwake,
+    /// No opinion
+    Neutral,
+    /// Let the machine sleep, whatever else wants it awake
+    ForceSleep,
+}
+
+impl ConditionResult {
+    /// KeepAwake while a watched source holds, otherwise Neutral
+    pub fn keep_awake_if(holds: bool) -> Self {
+        if holds {
+            ConditionResult::KeepAwake
+        } else {
+            ConditionResult::Neutral
+        }
+    }
+}
+
+/// Shared state every condition is evaluated against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct EvalContext {
+    /// The user turned wake on (not a watcher)
+    pub manual_awake: bool,
+    /// The master switch is off
+    pub master_off: bool,
+    /// All automation is muted
+    pub muted: bool,
+}
+
+/// A keep-awake trigger evaluated by the `Supervisor`
+pub trait Condition: Send {
+    /// Short identifier (e.g. `window title`)
+    fn name(&self) -> &str;
+
+    /// What the condition asks for right now
+    fn evaluate(&self, ctx: &EvalContext) -> ConditionResult;
+
+    /// Reason reported while the condition keeps wake on
+    fn reason(&self) -> AwakeReason {
+        AwakeReason::Watcher(self.name().to_string())
+    }
+
+    /// Whether muting automation silences the condition (false for the
+    /// user's own controls)
+    fn is_automation(&self) -> bool {
+        true
+    }
+}
+
+/// The user's own wake toggle
+pub struct ManualCondition;
+
+impl Condition for ManualCondition {
+    fn name(&self) -> &str {
+        "manual"
+    }
+
+    fn evaluate(&self, ctx: &EvalContext) -> ConditionResult {
+        if ctx.manual_awake {
+            ConditionResult::KeepAwake
+        } else {
+            ConditionResult::Neutral
+        }
+    }
+
+    fn reason(&self) -> AwakeReason {
+        AwakeReason::Manual
+    }
+
+    fn is_automation(&self) -> bool {
+        false
+    }
+}
+
+/// The master switch: off forces sleep over every other condition
+pub struct MasterCondition;
+
+impl Condition for MasterCondition {
+    fn name(&self) -> &str {
+        "master switch"
+    }
+
+    fn evaluate(&self, ctx: &EvalContext) -> ConditionResult {
+        if ctx.master_off {
+            ConditionResult::ForceSleep
+        } else {
+            ConditionResult::Neutral
+        }
+    }
+
+    fn is_automation(&self) -> bool {
+        false
+    }
+}
+
+/// Platform sampling behind a `PatternCondition`
+pub trait PatternProbe: Send {
+    /// Condition name (e.g. `window title`)
+    const NAME: &'static str;
+
+    /// What the pattern is called in the reason (e.g. `window "Render"`)
+    const NOUN: &'static str;
+
+    /// Whether `pattern` is found right now, None while the platform can't
+    /// tell (enumeration unsupported or failing)
+    fn matches(&self, pattern: &str) -> Option<bool>;
+}
+
+/// Keeps wake on while the shared pattern is found by `P`
+///
+/// ## Design Intent
+/// The pattern is shared with the settings commands, so a change applies
+/// on the next poll; an unset pattern never holds.
+pub struct PatternCondition<P> {
+    pattern: Arc<Mutex<Option<String>>>,
+    probe: P,
+}
+
+impl<P: PatternProbe + Default> PatternCondition<P> {
+    /// Watch the shared `pattern`
+    pub fn new(pattern: Arc<Mutex<Option<String>>>) -> Self {
+        Self::with_probe(pattern, P::default())
+    }
+}
+
+impl<P: PatternProbe> PatternCondition<P> {
+    /// Watch the shared `pattern` through `probe`
+    pub fn with_probe(pattern: Arc<Mutex<Option<String>>>, probe: P) -> Self {
+        Self { pattern, probe }
+    }
+
+    fn pattern(&self) -> Option<String> {
+        match self.pattern.lock() {
+            Ok(pattern) => pattern.clone(),
+            Err(e) => {
+                log::error!("Mutex poisoned during {} watch: {}", P::NAME, e);
+                None
+            }
+        }
+    }
+
+    /// Sample whether the pattern is found
+    pub fn holds(&self) -> bool {
+        let Some(pattern) = self.pattern() else {
+            return false;
+        };
+        self.probe.matches(&pattern).unwrap_or_else(|| {
+            log::debug!("{} enumeration unavailable, watch inactive", P::NAME);
+            false
+        })
+    }
+}
+
+impl<P: PatternProbe> Condition for PatternCondition<P> {
+    fn name(&self) -> &str {
+        P::NAME
+    }
+
+    fn evaluate(&self, _ctx: &EvalContext) -> ConditionResult {
+        ConditionResult::keep_awake_if(self.holds())
+    }
+
+    fn reason(&self) -> AwakeReason {
+        AwakeReason::Watcher(format!(
+            "{} \"{}\"",
+            P::NOUN,
+            self.pattern().unwrap_or_default()
+        ))
+    }
+}
+
+/// One condition's debounced result, as handed to `combine`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Evaluated {
+    pub name: String,
+    pub reason: AwakeReason,
+    pub automation: bool,
+    pub result: ConditionResult,
+}
+
+/// Combined outcome of every condition
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Verdict {
+    /// Whether the conditions want the machine awake
+    pub awake: bool,
+    /// Conditions keeping wake on, in the order they were added
+    pub reasons: Vec<AwakeReason>,
+    /// Condition forcing sleep, if any
+    pub forced_by: Option<String>,
+    /// First automation condition keeping wake on, ignoring mute and
+    /// overrides (what the watcher edges follow)
+    pub watch: Option<String>,
+}
+
+/// Combine condition results with a fixed precedence
+///
+/// ## Precedence
+/// 1. ForceSleep from any condition (e.g. the master switch)
+/// 2. KeepAwake from any condition
+/// 3. Neutral: wake is not wanted
+///
+/// Automation conditions are skipped while muted.
+pub fn combine(ctx: &EvalContext, evaluated: &[Evaluated]) -> Verdict {
+    let watch = evaluated
+        .iter()
+        .filter(|e| e.automation && e.result == ConditionResult::KeepAwake)
+        .find_map(|e| match &e.reason {
+            AwakeReason::Watcher(description) => Some(description.clone()),
+            AwakeReason::Manual => None,
+        });
+
+    let effective: Vec<&Evaluated> = evaluated
+        .iter()
+        .filter(|e| !(ctx.muted && e.automation))
+        .collect();
+
+    if let Some(forcing) = effective
+        .iter()
+        .find(|e| e.result == ConditionResult::ForceSleep)
+    {
+        return Verdict {
+            awake: false,
+            reasons: Vec::new(),
+            forced_by: Some(forcing.name.clone()),
+            watch,
+        };
+    }
+
+    let reasons: Vec<AwakeReason> = effective
+        .iter()
+        .filter(|e| e.result == ConditionResult::KeepAwake)
+        .map(|e| e.reason.clone())
+        .collect();
+    Verdict {
+        awake: !reasons.is_empty(),
+        reasons,
+        forced_by: None,
+        watch,
+    }
+}
+
+/// A condition with its own debouncing
+struct Supervised {
+    condition: Box<dyn Condition>,
+    debouncer: Debouncer<ConditionResult>,
+    result: ConditionResult,
+}
+
+/// Owner of every condition
+///
+/// ## Design Intent
+/// Conditions are debounced individually, so one flickering source cannot
+/// hide another's stable state, then combined by `combine`. Conditions
+/// that read shared state rather than polling hardware (manual, master)
+/// are added with a threshold of 1 so they apply on the next tick.
+#[derive(Default)]
+pub struct Supervisor {
+    conditions: Vec<Supervised>,
+    verdict: Verdict,
+}
+
+impl Supervisor {
+    /// Supervise `condition`, reacting after `debounce_samples` agreeing polls
+    pub fn add(&mut self, condition: Box<dyn Condition>, debounce_samples: u32) {
+        self.conditions.push(Supervised {
+            condition,
+            debouncer: Debouncer::new(ConditionResult::Neutral, debounce_samples),
+            result: ConditionResult::Neutral,
+        });
+    }
+
+    /// Evaluate every condition once
+    ///
+    /// ## Returns
+    /// The new verdict if it differs from the previous poll, otherwise None
+    pub fn poll(&mut self, ctx: &EvalContext) -> Option<Verdict> {
+        let evaluated: Vec<Evaluated> = self
+            .conditions
+            .iter_mut()
+            .map(|supervised| {
+                let sample = supervised.condition.evaluate(ctx);
+                if let Some(result) = supervised.debouncer.update(sample) {
+                    supervised.result = result;
+                }
+                Evaluated {
+                    name: supervised.condition.name().to_string(),
+                    reason: supervised.condition.reason(),
+                    automation: supervised.condition.is_automation(),
+                    result: supervised.result,
+                }
+            })
+            .collect();
+
+        let verdict = combine(ctx, &evaluated);
+        if verdict == self.verdict {
+            return None;
+        }
+        self.verdict = verdict.clone();
+        Some(verdict)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_no_automation_keeps_current_state() {
+        let inputs = AutomationInputs {
+            current_awake: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            resolve_desired_state(inputs),
+            DesiredState {
+                awake: true,
+                paused: false,
+                reasons: vec![AwakeReason::Manual],
+            }
+        );
+    }
+
+    #[test]
+    fn test_watcher_request_sets_state() {
+        let inputs = AutomationInputs {
+            current_awake: false,
+            watcher_request: Some(true),
+            ..Default::default()
+        };
+        assert!(resolve_desired_state(inputs).awake);
+    }
+
+    #[test]
+    fn test_power_pause_applies_when_unmuted() {
+        let inputs = AutomationInputs {
+            current_awake: true,
+            power_pause: true,
+            ..Default::default()
+        };
+        let desired = resolve_desired_state(inputs);
+        assert!(desired.awake);
+        assert!(desired.paused);
+    }
+
+    #[test]
+    fn test_muted_watcher_cannot_enable_over_manual_off() {
+        let inputs = AutomationInputs {
+            current_awake: false,
+            muted: true,
+            watcher_request: Some(true),
+            ..Default::default()
+        };
+        assert!(!resolve_desired_state(inputs).awake);
+    }
+
+    #[test]
+    fn test_muted_watcher_cannot_disable_manual_on() {
+        let inputs = AutomationInputs {
+            current_awake: true,
+            muted: true,
+            watcher_request: Some(false),
+            ..Default::default()
+        };
+        assert!(resolve_desired_state(inputs).awake);
+    }
+
+    #[test]
+    fn test_muted_power_policy_does_not_pause() {
+        let inputs = AutomationInputs {
+            current_awake: true,
+            muted: true,
+            power_pause: true,
+            ..Default::default()
+        };
+        assert!(!resolve_desired_state(inputs).paused);
+    }
+
+    #[test]
+    fn test_master_off_blocks_all_automation() {
+        let inputs = AutomationInputs {
+            master_off: true,
+            current_awake: false,
+            watcher_request: Some(true),
+            power_pause: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            resolve_desired_state(inputs),
+            DesiredState {
+                awake: false,
+                paused: false,
+                reasons: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_manual_and_watcher_reasons_reported_together() {
+        let inputs = AutomationInputs {
+            current_awake: true,
+            active_watch: Some("window \"Render\""),
+            ..Default::default()
+        };
+        assert_eq!(
+            resolve_desired_state(inputs).reasons,
+            vec![
+                AwakeReason::Manual,
+                AwakeReason::Watcher("window \"Render\"".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_watcher_edge_reports_only_watcher() {
+        let inputs = AutomationInputs {
+            current_awake: false,
+            watcher_request: Some(true),
+            active_watch: Some("window \"Render\""),
+            ..Default::default()
+        };
+        assert_eq!(
+            resolve_desired_state(inputs).reasons,
+            vec![AwakeReason::Watcher("window \"Render\"".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_paused_session_has_no_reasons() {
+        let inputs = AutomationInputs {
+            current_awake: true,
+            power_pause: true,
+            ..Default::default()
+        };
+        assert!(resolve_desired_state(inputs).reasons.is_empty());
+    }
+
+    #[test]
+    fn test_muted_reports_manual_only() {
+        let inputs = AutomationInputs {
+            current_awake: true,
+            muted: true,
+            enabled_by_watcher: true,
+            active_watch: Some("window \"Render\""),
+            ..Default::default()
+        };
+        assert_eq!(
+            resolve_desired_state(inputs).reasons,
+            vec![AwakeReason::Manual]
+        );
+    }
+
+    #[test]
+    fn test_master_off_overrides_mute() {
+        let inputs = AutomationInputs {
+            master_off: true,
+            muted: true,
+            current_awake: true,
+            ..Default::default()
+        };
+        assert!(!resolve_desired_state(inputs).awake);
+    }
+
+    #[test]
+    fn test_watcher_request_edges() {
+        assert_eq!(watcher_request(true, false, false), Some(true));
+        assert_eq!(watcher_request(true, true, false), None);
+        assert_eq!(watcher_request(false, true, true), Some(false));
+        // A manual session outlives the condition
+        assert_eq!(watcher_request(false, true, false), None);
+    }
+
+    #[test]
+    fn test_debounced_connect_and_disconnect() {
+        let mut debouncer = Debouncer::new(false, 2);
+        let mut awake = false;
+        let mut enabled_by_watcher = false;
+        let mut requests = Vec::new();
+
+        // Connect, a one-poll dropout, then disconnect
+        for connected in [true, true, true, false, true, false, false] {
+            let Some(matched) = debouncer.update(connected) else {
+                continue;
+            };
+            let request = watcher_request(matched, awake, enabled_by_watcher);
+            let desired = resolve_desired_state(AutomationInputs {
+                current_awake: awake,
+                watcher_request: request,
+                enabled_by_watcher,
+                ..Default::default()
+            });
+            enabled_by_watcher = matched && desired.awake;
+            awake = desired.awake;
+            requests.push(request);
+        }
+
+        assert_eq!(requests, vec![Some(true), Some(false)]);
+        assert!(!awake);
+    }
+
+    /// Probe finding the patterns it is given, or unavailable
+    #[derive(Default)]
+    struct MockProbe {
+        found: Option<Vec<&'static str>>,
+    }
+
+    impl PatternProbe for MockProbe {
+        const NAME: &'static str = "mock";
+        const NOUN: &'static str = "thing";
+
+        fn matches(&self, pattern: &str) -> Option<bool> {
+            self.found.as_ref().map(|found| found.contains(&pattern))
+        }
+    }
+
+    #[test]
+    fn test_pattern_condition_holds_only_for_a_found_pattern() {
+        let ctx = EvalContext::default();
+        let pattern = Arc::new(Mutex::new(None));
+        let probe = MockProbe {
+            found: Some(vec!["render"]),
+        };
+        let condition = PatternCondition::with_probe(pattern.clone(), probe);
+        assert_eq!(condition.evaluate(&ctx), ConditionResult::Neutral);
+
+        *pattern.lock().unwrap() = Some("render".to_string());
+        assert_eq!(condition.evaluate(&ctx), ConditionResult::KeepAwake);
+        assert_eq!(
+            condition.reason(),
+            AwakeReason::Watcher("thing \"render\"".to_string())
+        );
+
+        *pattern.lock().unwrap() = Some("build".to_string());
+        assert_eq!(condition.evaluate(&ctx), ConditionResult::Neutral);
+
+        // Enumeration unavailable never holds
+        let unavailable = PatternCondition::<MockProbe>::new(pattern);
+        assert!(!unavailable.holds());
+    }
+
+    /// Condition toggled by the test
+    struct MockCondition {
+        name: &'static str,
+        result: Arc<Mutex<ConditionResult>>,
+    }
+
+    impl Condition for MockCondition {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn evaluate(&self, _ctx: &EvalContext) -> ConditionResult {
+            *self.result.lock().unwrap()
+        }
+    }
+
+    fn mock(supervisor: &mut Supervisor, name: &'static str) -> Arc<Mutex<ConditionResult>> {
+        let result = Arc::new(Mutex::new(ConditionResult::Neutral));
+        supervisor.add(
+            Box::new(MockCondition {
+                name,
+                result: result.clone(),
+            }),
+            2,
+        );
+        result
+    }
+
+    fn set(result: &Arc<Mutex<ConditionResult>>, value: ConditionResult) {
+        *result.lock().unwrap() = value;
+    }
+
+    fn evaluated(name: &str, result: ConditionResult) -> Evaluated {
+        Evaluated {
+            name: name.to_string(),
+            reason: AwakeReason::Watcher(name.to_string()),
+            automation: true,
+            result,
+        }
+    }
+
+    fn manual(result: ConditionResult) -> Evaluated {
+        Evaluated {
+            name: "manual".to_string(),
+            reason: AwakeReason::Manual,
+            automation: false,
+            result,
+        }
+    }
+
+    #[test]
+    fn test_combine_force_sleep_beats_keep_awake() {
+        let verdict = combine(
+            &EvalContext::default(),
+            &[
+                manual(ConditionResult::KeepAwake),
+                evaluated("window", ConditionResult::KeepAwake),
+                evaluated("cap", ConditionResult::ForceSleep),
+            ],
+        );
+        assert!(!verdict.awake);
+        assert!(verdict.reasons.is_empty());
+        assert_eq!(verdict.forced_by.as_deref(), Some("cap"));
+        // Watcher edges still see the window holding
+        assert_eq!(verdict.watch.as_deref(), Some("window"));
+    }
+
+    #[test]
+    fn test_combine_keep_awake_beats_neutral() {
+        let verdict = combine(
+            &EvalContext::default(),
+            &[
+                manual(ConditionResult::KeepAwake),
+                evaluated("window", ConditionResult::Neutral),
+                evaluated("device", ConditionResult::KeepAwake),
+            ],
+        );
+        assert!(verdict.awake);
+        assert_eq!(
+            verdict.reasons,
+            vec![
+                AwakeReason::Manual,
+                AwakeReason::Watcher("device".to_string())
+            ]
+        );
+        assert_eq!(verdict.forced_by, None);
+    }
+
+    #[test]
+    fn test_combine_all_neutral_allows_sleep() {
+        let verdict = combine(
+            &EvalContext::default(),
+            &[
+                manual(ConditionResult::Neutral),
+                evaluated("window", ConditionResult::Neutral),
+            ],
+        );
+        assert_eq!(verdict, Verdict::default());
+    }
+
+    #[test]
+    fn test_combine_muted_keeps_only_user_conditions() {
+        let ctx = EvalContext {
+            muted: true,
+            ..Default::default()
+        };
+        let verdict = combine(
+            &ctx,
+            &[
+                manual(ConditionResult::Neutral),
+                evaluated("window", ConditionResult::KeepAwake),
+                evaluated("cap", ConditionResult::ForceSleep),
+            ],
+        );
+        assert!(!verdict.awake);
+        assert_eq!(verdict.forced_by, None);
+        assert_eq!(verdict.watch.as_deref(), Some("window"));
+    }
+
+    #[test]
+    fn test_master_and_manual_conditions() {
+        let ctx = EvalContext {
+            manual_awake: true,
+            master_off: true,
+            muted: true,
+        };
+        assert_eq!(ManualCondition.evaluate(&ctx), ConditionResult::KeepAwake);
+        assert_eq!(MasterCondition.evaluate(&ctx), ConditionResult::ForceSleep);
+
+        let mut supervisor = Supervisor::default();
+        supervisor.add(Box::new(ManualCondition), 1);
+        supervisor.add(Box::new(MasterCondition), 1);
+        let verdict = supervisor.poll(&ctx).unwrap();
+        assert!(!verdict.awake);
+        assert_eq!(verdict.forced_by.as_deref(), Some("master switch"));
+
+        let on = EvalContext {
+            master_off: false,
+            ..ctx
+        };
+        let verdict = supervisor.poll(&on).unwrap();
+        assert!(verdict.awake);
+        assert_eq!(verdict.reasons, vec![AwakeReason::Manual]);
+        assert_eq!(supervisor.poll(&on), None);
+    }
+
+    #[test]
+    fn test_supervisor_hands_over_between_conditions() {
+        let mut supervisor = Supervisor::default();
+        let window = mock(&mut supervisor, "window");
+        let device = mock(&mut supervisor, "device");
+        let ctx = EvalContext::default();
+
+        set(&window, ConditionResult::KeepAwake);
+        assert_eq!(supervisor.poll(&ctx), None);
+        let verdict = supervisor.poll(&ctx).unwrap();
+        assert!(verdict.awake);
+        assert_eq!(verdict.watch.as_deref(), Some("window"));
+
+        // The device takes over before the window closes: wake never drops
+        set(&device, ConditionResult::KeepAwake);
+        assert_eq!(supervisor.poll(&ctx), None);
+        set(&window, ConditionResult::Neutral);
+        let verdict = supervisor.poll(&ctx).unwrap();
+        assert_eq!(verdict.reasons.len(), 2);
+        assert_eq!(verdict.watch.as_deref(), Some("window"));
+        let verdict = supervisor.poll(&ctx).unwrap();
+        assert!(verdict.awake);
+        assert_eq!(verdict.watch.as_deref(), Some("device"));
+
+        set(&device, ConditionResult::Neutral);
+        assert_eq!(supervisor.poll(&ctx), None);
+        assert_eq!(supervisor.poll(&ctx), Some(Verdict::default()));
+    }
+
+    #[test]
+    fn test_supervisor_ignores_single_flicker() {
+        let mut supervisor = Supervisor::default();
+        let window = mock(&mut supervisor, "window");
+        let device = mock(&mut supervisor, "device");
+        let ctx = EvalContext::default();
+
+        set(&window, ConditionResult::KeepAwake);
+        assert_eq!(supervisor.poll(&ctx), None);
+        set(&window, ConditionResult::Neutral);
+        set(&device, ConditionResult::ForceSleep);
+        assert_eq!(supervisor.poll(&ctx), None);
+        set(&device, ConditionResult::Neutral);
+        assert_eq!(supervisor.poll(&ctx), None);
+        assert_eq!(supervisor.poll(&ctx), None);
+    }
+}
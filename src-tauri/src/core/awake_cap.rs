@@ -0,0 +1,130 @@
+//! Maximum awake duration
+//!
+//! Pure rules for the `max_session_secs` safety cap.
+//!
+//! ## Design Intent
+//! A backstop against a machine left awake for days, independent of
+//! timers, schedules and watchers: once wake has been on continuously for
+//! the cap it is turned off and stays off until the user enables it again.
+//! The caller keeps the clock; this only validates and decides.
+
+use std::time::Duration;
+
+/// Convert a cap in seconds to a duration
+///
+/// ## Returns
+/// The cap (None if no cap is set), or error string if `secs` is zero
+pub fn max_session_duration(secs: Option<u64>) -> Result<Option<Duration>, String> {
+    match secs {
+        Some(0) => Err("Maximum session length must be positive, got 0 seconds".to_string()),
+        secs => Ok(secs.map(Duration::from_secs)),
+    }
+}
+
+/// Convert a cap in hours (the `max_awake_hours` of state version 1) to
+/// whole seconds, when migrating
+///
+/// ## Returns
+/// The cap in seconds (None if no cap is set), or error string if `hours`
+/// is not positive, finite, or under one second
+pub fn hours_to_session_secs(hours: Option<f64>) -> Result<Option<u64>, String> {
+    match max_awake_duration(hours)? {
+        Some(cap) if cap.as_secs() == 0 => Err(format!(
+            "Maximum awake hours must be at least one second, got {}",
+            hours.unwrap_or_default()
+        )),
+        cap => Ok(cap.map(|cap| cap.as_secs())),
+    }
+}
+
+/// Convert a cap in hours to a duration
+///
+/// ## Returns
+/// The cap (None if no cap is set), or error string if `hours` is not a
+/// positive, finite number
+pub fn max_awake_duration(hours: Option<f64>) -> Result<Option<Duration>, String> {
+    let Some(hours) = hours else {
+        return Ok(None);
+    };
+    if hours.is_nan() || hours <= 0.0 {
+        return Err(format!(
+            "Maximum awake hours must be positive, got {}",
+            hours
+        ));
+    }
+    Duration::try_from_secs_f64(hours * 3600.0)
+        .map(Some)
+        .map_err(|e| format!("Invalid maximum awake hours {}: {}", hours, e))
+}
+
+/// Returns true once wake has been on for at least `cap`
+pub fn awake_cap_reached(awake_for: Duration, cap: Option<Duration>) -> bool {
+    cap.is_some_and(|cap| awake_for >= cap)
+}
+
+/// Time left before the cap is reached, None if no cap is set
+pub fn awake_cap_remaining(awake_for: Duration, cap: Option<Duration>) -> Option<Duration> {
+    cap.map(|cap| cap.saturating_sub(awake_for))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_max_awake_duration_converts_hours() {
+        assert_eq!(max_awake_duration(None), Ok(None));
+        assert_eq!(
+            max_awake_duration(Some(1.5)),
+            Ok(Some(Duration::from_secs(5400)))
+        );
+    }
+
+    #[test]
+    fn test_max_awake_duration_rejects_invalid_hours() {
+        assert!(max_awake_duration(Some(0.0)).is_err());
+        assert!(max_awake_duration(Some(-2.0)).is_err());
+        assert!(max_awake_duration(Some(f64::NAN)).is_err());
+        assert!(max_awake_duration(Some(f64::INFINITY)).is_err());
+    }
+
+    #[test]
+    fn test_max_session_duration() {
+        assert_eq!(max_session_duration(None), Ok(None));
+        assert_eq!(
+            max_session_duration(Some(90)),
+            Ok(Some(Duration::from_secs(90)))
+        );
+        assert!(max_session_duration(Some(0)).is_err());
+    }
+
+    #[test]
+    fn test_hours_to_session_secs() {
+        assert_eq!(hours_to_session_secs(None), Ok(None));
+        assert_eq!(hours_to_session_secs(Some(1.5)), Ok(Some(5400)));
+        assert!(hours_to_session_secs(Some(0.0001)).is_err());
+        assert!(hours_to_session_secs(Some(-1.0)).is_err());
+    }
+
+    #[test]
+    fn test_awake_cap_reached() {
+        let cap = Some(Duration::from_secs(3600));
+        assert!(!awake_cap_reached(Duration::from_secs(3599), cap));
+        assert!(awake_cap_reached(Duration::from_secs(3600), cap));
+        assert!(!awake_cap_reached(Duration::from_secs(u64::MAX), None));
+    }
+
+    #[test]
+    fn test_awake_cap_remaining() {
+        let cap = Some(Duration::from_secs(3600));
+        assert_eq!(
+            awake_cap_remaining(Duration::from_secs(600), cap),
+            Some(Duration::from_secs(3000))
+        );
+        assert_eq!(
+            awake_cap_remaining(Duration::from_secs(7200), cap),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(awake_cap_remaining(Duration::from_secs(600), None), None);
+    }
+}
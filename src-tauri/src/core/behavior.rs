@@ -0,0 +1,208 @@
+//! Wake behavior explanation
+//!
+//! Describes what each (platform, screen mode, wake method) combination
+//! actually does, for help tables and generated documentation.
+//!
+//! ## Design Intent
+//! The rules are spread across the display controller and
+//! `create_wake_input`; this restates them as data in one place so the
+//! documented behavior can be snapshot-tested instead of drifting from the
+//! code. Takes the OS name as input so every platform's table can be
+//! checked from any build.
+//!
+//! ## Platform Behavior
+//! - Windows: `SetThreadExecutionState` always runs; F15 is only pressed
+//!   with KeepScreenOn
+//! - macOS: An IOKit power assertion always runs; F15 is only pressed with
+//!   KeepScreenOn
+//! - Linux: A logind inhibitor lock always runs; F15 is only pressed with
+//!   KeepScreenOn (assumes systemd, see `platform::supports_sleep_inhibit`)
+//! - Other: F15 simulation does everything and keeps the display on too
+
+use crate::core::ScreenMode;
+use serde::Serialize;
+
+/// Mechanisms the platform can use to keep the system awake
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WakeMethod {
+    /// Periodic F15 key simulation
+    F15Simulation,
+    /// Windows `SetThreadExecutionState`
+    ExecutionState,
+    /// macOS IOKit power assertion
+    PowerAssertion,
+    /// Linux logind inhibitor lock
+    LogindInhibit,
+}
+
+/// What one wake method does in one screen mode
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct BehaviorRow {
+    /// OS name as in `std::env::consts::OS`
+    pub platform: String,
+    pub screen_mode: ScreenMode,
+    pub wake_method: WakeMethod,
+    /// The method is active while awake in this mode
+    pub method_used: bool,
+    /// The mode as a whole keeps the system from sleeping
+    pub keeps_system_awake: bool,
+    /// The mode as a whole keeps the display on
+    pub keeps_display_on: bool,
+    /// One-line explanation for help text
+    pub summary: String,
+}
+
+/// Explain one combination on the platform named `os`
+pub fn explain_behavior(os: &str, screen_mode: ScreenMode, wake_method: WakeMethod) -> BehaviorRow {
+    let windows = os == "windows";
+    let macos = os == "macos";
+    let linux = os == "linux";
+    let native = windows || macos || linux;
+    let keeps_display_on = !native || screen_mode.should_keep_display_on();
+    let method_used = match wake_method {
+        WakeMethod::F15Simulation => keeps_display_on,
+        WakeMethod::ExecutionState => windows,
+        WakeMethod::PowerAssertion => macos,
+        WakeMethod::LogindInhibit => linux,
+    };
+
+    let summary = match (wake_method, method_used) {
+        (WakeMethod::F15Simulation, true) if native => {
+            "F15 is pressed periodically so the display counts as in use"
+        }
+        (WakeMethod::F15Simulation, true) => {
+            "F15 is pressed periodically, keeping both the system and display awake"
+        }
+        (WakeMethod::F15Simulation, false) => "No keys are pressed, so the display may sleep",
+        (WakeMethod::ExecutionState, true) if screen_mode == ScreenMode::AwayMode => {
+            "SetThreadExecutionState requests away mode: the system keeps working while it appears asleep"
+        }
+        (WakeMethod::ExecutionState, true) if keeps_display_on => {
+            "SetThreadExecutionState requires both the system and the display"
+        }
+        (WakeMethod::ExecutionState, true) => {
+            "SetThreadExecutionState requires the system only, the display may sleep"
+        }
+        (WakeMethod::PowerAssertion, true) if keeps_display_on => {
+            "A display sleep assertion keeps both the system and the display awake"
+        }
+        (WakeMethod::PowerAssertion, true) => {
+            "A system sleep assertion keeps the system awake, the display may sleep"
+        }
+        (WakeMethod::LogindInhibit, true) if keeps_display_on => {
+            "A logind idle and sleep inhibitor keeps the system and display awake"
+        }
+        (WakeMethod::LogindInhibit, true) => {
+            "A logind sleep inhibitor keeps the system awake, the display may sleep"
+        }
+        (
+            WakeMethod::ExecutionState | WakeMethod::PowerAssertion | WakeMethod::LogindInhibit,
+            false,
+        ) => "Not available on this platform",
+    };
+
+    BehaviorRow {
+        platform: os.to_string(),
+        screen_mode,
+        wake_method,
+        method_used,
+        keeps_system_awake: true,
+        keeps_display_on,
+        summary: summary.to_string(),
+    }
+}
+
+/// Explain every pairing of `modes` and `methods` on the platform named `os`
+///
+/// ## Returns
+/// One row per pair, grouped by screen mode in the order given
+pub fn behavior_matrix(os: &str, modes: &[ScreenMode], methods: &[WakeMethod]) -> Vec<BehaviorRow> {
+    modes
+        .iter()
+        .flat_map(|&mode| {
+            methods
+                .iter()
+                .map(move |&method| explain_behavior(os, mode, method))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_windows_allow_screen_off_skips_f15() {
+        let f15 = explain_behavior(
+            "windows",
+            ScreenMode::AllowScreenOff,
+            WakeMethod::F15Simulation,
+        );
+        let execution_state = explain_behavior(
+            "windows",
+            ScreenMode::AllowScreenOff,
+            WakeMethod::ExecutionState,
+        );
+        assert!(!f15.method_used);
+        assert!(execution_state.method_used);
+        assert!(!execution_state.keeps_display_on);
+        assert!(execution_state.keeps_system_awake);
+    }
+
+    #[test]
+    fn test_windows_keep_screen_on_uses_both_methods() {
+        let rows = behavior_matrix(
+            "windows",
+            &[ScreenMode::KeepScreenOn],
+            &[WakeMethod::F15Simulation, WakeMethod::ExecutionState],
+        );
+        assert_eq!(rows.len(), 2);
+        assert!(rows
+            .iter()
+            .all(|row| row.method_used && row.keeps_display_on));
+    }
+
+    #[test]
+    fn test_other_platforms_rely_on_f15() {
+        let row = explain_behavior("linux", ScreenMode::KeepScreenOn, WakeMethod::F15Simulation);
+        assert!(row.method_used);
+        assert!(row.keeps_display_on);
+        assert_eq!(row.platform, "linux");
+
+        let execution_state = explain_behavior(
+            "macos",
+            ScreenMode::KeepScreenOn,
+            WakeMethod::ExecutionState,
+        );
+        assert!(!execution_state.method_used);
+    }
+
+    #[test]
+    fn test_linux_allow_screen_off_uses_inhibitor_only() {
+        let f15 = explain_behavior(
+            "linux",
+            ScreenMode::AllowScreenOff,
+            WakeMethod::F15Simulation,
+        );
+        let inhibit = explain_behavior(
+            "linux",
+            ScreenMode::AllowScreenOff,
+            WakeMethod::LogindInhibit,
+        );
+        assert!(!f15.method_used);
+        assert!(inhibit.method_used);
+        assert!(!inhibit.keeps_display_on);
+    }
+
+    #[test]
+    fn test_macos_allow_screen_off_uses_assertion_only() {
+        let rows = behavior_matrix(
+            "macos",
+            &[ScreenMode::AllowScreenOff],
+            &[WakeMethod::F15Simulation, WakeMethod::PowerAssertion],
+        );
+        assert!(!rows[0].method_used);
+        assert!(rows[1].method_used);
+        assert!(rows.iter().all(|row| !row.keeps_display_on));
+    }
+}
@@ -0,0 +1,264 @@
+//! Command line parsing
+//!
+//! Pure parsing of the arguments that select how Tea runs.
+//!
+//! ## Design Intent
+//! Without arguments Tea starts as a tray app. `--once --for <duration>`
+//! runs a single headless session for scripts wrapping one task, and
+//! `--enable` / `--disable` / `--status` control the running instance
+//! from scripts. Parsing is kept free of I/O so the accepted forms
+//! are covered by tests.
+//!
+//! ## Accepted Forms
+//! - `--once --for 2h` (flags in any order)
+//! - `--once --for=1h30m`
+//! - `--enable`, `--disable`, `--status` (one at a time, not with `--once`)
+//! - `--toggle`: starts the tray; launched while Tea already runs, the
+//!   running instance toggles wake (see `is_toggle_request`)
+//! - `--config-dir <dir>` / `--config-dir=<dir>`: combines with any of the
+//!   above; read separately by `config_dir_arg`
+//! - `--dry-run`: combines with the tray and `--once`; the wake service
+//!   logs what it would do instead of doing it. Read by `dry_run_arg`
+//!
+//! Other arguments are left to the tray app.
+
+use super::duration::parse_duration_human;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Flag selecting the config directory
+const CONFIG_DIR_FLAG: &str = "--config-dir";
+
+/// Flag selecting a dry run
+const DRY_RUN_FLAG: &str = "--dry-run";
+
+/// How the process should run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CliAction {
+    /// Start the tray application
+    Tray,
+    /// Keep awake headless for the duration, then exit
+    Once(Duration),
+    /// Ask the running instance to turn wake on, then exit
+    Enable,
+    /// Ask the running instance to turn wake off, then exit
+    Disable,
+    /// Print the running instance's state as JSON (the saved state when
+    /// none is reachable), then exit
+    Status,
+}
+
+/// Parse command line arguments (without the program name)
+///
+/// ## Returns
+/// The selected action, or a usage error string
+pub fn parse_cli_args<I>(args: I) -> Result<CliAction, String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut once = false;
+    let mut duration = None;
+    let mut control = None;
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        let flag = match arg.as_str() {
+            "--enable" => Some(CliAction::Enable),
+            "--disable" => Some(CliAction::Disable),
+            "--status" => Some(CliAction::Status),
+            _ => None,
+        };
+        if let Some(flag) = flag {
+            if control.is_some_and(|previous| previous != flag) {
+                return Err("Use only one of --enable, --disable and --status".to_string());
+            }
+            control = Some(flag);
+        } else if arg == "--once" {
+            once = true;
+        } else if arg == "--for" {
+            let value = args
+                .next()
+                .ok_or_else(|| "--for requires a duration (e.g. 2h)".to_string())?;
+            duration = Some(parse_duration_human(&value)?);
+        } else if let Some(value) = arg.strip_prefix("--for=") {
+            duration = Some(parse_duration_human(value)?);
+        } else if arg == CONFIG_DIR_FLAG && args.next().is_none() {
+            return Err("--config-dir requires a directory".to_string());
+        }
+    }
+
+    if let Some(control) = control {
+        if once || duration.is_some() {
+            return Err(
+                "--once cannot be combined with --enable, --disable or --status".to_string(),
+            );
+        }
+        return Ok(control);
+    }
+
+    match (once, duration) {
+        (true, Some(duration)) => Ok(CliAction::Once(duration)),
+        (true, None) => Err("--once requires --for <duration> (e.g. --for 2h)".to_string()),
+        (false, Some(_)) => Err("--for is only valid with --once".to_string()),
+        (false, None) => Ok(CliAction::Tray),
+    }
+}
+
+/// Config directory given by `--config-dir`, if any
+///
+/// ## Returns
+/// The last value given, None without the flag or with an empty value
+pub fn config_dir_arg<I>(args: I) -> Option<PathBuf>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut dir = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == CONFIG_DIR_FLAG {
+            dir = args.next();
+        } else if let Some(value) = arg.strip_prefix("--config-dir=") {
+            dir = Some(value.to_string());
+        }
+    }
+    dir.filter(|dir| !dir.is_empty()).map(PathBuf::from)
+}
+
+/// Whether `--dry-run` was given
+///
+/// A value following `--config-dir` is a directory, not a flag.
+pub fn dry_run_arg<I>(args: I) -> bool
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == CONFIG_DIR_FLAG {
+            args.next();
+        } else if arg == DRY_RUN_FLAG {
+            return true;
+        }
+    }
+    false
+}
+
+/// Whether a second launch asks the running instance to toggle wake
+///
+/// ## Arguments
+/// * `args` - Arguments of the second launch (without the program name)
+///
+/// ## Returns
+/// True for no arguments (a plain relaunch) or `--toggle`. `--config-dir`
+/// does not count, so a portable shortcut relaunching with it still toggles.
+pub fn is_toggle_request<I>(args: I) -> bool
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut rest = Vec::new();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == CONFIG_DIR_FLAG {
+            args.next();
+        } else if !arg.starts_with("--config-dir=") {
+            rest.push(arg);
+        }
+    }
+    rest.is_empty() || rest.iter().any(|arg| arg == "--toggle")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliAction, String> {
+        parse_cli_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_no_args_starts_tray() {
+        assert_eq!(parse(&[]), Ok(CliAction::Tray));
+    }
+
+    #[test]
+    fn test_once_for_duration() {
+        let two_hours = CliAction::Once(Duration::from_secs(2 * 3600));
+        assert_eq!(parse(&["--once", "--for", "2h"]), Ok(two_hours));
+        assert_eq!(parse(&["--for", "2h", "--once"]), Ok(two_hours));
+        assert_eq!(parse(&["--once", "--for=2h"]), Ok(two_hours));
+    }
+
+    #[test]
+    fn test_once_requires_duration() {
+        assert!(parse(&["--once"]).is_err());
+        assert!(parse(&["--once", "--for"]).is_err());
+        assert!(parse(&["--once", "--for", "soon"]).is_err());
+    }
+
+    #[test]
+    fn test_control_flags() {
+        assert_eq!(parse(&["--enable"]), Ok(CliAction::Enable));
+        assert_eq!(parse(&["--disable"]), Ok(CliAction::Disable));
+        assert_eq!(parse(&["--status", "--status"]), Ok(CliAction::Status));
+    }
+
+    #[test]
+    fn test_control_flags_are_exclusive() {
+        assert!(parse(&["--enable", "--disable"]).is_err());
+        assert!(parse(&["--status", "--once", "--for", "2h"]).is_err());
+    }
+
+    #[test]
+    fn test_toggle_starts_tray_and_toggles_second_instance() {
+        assert_eq!(parse(&["--toggle"]), Ok(CliAction::Tray));
+
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert!(is_toggle_request(args(&[])));
+        assert!(is_toggle_request(args(&["--toggle"])));
+        assert!(!is_toggle_request(args(&["--verbose"])));
+    }
+
+    #[test]
+    fn test_config_dir_flag() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            config_dir_arg(args(&["--config-dir", "E:\\tea", "--enable"])),
+            Some(PathBuf::from("E:\\tea"))
+        );
+        assert_eq!(
+            config_dir_arg(args(&["--config-dir=portable"])),
+            Some(PathBuf::from("portable"))
+        );
+        assert_eq!(config_dir_arg(args(&["--config-dir="])), None);
+        assert_eq!(config_dir_arg(args(&["--status"])), None);
+        assert!(is_toggle_request(args(&["--config-dir", "portable"])));
+        assert!(!is_toggle_request(args(&[
+            "--config-dir=portable",
+            "--verbose"
+        ])));
+
+        assert_eq!(
+            parse(&["--config-dir", "--status", "--enable"]),
+            Ok(CliAction::Enable)
+        );
+        assert!(parse(&["--config-dir"]).is_err());
+    }
+
+    #[test]
+    fn test_dry_run_flag() {
+        let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert!(dry_run_arg(args(&["--dry-run"])));
+        assert!(dry_run_arg(args(&["--once", "--for", "1m", "--dry-run"])));
+        assert!(!dry_run_arg(args(&["--config-dir", "--dry-run"])));
+        assert!(!dry_run_arg(args(&[])));
+        assert_eq!(
+            parse(&["--dry-run", "--once", "--for", "1m"]),
+            Ok(CliAction::Once(Duration::from_secs(60)))
+        );
+        assert_eq!(parse(&["--dry-run"]), Ok(CliAction::Tray));
+    }
+
+    #[test]
+    fn test_for_without_once_is_rejected() {
+        assert!(parse(&["--for", "2h"]).is_err());
+    }
+}
@@ -0,0 +1,117 @@
+//! Follow-up action when a watched condition ends
+//!
+//! Pure planning of what happens after a watcher releases wake.
+//!
+//! ## Design Intent
+//! "Keep awake until my render finishes, then sleep" needs more than
+//! disabling wake. The system action is always delayed so the user can
+//! cancel it; this module decides whether one is due and how to announce
+//! it, leaving the timer and power call to the command layer.
+
+use super::duration::format_duration_human;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Delay before a system action when none is configured
+pub const DEFAULT_CONDITION_END_DELAY_SECS: u64 = 30;
+
+/// What to do when a watched condition ends
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ConditionEndAction {
+    /// Only disable sleep prevention
+    #[default]
+    Disable,
+    /// Disable, then put the system to sleep
+    Sleep,
+    /// Disable, then hibernate
+    Hibernate,
+    /// Disable, then shut down
+    Shutdown,
+}
+
+impl ConditionEndAction {
+    /// Progressive verb for announcements (e.g. "Sleeping")
+    fn verb(self) -> &'static str {
+        match self {
+            ConditionEndAction::Disable => "Disabling",
+            ConditionEndAction::Sleep => "Sleeping",
+            ConditionEndAction::Hibernate => "Hibernating",
+            ConditionEndAction::Shutdown => "Shutting down",
+        }
+    }
+}
+
+/// Resolved follow-up for a condition that just ended
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConditionEndPlan {
+    /// Nothing beyond disabling wake
+    DisableOnly,
+    /// Run `action` after `delay` unless cancelled
+    Delayed {
+        action: ConditionEndAction,
+        delay: Duration,
+        /// Announcement, e.g. "Sleeping in 30s"
+        notice: String,
+    },
+}
+
+/// Plan the follow-up for a watched condition that ended
+///
+/// ## Arguments
+/// * `action` - Configured follow-up
+/// * `delay_secs` - Configured delay (None uses the default)
+///
+/// ## Returns
+/// `DisableOnly` for `Disable`, otherwise a delayed system action
+pub fn plan_condition_end(action: ConditionEndAction, delay_secs: Option<u64>) -> ConditionEndPlan {
+    if action == ConditionEndAction::Disable {
+        return ConditionEndPlan::DisableOnly;
+    }
+    let delay = Duration::from_secs(delay_secs.unwrap_or(DEFAULT_CONDITION_END_DELAY_SECS));
+    ConditionEndPlan::Delayed {
+        action,
+        delay,
+        notice: format!("{} in {}", action.verb(), format_duration_human(delay)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disable_needs_no_system_action() {
+        assert_eq!(
+            plan_condition_end(ConditionEndAction::Disable, Some(5)),
+            ConditionEndPlan::DisableOnly
+        );
+    }
+
+    #[test]
+    fn test_sleep_uses_default_delay() {
+        assert_eq!(
+            plan_condition_end(ConditionEndAction::Sleep, None),
+            ConditionEndPlan::Delayed {
+                action: ConditionEndAction::Sleep,
+                delay: Duration::from_secs(30),
+                notice: "Sleeping in 30s".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_configured_delay_is_announced() {
+        let ConditionEndPlan::Delayed { delay, notice, .. } =
+            plan_condition_end(ConditionEndAction::Shutdown, Some(120))
+        else {
+            panic!("expected a delayed action");
+        };
+        assert_eq!(delay, Duration::from_secs(120));
+        assert_eq!(notice, "Shutting down in 2m");
+    }
+
+    #[test]
+    fn test_default_action_is_disable() {
+        assert_eq!(ConditionEndAction::default(), ConditionEndAction::Disable);
+    }
+}
@@ -0,0 +1,106 @@
+//! Sample debouncing for watchers
+//!
+//! Pure state machine that turns noisy samples into stable edges.
+//!
+//! ## Design Intent
+//! Watchers poll external state (windows, processes, devices) that can
+//! flicker for a single sample, e.g. while a window is being recreated.
+//! A change is only reported once it has been observed for a number of
+//! consecutive samples, so wake is not toggled on and off by transients.
+
+/// Debounces a stream of samples (booleans unless stated otherwise)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Debouncer<T = bool> {
+    /// Current stable value
+    stable: T,
+    /// Value the disagreeing samples agree on
+    candidate: T,
+    /// Consecutive samples equal to `candidate` and not to `stable`
+    pending: u32,
+    /// Consecutive disagreeing samples required to flip
+    threshold: u32,
+}
+
+impl<T: Copy + PartialEq> Debouncer<T> {
+    /// Create a debouncer
+    ///
+    /// ## Arguments
+    /// * `initial` - Starting stable value
+    /// * `threshold` - Consecutive samples needed to change (minimum 1)
+    pub fn new(initial: T, threshold: u32) -> Self {
+        Self {
+            stable: initial,
+            candidate: initial,
+            pending: 0,
+            threshold: threshold.max(1),
+        }
+    }
+
+    /// Feed one sample
+    ///
+    /// ## Returns
+    /// `Some(new_value)` when the stable value changes, otherwise `None`
+    pub fn update(&mut self, sample: T) -> Option<T> {
+        if sample == self.stable {
+            self.pending = 0;
+            return None;
+        }
+
+        if sample != self.candidate {
+            self.candidate = sample;
+            self.pending = 0;
+        }
+        self.pending += 1;
+        if self.pending >= self.threshold {
+            self.stable = sample;
+            self.pending = 0;
+            Some(sample)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flips_after_threshold() {
+        let mut debouncer = Debouncer::new(false, 2);
+        assert_eq!(debouncer.update(true), None);
+        assert_eq!(debouncer.update(true), Some(true));
+        assert_eq!(debouncer.update(true), None);
+    }
+
+    #[test]
+    fn test_transient_sample_is_ignored() {
+        let mut debouncer = Debouncer::new(false, 2);
+        assert_eq!(debouncer.update(true), None);
+        assert_eq!(debouncer.update(false), None);
+        assert_eq!(debouncer.update(true), None);
+        assert_eq!(debouncer.update(false), None);
+    }
+
+    #[test]
+    fn test_repeated_stable_samples_report_nothing() {
+        let mut debouncer = Debouncer::new(true, 1);
+        assert_eq!(debouncer.update(true), None);
+        assert_eq!(debouncer.update(true), None);
+    }
+
+    #[test]
+    fn test_zero_threshold_behaves_as_one() {
+        let mut debouncer = Debouncer::new(false, 0);
+        assert_eq!(debouncer.update(true), Some(true));
+        assert_eq!(debouncer.update(false), Some(false));
+    }
+
+    #[test]
+    fn test_alternating_new_values_do_not_accumulate() {
+        let mut debouncer = Debouncer::new(0u8, 2);
+        assert_eq!(debouncer.update(1), None);
+        assert_eq!(debouncer.update(2), None);
+        assert_eq!(debouncer.update(2), Some(2));
+    }
+}
@@ -0,0 +1,126 @@
+//! Device matching
+//!
+//! Pure predicate deciding whether a connected device matches a watch
+//! pattern.
+//!
+//! ## Design Intent
+//! Kept separate from device enumeration so matching rules can be tested
+//! without real hardware.
+//!
+//! ## Rules
+//! - A `VID:PID` pattern (e.g. `17EF:A396`, hex) matches a device whose
+//!   hardware ids carry the same `VID_xxxx&PID_yyyy` pair
+//! - Any other pattern is a case-insensitive substring of the device name
+//! - Surrounding whitespace in the pattern is ignored
+//! - An empty pattern never matches (it would match every device)
+
+/// A connected device as reported by the platform
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DeviceInfo {
+    /// Friendly name or description (e.g. "ThinkPad USB-C Dock")
+    pub name: String,
+    /// Hardware ids (e.g. `USB\VID_17EF&PID_A396&REV_0100`)
+    pub hardware_ids: Vec<String>,
+}
+
+/// Parse a `VID:PID` pattern into its two hex ids
+pub fn parse_vid_pid(pattern: &str) -> Option<(u16, u16)> {
+    let (vid, pid) = pattern.trim().split_once(':')?;
+    if vid.len() != 4 || pid.len() != 4 {
+        return None;
+    }
+    let vid = u16::from_str_radix(vid, 16).ok()?;
+    let pid = u16::from_str_radix(pid, 16).ok()?;
+    Some((vid, pid))
+}
+
+/// Extract the vendor and product ids from a hardware id, if it has both
+fn hardware_id_vid_pid(hardware_id: &str) -> Option<(u16, u16)> {
+    let upper = hardware_id.to_uppercase();
+    let hex_after = |tag: &str| {
+        let start = upper.find(tag)? + tag.len();
+        let digits = upper.get(start..start + 4)?;
+        u16::from_str_radix(digits, 16).ok()
+    };
+    Some((hex_after("VID_")?, hex_after("PID_")?))
+}
+
+/// Returns true if `device` matches the watch `pattern`
+pub fn device_matches(device: &DeviceInfo, pattern: &str) -> bool {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return false;
+    }
+    if let Some(ids) = parse_vid_pid(pattern) {
+        return device
+            .hardware_ids
+            .iter()
+            .any(|id| hardware_id_vid_pid(id) == Some(ids));
+    }
+    device.name.to_lowercase().contains(&pattern.to_lowercase())
+}
+
+/// Returns true if any of `devices` matches the watch `pattern`
+pub fn any_device_matches(devices: &[DeviceInfo], pattern: &str) -> bool {
+    devices.iter().any(|device| device_matches(device, pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dock() -> DeviceInfo {
+        DeviceInfo {
+            name: "ThinkPad USB-C Dock Gen2".to_string(),
+            hardware_ids: vec![
+                r"USB\VID_17EF&PID_A396&REV_0100".to_string(),
+                r"USB\VID_17EF&PID_A396".to_string(),
+            ],
+        }
+    }
+
+    #[test]
+    fn test_name_substring_matches_case_insensitively() {
+        assert!(device_matches(&dock(), "usb-c dock"));
+        assert!(device_matches(&dock(), "  THINKPAD "));
+        assert!(!device_matches(&dock(), "Surface Dock"));
+    }
+
+    #[test]
+    fn test_vid_pid_matches_hardware_id() {
+        assert!(device_matches(&dock(), "17EF:A396"));
+        assert!(device_matches(&dock(), "17ef:a396"));
+        assert!(!device_matches(&dock(), "17EF:A395"));
+    }
+
+    #[test]
+    fn test_vid_pid_does_not_match_name() {
+        let named = DeviceInfo {
+            name: "Adapter 17EF:A396".to_string(),
+            hardware_ids: Vec::new(),
+        };
+        assert!(!device_matches(&named, "17EF:A396"));
+    }
+
+    #[test]
+    fn test_parse_vid_pid() {
+        assert_eq!(parse_vid_pid("17EF:A396"), Some((0x17EF, 0xA396)));
+        assert_eq!(parse_vid_pid("17EF"), None);
+        assert_eq!(parse_vid_pid("17E:A396"), None);
+        assert_eq!(parse_vid_pid("Dock:Gen2"), None);
+    }
+
+    #[test]
+    fn test_empty_pattern_never_matches() {
+        assert!(!device_matches(&dock(), ""));
+        assert!(!device_matches(&dock(), "   "));
+    }
+
+    #[test]
+    fn test_any_device_matches() {
+        let devices = [DeviceInfo::default(), dock()];
+        assert!(any_device_matches(&devices, "dock"));
+        assert!(!any_device_matches(&devices, "webcam"));
+        assert!(!any_device_matches(&[], "dock"));
+    }
+}
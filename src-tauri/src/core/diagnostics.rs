@@ -0,0 +1,153 @@
+//! Diagnostics report for bug reports
+//!
+//! Formats what support needs to know when "it isn't working" into plain
+//! text the user can paste into an issue.
+//!
+//! ## Design Intent
+//! The values are gathered by `commands::collect_diagnostics_impl` and come
+//! in as plain data, so the format is tested without a running app. Only
+//! wake settings and state are included: no watch targets, profile names
+//! or shortcuts. The home directory in log lines is replaced with `~` so
+//! the report does not reveal the account name.
+
+use super::{ScreenMode, WakeKey, WakeStrategy};
+
+/// Number of log lines included in the report
+pub const LOG_TAIL_LINES: usize = 20;
+
+/// Everything the diagnostics report shows
+#[derive(Debug, Clone)]
+pub struct DiagnosticsInput<'a> {
+    pub version: &'a str,
+    /// OS name as in `std::env::consts::OS`
+    pub os: &'a str,
+    pub arch: &'a str,
+    pub is_awake: bool,
+    pub screen_mode: ScreenMode,
+    /// Input is simulated in this mode (false: platform API only)
+    pub input_simulation: bool,
+    pub wake_strategy: WakeStrategy,
+    pub wake_key: WakeKey,
+    /// Start at login, or why it could not be read
+    pub autostart: Result<bool, String>,
+    /// Most recent log lines, oldest first
+    pub log_lines: &'a [String],
+    /// Home directory to redact from log lines
+    pub home_dir: Option<&'a str>,
+}
+
+/// Replace the home directory in `line` with `~`
+fn redact_home(line: &str, home_dir: Option<&str>) -> String {
+    match home_dir {
+        Some(home) if !home.is_empty() => line.replace(home, "~"),
+        _ => line.to_string(),
+    }
+}
+
+/// Format the diagnostics report
+pub fn format_report(input: &DiagnosticsInput) -> String {
+    let yes_no = |value: bool| if value { "yes" } else { "no" };
+    let simulation = if !input.input_simulation {
+        "no (platform API only)".to_string()
+    } else {
+        match input.wake_strategy {
+            WakeStrategy::KeyPress => format!("yes ({} key press)", input.wake_key.label()),
+            WakeStrategy::MouseJiggle => "yes (mouse jiggle)".to_string(),
+        }
+    };
+    let autostart = match &input.autostart {
+        Ok(enabled) => yes_no(*enabled).to_string(),
+        Err(e) => format!("unknown ({})", e),
+    };
+
+    let mut report = format!(
+        "{} diagnostics\n\
+         Version: {}\n\
+         OS: {} ({})\n\
+         Awake: {}\n\
+         Screen mode: {:?}\n\
+         Input simulation: {}\n\
+         Start at login: {}\n\
+         Recent log:\n",
+        super::APP_NAME,
+        input.version,
+        input.os,
+        input.arch,
+        yes_no(input.is_awake),
+        input.screen_mode,
+        simulation,
+        autostart,
+    );
+    if input.log_lines.is_empty() {
+        report.push_str("  (none)\n");
+    }
+    for line in input.log_lines {
+        report.push_str("  ");
+        report.push_str(&redact_home(line, input.home_dir));
+        report.push('\n');
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_lists_state_and_redacts_home() {
+        let log_lines = vec![
+            "[12:00:00 INFO tea] Starting wake service".to_string(),
+            "[12:00:01 INFO tea] State saved to /home/alex/.config/tea/state.json".to_string(),
+        ];
+        let input = DiagnosticsInput {
+            version: "2.0.0",
+            os: "linux",
+            arch: "x86_64",
+            is_awake: true,
+            screen_mode: ScreenMode::KeepScreenOn,
+            input_simulation: true,
+            wake_strategy: WakeStrategy::KeyPress,
+            wake_key: WakeKey::F15,
+            autostart: Ok(false),
+            log_lines: &log_lines,
+            home_dir: Some("/home/alex"),
+        };
+
+        assert_eq!(
+            format_report(&input),
+            "Tea diagnostics\n\
+             Version: 2.0.0\n\
+             OS: linux (x86_64)\n\
+             Awake: yes\n\
+             Screen mode: KeepScreenOn\n\
+             Input simulation: yes (F15 key press)\n\
+             Start at login: no\n\
+             Recent log:\n  \
+             [12:00:00 INFO tea] Starting wake service\n  \
+             [12:00:01 INFO tea] State saved to ~/.config/tea/state.json\n"
+        );
+    }
+
+    #[test]
+    fn test_report_without_simulation_or_autostart() {
+        let input = DiagnosticsInput {
+            version: "2.0.0",
+            os: "windows",
+            arch: "x86_64",
+            is_awake: false,
+            screen_mode: ScreenMode::AllowScreenOff,
+            input_simulation: false,
+            wake_strategy: WakeStrategy::MouseJiggle,
+            wake_key: WakeKey::F15,
+            autostart: Err("plugin unavailable".to_string()),
+            log_lines: &[],
+            home_dir: None,
+        };
+
+        let report = format_report(&input);
+        assert!(report.contains("Awake: no\n"));
+        assert!(report.contains("Input simulation: no (platform API only)\n"));
+        assert!(report.contains("Start at login: unknown (plugin unavailable)\n"));
+        assert!(report.ends_with("Recent log:\n  (none)\n"));
+    }
+}
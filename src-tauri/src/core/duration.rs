@@ -0,0 +1,204 @@
+//! Human-readable duration formatting
+//!
+//! Pure formatting of durations for tooltips, notifications and logs.
+//!
+//! ## Design Intent
+//! Every countdown shown to the user goes through one function so features
+//! never disagree on whether to print "90s", "1m 30s" or "1:30".
+//!
+//! ## Rules
+//! - Units are days, hours, minutes and seconds (days is the largest unit)
+//! - At most two units are shown: the largest non-zero unit and the next one
+//! - A zero second unit is omitted ("2h", not "2h 0m")
+//! - Zero renders as "0s"; sub-second remainders are truncated
+//!
+//! `parse_duration_human` accepts the same units back ("2h", "1h 30m",
+//! "90s"), so anything printed can be typed in again.
+
+use std::time::Duration;
+
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+const DAY: u64 = 24 * HOUR;
+
+/// Format a duration as e.g. "1h 23m", "45s" or "2d"
+///
+/// ## Arguments
+/// * `d` - Duration to format
+///
+/// ## Returns
+/// Compact human-readable text following the module rules
+pub fn format_duration_human(d: Duration) -> String {
+    let secs = d.as_secs();
+
+    let units = [
+        (secs / DAY, "d"),
+        ((secs % DAY) / HOUR, "h"),
+        ((secs % HOUR) / MINUTE, "m"),
+        (secs % MINUTE, "s"),
+    ];
+
+    let Some(first) = units.iter().position(|(value, _)| *value > 0) else {
+        return "0s".to_string();
+    };
+
+    let (value, unit) = units[first];
+    match units.get(first + 1) {
+        Some((next, next_unit)) if *next > 0 => {
+            format!("{}{} {}{}", value, unit, next, next_unit)
+        }
+        _ => format!("{}{}", value, unit),
+    }
+}
+
+/// Parse a duration such as "2h", "1h 30m", "1h30m" or "90s"
+///
+/// ## Arguments
+/// * `text` - One or more `<number><unit>` parts, unit one of d, h, m, s;
+///   whitespace between parts is optional
+///
+/// ## Returns
+/// The total duration, or error string for empty, zero or malformed input
+pub fn parse_duration_human(text: &str) -> Result<Duration, String> {
+    let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if compact.is_empty() {
+        return Err("Duration is empty".to_string());
+    }
+
+    let mut total: u64 = 0;
+    let mut digits = String::new();
+    for c in compact.chars() {
+        if c.is_ascii_digit() {
+            digits.push(c);
+            continue;
+        }
+        let unit = match c.to_ascii_lowercase() {
+            'd' => DAY,
+            'h' => HOUR,
+            'm' => MINUTE,
+            's' => 1,
+            _ => return Err(format!("Invalid duration '{}': unknown unit '{}'", text, c)),
+        };
+        let value: u64 = digits
+            .parse()
+            .map_err(|_| format!("Invalid duration '{}': missing number before '{}'", text, c))?;
+        total = value
+            .checked_mul(unit)
+            .and_then(|secs| total.checked_add(secs))
+            .ok_or_else(|| format!("Invalid duration '{}': too large", text))?;
+        digits.clear();
+    }
+
+    if !digits.is_empty() {
+        return Err(format!(
+            "Invalid duration '{}': missing unit after '{}'",
+            text, digits
+        ));
+    }
+    if total == 0 {
+        return Err(format!(
+            "Invalid duration '{}': must be greater than zero",
+            text
+        ));
+    }
+    Ok(Duration::from_secs(total))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fmt(secs: u64) -> String {
+        format_duration_human(Duration::from_secs(secs))
+    }
+
+    #[test]
+    fn test_zero() {
+        assert_eq!(fmt(0), "0s");
+    }
+
+    #[test]
+    fn test_sub_second_truncates_to_zero() {
+        assert_eq!(format_duration_human(Duration::from_millis(999)), "0s");
+    }
+
+    #[test]
+    fn test_sub_minute() {
+        assert_eq!(fmt(1), "1s");
+        assert_eq!(fmt(45), "45s");
+        assert_eq!(fmt(59), "59s");
+    }
+
+    #[test]
+    fn test_minutes_and_seconds() {
+        assert_eq!(fmt(60), "1m");
+        assert_eq!(fmt(90), "1m 30s");
+        assert_eq!(fmt(15 * MINUTE), "15m");
+    }
+
+    #[test]
+    fn test_exact_hour() {
+        assert_eq!(fmt(HOUR), "1h");
+        assert_eq!(fmt(2 * HOUR), "2h");
+    }
+
+    #[test]
+    fn test_hours_and_minutes() {
+        assert_eq!(fmt(HOUR + 23 * MINUTE), "1h 23m");
+    }
+
+    #[test]
+    fn test_seconds_dropped_below_hours() {
+        // Only the two most significant units are shown
+        assert_eq!(fmt(HOUR + 23 * MINUTE + 45), "1h 23m");
+        assert_eq!(fmt(HOUR + 45), "1h");
+    }
+
+    #[test]
+    fn test_multi_day() {
+        assert_eq!(fmt(2 * DAY), "2d");
+        assert_eq!(fmt(2 * DAY + 3 * HOUR), "2d 3h");
+        assert_eq!(fmt(2 * DAY + 59 * MINUTE), "2d");
+    }
+
+    #[test]
+    fn test_days_are_largest_unit() {
+        assert_eq!(fmt(400 * DAY), "400d");
+    }
+
+    #[test]
+    fn test_parse_single_and_combined_units() {
+        assert_eq!(
+            parse_duration_human("2h"),
+            Ok(Duration::from_secs(2 * HOUR))
+        );
+        assert_eq!(parse_duration_human("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(
+            parse_duration_human("1h 30m"),
+            Ok(Duration::from_secs(HOUR + 30 * MINUTE))
+        );
+        assert_eq!(
+            parse_duration_human("1d2h"),
+            Ok(Duration::from_secs(DAY + 2 * HOUR))
+        );
+    }
+
+    #[test]
+    fn test_parse_round_trips_formatting() {
+        for secs in [45, 90, HOUR + 23 * MINUTE, 2 * DAY + 3 * HOUR] {
+            assert_eq!(
+                parse_duration_human(&fmt(secs)),
+                Ok(Duration::from_secs(secs))
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_input() {
+        assert!(parse_duration_human("").is_err());
+        assert!(parse_duration_human("2").is_err());
+        assert!(parse_duration_human("h").is_err());
+        assert!(parse_duration_human("2x").is_err());
+        assert!(parse_duration_human("0m").is_err());
+    }
+}
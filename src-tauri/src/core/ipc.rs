@@ -0,0 +1,182 @@
+//! Local control protocol
+//!
+//! Framing and parsing for the control endpoint served by `crate::ipc`,
+//! which lets scripts (e.g. home automation) and `tea --enable` /
+//! `--disable` / `--status` turn wake on and off.
+//!
+//! ## Design Intent
+//! The protocol is line based so it can be driven with `nc -U` or a few
+//! lines of PowerShell: each request is one line holding a command, and
+//! each gets one line of JSON back. Commands are case-insensitive:
+//! - `ENABLE`: turn wake on
+//! - `DISABLE`: turn wake off
+//! - `STATUS`: report without changing anything
+//!
+//! Replies use the fields of `tea --status`, e.g.
+//! `{"sleep_disabled":true,"screen_mode":"KeepScreenOn"}`, or
+//! `{"error":"..."}` when the request failed.
+
+use super::ScreenMode;
+use serde::{Deserialize, Serialize};
+
+/// Longest request line accepted, newline included
+pub const MAX_REQUEST_BYTES: usize = 64;
+
+/// Name of the control pipe for one session and config directory
+///
+/// ## Design Intent
+/// Named pipes share one namespace across every session on the machine, so
+/// a fixed name would let the first user (or `--config-dir` instance) to
+/// start claim it for everyone. The session id separates users and RDP
+/// sessions; a hash of the config directory separates instances within one
+/// session. The directory is compared case-insensitively, as Windows paths
+/// are.
+///
+/// ## Returns
+/// `\\.\pipe\tea-control-<session id>-<16 hex digits>`
+#[cfg(any(windows, test))]
+pub fn pipe_name(session_id: u32, config_dir: &str) -> String {
+    // FNV-1a: stable across builds, unlike `DefaultHasher`
+    let hash = config_dir
+        .to_lowercase()
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!(r"\\.\pipe\tea-control-{}-{:016x}", session_id, hash)
+}
+
+/// Command sent over the control endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpcRequest {
+    Enable,
+    Disable,
+    Status,
+}
+
+impl IpcRequest {
+    /// The request line's command, as sent by `tea --enable` and friends
+    pub fn command(self) -> &'static str {
+        match self {
+            Self::Enable => "ENABLE",
+            Self::Disable => "DISABLE",
+            Self::Status => "STATUS",
+        }
+    }
+}
+
+/// Parse one request line
+///
+/// ## Returns
+/// The command, or an error naming the accepted commands
+pub fn parse_request(line: &str) -> Result<IpcRequest, String> {
+    match line.trim().to_ascii_uppercase().as_str() {
+        "ENABLE" => Ok(IpcRequest::Enable),
+        "DISABLE" => Ok(IpcRequest::Disable),
+        "STATUS" => Ok(IpcRequest::Status),
+        other => Err(format!(
+            "Unknown command \"{}\"; expected ENABLE, DISABLE or STATUS",
+            other
+        )),
+    }
+}
+
+/// Reply to one request
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+enum IpcResponse {
+    Status {
+        sleep_disabled: bool,
+        screen_mode: ScreenMode,
+    },
+    Error {
+        error: String,
+    },
+}
+
+/// Format the reply line for a request's outcome, newline included
+///
+/// ## Arguments
+/// * `outcome` - Wake state and screen mode after the request, or error
+pub fn format_response(outcome: Result<(bool, ScreenMode), String>) -> String {
+    let response = match outcome {
+        Ok((sleep_disabled, screen_mode)) => IpcResponse::Status {
+            sleep_disabled,
+            screen_mode,
+        },
+        Err(error) => IpcResponse::Error { error },
+    };
+    let mut line = serde_json::to_string(&response)
+        .unwrap_or_else(|e| format!("{{\"error\":\"Failed to format reply: {}\"}}", e));
+    line.push('\n');
+    line
+}
+
+/// Parse a reply line from the control endpoint
+///
+/// ## Returns
+/// The wake state and screen mode reported, or the error the endpoint
+/// replied with or one describing an unreadable reply
+pub fn parse_response(line: &str) -> Result<(bool, ScreenMode), String> {
+    match serde_json::from_str(line.trim()) {
+        Ok(IpcResponse::Status {
+            sleep_disabled,
+            screen_mode,
+        }) => Ok((sleep_disabled, screen_mode)),
+        Ok(IpcResponse::Error { error }) => Err(error),
+        Err(e) => Err(format!("Unreadable reply \"{}\": {}", line.trim(), e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request_ignores_case_and_whitespace() {
+        assert_eq!(parse_request("ENABLE\n"), Ok(IpcRequest::Enable));
+        assert_eq!(parse_request("  disable\r\n"), Ok(IpcRequest::Disable));
+        assert_eq!(parse_request("Status"), Ok(IpcRequest::Status));
+        assert!(parse_request("toggle\n").is_err());
+        assert!(parse_request("").is_err());
+    }
+
+    #[test]
+    fn test_commands_parse_back() {
+        for request in [IpcRequest::Enable, IpcRequest::Disable, IpcRequest::Status] {
+            assert_eq!(parse_request(request.command()), Ok(request));
+        }
+    }
+
+    #[test]
+    fn test_parse_response_reads_formatted_replies() {
+        let ok = Ok((false, ScreenMode::AllowScreenOff));
+        assert_eq!(parse_response(&format_response(ok.clone())), ok);
+        let failed = Err("Tea is paused (master off)".to_string());
+        assert_eq!(parse_response(&format_response(failed.clone())), failed);
+        assert!(parse_response("")
+            .unwrap_err()
+            .starts_with("Unreadable reply"));
+    }
+
+    #[test]
+    fn test_pipe_name_is_per_session_and_config_dir() {
+        let name = pipe_name(1, r"C:\Users\Ann\AppData\Roaming\tea");
+        assert!(name.starts_with(r"\\.\pipe\tea-control-1-"));
+        assert_eq!(name, pipe_name(1, r"c:\users\ann\appdata\roaming\TEA"));
+        assert_ne!(name, pipe_name(2, r"C:\Users\Ann\AppData\Roaming\tea"));
+        assert_ne!(name, pipe_name(1, r"D:\portable\tea"));
+    }
+
+    #[test]
+    fn test_format_response_is_one_json_line() {
+        assert_eq!(
+            format_response(Ok((true, ScreenMode::KeepScreenOn))),
+            "{\"sleep_disabled\":true,\"screen_mode\":\"KeepScreenOn\"}\n"
+        );
+        assert_eq!(
+            format_response(Err("Tea is paused (master off)".to_string())),
+            "{\"error\":\"Tea is paused (master off)\"}\n"
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Log file naming and rotation
+//!
+//! Decides which file today's log goes to and which old files to delete.
+//!
+//! ## Design Intent
+//! One file per UTC day, named `tea-YYYY-MM-DD.log` so the names sort by
+//! date. Old days are deleted, oldest first, once all log files together
+//! exceed a size cap; today's file is always kept. Only names matching the
+//! pattern are ever considered, so the state file next to the logs is never
+//! touched.
+
+/// Seconds in a day
+const SECS_PER_DAY: u64 = 86_400;
+
+/// Prefix of every log file name
+pub const LOG_FILE_PREFIX: &str = "tea-";
+
+/// Suffix of every log file name
+pub const LOG_FILE_SUFFIX: &str = ".log";
+
+/// Upper bound on the combined size of all log files, in bytes
+pub const MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+
+/// Calendar date `(year, month, day)` of a day count since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // Howard Hinnant's civil_from_days
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Name of the log file for the UTC day containing `unix_secs`
+pub fn log_file_name(unix_secs: u64) -> String {
+    let (year, month, day) = civil_from_days((unix_secs / SECS_PER_DAY) as i64);
+    format!(
+        "{}{:04}-{:02}-{:02}{}",
+        LOG_FILE_PREFIX, year, month, day, LOG_FILE_SUFFIX
+    )
+}
+
+/// UTC timestamp for a log line, e.g. `2026-10-16T09:05:00Z`
+pub fn log_timestamp(unix_secs: u64) -> String {
+    let (year, month, day) = civil_from_days((unix_secs / SECS_PER_DAY) as i64);
+    let secs = unix_secs % SECS_PER_DAY;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3_600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// Whether a directory entry is one of our log files
+pub fn is_log_file_name(name: &str) -> bool {
+    name.starts_with(LOG_FILE_PREFIX) && name.ends_with(LOG_FILE_SUFFIX)
+}
+
+/// Log files to delete so the rest fit in `max_total` bytes
+///
+/// ## Arguments
+/// * `files` - Name and size of every file in the log directory
+/// * `current` - Name of the file being written, never pruned
+/// * `max_total` - Size cap for all log files together
+///
+/// ## Returns
+/// Names to delete, oldest first
+pub fn files_to_prune(files: &[(String, u64)], current: &str, max_total: u64) -> Vec<String> {
+    let mut logs: Vec<&(String, u64)> = files
+        .iter()
+        .filter(|(name, _)| is_log_file_name(name))
+        .collect();
+    // Newest first; the date in the name sorts lexically
+    logs.sort_by(|a, b| b.0.cmp(&a.0));
+
+    let mut total = logs
+        .iter()
+        .find(|(name, _)| name == current)
+        .map_or(0, |(_, size)| *size);
+    let mut prune = Vec::new();
+    for (name, size) in logs.into_iter().filter(|(name, _)| name != current) {
+        total += size;
+        if total > max_total {
+            prune.push(name.clone());
+        }
+    }
+    prune.reverse();
+    prune
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_file_name_is_utc_date() {
+        assert_eq!(log_file_name(0), "tea-1970-01-01.log");
+        // 2024-02-29 23:59:59 UTC, a leap day
+        assert_eq!(log_file_name(1_709_251_199), "tea-2024-02-29.log");
+        assert_eq!(log_file_name(1_709_251_200), "tea-2024-03-01.log");
+    }
+
+    #[test]
+    fn test_log_timestamp() {
+        assert_eq!(log_timestamp(1_709_251_199), "2024-02-29T23:59:59Z");
+    }
+
+    #[test]
+    fn test_prune_oldest_beyond_cap_and_keep_current() {
+        let files = vec![
+            ("tea-2024-03-01.log".to_string(), 40),
+            ("tea-2024-02-28.log".to_string(), 40),
+            ("state.json".to_string(), 1_000),
+            ("tea-2024-02-29.log".to_string(), 40),
+            ("tea-2024-03-02.log".to_string(), 500),
+        ];
+
+        assert_eq!(
+            files_to_prune(&files, "tea-2024-03-02.log", 600),
+            vec!["tea-2024-02-28.log".to_string()]
+        );
+        // An oversized current file is kept, everything else goes
+        assert_eq!(files_to_prune(&files, "tea-2024-03-02.log", 100).len(), 3);
+        assert!(files_to_prune(&files, "tea-2024-03-02.log", 10_000).is_empty());
+    }
+}
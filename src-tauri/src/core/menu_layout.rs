@@ -0,0 +1,200 @@
+//! Tray menu layout
+//!
+//! Pure description of which entries the tray menu shows, in order.
+//!
+//! ## Design Intent
+//! The default menu stays simple; power users can opt into advanced
+//! entries. Deciding the layout here keeps that choice testable without
+//! building a real menu, and gives the tray the position to insert
+//! advanced entries at when the option is turned on at runtime.
+
+/// One entry of the tray menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuEntry {
+    ToggleSleep,
+    /// Submenu enabling wake for a preset duration
+    DisableSleepFor,
+    /// Submenu allowing sleep for a while before wake resumes
+    PauseFor,
+    Master,
+    ScreenOn,
+    ScreenOff,
+    /// Away Mode screen mode (Windows)
+    AwayMode,
+    StartAtLogin,
+    /// Checkbox starting the next launch with sleep allowed
+    SessionOnly,
+    /// Checkbox for wake change notifications
+    Notifications,
+    /// Checkbox serving the control endpoint for `tea --enable` and scripts
+    CommandLineControl,
+    /// Submenu choosing the wake interval
+    /// Submenu of saved settings profiles
+    Profile,
+    WakeInterval,
+    /// Submenu choosing key press or mouse jiggle
+    WakeStrategy,
+    /// Submenu showing and clearing the watched process
+    WatchProcess,
+    /// Advanced: diagnostics submenu (debug log capture)
+    Diagnostics,
+    /// Opens the status window
+    ShowStatus,
+    Quit,
+    Separator,
+}
+
+impl MenuEntry {
+    /// Whether the entry is only shown with the advanced menu
+    pub fn is_advanced(self) -> bool {
+        matches!(self, MenuEntry::Diagnostics)
+    }
+}
+
+/// What the layout depends on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MenuOptions {
+    /// The platform offers Keep Screen On
+    pub screen_on: bool,
+    /// The platform offers Allow Screen Off
+    pub screen_off: bool,
+    /// The platform offers Away Mode
+    pub away_mode: bool,
+    /// The user opted into advanced entries
+    pub show_advanced: bool,
+}
+
+/// Entries of the tray menu, top to bottom
+pub fn menu_layout(options: MenuOptions) -> Vec<MenuEntry> {
+    let mut entries = vec![
+        MenuEntry::ToggleSleep,
+        MenuEntry::DisableSleepFor,
+        MenuEntry::PauseFor,
+        MenuEntry::Master,
+    ];
+
+    // Screen mode section only where the platform offers a mode
+    if options.screen_on || options.screen_off || options.away_mode {
+        entries.push(MenuEntry::Separator);
+        if options.screen_on {
+            entries.push(MenuEntry::ScreenOn);
+        }
+        if options.screen_off {
+            entries.push(MenuEntry::ScreenOff);
+        }
+        if options.away_mode {
+            entries.push(MenuEntry::AwayMode);
+        }
+    }
+
+    entries.push(MenuEntry::Separator);
+    entries.push(MenuEntry::StartAtLogin);
+    entries.push(MenuEntry::SessionOnly);
+    entries.push(MenuEntry::Notifications);
+    entries.push(MenuEntry::CommandLineControl);
+    entries.push(MenuEntry::Profile);
+    entries.push(MenuEntry::WakeInterval);
+    entries.push(MenuEntry::WakeStrategy);
+    entries.push(MenuEntry::WatchProcess);
+    if options.show_advanced {
+        entries.push(MenuEntry::Diagnostics);
+    }
+    entries.push(MenuEntry::Separator);
+    entries.push(MenuEntry::ShowStatus);
+    entries.push(MenuEntry::Quit);
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_simple_menu_has_no_advanced_entries() {
+        let entries = menu_layout(MenuOptions {
+            screen_on: true,
+            screen_off: true,
+            away_mode: true,
+            show_advanced: false,
+        });
+        assert!(!entries.iter().any(|entry| entry.is_advanced()));
+        assert_eq!(entries.first(), Some(&MenuEntry::ToggleSleep));
+        assert_eq!(entries.last(), Some(&MenuEntry::Quit));
+    }
+
+    #[test]
+    fn test_advanced_menu_adds_diagnostics_only() {
+        let options = MenuOptions {
+            screen_on: true,
+            screen_off: true,
+            away_mode: false,
+            show_advanced: false,
+        };
+        let simple = menu_layout(options);
+        let advanced = menu_layout(MenuOptions {
+            show_advanced: true,
+            ..options
+        });
+
+        assert!(advanced.contains(&MenuEntry::Diagnostics));
+        let without_advanced: Vec<_> = advanced
+            .into_iter()
+            .filter(|entry| !entry.is_advanced())
+            .collect();
+        assert_eq!(without_advanced, simple);
+    }
+
+    #[test]
+    fn test_away_mode_listed_after_screen_modes() {
+        let entries = menu_layout(MenuOptions {
+            screen_on: true,
+            screen_off: true,
+            away_mode: true,
+            show_advanced: false,
+        });
+        let screen: Vec<_> = entries
+            .iter()
+            .copied()
+            .skip_while(|entry| *entry != MenuEntry::ScreenOn)
+            .take(3)
+            .collect();
+        assert_eq!(
+            screen,
+            vec![
+                MenuEntry::ScreenOn,
+                MenuEntry::ScreenOff,
+                MenuEntry::AwayMode
+            ]
+        );
+    }
+
+    #[test]
+    fn test_screen_section_omitted_without_screen_modes() {
+        let entries = menu_layout(MenuOptions {
+            show_advanced: true,
+            ..Default::default()
+        });
+        assert_eq!(
+            entries,
+            vec![
+                MenuEntry::ToggleSleep,
+                MenuEntry::DisableSleepFor,
+                MenuEntry::PauseFor,
+                MenuEntry::Master,
+                MenuEntry::Separator,
+                MenuEntry::StartAtLogin,
+                MenuEntry::SessionOnly,
+                MenuEntry::Notifications,
+                MenuEntry::CommandLineControl,
+                MenuEntry::Profile,
+                MenuEntry::WakeInterval,
+                MenuEntry::WakeStrategy,
+                MenuEntry::WatchProcess,
+                MenuEntry::Diagnostics,
+                MenuEntry::Separator,
+                MenuEntry::ShowStatus,
+                MenuEntry::Quit,
+            ]
+        );
+    }
+}
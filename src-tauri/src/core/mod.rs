@@ -3,8 +3,58 @@
 //! Contains pure, platform-agnostic logic with no I/O or external dependencies.
 //! All functions here are deterministic and easily testable.
 
+/// Product name shown in the tray tooltip, notifications and windows
+///
+/// The one source for user-facing branding, so tooltip text and its tests
+/// cannot drift apart from the rest of the UI.
+pub const APP_NAME: &str = "Tea";
+
+pub mod automation;
+pub mod awake_cap;
+pub mod behavior;
+pub mod cli;
+pub mod condition_end;
+pub mod debounce;
+pub mod device_match;
+pub mod diagnostics;
+pub mod duration;
+pub mod ipc;
+pub mod log_rotation;
+pub mod menu_layout;
+pub mod net_watch;
+pub mod override_guard;
+pub mod power;
+pub mod process_watch;
+pub mod profile;
+pub mod remote_session;
+pub mod schedule;
 pub mod screen_mode;
+pub mod self_check;
+pub mod session_event;
+pub mod sleep_risk;
+pub mod timed_awake;
 pub mod tooltip;
+pub mod tray_view;
+pub mod wake_interval;
+pub mod wake_key;
+pub mod wake_strategy;
+pub mod window_title;
 
+pub use automation::{resolve_desired_state, watcher_request, AutomationInputs, AwakeReason};
+pub use behavior::{BehaviorRow, WakeMethod};
+pub use cli::{config_dir_arg, dry_run_arg, parse_cli_args, CliAction};
+pub use condition_end::{plan_condition_end, ConditionEndAction, ConditionEndPlan};
+pub use debounce::Debouncer;
+pub use duration::format_duration_human;
+pub use menu_layout::{menu_layout, MenuEntry, MenuOptions};
+pub use power::PowerSource;
+pub use profile::ProfileSettings;
+pub use schedule::Schedule;
 pub use screen_mode::ScreenMode;
-pub use tooltip::TooltipText;
+pub use self_check::{CheckResult, StartupCheck};
+pub use sleep_risk::SleepRisk;
+pub use tooltip::{label_prefixed, TooltipText};
+pub use tray_view::{IconBadge, TrayAppearance, TrayIconKind};
+pub use wake_interval::{resolve_wake_interval, validate_wake_interval};
+pub use wake_key::WakeKey;
+pub use wake_strategy::WakeStrategy;
@@ -0,0 +1,110 @@
+//! Network target matching
+//!
+//! Parses the watched network target and decides whether an interface
+//! list contains it.
+//!
+//! ## Design Intent
+//! One setting covers both ways of telling that e.g. a work VPN is up:
+//! the VPN's interface exists, or a host only reachable through it
+//! answers. Kept separate from interface enumeration and connecting so
+//! the rules can be tested without a network.
+//!
+//! ## Rules
+//! - `host:port` (e.g. `intranet.corp:443`, `[fd00::1]:22`) is a host that
+//!   must accept a TCP connection; ICMP needs privileges Tea does not have
+//! - Anything else is an interface name (e.g. `wg0`, `utun3`, or a Windows
+//!   adapter name such as `Work VPN`)
+//! - Interface names match case-insensitively on the whole name
+//! - Surrounding whitespace is ignored; an empty target never matches
+
+/// What the network watcher looks for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NetTarget {
+    /// An interface with this name is up
+    Interface(String),
+    /// A TCP connection to `host:port` succeeds
+    Host { host: String, port: u16 },
+}
+
+impl NetTarget {
+    /// Parse a watched target, None if it is empty
+    pub fn parse(target: &str) -> Option<Self> {
+        let target = target.trim();
+        if target.is_empty() {
+            return None;
+        }
+        if let Some((host, port)) = target.rsplit_once(':') {
+            let (host, bracketed) = match host.strip_prefix('[').and_then(|h| h.strip_suffix(']')) {
+                Some(host) => (host, true),
+                None => (host, false),
+            };
+            // A bare IPv6 address has colons but no port
+            let is_host = !host.is_empty() && (bracketed || !host.contains(':'));
+            if let (true, Ok(port)) = (is_host, port.parse::<u16>()) {
+                return Some(Self::Host {
+                    host: host.to_string(),
+                    port,
+                });
+            }
+        }
+        Some(Self::Interface(target.to_string()))
+    }
+}
+
+/// Returns true if any of `interfaces` is the interface `name`
+pub fn any_interface_matches<'a, I>(interfaces: I, name: &str) -> bool
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let name = name.trim();
+    !name.is_empty()
+        && interfaces
+            .into_iter()
+            .any(|interface| interface.trim().eq_ignore_ascii_case(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_distinguishes_hosts_from_interfaces() {
+        assert_eq!(
+            NetTarget::parse(" intranet.corp:443 "),
+            Some(NetTarget::Host {
+                host: "intranet.corp".to_string(),
+                port: 443
+            })
+        );
+        assert_eq!(
+            NetTarget::parse("[fd00::1]:22"),
+            Some(NetTarget::Host {
+                host: "fd00::1".to_string(),
+                port: 22
+            })
+        );
+        assert_eq!(
+            NetTarget::parse("wg0"),
+            Some(NetTarget::Interface("wg0".to_string()))
+        );
+        assert_eq!(
+            NetTarget::parse("fd00::1"),
+            Some(NetTarget::Interface("fd00::1".to_string()))
+        );
+        assert_eq!(
+            NetTarget::parse("vpn:https"),
+            Some(NetTarget::Interface("vpn:https".to_string()))
+        );
+        assert_eq!(NetTarget::parse("  "), None);
+    }
+
+    #[test]
+    fn test_interface_matches_whole_name_case_insensitively() {
+        let interfaces = ["lo", "eth0", "Work VPN"];
+        assert!(any_interface_matches(interfaces, "work vpn"));
+        assert!(any_interface_matches(interfaces, " eth0 "));
+        assert!(!any_interface_matches(interfaces, "eth"));
+        assert!(!any_interface_matches(interfaces, ""));
+        assert!(!any_interface_matches([], "wg0"));
+    }
+}
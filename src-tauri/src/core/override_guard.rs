@@ -0,0 +1,85 @@
+//! Manual override guard
+//!
+//! Pure decision for a manual disable while a watcher wants wake on.
+//!
+//! ## Design Intent
+//! If wake is on because a watched condition is active (e.g. a render
+//! window), switching it off by hand may defeat the user's own intent. A
+//! soft warning is always given; a second confirmation is only required
+//! when the user opted in.
+
+/// What to do with a manual disable request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverrideDecision {
+    /// Nothing is being overridden
+    Proceed,
+    /// Disable, but warn that a watched condition is still active
+    Warn(String),
+    /// Ask for a second confirmation before disabling
+    Confirm(String),
+}
+
+/// Decide how to handle a manual disable
+///
+/// ## Arguments
+/// * `active_watch` - Description of the watched condition keeping wake
+///   on, if any
+/// * `confirm_required` - User preference to confirm such overrides
+/// * `confirmed` - The user already confirmed this disable
+pub fn decide_manual_disable(
+    active_watch: Option<&str>,
+    confirm_required: bool,
+    confirmed: bool,
+) -> OverrideDecision {
+    let Some(watch) = active_watch else {
+        return OverrideDecision::Proceed;
+    };
+
+    if confirm_required && !confirmed {
+        OverrideDecision::Confirm(format!(
+            "{} is still active; confirm to disable sleep prevention",
+            watch
+        ))
+    } else {
+        OverrideDecision::Warn(format!("Disabled manually while {} is still active", watch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_active_watcher_proceeds() {
+        assert_eq!(
+            decide_manual_disable(None, true, false),
+            OverrideDecision::Proceed
+        );
+    }
+
+    #[test]
+    fn test_active_watcher_warns_by_default() {
+        assert_eq!(
+            decide_manual_disable(Some("window \"Render\""), false, false),
+            OverrideDecision::Warn(
+                "Disabled manually while window \"Render\" is still active".to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_active_watcher_requires_confirmation_when_enabled() {
+        assert!(matches!(
+            decide_manual_disable(Some("window \"Render\""), true, false),
+            OverrideDecision::Confirm(_)
+        ));
+    }
+
+    #[test]
+    fn test_confirmed_override_warns() {
+        assert!(matches!(
+            decide_manual_disable(Some("window \"Render\""), true, true),
+            OverrideDecision::Warn(_)
+        ));
+    }
+}
@@ -0,0 +1,159 @@
+//! Power source policy
+//!
+//! Pure decisions about how the power source affects sleep prevention.
+//!
+//! ## Design Intent
+//! Platform code reports *what* the power source is; this module decides
+//! *what to do* about it. Keeping the decision pure lets it be tested
+//! without a laptop on hand.
+//!
+//! ## Why separate from the enabled flag
+//! Power-driven pausing is temporary and automatic. It must never change the
+//! user's manual enabled preference, so it is modelled as a separate pause
+//! decision that the wake service consults.
+//!
+//! The battery floor is the opposite: a safety stop that turns wake off
+//! like a manual disable, so plugging back in does not re-enable it.
+//! Refusing on battery is stricter still: wake cannot be turned on at all
+//! while unplugged.
+
+use serde::{Deserialize, Serialize};
+
+/// Where the system is currently drawing power from
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PowerSource {
+    /// External power (AC adapter) is connected
+    Ac,
+    /// Running on battery
+    Battery,
+    /// Source could not be determined (desktops, unsupported platforms)
+    Unknown,
+}
+
+/// Returns true if wake should be paused for the given power source
+///
+/// ## Arguments
+/// * `only_on_ac` - User preference to keep awake only while plugged in
+/// * `source` - Current power source
+///
+/// ## Design Intent
+/// Only a positively detected battery source pauses. An unknown source is
+/// treated like AC so desktops and unsupported platforms keep working.
+pub fn should_pause_for_power(only_on_ac: bool, source: PowerSource) -> bool {
+    only_on_ac && source == PowerSource::Battery
+}
+
+/// Returns true if wake should be turned off to protect the battery
+///
+/// ## Arguments
+/// * `floor_percent` - User's battery floor, None when the feature is off
+/// * `source` - Current power source
+/// * `percent` - Current charge, None when unknown (e.g. desktops)
+///
+/// ## Design Intent
+/// Only a known charge below the floor while on battery counts, so
+/// desktops and unreadable batteries make the feature a no-op.
+pub fn below_battery_floor(
+    floor_percent: Option<u8>,
+    source: PowerSource,
+    percent: Option<u8>,
+) -> bool {
+    match (floor_percent, percent) {
+        (Some(floor), Some(percent)) => source == PowerSource::Battery && percent < floor,
+        _ => false,
+    }
+}
+
+/// Returns true if a manual enable must be refused for the power source
+///
+/// ## Arguments
+/// * `refuse_on_battery` - User preference to never enable on battery
+/// * `source` - Current power source
+///
+/// ## Design Intent
+/// Like `should_pause_for_power`, only a positively detected battery
+/// refuses, so desktops without a battery can always enable.
+pub fn refuses_enable_on_battery(refuse_on_battery: bool, source: PowerSource) -> bool {
+    refuse_on_battery && source == PowerSource::Battery
+}
+
+/// Check a battery floor chosen by the user
+///
+/// ## Returns
+/// The floor, or error string unless it is None or 1-100
+pub fn validate_battery_floor(floor_percent: Option<u8>) -> Result<Option<u8>, String> {
+    match floor_percent {
+        Some(floor) if floor == 0 || floor > 100 => Err(format!(
+            "Battery floor must be between 1 and 100 percent, got {}",
+            floor
+        )),
+        _ => Ok(floor_percent),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_battery_pauses_when_only_on_ac() {
+        assert!(should_pause_for_power(true, PowerSource::Battery));
+    }
+
+    #[test]
+    fn test_ac_does_not_pause() {
+        assert!(!should_pause_for_power(true, PowerSource::Ac));
+    }
+
+    #[test]
+    fn test_unknown_source_does_not_pause() {
+        // Desktops without a battery must not be paused
+        assert!(!should_pause_for_power(true, PowerSource::Unknown));
+    }
+
+    #[test]
+    fn test_refuse_on_battery() {
+        assert!(!refuses_enable_on_battery(true, PowerSource::Ac));
+        assert!(refuses_enable_on_battery(true, PowerSource::Battery));
+        assert!(!refuses_enable_on_battery(false, PowerSource::Battery));
+        // Desktops without a battery report an unknown source
+        assert!(!refuses_enable_on_battery(true, PowerSource::Unknown));
+    }
+
+    #[test]
+    fn test_below_battery_floor_only_on_battery() {
+        assert!(below_battery_floor(
+            Some(20),
+            PowerSource::Battery,
+            Some(19)
+        ));
+        assert!(!below_battery_floor(
+            Some(20),
+            PowerSource::Battery,
+            Some(20)
+        ));
+        assert!(!below_battery_floor(Some(20), PowerSource::Ac, Some(5)));
+        assert!(!below_battery_floor(None, PowerSource::Battery, Some(5)));
+    }
+
+    #[test]
+    fn test_below_battery_floor_without_battery_is_noop() {
+        assert!(!below_battery_floor(Some(20), PowerSource::Unknown, None));
+        assert!(!below_battery_floor(Some(20), PowerSource::Battery, None));
+    }
+
+    #[test]
+    fn test_validate_battery_floor() {
+        assert_eq!(validate_battery_floor(None), Ok(None));
+        assert_eq!(validate_battery_floor(Some(15)), Ok(Some(15)));
+        assert!(validate_battery_floor(Some(0)).is_err());
+        assert!(validate_battery_floor(Some(101)).is_err());
+    }
+
+    #[test]
+    fn test_option_off_never_pauses() {
+        assert!(!should_pause_for_power(false, PowerSource::Battery));
+        assert!(!should_pause_for_power(false, PowerSource::Ac));
+        assert!(!should_pause_for_power(false, PowerSource::Unknown));
+    }
+}
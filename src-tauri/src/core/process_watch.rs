@@ -0,0 +1,82 @@
+//! Process matching
+//!
+//! Pure predicate deciding whether a running process matches the watched
+//! process name.
+//!
+//! ## Design Intent
+//! Kept separate from process enumeration so matching rules can be tested
+//! without spawning processes. Several matching processes (e.g. a build
+//! tool's workers) are still one trigger: the watcher only asks whether
+//! any process matches.
+//!
+//! ## Rules
+//! - Case-insensitive match on the whole executable name, not a substring,
+//!   so watching `make` does not fire for `cmake`
+//! - A trailing `.exe` is ignored on either side, so `blender` and
+//!   `blender.exe` are the same on every platform
+//! - A path is reduced to its last component
+//! - Surrounding whitespace in the name is ignored
+//! - An empty name never matches
+
+/// Normalize a process or watch name for comparison
+fn normalize(name: &str) -> String {
+    let name = name.trim();
+    let base = name.rsplit(['/', '\\']).next().unwrap_or(name);
+    let lower = base.to_lowercase();
+    match lower.strip_suffix(".exe") {
+        Some(stem) => stem.to_string(),
+        None => lower,
+    }
+}
+
+/// Returns true if `process` is the watched process `name`
+pub fn process_matches(process: &str, name: &str) -> bool {
+    let name = normalize(name);
+    !name.is_empty() && normalize(process) == name
+}
+
+/// Returns true if any of `processes` is the watched process `name`
+pub fn any_process_matches<'a, I>(processes: I, name: &str) -> bool
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    processes
+        .into_iter()
+        .any(|process| process_matches(process, name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whole_name_matches_case_insensitively() {
+        assert!(process_matches("Blender", "blender"));
+        assert!(!process_matches("cmake", "make"));
+    }
+
+    #[test]
+    fn test_exe_suffix_and_path_ignored() {
+        assert!(process_matches("blender.exe", "blender"));
+        assert!(process_matches("blender", "Blender.EXE"));
+        assert!(process_matches(
+            "/Applications/Blender.app/Contents/MacOS/Blender",
+            "blender"
+        ));
+        assert!(process_matches(r"C:\Tools\ffmpeg.exe", "ffmpeg"));
+    }
+
+    #[test]
+    fn test_empty_name_never_matches() {
+        assert!(!process_matches("", ""));
+        assert!(!process_matches("blender", "   "));
+    }
+
+    #[test]
+    fn test_many_matches_are_one_trigger() {
+        let processes = ["cargo", "rustc", "rustc", "rustc"];
+        assert!(any_process_matches(processes, "rustc"));
+        assert!(!any_process_matches(processes, "ninja"));
+        assert!(!any_process_matches([], "rustc"));
+    }
+}
@@ -0,0 +1,94 @@
+//! Named settings profiles
+//!
+//! Bundles of the settings users switch between as a whole (e.g. work and
+//! home), and their tray menu entries.
+//!
+//! ## Design Intent
+//! A profile holds screen mode, wake interval and wake key. Selecting one
+//! copies them into the regular settings, so everything that reads those
+//! settings keeps working unchanged and later tweaks from the menu apply
+//! as usual until the next switch. State files from before profiles get a
+//! "Default" profile made from their settings (see `persistence`).
+
+use super::screen_mode::ScreenMode;
+use super::wake_key::WakeKey;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Profile synthesized from the settings of a state file without profiles
+pub const DEFAULT_PROFILE: &str = "Default";
+
+/// Menu id prefix of the profile entries
+const MENU_ID_PREFIX: &str = "profile_";
+
+/// Settings applied together when a profile is selected
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ProfileSettings {
+    #[serde(default)]
+    pub screen_mode: ScreenMode,
+    /// Global wake interval in seconds, None for the built-in default
+    #[serde(default)]
+    pub wake_interval_secs: Option<u64>,
+    #[serde(default)]
+    pub wake_key: WakeKey,
+}
+
+/// Check a profile name before it is stored
+///
+/// ## Returns
+/// The trimmed name, or an error if it is empty
+pub fn validate_profile_name(name: &str) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("Profile name must not be empty".to_string());
+    }
+    Ok(name.to_string())
+}
+
+/// Profile names in menu order (alphabetical)
+pub fn profile_names(profiles: &HashMap<String, ProfileSettings>) -> Vec<String> {
+    let mut names: Vec<String> = profiles.keys().cloned().collect();
+    names.sort();
+    names
+}
+
+/// Menu id of a profile entry
+pub fn profile_menu_id(name: &str) -> String {
+    format!("{}{}", MENU_ID_PREFIX, name)
+}
+
+/// Profile name for a menu id, None if the id is not a profile entry
+pub fn profile_from_menu_id(id: &str) -> Option<&str> {
+    id.strip_prefix(MENU_ID_PREFIX)
+}
+
+/// Menu text for a profile, checked when it is the active one
+pub fn profile_menu_text(name: &str, active: bool) -> String {
+    let check = if active { "\u{2713} " } else { "" };
+    format!("{}{}", check, name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_menu_id_round_trip() {
+        assert_eq!(profile_from_menu_id(&profile_menu_id("Work")), Some("Work"));
+        assert_eq!(profile_from_menu_id("wake_interval_60"), None);
+        assert_eq!(profile_menu_text("Home", true), "\u{2713} Home");
+        assert_eq!(profile_menu_text("Home", false), "Home");
+    }
+
+    #[test]
+    fn test_profile_names_sorted_and_validated() {
+        let profiles = HashMap::from([
+            ("Work".to_string(), ProfileSettings::default()),
+            (DEFAULT_PROFILE.to_string(), ProfileSettings::default()),
+            ("Home".to_string(), ProfileSettings::default()),
+        ]);
+        assert_eq!(profile_names(&profiles), vec!["Default", "Home", "Work"]);
+        assert_eq!(validate_profile_name("  Work "), Ok("Work".to_string()));
+        assert!(validate_profile_name("   ").is_err());
+    }
+}
@@ -0,0 +1,146 @@
+//! Remote session filtering
+//!
+//! Pure predicate deciding whether any login session is an interactive
+//! remote one (SSH, RDP) that should keep the machine awake.
+//!
+//! ## Design Intent
+//! Kept separate from the platform query so the filter can be tested
+//! without logind or Remote Desktop Services. This is about sessions
+//! other people have open on this machine, not about whether Tea itself
+//! runs in a remote session.
+//!
+//! ## Rules
+//! A session counts if it is remote, belongs to a user (not a greeter or
+//! manager), is a terminal or remote desktop (`tty`, `ssh`, `rdp`), and is
+//! `active` or `online` (logged in but not in the foreground).
+
+/// One login session as reported by the platform
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct SessionInfo {
+    /// Session type (logind `Type`: `tty`, `x11`, `wayland`; `rdp` on Windows)
+    pub kind: String,
+    /// The session was opened from another machine
+    pub remote: bool,
+    /// Session state (logind `State`: `active`, `online`, `closing`)
+    pub state: String,
+    /// Session class (logind `Class`: `user`, `greeter`, `manager`)
+    pub class: String,
+}
+
+/// Returns true if `session` is an interactive remote user session
+pub fn is_interactive_remote(session: &SessionInfo) -> bool {
+    session.remote
+        && session.class == "user"
+        && matches!(session.kind.as_str(), "tty" | "ssh" | "rdp")
+        && matches!(session.state.as_str(), "active" | "online")
+}
+
+/// Returns true if any of `sessions` is an interactive remote session
+pub fn any_interactive_remote(sessions: &[SessionInfo]) -> bool {
+    sessions.iter().any(is_interactive_remote)
+}
+
+/// Parse `loginctl show-session -p Type -p Remote -p State -p Class`
+///
+/// ## Returns
+/// The session, with missing properties left empty (which never matches)
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))] // Only logind reports properties
+pub fn parse_logind_properties(text: &str) -> SessionInfo {
+    let mut session = SessionInfo::default();
+    for line in text.lines() {
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "Type" => session.kind = value.to_string(),
+            "Remote" => session.remote = value == "yes",
+            "State" => session.state = value.to_string(),
+            "Class" => session.class = value.to_string(),
+            _ => {}
+        }
+    }
+    session
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ssh_session() -> SessionInfo {
+        SessionInfo {
+            kind: "tty".to_string(),
+            remote: true,
+            state: "active".to_string(),
+            class: "user".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_active_ssh_session_counts() {
+        assert!(is_interactive_remote(&ssh_session()));
+        let online = SessionInfo {
+            state: "online".to_string(),
+            ..ssh_session()
+        };
+        assert!(is_interactive_remote(&online));
+    }
+
+    #[test]
+    fn test_local_session_does_not_count() {
+        let local = SessionInfo {
+            remote: false,
+            ..ssh_session()
+        };
+        assert!(!is_interactive_remote(&local));
+    }
+
+    #[test]
+    fn test_closing_or_non_user_sessions_do_not_count() {
+        let closing = SessionInfo {
+            state: "closing".to_string(),
+            ..ssh_session()
+        };
+        let manager = SessionInfo {
+            class: "manager".to_string(),
+            ..ssh_session()
+        };
+        assert!(!is_interactive_remote(&closing));
+        assert!(!is_interactive_remote(&manager));
+    }
+
+    #[test]
+    fn test_graphical_remote_session_only_counts_as_rdp() {
+        let x11 = SessionInfo {
+            kind: "x11".to_string(),
+            ..ssh_session()
+        };
+        let rdp = SessionInfo {
+            kind: "rdp".to_string(),
+            ..ssh_session()
+        };
+        assert!(!is_interactive_remote(&x11));
+        assert!(is_interactive_remote(&rdp));
+    }
+
+    #[test]
+    fn test_any_interactive_remote() {
+        assert!(any_interactive_remote(&[
+            SessionInfo::default(),
+            ssh_session()
+        ]));
+        assert!(!any_interactive_remote(&[SessionInfo::default()]));
+        assert!(!any_interactive_remote(&[]));
+    }
+
+    #[test]
+    fn test_parse_logind_properties() {
+        let session = parse_logind_properties("Type=tty\nRemote=yes\nState=active\nClass=user\n");
+        assert_eq!(session, ssh_session());
+
+        let local = parse_logind_properties("Type=wayland\nRemote=no\nState=active");
+        assert!(!local.remote);
+        assert_eq!(local.kind, "wayland");
+        assert_eq!(local.class, "");
+    }
+}
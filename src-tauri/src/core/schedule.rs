@@ -0,0 +1,379 @@
+//! Weekly wake schedule
+//!
+//! Pure decisions for turning wake on and off at configured times of day.
+//!
+//! ## Design Intent
+//! A schedule is a list of windows, each a set of weekdays with a local
+//! start and end time. Everything is evaluated on the local wall clock
+//! (`NaiveDateTime`), never on instants: a window from 09:00 to 18:00 is
+//! active whenever the clock on the wall says so, whatever the UTC offset
+//! is that day. DST transitions therefore need no special case. A window
+//! starting inside a skipped hour becomes active as soon as the clock is
+//! past its start, and an hour that repeats is simply active twice.
+//!
+//! ## Windows
+//! - `start < end`: active from `start` to `end` on each listed day
+//! - `start > end`: overnight; starts on a listed day and ends the next
+//!   morning (Fri 22:00-06:00 is active early on Saturday)
+//! - `start == end`: 24 hours from `start`
+//!
+//! ## Manual Override
+//! A manual change while a schedule is configured holds until the next
+//! boundary, the next moment the schedule flips between active and
+//! inactive (see `next_boundary`). From then on the schedule drives wake
+//! again.
+
+use chrono::{Datelike, Duration, NaiveDateTime, NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+
+/// Day mask for every day of the week
+pub const ALL_DAYS: u8 = 0b111_1111;
+
+/// Bit of `day` in a day mask (Monday is bit 0)
+pub fn day_bit(day: Weekday) -> u8 {
+    1 << day.num_days_from_monday()
+}
+
+/// One recurring window in which wake should be on
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScheduleWindow {
+    /// Days the window starts on, Monday in bit 0 (see `day_bit`)
+    pub days: u8,
+    /// Local time the window starts
+    pub start: NaiveTime,
+    /// Local time the window ends, the next day if not after `start`
+    pub end: NaiveTime,
+}
+
+impl ScheduleWindow {
+    fn starts_on(&self, day: Weekday) -> bool {
+        self.days & day_bit(day) != 0
+    }
+
+    /// Whether the window covers the local time `at`
+    pub fn is_active(&self, at: NaiveDateTime) -> bool {
+        let time = at.time();
+        let day = at.weekday();
+        if self.start < self.end {
+            self.starts_on(day) && self.start <= time && time < self.end
+        } else {
+            // Runs past midnight: started today, or started yesterday and
+            // not yet over
+            (self.starts_on(day) && time >= self.start)
+                || (self.starts_on(day.pred()) && time < self.end)
+        }
+    }
+}
+
+/// The configured wake schedule; empty means no schedule
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct Schedule {
+    #[serde(default)]
+    pub windows: Vec<ScheduleWindow>,
+}
+
+impl Schedule {
+    /// Whether no window is configured, so the schedule never acts
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    /// Validate a schedule before it is applied
+    ///
+    /// ## Returns
+    /// Ok, or a message naming the first bad window
+    pub fn validate(&self) -> Result<(), String> {
+        for (index, window) in self.windows.iter().enumerate() {
+            if window.days == 0 || window.days & !ALL_DAYS != 0 {
+                return Err(format!(
+                    "Schedule window {} has an invalid day mask: {:#b}",
+                    index + 1,
+                    window.days
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether any window covers the local time `at`
+    pub fn is_active(&self, at: NaiveDateTime) -> bool {
+        self.windows.iter().any(|window| window.is_active(at))
+    }
+
+    /// First local time after `after` at which the schedule flips
+    ///
+    /// ## Design Intent
+    /// Window starts and ends are the only points where activity can
+    /// change, so the earliest one whose activity differs from now is the
+    /// next flip. Edges of overlapping windows that do not flip anything
+    /// are skipped.
+    ///
+    /// ## Returns
+    /// The boundary, or None if the schedule never flips (empty, or
+    /// active around the clock)
+    pub fn next_boundary(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let active = self.is_active(after);
+        let today = after.date();
+        let mut edges: Vec<NaiveDateTime> = Vec::new();
+        // Overnight windows that started yesterday end today, and a full
+        // week ahead covers every start at least once
+        for offset in -1..=7 {
+            let day = today + Duration::days(offset);
+            for window in self.windows.iter().filter(|w| w.starts_on(day.weekday())) {
+                let start = day.and_time(window.start);
+                let end_day = if window.start < window.end {
+                    day
+                } else {
+                    day + Duration::days(1)
+                };
+                edges.push(start);
+                edges.push(end_day.and_time(window.end));
+            }
+        }
+        edges.retain(|edge| *edge > after);
+        edges.sort();
+        edges
+            .into_iter()
+            .find(|edge| self.is_active(*edge) != active)
+    }
+}
+
+/// Whether a manual override held until `until` has run out at `now`
+///
+/// Compared on the wall clock, so a boundary inside a skipped DST hour has
+/// passed as soon as the clock jumps beyond it.
+pub fn override_expired(until: NaiveDateTime, now: NaiveDateTime) -> bool {
+    now >= until
+}
+
+/// What the schedule asks of wake at `now`
+///
+/// ## Arguments
+/// * `schedule` - Configured schedule
+/// * `now` - Current local time
+/// * `override_until` - End of a manual override, if one is in effect
+///
+/// ## Returns
+/// The wake state the schedule wants, or None if it has no say (no
+/// windows, or overridden)
+pub fn schedule_request(
+    schedule: &Schedule,
+    now: NaiveDateTime,
+    override_until: Option<NaiveDateTime>,
+) -> Option<bool> {
+    if schedule.is_empty() {
+        return None;
+    }
+    if override_until.is_some_and(|until| !override_expired(until, now)) {
+        return None;
+    }
+    Some(schedule.is_active(now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    /// Day mask for Monday to Friday
+    const WEEKDAYS: u8 = 0b001_1111;
+
+    fn at(year: i32, month: u32, day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(year, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    fn work_hours() -> Schedule {
+        Schedule {
+            windows: vec![ScheduleWindow {
+                days: WEEKDAYS,
+                start: time(9, 0),
+                end: time(18, 0),
+            }],
+        }
+    }
+
+    fn friday_night() -> Schedule {
+        Schedule {
+            windows: vec![ScheduleWindow {
+                days: day_bit(Weekday::Fri),
+                start: time(22, 0),
+                end: time(6, 0),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_work_hours_window_edges() {
+        // 2026-10-16 is a Friday
+        let schedule = work_hours();
+        assert!(!schedule.is_active(at(2026, 10, 16, 8, 59)));
+        assert!(schedule.is_active(at(2026, 10, 16, 9, 0)));
+        assert!(schedule.is_active(at(2026, 10, 16, 17, 59)));
+        assert!(!schedule.is_active(at(2026, 10, 16, 18, 0)));
+        assert!(!schedule.is_active(at(2026, 10, 17, 12, 0)));
+    }
+
+    #[test]
+    fn test_overnight_window_belongs_to_its_start_day() {
+        let schedule = friday_night();
+        assert!(!schedule.is_active(at(2026, 10, 16, 21, 59)));
+        assert!(schedule.is_active(at(2026, 10, 16, 22, 0)));
+        assert!(schedule.is_active(at(2026, 10, 17, 0, 0)));
+        assert!(schedule.is_active(at(2026, 10, 17, 5, 59)));
+        assert!(!schedule.is_active(at(2026, 10, 17, 6, 0)));
+        // Saturday night is not listed
+        assert!(!schedule.is_active(at(2026, 10, 17, 23, 0)));
+        // Thursday night neither, so Friday morning is off
+        assert!(!schedule.is_active(at(2026, 10, 16, 3, 0)));
+    }
+
+    #[test]
+    fn test_equal_start_and_end_is_a_full_day() {
+        let schedule = Schedule {
+            windows: vec![ScheduleWindow {
+                days: day_bit(Weekday::Sat),
+                start: time(0, 0),
+                end: time(0, 0),
+            }],
+        };
+        assert!(schedule.is_active(at(2026, 10, 17, 0, 0)));
+        assert!(schedule.is_active(at(2026, 10, 17, 23, 59)));
+        assert!(!schedule.is_active(at(2026, 10, 18, 0, 0)));
+    }
+
+    #[test]
+    fn test_next_boundary() {
+        let schedule = work_hours();
+        assert_eq!(
+            schedule.next_boundary(at(2026, 10, 16, 12, 0)),
+            Some(at(2026, 10, 16, 18, 0))
+        );
+        // Friday evening: next start is Monday morning
+        assert_eq!(
+            schedule.next_boundary(at(2026, 10, 16, 18, 0)),
+            Some(at(2026, 10, 19, 9, 0))
+        );
+        assert_eq!(
+            friday_night().next_boundary(at(2026, 10, 17, 1, 0)),
+            Some(at(2026, 10, 17, 6, 0))
+        );
+        assert_eq!(
+            Schedule::default().next_boundary(at(2026, 10, 16, 12, 0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_next_boundary_skips_overlapping_edges() {
+        let mut schedule = work_hours();
+        schedule.windows.push(ScheduleWindow {
+            days: WEEKDAYS,
+            start: time(17, 0),
+            end: time(20, 0),
+        });
+        assert_eq!(
+            schedule.next_boundary(at(2026, 10, 16, 12, 0)),
+            Some(at(2026, 10, 16, 20, 0))
+        );
+
+        let always = Schedule {
+            windows: vec![ScheduleWindow {
+                days: ALL_DAYS,
+                start: time(0, 0),
+                end: time(0, 0),
+            }],
+        };
+        assert_eq!(always.next_boundary(at(2026, 10, 16, 12, 0)), None);
+    }
+
+    #[test]
+    fn test_spring_forward_skipped_start() {
+        // Clocks jump from 01:59 to 03:00 (2026-03-29 in the EU); a window
+        // starting at 02:30 is active from the first reading past it
+        let schedule = Schedule {
+            windows: vec![ScheduleWindow {
+                days: ALL_DAYS,
+                start: time(2, 30),
+                end: time(4, 0),
+            }],
+        };
+        let before = at(2026, 3, 29, 1, 59);
+        let after = at(2026, 3, 29, 3, 0);
+        assert_eq!(schedule_request(&schedule, before, None), Some(false));
+        assert_eq!(schedule_request(&schedule, after, None), Some(true));
+
+        // An override until the skipped 02:30 ends with the jump
+        let until = schedule.next_boundary(before).unwrap();
+        assert_eq!(until, at(2026, 3, 29, 2, 30));
+        assert_eq!(schedule_request(&schedule, before, Some(until)), None);
+        assert_eq!(schedule_request(&schedule, after, Some(until)), Some(true));
+    }
+
+    #[test]
+    fn test_fall_back_repeated_hour() {
+        // Clocks go from 02:59 back to 02:00 (2026-10-25 in the EU); a
+        // window ending at 02:30 is over once the clock first reads 02:30
+        let schedule = Schedule {
+            windows: vec![ScheduleWindow {
+                days: ALL_DAYS,
+                start: time(22, 0),
+                end: time(2, 30),
+            }],
+        };
+        let readings = [
+            (at(2026, 10, 25, 2, 29), Some(true)),
+            (at(2026, 10, 25, 2, 30), Some(false)),
+            // The repeated hour reads 02:15 again
+            (at(2026, 10, 25, 2, 15), Some(true)),
+            (at(2026, 10, 25, 2, 45), Some(false)),
+        ];
+        for (now, expected) in readings {
+            assert_eq!(schedule_request(&schedule, now, None), expected, "{}", now);
+        }
+        // An override that already ran out stays run out when the clock
+        // goes back
+        let until = at(2026, 10, 25, 2, 30);
+        assert!(override_expired(until, at(2026, 10, 25, 2, 30)));
+    }
+
+    #[test]
+    fn test_schedule_request_without_windows_or_while_overridden() {
+        let now = at(2026, 10, 16, 12, 0);
+        assert_eq!(schedule_request(&Schedule::default(), now, None), None);
+        assert_eq!(
+            schedule_request(&work_hours(), now, Some(at(2026, 10, 16, 18, 0))),
+            None
+        );
+        assert_eq!(
+            schedule_request(&work_hours(), now, Some(at(2026, 10, 16, 11, 0))),
+            Some(true)
+        );
+    }
+
+    #[test]
+    fn test_validate_day_mask() {
+        assert!(work_hours().validate().is_ok());
+        let mut schedule = work_hours();
+        schedule.windows[0].days = 0;
+        assert!(schedule.validate().is_err());
+        schedule.windows[0].days = 0b1000_0000;
+        assert!(schedule.validate().is_err());
+    }
+
+    #[test]
+    fn test_schedule_serialization() {
+        let json = r#"{"windows":[{"days":31,"start":"09:00","end":"18:00"}]}"#;
+        let schedule: Schedule = serde_json::from_str(json).unwrap();
+        assert_eq!(schedule, work_hours());
+        let round_trip: Schedule =
+            serde_json::from_str(&serde_json::to_string(&schedule).unwrap()).unwrap();
+        assert_eq!(round_trip, schedule);
+    }
+}
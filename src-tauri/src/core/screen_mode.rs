@@ -11,9 +11,12 @@
 //! Keeping it separate allows easy extension (e.g., adding timed modes).
 //!
 //! ## Platform Support
-//! AllowScreenOff is only supported on Windows where ES_SYSTEM_REQUIRED can keep
-//! the system awake without F15 simulation. On other platforms, F15 simulation
-//! prevents both system and display sleep, making AllowScreenOff impossible.
+//! AllowScreenOff needs an API that keeps the system awake without F15
+//! simulation: ES_SYSTEM_REQUIRED on Windows, a system-only power assertion
+//! on macOS and a logind `sleep` inhibitor on Linux. Elsewhere F15
+//! simulation prevents both system and display sleep, making AllowScreenOff
+//! impossible. AwayMode relies on ES_AWAYMODE_REQUIRED and exists on
+//! Windows only.
 
 use serde::{Deserialize, Serialize};
 
@@ -23,10 +26,11 @@ use serde::{Deserialize, Serialize};
 /// the system is being kept awake.
 ///
 /// ## Platform Constraints
-/// AllowScreenOff requires platform-specific APIs (Windows SetThreadExecutionState)
+/// AllowScreenOff requires platform-specific APIs (Windows SetThreadExecutionState,
+/// macOS IOKit power assertions, Linux logind inhibitor locks)
 /// to keep system awake without input simulation. Not all modes are available
 /// on all platforms.
-#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
 pub enum ScreenMode {
     /// Prevent display from sleeping or dimming
     ///
@@ -38,20 +42,59 @@ pub enum ScreenMode {
 
     /// Allow display to sleep normally while keeping system awake
     ///
-    /// **Windows only** - Not supported on macOS/Linux.
+    /// Supported on Windows, macOS and Linux.
     ///
     /// On Windows: Uses ES_SYSTEM_REQUIRED without F15 (allows display sleep)
+    /// On macOS: A system-only power assertion without F15
+    /// On Linux: A logind `sleep` inhibitor; falls back to F15 (keeping the
+    /// display on) where systemd is missing
     /// On other platforms: Not available (would require F15 which prevents display sleep)
+    #[default]
     AllowScreenOff,
-}
 
-impl Default for ScreenMode {
-    fn default() -> Self {
-        ScreenMode::AllowScreenOff
-    }
+    /// Keep the system working while it appears asleep (media scenarios)
+    ///
+    /// **Windows only** - uses ES_AWAYMODE_REQUIRED.
+    ///
+    /// On Windows: ES_SYSTEM_REQUIRED | ES_AWAYMODE_REQUIRED without F15; a
+    /// sleep request turns the display and audio off instead of sleeping.
+    /// Only honored on desktop editions whose power policy allows away mode.
+    AwayMode,
 }
 
 impl ScreenMode {
+    /// Every screen mode, in menu order
+    pub const ALL: [ScreenMode; 3] = [
+        ScreenMode::KeepScreenOn,
+        ScreenMode::AllowScreenOff,
+        ScreenMode::AwayMode,
+    ];
+
+    /// Screen modes available on the current platform
+    pub fn supported() -> Vec<ScreenMode> {
+        Self::ALL
+            .into_iter()
+            .filter(|mode| mode.is_supported())
+            .collect()
+    }
+
+    /// This mode if it is in `supported`, otherwise Keep Screen On
+    ///
+    /// ## Design Intent
+    /// A state file copied from another platform (or hand-edited) can name
+    /// a mode this platform cannot honor. Keep Screen On works everywhere
+    /// and errs on the side of keeping the machine usable.
+    ///
+    /// ## Arguments
+    /// * `supported` - Modes the platform supports, see `supported()`
+    pub fn coerce_to(self, supported: &[ScreenMode]) -> ScreenMode {
+        if supported.contains(&self) {
+            self
+        } else {
+            ScreenMode::KeepScreenOn
+        }
+    }
+
     /// Returns true if this mode requires display to stay active
     ///
     /// ## Design Intent
@@ -69,16 +112,22 @@ impl ScreenMode {
     ///
     /// ## Platform Behavior
     /// - KeepScreenOn: Supported on all platforms
-    /// - AllowScreenOff: Windows only (requires ES_SYSTEM_REQUIRED without F15)
+    /// - AllowScreenOff: Windows (ES_SYSTEM_REQUIRED without F15), macOS
+    ///   (system-only power assertion) and Linux (logind `sleep` inhibitor;
+    ///   falls back to F15 where systemd is missing)
+    /// - AwayMode: Windows only (ES_AWAYMODE_REQUIRED)
     ///
     /// ## Why this exists
-    /// On non-Windows platforms, preventing system sleep requires F15 simulation,
-    /// which also prevents display sleep. Therefore AllowScreenOff cannot work
-    /// as intended on those platforms.
+    /// On platforms without such an API, preventing system sleep requires F15
+    /// simulation, which also prevents display sleep. Therefore AllowScreenOff
+    /// cannot work as intended on those platforms.
     pub fn is_supported(self) -> bool {
         match self {
             ScreenMode::KeepScreenOn => true,
-            ScreenMode::AllowScreenOff => cfg!(windows),
+            ScreenMode::AllowScreenOff => {
+                cfg!(any(windows, target_os = "macos", target_os = "linux"))
+            }
+            ScreenMode::AwayMode => cfg!(windows),
         }
     }
 }
@@ -102,6 +151,20 @@ mod tests {
         assert!(!ScreenMode::AllowScreenOff.should_keep_display_on());
     }
 
+    #[test]
+    fn test_away_mode_does_not_require_display() {
+        assert!(!ScreenMode::AwayMode.should_keep_display_on());
+    }
+
+    #[test]
+    fn test_away_mode_supported_on_windows_only() {
+        assert_eq!(ScreenMode::AwayMode.is_supported(), cfg!(windows));
+        assert_eq!(
+            ScreenMode::AwayMode.coerce_to(&[ScreenMode::KeepScreenOn, ScreenMode::AllowScreenOff]),
+            ScreenMode::KeepScreenOn
+        );
+    }
+
     #[test]
     fn test_screen_modes_are_distinct() {
         assert_ne!(ScreenMode::KeepScreenOn, ScreenMode::AllowScreenOff);
@@ -115,16 +178,42 @@ mod tests {
     }
 
     #[test]
-    #[cfg(windows)]
+    fn test_unsupported_mode_coerces_to_keep_screen_on() {
+        let keep_only = [ScreenMode::KeepScreenOn];
+        assert_eq!(
+            ScreenMode::AllowScreenOff.coerce_to(&keep_only),
+            ScreenMode::KeepScreenOn
+        );
+        assert_eq!(
+            ScreenMode::KeepScreenOn.coerce_to(&keep_only),
+            ScreenMode::KeepScreenOn
+        );
+        assert_eq!(
+            ScreenMode::AllowScreenOff.coerce_to(&ScreenMode::ALL),
+            ScreenMode::AllowScreenOff
+        );
+    }
+
+    #[test]
+    fn test_supported_modes_match_is_supported() {
+        let supported = ScreenMode::supported();
+        for mode in ScreenMode::ALL {
+            assert_eq!(supported.contains(&mode), mode.is_supported());
+        }
+    }
+
+    #[test]
+    #[cfg(any(windows, target_os = "macos", target_os = "linux"))]
     fn test_allow_screen_off_supported_on_windows() {
-        // AllowScreenOff is supported on Windows (ES_SYSTEM_REQUIRED API available)
+        // AllowScreenOff is supported on Windows (ES_SYSTEM_REQUIRED API available),
+        // macOS (system-only power assertion) and Linux (logind inhibitor)
         assert!(ScreenMode::AllowScreenOff.is_supported());
     }
 
     #[test]
-    #[cfg(not(windows))]
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
     fn test_allow_screen_off_not_supported_on_non_windows() {
-        // AllowScreenOff is NOT supported on non-Windows (F15 prevents display sleep)
+        // AllowScreenOff is NOT supported elsewhere (F15 prevents display sleep)
         assert!(!ScreenMode::AllowScreenOff.is_supported());
     }
 }
@@ -0,0 +1,118 @@
+//! Startup self-check aggregation
+//!
+//! Pure summary of the individual checks run at launch.
+//!
+//! ## Design Intent
+//! Platform code performs the checks (decode icons, write config, create an
+//! input simulator); this module only decides what the results mean. That
+//! keeps the "is the app healthy?" rule testable with injected results.
+
+use serde::Serialize;
+
+/// Outcome of a single startup check
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct CheckResult {
+    /// Short check name (e.g. "icons")
+    pub name: String,
+    /// Whether the check passed
+    pub passed: bool,
+    /// Whether a failure prevents the app from working
+    pub critical: bool,
+    /// Failure reason, if any
+    pub detail: Option<String>,
+}
+
+impl CheckResult {
+    /// A passing check
+    pub fn pass(name: &str, critical: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            passed: true,
+            critical,
+            detail: None,
+        }
+    }
+
+    /// A failing check with a reason
+    pub fn fail(name: &str, critical: bool, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            passed: false,
+            critical,
+            detail: Some(detail.into()),
+        }
+    }
+}
+
+/// Aggregated result of all startup checks
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct StartupCheck {
+    /// Individual results, in the order they ran
+    pub checks: Vec<CheckResult>,
+    /// False if any critical check failed
+    pub ok: bool,
+}
+
+impl StartupCheck {
+    /// Aggregate individual check results
+    pub fn from_results(checks: Vec<CheckResult>) -> Self {
+        let ok = checks.iter().all(|check| check.passed || !check.critical);
+        Self { checks, ok }
+    }
+
+    /// One-line summary for logs and the tray tooltip
+    pub fn summary(&self) -> String {
+        let failed: Vec<&str> = self
+            .checks
+            .iter()
+            .filter(|check| !check.passed)
+            .map(|check| check.name.as_str())
+            .collect();
+
+        if failed.is_empty() {
+            format!("All {} startup checks passed", self.checks.len())
+        } else {
+            format!("Startup check failed: {}", failed.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_all_passing_is_ok() {
+        let check = StartupCheck::from_results(vec![
+            CheckResult::pass("icons", true),
+            CheckResult::pass("config", true),
+        ]);
+        assert!(check.ok);
+        assert_eq!(check.summary(), "All 2 startup checks passed");
+    }
+
+    #[test]
+    fn test_critical_failure_is_not_ok() {
+        let check = StartupCheck::from_results(vec![
+            CheckResult::pass("icons", true),
+            CheckResult::fail("config", true, "read-only"),
+        ]);
+        assert!(!check.ok);
+        assert_eq!(check.summary(), "Startup check failed: config");
+    }
+
+    #[test]
+    fn test_non_critical_failure_is_still_ok() {
+        let check = StartupCheck::from_results(vec![
+            CheckResult::pass("icons", true),
+            CheckResult::fail("input", false, "no display"),
+        ]);
+        assert!(check.ok);
+        assert_eq!(check.summary(), "Startup check failed: input");
+    }
+
+    #[test]
+    fn test_no_checks_is_ok() {
+        assert!(StartupCheck::from_results(Vec::new()).ok);
+    }
+}
@@ -0,0 +1,94 @@
+//! Session change events
+//!
+//! Decodes Windows session notifications and decides which of them should
+//! make a running wake session reassert its display request.
+//!
+//! ## Design Intent
+//! Locking the workstation or connecting over Remote Desktop can leave the
+//! display and execution state Tea set behind (e.g. a display request
+//! dropped on the console session once it is handed to RDP). Re-applying
+//! the screen mode when the user comes back is cheap and idempotent. Kept
+//! separate from the window hook in `platform` so the trigger rules can be
+//! tested without a session to lock.
+//!
+//! ## Rules
+//! - Unlock, console connect, remote connect and logon reassert
+//! - Lock, disconnects and logoff do not: nobody is there to keep awake for
+//!   until the session comes back, and the next connect or unlock reasserts
+//! - Other codes (remote control, session create/terminate) are ignored
+
+/// A `WM_WTSSESSION_CHANGE` notification for Tea's session
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(windows), allow(dead_code))] // Only Windows reports session changes
+pub enum SessionEvent {
+    ConsoleConnect,
+    ConsoleDisconnect,
+    RemoteConnect,
+    RemoteDisconnect,
+    Logon,
+    Logoff,
+    Lock,
+    Unlock,
+}
+
+impl SessionEvent {
+    /// Decode the `wParam` of `WM_WTSSESSION_CHANGE` (`WTS_*` status codes)
+    #[cfg(any(windows, test))]
+    pub fn from_wts_code(code: u32) -> Option<Self> {
+        match code {
+            1 => Some(Self::ConsoleConnect),
+            2 => Some(Self::ConsoleDisconnect),
+            3 => Some(Self::RemoteConnect),
+            4 => Some(Self::RemoteDisconnect),
+            5 => Some(Self::Logon),
+            6 => Some(Self::Logoff),
+            7 => Some(Self::Lock),
+            8 => Some(Self::Unlock),
+            _ => None,
+        }
+    }
+
+    /// Whether a wake session should re-apply its display request
+    pub fn reasserts_wake(self) -> bool {
+        matches!(
+            self,
+            Self::Unlock | Self::ConsoleConnect | Self::RemoteConnect | Self::Logon
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_wts_code_decodes_known_codes() {
+        assert_eq!(
+            SessionEvent::from_wts_code(1),
+            Some(SessionEvent::ConsoleConnect)
+        );
+        assert_eq!(SessionEvent::from_wts_code(7), Some(SessionEvent::Lock));
+        assert_eq!(SessionEvent::from_wts_code(8), Some(SessionEvent::Unlock));
+        // WTS_SESSION_REMOTE_CONTROL, WTS_SESSION_CREATE
+        assert_eq!(SessionEvent::from_wts_code(9), None);
+        assert_eq!(SessionEvent::from_wts_code(10), None);
+        assert_eq!(SessionEvent::from_wts_code(0), None);
+    }
+
+    #[test]
+    fn test_only_returning_sessions_reassert() {
+        let reasserting: Vec<_> = (0..16)
+            .filter_map(SessionEvent::from_wts_code)
+            .filter(|event| event.reasserts_wake())
+            .collect();
+        assert_eq!(
+            reasserting,
+            vec![
+                SessionEvent::ConsoleConnect,
+                SessionEvent::RemoteConnect,
+                SessionEvent::Logon,
+                SessionEvent::Unlock,
+            ]
+        );
+    }
+}
@@ -0,0 +1,131 @@
+//! Sleep risk assessment
+//!
+//! Pure check of whether the wake cadence beats the OS idle-sleep timeout.
+//!
+//! ## Design Intent
+//! F15 presses only help if one arrives before the OS decides the machine
+//! has been idle long enough to sleep. Comparing the wake interval with the
+//! configured timeout turns that relationship into actionable guidance.
+
+use serde::Serialize;
+use std::time::Duration;
+
+/// A margin below this fraction of the timeout is reported as marginal
+const MARGINAL_FRACTION: u32 = 4;
+
+/// How likely the system is to sleep despite wake being enabled
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SleepRisk {
+    /// Interval comfortably below the timeout, or nothing to beat
+    Safe,
+    /// Interval below the timeout by only this many seconds
+    Marginal(u64),
+    /// Interval is at or above the timeout; sleep can happen between presses
+    Unsafe,
+    /// The OS timeout could not be determined
+    Unknown,
+}
+
+/// Assess sleep risk for a wake interval
+///
+/// ## Arguments
+/// * `interval` - Time between wake cycles
+/// * `timeout` - OS idle-to-sleep timeout; `Some(ZERO)` means never sleeps,
+///   `None` means unknown
+/// * `api_keeps_awake` - A platform API (Windows execution state, macOS power
+///   assertion, Linux logind inhibitor) holds the system awake regardless
+///   of presses
+///
+/// ## Returns
+/// `Marginal(margin)` when the margin is under a quarter of the timeout
+pub fn assess_sleep_risk(
+    interval: Duration,
+    timeout: Option<Duration>,
+    api_keeps_awake: bool,
+) -> SleepRisk {
+    if api_keeps_awake {
+        return SleepRisk::Safe;
+    }
+    let Some(timeout) = timeout else {
+        return SleepRisk::Unknown;
+    };
+    if timeout.is_zero() {
+        return SleepRisk::Safe;
+    }
+    if interval >= timeout {
+        return SleepRisk::Unsafe;
+    }
+
+    let margin = timeout - interval;
+    if margin < timeout / MARGINAL_FRACTION {
+        SleepRisk::Marginal(margin.as_secs())
+    } else {
+        SleepRisk::Safe
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secs(s: u64) -> Duration {
+        Duration::from_secs(s)
+    }
+
+    #[test]
+    fn test_short_interval_is_safe() {
+        assert_eq!(
+            assess_sleep_risk(secs(60), Some(secs(600)), false),
+            SleepRisk::Safe
+        );
+    }
+
+    #[test]
+    fn test_small_margin_is_marginal() {
+        assert_eq!(
+            assess_sleep_risk(secs(500), Some(secs(600)), false),
+            SleepRisk::Marginal(100)
+        );
+    }
+
+    #[test]
+    fn test_margin_at_quarter_is_safe() {
+        assert_eq!(
+            assess_sleep_risk(secs(450), Some(secs(600)), false),
+            SleepRisk::Safe
+        );
+    }
+
+    #[test]
+    fn test_interval_at_or_above_timeout_is_unsafe() {
+        assert_eq!(
+            assess_sleep_risk(secs(600), Some(secs(600)), false),
+            SleepRisk::Unsafe
+        );
+        assert_eq!(
+            assess_sleep_risk(secs(900), Some(secs(600)), false),
+            SleepRisk::Unsafe
+        );
+    }
+
+    #[test]
+    fn test_never_sleeping_is_safe() {
+        assert_eq!(
+            assess_sleep_risk(secs(900), Some(Duration::ZERO), false),
+            SleepRisk::Safe
+        );
+    }
+
+    #[test]
+    fn test_unknown_timeout() {
+        assert_eq!(assess_sleep_risk(secs(60), None, false), SleepRisk::Unknown);
+    }
+
+    #[test]
+    fn test_platform_api_is_always_safe() {
+        assert_eq!(
+            assess_sleep_risk(secs(900), Some(secs(60)), true),
+            SleepRisk::Safe
+        );
+    }
+}
@@ -0,0 +1,268 @@
+//! Timed sleep prevention presets
+//!
+//! Durations offered for "disable sleep for ..." before wake turns itself
+//! off again.
+//!
+//! ## Design Intent
+//! Wake enabled for a long download is easy to forget, leaving the
+//! machine awake all night. A preset enables wake together with an
+//! auto-disable timer; "Until I disable" is the plain toggle. The list is
+//! defined here so the tray submenu and the frontend offer the same
+//! choices.
+//!
+//! The deadline is persisted as a UNIX timestamp so a restart before it
+//! passes keeps the remaining time rather than staying awake forever.
+//! Wake marked "session only" never survives a restart, and that wins over
+//! any deadline still pending.
+
+use std::time::Duration;
+
+/// One entry of the timed wake submenu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AwakePreset {
+    /// Menu item id
+    pub id: &'static str,
+    /// Menu item text
+    pub label: &'static str,
+    /// Time until wake turns off again, None to stay on until disabled
+    pub duration: Option<Duration>,
+}
+
+/// Presets in menu order
+pub const AWAKE_PRESETS: [AwakePreset; 4] = [
+    AwakePreset {
+        id: "awake_for_15m",
+        label: "15 minutes",
+        duration: Some(Duration::from_secs(15 * 60)),
+    },
+    AwakePreset {
+        id: "awake_for_1h",
+        label: "1 hour",
+        duration: Some(Duration::from_secs(60 * 60)),
+    },
+    AwakePreset {
+        id: "awake_for_2h",
+        label: "2 hours",
+        duration: Some(Duration::from_secs(2 * 60 * 60)),
+    },
+    AwakePreset {
+        id: "awake_until_disabled",
+        label: "Until I disable",
+        duration: None,
+    },
+];
+
+/// Pauses offered in the "Pause For" submenu, in menu order
+///
+/// A pause lets the machine sleep and turns wake back on afterwards, the
+/// reverse of a timed wake.
+pub const PAUSE_PRESETS: [AwakePreset; 3] = [
+    AwakePreset {
+        id: "pause_for_5m",
+        label: "5 minutes",
+        duration: Some(Duration::from_secs(5 * 60)),
+    },
+    AwakePreset {
+        id: "pause_for_15m",
+        label: "15 minutes",
+        duration: Some(Duration::from_secs(15 * 60)),
+    },
+    AwakePreset {
+        id: "pause_for_30m",
+        label: "30 minutes",
+        duration: Some(Duration::from_secs(30 * 60)),
+    },
+];
+
+/// Longest accepted timed wake
+///
+/// Also bounds a restored deadline: one further away than this can only
+/// come from a clock that went backwards since it was saved.
+pub const MAX_TIMED_AWAKE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// What a persisted auto-disable deadline means at startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoredDeadline {
+    /// No deadline was saved
+    None,
+    /// The deadline passed (or the clock is not trustworthy); start with
+    /// wake off
+    Expired,
+    /// Keep wake on and disable it after the remaining time
+    Remaining(Duration),
+    /// Wake was only meant for the previous session; start with wake off
+    SessionEnded,
+}
+
+/// Find the preset behind a menu item id
+pub fn preset_by_id(id: &str) -> Option<AwakePreset> {
+    AWAKE_PRESETS.into_iter().find(|preset| preset.id == id)
+}
+
+/// Find the pause preset behind a menu item id
+pub fn pause_preset_by_id(id: &str) -> Option<AwakePreset> {
+    PAUSE_PRESETS.into_iter().find(|preset| preset.id == id)
+}
+
+/// Check a timed wake duration chosen by the user
+pub fn validate_timed_awake(duration: Duration) -> Result<Duration, String> {
+    if duration.is_zero() {
+        return Err("Duration must be longer than zero".to_string());
+    }
+    if duration > MAX_TIMED_AWAKE {
+        return Err("Duration must be at most 24 hours".to_string());
+    }
+    Ok(duration)
+}
+
+/// UNIX timestamp `duration` after `now`
+pub fn deadline_after(now: i64, duration: Duration) -> i64 {
+    now.saturating_add(i64::try_from(duration.as_secs()).unwrap_or(i64::MAX))
+}
+
+/// Interpret a persisted deadline
+///
+/// ## Arguments
+/// * `disable_at` - Saved UNIX timestamp, None when wake had no limit
+/// * `now` - Current UNIX timestamp, None when the clock is before 1970
+///
+/// ## Returns
+/// `Expired` for a deadline in the past, an unreadable clock, or a
+/// deadline more than `MAX_TIMED_AWAKE` away (the clock went backwards)
+pub fn restore_deadline(disable_at: Option<i64>, now: Option<i64>) -> RestoredDeadline {
+    let Some(disable_at) = disable_at else {
+        return RestoredDeadline::None;
+    };
+    let Some(now) = now else {
+        return RestoredDeadline::Expired;
+    };
+    match u64::try_from(disable_at.saturating_sub(now)) {
+        Ok(secs) if secs > 0 && Duration::from_secs(secs) <= MAX_TIMED_AWAKE => {
+            RestoredDeadline::Remaining(Duration::from_secs(secs))
+        }
+        _ => RestoredDeadline::Expired,
+    }
+}
+
+/// Decide the wake state at startup from the persisted state
+///
+/// ## Arguments
+/// * `sleep_disabled` - Saved wake preference
+/// * `session_only` - Saved "only this session" flag
+/// * `disable_at` - Saved UNIX timestamp, None when wake had no limit
+/// * `now` - Current UNIX timestamp, None when the clock is before 1970
+///
+/// ## Returns
+/// `SessionEnded` whenever the session-only flag is set, even with a
+/// deadline still pending; otherwise `restore_deadline` for saved wake
+pub fn restore_on_startup(
+    sleep_disabled: bool,
+    session_only: bool,
+    disable_at: Option<i64>,
+    now: Option<i64>,
+) -> RestoredDeadline {
+    if session_only {
+        RestoredDeadline::SessionEnded
+    } else if sleep_disabled {
+        restore_deadline(disable_at, now)
+    } else {
+        RestoredDeadline::None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_preset_by_id() {
+        let preset = preset_by_id("awake_for_1h").unwrap();
+        assert_eq!(preset.duration, Some(Duration::from_secs(3600)));
+        assert_eq!(preset_by_id("awake_until_disabled").unwrap().duration, None);
+        assert_eq!(preset_by_id("toggle_sleep"), None);
+    }
+
+    #[test]
+    fn test_validate_timed_awake() {
+        assert!(validate_timed_awake(Duration::ZERO).is_err());
+        assert!(validate_timed_awake(MAX_TIMED_AWAKE + Duration::from_secs(1)).is_err());
+        assert_eq!(validate_timed_awake(MAX_TIMED_AWAKE), Ok(MAX_TIMED_AWAKE));
+    }
+
+    #[test]
+    fn test_restore_deadline() {
+        let now = 1_700_000_000;
+        assert_eq!(restore_deadline(None, Some(now)), RestoredDeadline::None);
+        assert_eq!(
+            restore_deadline(Some(now + 600), Some(now)),
+            RestoredDeadline::Remaining(Duration::from_secs(600))
+        );
+        assert_eq!(
+            restore_deadline(Some(now), Some(now)),
+            RestoredDeadline::Expired
+        );
+        assert_eq!(
+            restore_deadline(Some(now - 600), Some(now)),
+            RestoredDeadline::Expired
+        );
+        assert_eq!(
+            restore_deadline(Some(now + 600), None),
+            RestoredDeadline::Expired
+        );
+    }
+
+    #[test]
+    fn test_restore_deadline_treats_clock_going_back_as_expired() {
+        let saved_at = 1_700_000_000;
+        let disable_at = deadline_after(saved_at, Duration::from_secs(3600));
+        // Clock set back two days since the deadline was saved
+        let now = saved_at - 2 * 24 * 60 * 60;
+        assert_eq!(
+            restore_deadline(Some(disable_at), Some(now)),
+            RestoredDeadline::Expired
+        );
+    }
+
+    #[test]
+    fn test_session_only_takes_precedence_at_startup() {
+        let now = 1_700_000_000;
+        assert_eq!(
+            restore_on_startup(true, true, Some(now + 600), Some(now)),
+            RestoredDeadline::SessionEnded
+        );
+        assert_eq!(
+            restore_on_startup(true, false, Some(now + 600), Some(now)),
+            RestoredDeadline::Remaining(Duration::from_secs(600))
+        );
+        // The flag is cleared even when wake was already off
+        assert_eq!(
+            restore_on_startup(false, true, None, Some(now)),
+            RestoredDeadline::SessionEnded
+        );
+        assert_eq!(
+            restore_on_startup(false, false, Some(now + 600), Some(now)),
+            RestoredDeadline::None
+        );
+    }
+
+    #[test]
+    fn test_preset_ids_are_unique() {
+        let all: Vec<AwakePreset> = AWAKE_PRESETS.into_iter().chain(PAUSE_PRESETS).collect();
+        for (i, preset) in all.iter().enumerate() {
+            assert!(all[i + 1..].iter().all(|other| other.id != preset.id));
+        }
+    }
+
+    #[test]
+    fn test_pause_presets_have_valid_durations() {
+        for preset in PAUSE_PRESETS {
+            let duration = preset.duration.unwrap();
+            assert_eq!(validate_timed_awake(duration), Ok(duration));
+        }
+        assert_eq!(
+            pause_preset_by_id("pause_for_15m").unwrap().duration,
+            Some(Duration::from_secs(15 * 60))
+        );
+        assert_eq!(pause_preset_by_id("awake_for_15m"), None);
+    }
+}
@@ -3,13 +3,36 @@
 //! Pure functions for generating context-appropriate tooltip text.
 //!
 //! ## Design Intent
-//! Centralizes tooltip logic to ensure consistency and testability.
+//! Centralizes tooltip logic to ensure consistency and testability. The
+//! product name comes from `APP_NAME`; the `*_named` variants take it as a
+//! parameter so tests can pin the wording independently of the branding.
+//!
+//! ## Templates
+//! Users can replace the state line with their own template. `{app}`,
+//! `{state}`, `{mode}`, `{interval}` and `{label}` are filled in; any other
+//! `{...}` is left as written, so a typo shows up in the tooltip instead
+//! of failing. Without a template the built-in wording is used.
 //!
 //! ## Why separate
 //! Tooltip generation is pure business logic with clear inputs/outputs.
 //! Separating it from UI code allows unit testing and reuse.
 
+use super::duration::format_duration_human;
 use super::screen_mode::ScreenMode;
+use super::APP_NAME;
+use std::time::Duration;
+
+/// Prefix text with the instance label as `[label] `, if there is one
+///
+/// ## Design Intent
+/// Shared by tooltips and notifications, so several instances can be told
+/// apart the same way wherever they show text.
+pub fn label_prefixed(text: &str, label: Option<&str>) -> String {
+    match label {
+        Some(label) => format!("[{}] {}", label, text),
+        None => text.to_string(),
+    }
+}
 
 /// Tooltip text for tray icon
 ///
@@ -23,19 +46,125 @@ impl TooltipText {
     /// ## Arguments
     /// * `is_awake` - Whether system wake is currently active
     /// * `screen_mode` - Current screen mode preference
+    /// * `label` - Optional instance label, shown as a `[label] ` prefix
     ///
     /// ## Returns
     /// Human-readable tooltip text describing current state
-    pub fn for_state(is_awake: bool, screen_mode: ScreenMode) -> Self {
-        let text = if is_awake {
+    pub fn for_state(is_awake: bool, screen_mode: ScreenMode, label: Option<&str>) -> Self {
+        Self::for_state_named(APP_NAME, is_awake, screen_mode, label)
+    }
+
+    /// `for_state` with an explicit product name
+    pub fn for_state_named(
+        app_name: &str,
+        is_awake: bool,
+        screen_mode: ScreenMode,
+        label: Option<&str>,
+    ) -> Self {
+        Self::compose(app_name, Self::status(is_awake, screen_mode), label)
+    }
+
+    /// `for_state`, or the user's template with its placeholders filled in
+    ///
+    /// ## Arguments
+    /// * `template` - Persisted template, None for the built-in wording
+    /// * `interval` - Wake interval for `{interval}`
+    ///
+    /// ## Returns
+    /// The tooltip; the label is only shown where the template has `{label}`
+    pub fn for_state_templated(
+        template: Option<&str>,
+        is_awake: bool,
+        screen_mode: ScreenMode,
+        interval: Duration,
+        label: Option<&str>,
+    ) -> Self {
+        let Some(template) = template else {
+            return Self::for_state(is_awake, screen_mode, label);
+        };
+        TooltipText(fill_template(template, |name| match name {
+            "app" => Some(APP_NAME.to_string()),
+            "state" => Some(Self::status(is_awake, screen_mode).to_string()),
+            "mode" => Some(mode_name(screen_mode).to_string()),
+            "interval" => Some(format_duration_human(interval)),
+            "label" => Some(label.unwrap_or_default().to_string()),
+            _ => None,
+        }))
+    }
+
+    /// Built-in status wording
+    fn status(is_awake: bool, screen_mode: ScreenMode) -> &'static str {
+        if is_awake {
             match screen_mode {
-                ScreenMode::KeepScreenOn => "Tea - Screen & System On",
-                ScreenMode::AllowScreenOff => "Tea - System On, Screen Can Sleep",
+                ScreenMode::KeepScreenOn => "Screen & System On",
+                ScreenMode::AllowScreenOff => "System On, Screen Can Sleep",
+                ScreenMode::AwayMode => "Away Mode, System Working",
             }
         } else {
-            "Tea - Sleep prevention disabled"
+            "Sleep prevention disabled"
+        }
+    }
+
+    /// Tooltip while the master switch is off
+    ///
+    /// ## Arguments
+    /// * `label` - Optional instance label, shown as a `[label] ` prefix
+    pub fn master_off(label: Option<&str>) -> Self {
+        Self::master_off_named(APP_NAME, label)
+    }
+
+    /// `master_off` with an explicit product name
+    pub fn master_off_named(app_name: &str, label: Option<&str>) -> Self {
+        Self::compose(app_name, "Paused (master off)", label)
+    }
+
+    /// `[label] <app_name> - <status>`
+    fn compose(app_name: &str, status: &str, label: Option<&str>) -> Self {
+        TooltipText(label_prefixed(&format!("{} - {}", app_name, status), label))
+    }
+
+    /// Tooltip while the busy indicator is shown: the busy label itself
+    pub fn busy(label: &str) -> Self {
+        TooltipText(label.to_string())
+    }
+
+    /// Append a warning line (e.g. a failed startup check)
+    pub fn with_warning(self, warning: &str) -> Self {
+        TooltipText(format!("{}\n\u{26A0} {}", self.0, warning))
+    }
+
+    /// Append the time left before wake turns itself off, e.g. "(1h 12m left)"
+    pub fn with_time_left(self, left: Duration) -> Self {
+        TooltipText(format!("{} ({} left)", self.0, format_duration_human(left)))
+    }
+
+    /// Append the time left before a pause ends, e.g. "(resumes in 12m)"
+    pub fn with_resume_in(self, left: Duration) -> Self {
+        TooltipText(format!(
+            "{} (resumes in {})",
+            self.0,
+            format_duration_human(left)
+        ))
+    }
+
+    /// Append how long wake has been on, e.g. "Awake for 1h 23m"
+    ///
+    /// ## Design Intent
+    /// Rounded down to whole minutes, since the tooltip is only refreshed
+    /// once a minute and seconds would be stale at once.
+    pub fn with_elapsed(self, elapsed: Duration) -> Self {
+        let minutes = Duration::from_secs(elapsed.as_secs() / 60 * 60);
+        let elapsed = if minutes.is_zero() {
+            "<1m".to_string()
+        } else {
+            format_duration_human(minutes)
         };
-        TooltipText(text.to_string())
+        TooltipText(format!("{}\nAwake for {}", self.0, elapsed))
+    }
+
+    /// Append the main reason wake is on
+    pub fn with_reason(self, reason: &str) -> Self {
+        TooltipText(format!("{}\n{}", self.0, reason))
     }
 
     /// Get the string value
@@ -44,6 +173,49 @@ impl TooltipText {
     }
 }
 
+/// Screen mode name for `{mode}`, as in the tray menu
+fn mode_name(screen_mode: ScreenMode) -> &'static str {
+    match screen_mode {
+        ScreenMode::KeepScreenOn => "Keep Screen On",
+        ScreenMode::AllowScreenOff => "Allow Screen Off",
+        ScreenMode::AwayMode => "Away Mode",
+    }
+}
+
+/// Replace each `{name}` in `template` with `value(name)`
+///
+/// ## Design Intent
+/// Single pass, so substituted text is never expanded again. Names
+/// `value` does not know, and an unclosed `{`, stay literal.
+fn fill_template(template: &str, value: impl Fn(&str) -> Option<String>) -> String {
+    let mut filled = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        filled.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        match after.find('}') {
+            Some(close) => {
+                let name = &after[..close];
+                match value(name) {
+                    Some(text) => filled.push_str(&text),
+                    None => {
+                        filled.push('{');
+                        filled.push_str(name);
+                        filled.push('}');
+                    }
+                }
+                rest = &after[close + 1..];
+            }
+            None => {
+                filled.push_str(&rest[open..]);
+                rest = "";
+            }
+        }
+    }
+    filled.push_str(rest);
+    filled
+}
+
 impl AsRef<str> for TooltipText {
     fn as_ref(&self) -> &str {
         &self.0
@@ -56,26 +228,185 @@ mod tests {
 
     #[test]
     fn test_tooltip_when_disabled() {
-        let tooltip = TooltipText::for_state(false, ScreenMode::default());
-        assert_eq!(tooltip.as_str(), "Tea - Sleep prevention disabled");
+        let tooltip = TooltipText::for_state(false, ScreenMode::default(), None);
+        assert_eq!(
+            tooltip.as_str(),
+            format!("{} - Sleep prevention disabled", APP_NAME)
+        );
     }
 
     #[test]
     fn test_tooltip_when_awake_with_screen_on() {
-        let tooltip = TooltipText::for_state(true, ScreenMode::KeepScreenOn);
-        assert_eq!(tooltip.as_str(), "Tea - Screen & System On");
+        let tooltip = TooltipText::for_state(true, ScreenMode::KeepScreenOn, None);
+        assert_eq!(
+            tooltip.as_str(),
+            format!("{} - Screen & System On", APP_NAME)
+        );
     }
 
     #[test]
     fn test_tooltip_when_awake_with_screen_off_allowed() {
-        let tooltip = TooltipText::for_state(true, ScreenMode::AllowScreenOff);
-        assert_eq!(tooltip.as_str(), "Tea - System On, Screen Can Sleep");
+        let tooltip = TooltipText::for_state(true, ScreenMode::AllowScreenOff, None);
+        assert_eq!(
+            tooltip.as_str(),
+            format!("{} - System On, Screen Can Sleep", APP_NAME)
+        );
     }
 
     #[test]
     fn test_screen_mode_does_not_affect_disabled_tooltip() {
-        let tooltip1 = TooltipText::for_state(false, ScreenMode::KeepScreenOn);
-        let tooltip2 = TooltipText::for_state(false, ScreenMode::AllowScreenOff);
+        let tooltip1 = TooltipText::for_state(false, ScreenMode::KeepScreenOn, None);
+        let tooltip2 = TooltipText::for_state(false, ScreenMode::AllowScreenOff, None);
         assert_eq!(tooltip1, tooltip2);
     }
+
+    #[test]
+    fn test_label_prefixes_tooltip() {
+        let tooltip = TooltipText::for_state(true, ScreenMode::KeepScreenOn, Some("Build Server"));
+        assert_eq!(
+            tooltip.as_str(),
+            format!("[Build Server] {} - Screen & System On", APP_NAME)
+        );
+    }
+
+    #[test]
+    fn test_label_prefixes_disabled_tooltip() {
+        let tooltip = TooltipText::for_state(false, ScreenMode::default(), Some("VM 2"));
+        assert_eq!(
+            tooltip.as_str(),
+            format!("[VM 2] {} - Sleep prevention disabled", APP_NAME)
+        );
+    }
+
+    #[test]
+    fn test_master_off_tooltip() {
+        assert_eq!(
+            TooltipText::master_off(None).as_str(),
+            format!("{} - Paused (master off)", APP_NAME)
+        );
+        assert_eq!(
+            TooltipText::master_off(Some("Laptop")).as_str(),
+            format!("[Laptop] {} - Paused (master off)", APP_NAME)
+        );
+    }
+
+    #[test]
+    fn test_tooltip_with_warning() {
+        let tooltip = TooltipText::for_state(false, ScreenMode::AllowScreenOff, None)
+            .with_warning("Startup check failed: input");
+        assert_eq!(
+            tooltip.as_str(),
+            format!(
+                "{} - Sleep prevention disabled\n\u{26A0} Startup check failed: input",
+                APP_NAME
+            )
+        );
+    }
+
+    #[test]
+    fn test_tooltip_with_reason() {
+        let tooltip = TooltipText::for_state(true, ScreenMode::KeepScreenOn, None)
+            .with_reason("Enabled manually");
+        assert_eq!(
+            tooltip.as_str(),
+            format!("{} - Screen & System On\nEnabled manually", APP_NAME)
+        );
+    }
+
+    #[test]
+    fn test_tooltip_uses_given_name() {
+        assert_eq!(
+            TooltipText::for_state_named("Awake", true, ScreenMode::KeepScreenOn, Some("VM 2"))
+                .as_str(),
+            "[VM 2] Awake - Screen & System On"
+        );
+        assert_eq!(
+            TooltipText::master_off_named("Awake", None).as_str(),
+            "Awake - Paused (master off)"
+        );
+        assert_eq!(
+            TooltipText::for_state(false, ScreenMode::default(), None),
+            TooltipText::for_state_named(APP_NAME, false, ScreenMode::default(), None)
+        );
+    }
+
+    #[test]
+    fn test_template_placeholders_substituted() {
+        let tooltip = TooltipText::for_state_templated(
+            Some("{label}: {state} via {mode}, every {interval} ({app})"),
+            true,
+            ScreenMode::KeepScreenOn,
+            Duration::from_secs(60),
+            Some("Build Server"),
+        );
+        assert_eq!(
+            tooltip.as_str(),
+            format!(
+                "Build Server: Screen & System On via Keep Screen On, every 1m ({})",
+                APP_NAME
+            )
+        );
+    }
+
+    #[test]
+    fn test_template_keeps_unknown_placeholders_literal() {
+        let tooltip = TooltipText::for_state_templated(
+            Some("{host} is {state} {unclosed"),
+            false,
+            ScreenMode::default(),
+            Duration::from_secs(60),
+            None,
+        );
+        assert_eq!(
+            tooltip.as_str(),
+            "{host} is Sleep prevention disabled {unclosed"
+        );
+    }
+
+    #[test]
+    fn test_no_template_falls_back_to_built_in() {
+        assert_eq!(
+            TooltipText::for_state_templated(
+                None,
+                true,
+                ScreenMode::AllowScreenOff,
+                Duration::from_secs(60),
+                Some("VM 2"),
+            ),
+            TooltipText::for_state(true, ScreenMode::AllowScreenOff, Some("VM 2"))
+        );
+    }
+
+    #[test]
+    fn test_time_left_appended_to_state() {
+        let tooltip = TooltipText::for_state(true, ScreenMode::KeepScreenOn, None)
+            .with_time_left(Duration::from_secs(72 * 60));
+        assert_eq!(
+            tooltip.as_str(),
+            format!("{} - Screen & System On (1h 12m left)", APP_NAME)
+        );
+    }
+
+    #[test]
+    fn test_elapsed_appended_in_whole_minutes() {
+        let tooltip = TooltipText::for_state(true, ScreenMode::KeepScreenOn, None)
+            .with_elapsed(Duration::from_secs(83 * 60 + 59));
+        assert_eq!(
+            tooltip.as_str(),
+            format!("{} - Screen & System On\nAwake for 1h 23m", APP_NAME)
+        );
+        let tooltip = TooltipText::for_state(true, ScreenMode::KeepScreenOn, None)
+            .with_elapsed(Duration::from_secs(20));
+        assert!(tooltip.as_str().ends_with("\nAwake for <1m"));
+    }
+
+    #[test]
+    fn test_resume_in_appended_to_state() {
+        let tooltip = TooltipText::for_state(false, ScreenMode::KeepScreenOn, None)
+            .with_resume_in(Duration::from_secs(12 * 60));
+        assert_eq!(
+            tooltip.as_str(),
+            format!("{} - Sleep prevention disabled (resumes in 12m)", APP_NAME)
+        );
+    }
 }
@@ -0,0 +1,178 @@
+//! Tray appearance
+//!
+//! Pure selection of which icon, tooltip and title the tray shows.
+//!
+//! ## Design Intent
+//! Several layers affect the tray: the wake state, warnings and cosmetic
+//! overlays such as the busy indicator. Resolving them here keeps overlay
+//! and restore behavior testable without a tray.
+
+use super::tooltip::TooltipText;
+
+/// Which tray icon to show
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayIconKind {
+    /// Sleep allowed
+    Sleep,
+    /// Sleep prevented
+    Awake,
+    /// Sleep prevented until a timer turns it off
+    Timed,
+    /// Busy indicator overlay (e.g. recording)
+    Busy,
+}
+
+/// Small status badge drawn over the wake icon
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IconBadge {
+    /// A timer is pending (clock)
+    Timer,
+    /// Wake is on but may not be effective (warning triangle)
+    Degraded,
+}
+
+/// Everything the tray displays
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrayAppearance {
+    pub icon: TrayIconKind,
+    /// Badge composed onto the icon, if any
+    pub badge: Option<IconBadge>,
+    pub tooltip: TooltipText,
+    /// Text shown next to the icon where supported (macOS/Linux)
+    pub title: Option<String>,
+}
+
+impl TrayAppearance {
+    /// Appearance for the wake state alone
+    pub fn for_wake(is_awake: bool, tooltip: TooltipText) -> Self {
+        Self {
+            icon: if is_awake {
+                TrayIconKind::Awake
+            } else {
+                TrayIconKind::Sleep
+            },
+            badge: None,
+            tooltip,
+            title: None,
+        }
+    }
+
+    /// Add a status badge to the wake icon
+    ///
+    /// ## Design Intent
+    /// `Degraded` outranks `Timer` when both apply, since a warning needs
+    /// attention and a timer does not. A pending timer also switches the
+    /// awake icon to the timed one, so it still shows under a warning.
+    pub fn with_badge(self, timer: bool, degraded: bool) -> Self {
+        let badge = if degraded {
+            Some(IconBadge::Degraded)
+        } else if timer {
+            Some(IconBadge::Timer)
+        } else {
+            None
+        };
+        let icon = if timer && self.icon == TrayIconKind::Awake {
+            TrayIconKind::Timed
+        } else {
+            self.icon
+        };
+        Self {
+            icon,
+            badge,
+            ..self
+        }
+    }
+
+    /// Overlay the busy indicator, if active
+    ///
+    /// ## Design Intent
+    /// The overlay replaces icon, tooltip and title regardless of wake
+    /// state. With no busy label the wake appearance is returned unchanged,
+    /// which is how clearing the indicator restores it.
+    pub fn with_busy(self, busy_label: Option<&str>) -> Self {
+        match busy_label {
+            Some(label) => Self {
+                icon: TrayIconKind::Busy,
+                badge: None,
+                tooltip: TooltipText::busy(label),
+                title: Some(label.to_string()),
+            },
+            None => self,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ScreenMode;
+
+    fn awake_appearance() -> TrayAppearance {
+        TrayAppearance::for_wake(
+            true,
+            TooltipText::for_state(true, ScreenMode::KeepScreenOn, None),
+        )
+    }
+
+    #[test]
+    fn test_wake_state_selects_icon() {
+        assert_eq!(awake_appearance().icon, TrayIconKind::Awake);
+        let asleep = TrayAppearance::for_wake(
+            false,
+            TooltipText::for_state(false, ScreenMode::KeepScreenOn, None),
+        );
+        assert_eq!(asleep.icon, TrayIconKind::Sleep);
+        assert_eq!(asleep.title, None);
+    }
+
+    #[test]
+    fn test_busy_overlays_icon_tooltip_and_title() {
+        let busy = awake_appearance().with_busy(Some("Recording"));
+        assert_eq!(busy.icon, TrayIconKind::Busy);
+        assert_eq!(busy.tooltip.as_str(), "Recording");
+        assert_eq!(busy.title.as_deref(), Some("Recording"));
+    }
+
+    #[test]
+    fn test_degraded_badge_outranks_timer() {
+        assert_eq!(awake_appearance().with_badge(false, false).badge, None);
+        assert_eq!(
+            awake_appearance().with_badge(true, false).badge,
+            Some(IconBadge::Timer)
+        );
+        assert_eq!(
+            awake_appearance().with_badge(true, true).badge,
+            Some(IconBadge::Degraded)
+        );
+    }
+
+    #[test]
+    fn test_timer_selects_timed_icon() {
+        assert_eq!(
+            awake_appearance().with_badge(true, true).icon,
+            TrayIconKind::Timed
+        );
+        assert_eq!(
+            awake_appearance().with_badge(false, true).icon,
+            TrayIconKind::Awake
+        );
+        let asleep = TrayAppearance::for_wake(
+            false,
+            TooltipText::for_state(false, ScreenMode::KeepScreenOn, None),
+        );
+        assert_eq!(asleep.with_badge(true, false).icon, TrayIconKind::Sleep);
+    }
+
+    #[test]
+    fn test_busy_hides_badge() {
+        let busy = awake_appearance()
+            .with_badge(true, true)
+            .with_busy(Some("Recording"));
+        assert_eq!(busy.badge, None);
+    }
+
+    #[test]
+    fn test_cleared_busy_restores_wake_appearance() {
+        assert_eq!(awake_appearance().with_busy(None), awake_appearance());
+    }
+}
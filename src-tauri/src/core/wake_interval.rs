@@ -0,0 +1,237 @@
+//! Wake interval resolution
+//!
+//! Pure selection of how often the wake service acts for a screen mode.
+//!
+//! ## Design Intent
+//! The F15 cadence matters for KeepScreenOn but is irrelevant for the
+//! API-only AllowScreenOff path on Windows. A per-mode override lets users
+//! tune one mode without affecting the other, falling back to the global
+//! interval and then the built-in default. Every result is clamped to
+//! `MIN_WAKE_INTERVAL_SECS` so a tiny value cannot make the loop spin.
+//!
+//! ## Jitter
+//! Many machines started together (e.g. a VDI farm) would otherwise press
+//! in lockstep. With `interval_jitter` set, each wait is scaled by a
+//! random factor within `JITTER_FRACTION` of the interval, drawn from a
+//! small PRNG the wake service seeds per process. The floor still applies.
+
+use super::screen_mode::ScreenMode;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// Interval used when nothing is configured
+pub const DEFAULT_WAKE_INTERVAL_SECS: u64 = 60;
+
+/// Shortest interval the wake service will use
+pub const MIN_WAKE_INTERVAL_SECS: u64 = 5;
+
+/// Intervals offered in the tray menu, in seconds
+pub const WAKE_INTERVAL_PRESETS: [u64; 3] = [30, 60, 120];
+
+/// Largest share of the interval jitter adds or removes (10%)
+pub const JITTER_FRACTION: f64 = 0.1;
+
+/// Menu id prefix of the wake interval presets
+const MENU_ID_PREFIX: &str = "wake_interval_";
+
+/// Resolve the wake interval for `mode`
+///
+/// ## Arguments
+/// * `mode` - Active screen mode
+/// * `per_mode` - Per-mode overrides in seconds
+/// * `global_secs` - Global interval in seconds, if configured
+///
+/// ## Returns
+/// The first non-zero value of: per-mode override, global interval,
+/// `DEFAULT_WAKE_INTERVAL_SECS`, raised to at least
+/// `MIN_WAKE_INTERVAL_SECS`. Zero is ignored so a bad config can never
+/// turn the loop into a busy spin.
+pub fn resolve_wake_interval(
+    mode: ScreenMode,
+    per_mode: &HashMap<ScreenMode, u64>,
+    global_secs: Option<u64>,
+) -> Duration {
+    let secs = per_mode
+        .get(&mode)
+        .copied()
+        .filter(|&secs| secs > 0)
+        .or(global_secs.filter(|&secs| secs > 0))
+        .unwrap_or(DEFAULT_WAKE_INTERVAL_SECS);
+    Duration::from_secs(secs.max(MIN_WAKE_INTERVAL_SECS))
+}
+
+/// Small xorshift64* generator for interval jitter
+///
+/// ## Design Intent
+/// Jitter only has to spread presses out, not be unpredictable, so a few
+/// lines of arithmetic replace a dependency. The same seed always yields
+/// the same sequence, which keeps tests deterministic.
+#[derive(Debug, Clone)]
+pub struct JitterRng(u64);
+
+impl JitterRng {
+    /// Generator seeded with `seed` (any value, zero included)
+    pub fn new(seed: u64) -> Self {
+        // xorshift never leaves an all-zero state
+        Self(seed | 1)
+    }
+
+    /// Next value, uniform in [0, 1)
+    pub fn next_unit(&mut self) -> f64 {
+        let mut x = self.0;
+        x ^= x >> 12;
+        x ^= x << 25;
+        x ^= x >> 27;
+        self.0 = x;
+        (x.wrapping_mul(0x2545_F491_4F6C_DD1D) >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// Wait before the next wake cycle with jitter applied
+///
+/// ## Arguments
+/// * `interval` - Resolved wake interval
+/// * `unit` - Random value in [0, 1), e.g. from `JitterRng::next_unit`
+///
+/// ## Returns
+/// `interval` scaled into the band of `JITTER_FRACTION` around it, never
+/// below `MIN_WAKE_INTERVAL_SECS`
+pub fn jittered_interval(interval: Duration, unit: f64) -> Duration {
+    let factor = 1.0 + JITTER_FRACTION * (2.0 * unit.clamp(0.0, 1.0) - 1.0);
+    interval
+        .mul_f64(factor)
+        .max(Duration::from_secs(MIN_WAKE_INTERVAL_SECS))
+}
+
+/// Check a wake interval before it is stored
+///
+/// ## Returns
+/// The interval, or an error if it is below `MIN_WAKE_INTERVAL_SECS`
+pub fn validate_wake_interval(secs: u64) -> Result<u64, String> {
+    if secs < MIN_WAKE_INTERVAL_SECS {
+        return Err(format!(
+            "Wake interval must be at least {} seconds",
+            MIN_WAKE_INTERVAL_SECS
+        ));
+    }
+    Ok(secs)
+}
+
+/// Menu id of a wake interval preset
+pub fn wake_interval_menu_id(secs: u64) -> String {
+    format!("{}{}", MENU_ID_PREFIX, secs)
+}
+
+/// Preset for a menu id, None if the id is not a wake interval preset
+pub fn wake_interval_from_menu_id(id: &str) -> Option<u64> {
+    let secs = id.strip_prefix(MENU_ID_PREFIX)?.parse().ok()?;
+    WAKE_INTERVAL_PRESETS.contains(&secs).then_some(secs)
+}
+
+/// Menu text for a preset, checked when it is the active interval
+pub fn wake_interval_menu_text(secs: u64, active: bool) -> String {
+    let check = if active { "\u{2713} " } else { "" };
+    format!("{}{} seconds", check, secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_when_unconfigured() {
+        assert_eq!(
+            resolve_wake_interval(ScreenMode::KeepScreenOn, &HashMap::new(), None),
+            Duration::from_secs(DEFAULT_WAKE_INTERVAL_SECS)
+        );
+    }
+
+    #[test]
+    fn test_global_interval_applies_to_all_modes() {
+        for mode in ScreenMode::ALL {
+            assert_eq!(
+                resolve_wake_interval(mode, &HashMap::new(), Some(30)),
+                Duration::from_secs(30)
+            );
+        }
+    }
+
+    #[test]
+    fn test_per_mode_override_wins() {
+        let per_mode = HashMap::from([(ScreenMode::KeepScreenOn, 15)]);
+        assert_eq!(
+            resolve_wake_interval(ScreenMode::KeepScreenOn, &per_mode, Some(30)),
+            Duration::from_secs(15)
+        );
+        assert_eq!(
+            resolve_wake_interval(ScreenMode::AllowScreenOff, &per_mode, Some(30)),
+            Duration::from_secs(30)
+        );
+    }
+
+    #[test]
+    fn test_zero_falls_back() {
+        let per_mode = HashMap::from([(ScreenMode::KeepScreenOn, 0)]);
+        assert_eq!(
+            resolve_wake_interval(ScreenMode::KeepScreenOn, &per_mode, Some(0)),
+            Duration::from_secs(DEFAULT_WAKE_INTERVAL_SECS)
+        );
+    }
+
+    #[test]
+    fn test_jitter_stays_within_band_and_floor() {
+        let interval = Duration::from_secs(60);
+        let low = interval.mul_f64(1.0 - JITTER_FRACTION);
+        let high = interval.mul_f64(1.0 + JITTER_FRACTION);
+        let floor = Duration::from_secs(MIN_WAKE_INTERVAL_SECS);
+
+        for seed in [0, 1, 42, u64::MAX] {
+            let mut rng = JitterRng::new(seed);
+            let mut waits = Vec::new();
+            for _ in 0..10_000 {
+                let wait = jittered_interval(interval, rng.next_unit());
+                assert!(wait >= low && wait <= high, "{:?} outside band", wait);
+                waits.push(wait);
+
+                let short = jittered_interval(floor, rng.next_unit());
+                assert!(short >= floor && short <= floor.mul_f64(1.0 + JITTER_FRACTION));
+            }
+            // Presses actually spread over the band
+            assert!(waits.iter().any(|&wait| wait < interval.mul_f64(0.95)));
+            assert!(waits.iter().any(|&wait| wait > interval.mul_f64(1.05)));
+        }
+    }
+
+    #[test]
+    fn test_jitter_rng_is_deterministic_per_seed() {
+        let sample = |seed| {
+            let mut rng = JitterRng::new(seed);
+            (0..5).map(|_| rng.next_unit()).collect::<Vec<_>>()
+        };
+        assert_eq!(sample(7), sample(7));
+        assert_ne!(sample(7), sample(8));
+    }
+
+    #[test]
+    fn test_small_interval_clamped_to_minimum() {
+        assert_eq!(
+            resolve_wake_interval(ScreenMode::KeepScreenOn, &HashMap::new(), Some(1)),
+            Duration::from_secs(MIN_WAKE_INTERVAL_SECS)
+        );
+        assert!(validate_wake_interval(MIN_WAKE_INTERVAL_SECS - 1).is_err());
+        assert_eq!(validate_wake_interval(30), Ok(30));
+    }
+
+    #[test]
+    fn test_menu_ids_round_trip_presets_only() {
+        for secs in WAKE_INTERVAL_PRESETS {
+            assert_eq!(
+                wake_interval_from_menu_id(&wake_interval_menu_id(secs)),
+                Some(secs)
+            );
+        }
+        assert_eq!(wake_interval_from_menu_id(&wake_interval_menu_id(7)), None);
+        assert_eq!(wake_interval_from_menu_id("quit"), None);
+        assert_eq!(wake_interval_menu_text(60, true), "\u{2713} 60 seconds");
+    }
+}
@@ -0,0 +1,104 @@
+//! Simulated wake key
+//!
+//! Which key the wake service presses every cycle.
+//!
+//! ## Design Intent
+//! F15 is safe on most setups, but some remote desktop clients capture it
+//! and flicker. Users can pick from a small fixed set of keys that
+//! applications rarely bind, rather than any key, so a bad choice can never
+//! type text or trigger shortcuts. Scroll Lock is a toggle, so it is
+//! pressed twice to leave its state (and LED) unchanged.
+//!
+//! ## Platform Behavior
+//! - Windows, Linux: All keys
+//! - macOS: No Scroll Lock key; it is unsupported and maps to F15
+
+use enigo::Key;
+use serde::{Deserialize, Serialize};
+
+/// Key pressed by the wake service
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum WakeKey {
+    F13,
+    F14,
+    /// Historic behavior
+    #[default]
+    F15,
+    /// Pressed twice per cycle so the lock state is unchanged
+    ScrollLock,
+}
+
+impl WakeKey {
+    /// Every wake key, in menu order
+    pub const ALL: [WakeKey; 4] = [
+        WakeKey::F13,
+        WakeKey::F14,
+        WakeKey::F15,
+        WakeKey::ScrollLock,
+    ];
+
+    /// Name for logs and menus
+    pub fn label(self) -> &'static str {
+        match self {
+            WakeKey::F13 => "F13",
+            WakeKey::F14 => "F14",
+            WakeKey::F15 => "F15",
+            WakeKey::ScrollLock => "Scroll Lock",
+        }
+    }
+
+    /// Whether this platform has the key
+    pub fn is_supported(self) -> bool {
+        self != WakeKey::ScrollLock || !cfg!(target_os = "macos")
+    }
+
+    /// Clicks per wake cycle; toggle keys are clicked twice
+    pub fn clicks(self) -> usize {
+        match self {
+            WakeKey::ScrollLock => 2,
+            _ => 1,
+        }
+    }
+
+    /// The enigo key to click
+    pub fn to_enigo_key(self) -> Key {
+        match self {
+            WakeKey::F13 => Key::F13,
+            WakeKey::F14 => Key::F14,
+            WakeKey::F15 => Key::F15,
+            #[cfg(target_os = "windows")]
+            WakeKey::ScrollLock => Key::Scroll,
+            #[cfg(all(unix, not(target_os = "macos")))]
+            WakeKey::ScrollLock => Key::ScrollLock,
+            #[cfg(target_os = "macos")]
+            WakeKey::ScrollLock => Key::F15,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_f15() {
+        assert_eq!(WakeKey::default(), WakeKey::F15);
+        assert_eq!(WakeKey::default().to_enigo_key(), Key::F15);
+    }
+
+    #[test]
+    fn test_only_toggle_key_clicked_twice() {
+        for key in WakeKey::ALL {
+            let expected = if key == WakeKey::ScrollLock { 2 } else { 1 };
+            assert_eq!(key.clicks(), expected, "{:?}", key);
+        }
+    }
+
+    #[test]
+    fn test_serializes_by_name() {
+        let json = serde_json::to_string(&WakeKey::ScrollLock).unwrap();
+        assert_eq!(json, r#""ScrollLock""#);
+        let key: WakeKey = serde_json::from_str(r#""F13""#).unwrap();
+        assert_eq!(key, WakeKey::F13);
+    }
+}
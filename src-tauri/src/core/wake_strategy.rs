@@ -0,0 +1,90 @@
+//! Wake input strategy
+//!
+//! What the wake service simulates every cycle: a key press or a tiny
+//! pointer movement.
+//!
+//! ## Design Intent
+//! Key presses are the default and work almost everywhere, but some kiosk
+//! software only resets its idle timer on pointer movement. The mouse
+//! jiggle moves the cursor by one pixel and straight back, so it never
+//! drifts. Like the wake key, the choice is read every cycle.
+
+use serde::{Deserialize, Serialize};
+
+/// Prefix of the tray menu ids for the strategies
+const MENU_ID_PREFIX: &str = "wake_strategy_";
+
+/// Input simulated by the wake service
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, Debug, Default)]
+pub enum WakeStrategy {
+    /// Press the configured wake key
+    #[default]
+    KeyPress,
+    /// Move the pointer by one pixel and back
+    MouseJiggle,
+}
+
+impl WakeStrategy {
+    /// Every strategy, in menu order
+    pub const ALL: [WakeStrategy; 2] = [WakeStrategy::KeyPress, WakeStrategy::MouseJiggle];
+
+    /// Name for logs and menus
+    pub fn label(self) -> &'static str {
+        match self {
+            WakeStrategy::KeyPress => "Key Press",
+            WakeStrategy::MouseJiggle => "Mouse Jiggle",
+        }
+    }
+
+    /// Tray menu item id
+    pub fn menu_id(self) -> String {
+        let name = match self {
+            WakeStrategy::KeyPress => "key_press",
+            WakeStrategy::MouseJiggle => "mouse_jiggle",
+        };
+        format!("{}{}", MENU_ID_PREFIX, name)
+    }
+
+    /// Strategy for a menu id, None if the id is not a strategy item
+    pub fn from_menu_id(id: &str) -> Option<WakeStrategy> {
+        Self::ALL
+            .into_iter()
+            .find(|strategy| strategy.menu_id() == id)
+    }
+
+    /// Menu text, checked when it is the active strategy
+    pub fn menu_text(self, active: bool) -> String {
+        let check = if active { "\u{2713} " } else { "" };
+        format!("{}{}", check, self.label())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_is_key_press() {
+        assert_eq!(WakeStrategy::default(), WakeStrategy::KeyPress);
+    }
+
+    #[test]
+    fn test_menu_id_round_trip() {
+        for strategy in WakeStrategy::ALL {
+            assert_eq!(
+                WakeStrategy::from_menu_id(&strategy.menu_id()),
+                Some(strategy)
+            );
+        }
+        assert_eq!(WakeStrategy::from_menu_id("wake_interval_30"), None);
+    }
+
+    #[test]
+    fn test_menu_text_marks_active() {
+        assert_eq!(
+            WakeStrategy::MouseJiggle.menu_text(true),
+            "\u{2713} Mouse Jiggle"
+        );
+        assert_eq!(WakeStrategy::KeyPress.menu_text(false), "Key Press");
+    }
+}
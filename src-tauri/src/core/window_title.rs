@@ -0,0 +1,71 @@
+//! Window title matching
+//!
+//! Pure predicate deciding whether a window title matches a watch pattern.
+//!
+//! ## Design Intent
+//! Kept separate from window enumeration so matching rules can be tested
+//! without a desktop session.
+//!
+//! ## Rules
+//! - Case-insensitive substring match
+//! - Surrounding whitespace in the pattern is ignored
+//! - An empty pattern never matches (it would match every window)
+
+/// Returns true if `title` matches the watch `pattern`
+pub fn title_matches(title: &str, pattern: &str) -> bool {
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return false;
+    }
+    title.to_lowercase().contains(&pattern.to_lowercase())
+}
+
+/// Returns true if any of `titles` matches the watch `pattern`
+pub fn any_title_matches<'a, I>(titles: I, pattern: &str) -> bool
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    titles
+        .into_iter()
+        .any(|title| title_matches(title, pattern))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substring_matches() {
+        assert!(title_matches("Blender Render - 45%", "Render"));
+    }
+
+    #[test]
+    fn test_match_is_case_insensitive() {
+        assert!(title_matches("Blender RENDER - 45%", "render"));
+        assert!(title_matches("blender render", "RENDER"));
+    }
+
+    #[test]
+    fn test_non_matching_title() {
+        assert!(!title_matches("Untitled - Notepad", "Render"));
+    }
+
+    #[test]
+    fn test_empty_pattern_never_matches() {
+        assert!(!title_matches("Anything", ""));
+        assert!(!title_matches("Anything", "   "));
+    }
+
+    #[test]
+    fn test_pattern_whitespace_is_trimmed() {
+        assert!(title_matches("Render Progress", "  progress "));
+    }
+
+    #[test]
+    fn test_any_title_matches() {
+        let titles = ["Inbox - Mail", "Render Progress"];
+        assert!(any_title_matches(titles, "progress"));
+        assert!(!any_title_matches(titles, "backup"));
+        assert!(!any_title_matches(std::iter::empty(), "progress"));
+    }
+}
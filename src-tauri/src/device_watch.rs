@@ -0,0 +1,37 @@
+//! Device watcher
+//!
+//! Keeps the system awake while a dock or specific USB device is plugged
+//! in, matched by name or by `VID:PID`.
+//!
+//! ## Design Intent
+//! Meant for "stay awake while docked": a dock resetting its hub briefly
+//! drops every device behind it, which debouncing (see `core::automation`)
+//! rides out. Matching rules live in `core::device_match`.
+//!
+//! Polling is used rather than `WM_DEVICECHANGE` so the watcher needs no
+//! hidden window; at this interval the difference is not noticeable.
+//!
+//! ## Platform Behavior
+//! - Windows: Enumerates present devices via SetupAPI
+//! - Other: Enumeration unsupported, watcher never matches
+
+use crate::core::automation::{PatternCondition, PatternProbe};
+use crate::core::device_match::any_device_matches;
+use crate::platform;
+
+/// A connected device matches the configured pattern
+pub type DeviceCondition = PatternCondition<ConnectedDevices>;
+
+/// Samples the devices present on the system
+#[derive(Default)]
+pub struct ConnectedDevices;
+
+impl PatternProbe for ConnectedDevices {
+    const NAME: &'static str = "device";
+    const NOUN: &'static str = "device";
+
+    fn matches(&self, pattern: &str) -> Option<bool> {
+        let devices = platform::connected_devices()?;
+        Some(any_device_matches(&devices, pattern))
+    }
+}
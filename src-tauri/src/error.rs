@@ -2,7 +2,21 @@
 //!
 //! Provides structured, explicit error handling for all fallible operations.
 //! Errors include human-readable messages, technical causes, and recovery hints.
+//!
+//! ## Conversions
+//! `From` implementations for the underlying library errors let `?` build
+//! the matching variant with a generic message and a hint chosen from the
+//! cause. Call sites that can name the file or operation involved still
+//! build the variant themselves, since that context is what makes the
+//! message useful.
+//!
+//! ## Programmatic Handling
+//! `kind()` gives a `Copy` category (with disk-full and permission I/O
+//! failures split out) and `recovery_hint()` the hint, so callers can
+//! react without parsing the `Display` text. Commands return them to the
+//! frontend as an `ErrorReport`.
 
+use serde::Serialize;
 use std::fmt;
 
 /// Application-wide error type
@@ -16,6 +30,8 @@ pub enum AppError {
         message: String,
         cause: String,
         recovery_hint: &'static str,
+        /// Kind of the underlying I/O failure, `InvalidData` for bad read-backs
+        io_kind: std::io::ErrorKind,
     },
     /// Failed to serialize or deserialize state
     StateSerialization {
@@ -35,6 +51,12 @@ pub enum AppError {
         cause: String,
         recovery_hint: &'static str,
     },
+    /// Platform refused a display power request
+    DisplayControl {
+        message: String,
+        cause: String,
+        recovery_hint: &'static str,
+    },
 }
 
 impl fmt::Display for AppError {
@@ -44,6 +66,7 @@ impl fmt::Display for AppError {
                 message,
                 cause,
                 recovery_hint,
+                ..
             } => write!(
                 f,
                 "State I/O error: {} (cause: {}, hint: {})",
@@ -76,10 +99,270 @@ impl fmt::Display for AppError {
                 "Input simulation error: {} (cause: {}, hint: {})",
                 message, cause, recovery_hint
             ),
+            AppError::DisplayControl {
+                message,
+                cause,
+                recovery_hint,
+            } => write!(
+                f,
+                "Display control error: {} (cause: {}, hint: {})",
+                message, cause, recovery_hint
+            ),
         }
     }
 }
 
 impl std::error::Error for AppError {}
 
+/// Category of an `AppError`, for deciding how to react to it
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppErrorKind {
+    /// State I/O refused for lack of permissions
+    PermissionDenied,
+    /// State I/O failed because the disk is full
+    DiskFull,
+    /// Any other state I/O failure
+    StateIo,
+    StateSerialization,
+    IconProcessing,
+    InputSimulation,
+    DisplayControl,
+}
+
+impl AppError {
+    /// Category of this error
+    pub fn kind(&self) -> AppErrorKind {
+        match self {
+            AppError::StateIo { io_kind, .. } => match io_kind {
+                std::io::ErrorKind::PermissionDenied => AppErrorKind::PermissionDenied,
+                std::io::ErrorKind::StorageFull => AppErrorKind::DiskFull,
+                _ => AppErrorKind::StateIo,
+            },
+            AppError::StateSerialization { .. } => AppErrorKind::StateSerialization,
+            AppError::IconProcessing { .. } => AppErrorKind::IconProcessing,
+            AppError::InputSimulation { .. } => AppErrorKind::InputSimulation,
+            AppError::DisplayControl { .. } => AppErrorKind::DisplayControl,
+        }
+    }
+
+    /// Human-readable message and technical cause, without the hint
+    pub fn message(&self) -> String {
+        match self {
+            AppError::StateIo { message, cause, .. }
+            | AppError::StateSerialization { message, cause, .. }
+            | AppError::IconProcessing { message, cause, .. }
+            | AppError::InputSimulation { message, cause, .. }
+            | AppError::DisplayControl { message, cause, .. } => {
+                format!("{}: {}", message, cause)
+            }
+        }
+    }
+
+    /// What the user can do about this error
+    pub fn recovery_hint(&self) -> &'static str {
+        match self {
+            AppError::StateIo { recovery_hint, .. }
+            | AppError::StateSerialization { recovery_hint, .. }
+            | AppError::IconProcessing { recovery_hint, .. }
+            | AppError::InputSimulation { recovery_hint, .. }
+            | AppError::DisplayControl { recovery_hint, .. } => recovery_hint,
+        }
+    }
+}
+
+/// Structured error returned by commands
+///
+/// ## Design Intent
+/// Serializes as `{ code, message, hint }` so the frontend can branch on
+/// `code` (e.g. prompt for permissions vs. freeing disk space) and show
+/// `message` and `hint` as they are.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+pub struct ErrorReport {
+    pub code: AppErrorKind,
+    pub message: String,
+    pub hint: &'static str,
+}
+
+impl fmt::Display for ErrorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (hint: {})", self.message, self.hint)
+    }
+}
+
+impl From<AppError> for ErrorReport {
+    fn from(e: AppError) -> Self {
+        ErrorReport {
+            code: e.kind(),
+            message: e.message(),
+            hint: e.recovery_hint(),
+        }
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(e: std::io::Error) -> Self {
+        let recovery_hint = match e.kind() {
+            std::io::ErrorKind::PermissionDenied => {
+                "Ensure you have write permissions to the config directory."
+            }
+            std::io::ErrorKind::StorageFull => "Free some disk space and try again.",
+            _ => "Ensure the disk is healthy and the config directory is accessible.",
+        };
+        AppError::StateIo {
+            message: "State file I/O failed".to_string(),
+            cause: e.to_string(),
+            recovery_hint,
+            io_kind: e.kind(),
+        }
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(e: serde_json::Error) -> Self {
+        let recovery_hint = if e.is_io() {
+            "Ensure the disk is healthy and the config directory is accessible."
+        } else {
+            "Fix or delete the state file to start from defaults."
+        };
+        AppError::StateSerialization {
+            message: "Failed to process application state".to_string(),
+            cause: e.to_string(),
+            recovery_hint,
+        }
+    }
+}
+
+impl From<enigo::NewConError> for AppError {
+    fn from(e: enigo::NewConError) -> Self {
+        let recovery_hint = match e {
+            enigo::NewConError::NoPermission => {
+                "Grant the application permission to control the keyboard and mouse (e.g. Accessibility on macOS)."
+            }
+            _ => "Ensure the application has necessary permissions for input simulation.",
+        };
+        AppError::InputSimulation {
+            message: "Failed to initialize input simulator".to_string(),
+            cause: e.to_string(),
+            recovery_hint,
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, AppError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_io_error_converts_to_state_io() {
+        let denied = std::io::Error::from(std::io::ErrorKind::PermissionDenied);
+        match AppError::from(denied) {
+            AppError::StateIo {
+                cause,
+                recovery_hint,
+                ..
+            } => {
+                assert!(!cause.is_empty());
+                assert!(recovery_hint.contains("permissions"));
+            }
+            other => panic!("unexpected variant: {:?}", other),
+        }
+        let full = std::io::Error::from(std::io::ErrorKind::StorageFull);
+        assert!(matches!(
+            AppError::from(full),
+            AppError::StateIo { recovery_hint, .. } if recovery_hint.contains("disk space")
+        ));
+    }
+
+    fn state_io(io_kind: std::io::ErrorKind) -> AppError {
+        AppError::StateIo {
+            message: "Failed to write state".to_string(),
+            cause: "os error".to_string(),
+            recovery_hint: "hint",
+            io_kind,
+        }
+    }
+
+    #[test]
+    fn test_kind_of_each_variant() {
+        use std::io::ErrorKind;
+        assert_eq!(
+            state_io(ErrorKind::PermissionDenied).kind(),
+            AppErrorKind::PermissionDenied
+        );
+        assert_eq!(
+            state_io(ErrorKind::StorageFull).kind(),
+            AppErrorKind::DiskFull
+        );
+        assert_eq!(state_io(ErrorKind::NotFound).kind(), AppErrorKind::StateIo);
+        assert_eq!(
+            state_io(ErrorKind::InvalidData).kind(),
+            AppErrorKind::StateIo
+        );
+
+        let others = [
+            (
+                AppError::from(serde_json::from_str::<u32>("x").unwrap_err()),
+                AppErrorKind::StateSerialization,
+            ),
+            (
+                AppError::IconProcessing {
+                    message: String::new(),
+                    cause: String::new(),
+                    recovery_hint: "",
+                },
+                AppErrorKind::IconProcessing,
+            ),
+            (
+                AppError::from(enigo::NewConError::NoPermission),
+                AppErrorKind::InputSimulation,
+            ),
+            (
+                AppError::DisplayControl {
+                    message: String::new(),
+                    cause: String::new(),
+                    recovery_hint: "",
+                },
+                AppErrorKind::DisplayControl,
+            ),
+        ];
+        for (error, kind) in others {
+            assert_eq!(error.kind(), kind);
+        }
+    }
+
+    #[test]
+    fn test_error_report_is_structured() {
+        let report = ErrorReport::from(state_io(std::io::ErrorKind::StorageFull));
+        assert_eq!(report.code, AppErrorKind::DiskFull);
+        assert_eq!(report.message, "Failed to write state: os error");
+        assert_eq!(report.hint, "hint");
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["code"], "DiskFull");
+        assert_eq!(json["hint"], "hint");
+    }
+
+    #[test]
+    fn test_serde_error_converts_to_state_serialization() {
+        let e = serde_json::from_str::<u32>("{not json").unwrap_err();
+        let cause = e.to_string();
+        assert!(matches!(
+            AppError::from(e),
+            AppError::StateSerialization { cause: converted, .. } if converted == cause
+        ));
+    }
+
+    #[test]
+    fn test_enigo_error_converts_to_input_simulation() {
+        assert!(matches!(
+            AppError::from(enigo::NewConError::NoPermission),
+            AppError::InputSimulation { recovery_hint, .. } if recovery_hint.contains("permission")
+        ));
+        assert!(matches!(
+            AppError::from(enigo::NewConError::Reply),
+            AppError::InputSimulation { .. }
+        ));
+    }
+}
@@ -0,0 +1,82 @@
+//! Global toggle shortcut
+//!
+//! Registers a system-wide key combination that toggles sleep prevention,
+//! so wake can be switched without reaching for the tray icon.
+//!
+//! ## Design Intent
+//! The shortcut is stored in `AppState.toggle_shortcut`, None meaning the
+//! built-in default. Another application may already own the combination;
+//! registration then fails, a warning is logged and the tray keeps working
+//! on its own. The press itself is handled in `main` like a click on the
+//! toggle item.
+//!
+//! ## Side Effects
+//! Registers and unregisters OS-wide hotkeys through
+//! `tauri-plugin-global-shortcut`.
+
+use std::str::FromStr;
+use tauri::AppHandle;
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+/// Shortcut used when none is configured
+pub const DEFAULT_TOGGLE_SHORTCUT: &str = "Ctrl+Alt+A";
+
+/// Shortcut to register for a configured value
+pub fn effective_shortcut(configured: Option<&str>) -> &str {
+    configured.unwrap_or(DEFAULT_TOGGLE_SHORTCUT)
+}
+
+/// Check a shortcut chosen by the user
+///
+/// ## Returns
+/// The trimmed shortcut, None for empty or None input (use the default),
+/// or error string if it is not a valid key combination
+pub fn normalize_shortcut(shortcut: Option<&str>) -> Result<Option<String>, String> {
+    let Some(shortcut) = shortcut.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(None);
+    };
+    Shortcut::from_str(shortcut)
+        .map_err(|e| format!("Invalid shortcut \"{}\": {}", shortcut, e))?;
+    Ok(Some(shortcut.to_string()))
+}
+
+/// Register the toggle shortcut with the OS
+///
+/// ## Returns
+/// Error string if the shortcut is invalid or taken by another application
+pub fn register(app: &AppHandle, shortcut: &str) -> Result<(), String> {
+    app.global_shortcut()
+        .register(shortcut)
+        .map_err(|e| format!("Failed to register shortcut {}: {}", shortcut, e))?;
+    log::info!("Registered toggle shortcut {}", shortcut);
+    Ok(())
+}
+
+/// Release a previously registered toggle shortcut
+pub fn unregister(app: &AppHandle, shortcut: &str) {
+    if let Err(e) = app.global_shortcut().unregister(shortcut) {
+        log::debug!("Shortcut {} was not registered: {}", shortcut, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_shortcut_parses() {
+        assert_eq!(effective_shortcut(None), DEFAULT_TOGGLE_SHORTCUT);
+        assert!(Shortcut::from_str(DEFAULT_TOGGLE_SHORTCUT).is_ok());
+    }
+
+    #[test]
+    fn test_normalize_shortcut() {
+        assert_eq!(normalize_shortcut(None), Ok(None));
+        assert_eq!(normalize_shortcut(Some("  ")), Ok(None));
+        assert_eq!(
+            normalize_shortcut(Some(" Ctrl+Shift+F12 ")),
+            Ok(Some("Ctrl+Shift+F12".to_string()))
+        );
+        assert!(normalize_shortcut(Some("Ctrl+Nope")).is_err());
+    }
+}
@@ -3,7 +3,10 @@
 //! Loads and processes embedded icon data for tray icon display.
 //!
 //! ## Design Intent
-//! Isolates icon processing logic with explicit error handling.
+//! Isolates icon processing logic with explicit error handling. Every icon
+//! is bundled at 16, 32 and 64 pixels; callers pass the display scale and
+//! get the closest size back together with its dimensions, so high-DPI
+//! displays get a sharp icon and standard displays keep the 32x32 one.
 //!
 //! ## Side Effects
 //! None - pure image processing of embedded data.
@@ -11,13 +14,109 @@
 //! ## Failure Modes
 //! - Corrupted icon data: Returns IconProcessing error
 
+use crate::core::{IconBadge, TrayIconKind};
 use crate::error::{AppError, Result};
 
-/// Embedded icon for "sleep allowed" state (gray)
-static ICON_ALLOW: &[u8] = include_bytes!("../icons/icon-allow-32x32.png");
+/// Tray icon size in pixels at a scale of 1.0
+pub const DEFAULT_ICON_SIZE: u32 = 32;
 
-/// Embedded icon for "sleep blocked" state (green)
-static ICON_BLOCK: &[u8] = include_bytes!("../icons/icon-block-32x32.png");
+/// Bundled tray icon sizes in pixels, smallest first
+pub const ICON_SIZES: [u32; 3] = [16, 32, 64];
+
+/// Width and height of a badge at the default icon size
+const BADGE_SIZE: usize = 12;
+
+/// Dark outline and detail color for badges
+const BADGE_INK: [u8; 4] = [0x20, 0x20, 0x20, 0xFF];
+
+/// Embedded icons for "sleep allowed" state (gray), by size
+static ICON_ALLOW: [(u32, &[u8]); 3] = [
+    (16, include_bytes!("../icons/icon-allow-16x16.png")),
+    (32, include_bytes!("../icons/icon-allow-32x32.png")),
+    (64, include_bytes!("../icons/icon-allow-64x64.png")),
+];
+
+/// Embedded icons for "sleep blocked" state (green), by size
+static ICON_BLOCK: [(u32, &[u8]); 3] = [
+    (16, include_bytes!("../icons/icon-block-16x16.png")),
+    (32, include_bytes!("../icons/icon-block-32x32.png")),
+    (64, include_bytes!("../icons/icon-block-64x64.png")),
+];
+
+/// Embedded icons for "sleep blocked until a timer ends" state (blue), by size
+static ICON_TIMED: [(u32, &[u8]); 3] = [
+    (16, include_bytes!("../icons/icon-timed-16x16.png")),
+    (32, include_bytes!("../icons/icon-timed-32x32.png")),
+    (64, include_bytes!("../icons/icon-timed-64x64.png")),
+];
+
+/// RGBA pixel data with its width and height
+pub type IconRgba = (Vec<u8>, u32, u32);
+
+/// Which embedded icon to load
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IconState {
+    /// Sleep allowed
+    Allow,
+    /// Sleep prevented
+    Block,
+    /// Sleep prevented until a timer turns it off
+    Timed,
+}
+
+impl IconState {
+    /// Every embedded icon
+    pub const ALL: [IconState; 3] = [IconState::Allow, IconState::Block, IconState::Timed];
+
+    /// Icon for a plain wake state without a timer
+    pub fn for_awake(is_awake: bool) -> Self {
+        if is_awake {
+            IconState::Block
+        } else {
+            IconState::Allow
+        }
+    }
+
+    /// Name for error messages
+    fn label(self) -> &'static str {
+        match self {
+            IconState::Allow => "sleep",
+            IconState::Block => "awake",
+            IconState::Timed => "timed",
+        }
+    }
+
+    /// Embedded PNG data at a bundled size
+    fn embedded(self, size: u32) -> &'static [u8] {
+        let icons = match self {
+            IconState::Allow => &ICON_ALLOW,
+            IconState::Block => &ICON_BLOCK,
+            IconState::Timed => &ICON_TIMED,
+        };
+        icons
+            .iter()
+            .find(|(icon_size, _)| *icon_size == size)
+            .unwrap_or(&icons[1])
+            .1
+    }
+}
+
+/// Bundled icon size for a display scale factor
+///
+/// ## Design Intent
+/// Picks the smallest bundled size that covers the scaled default, so the
+/// OS only ever scales down. Scales past the largest size get the largest,
+/// and nonsensical scales fall back to the default.
+pub fn icon_size_for_scale(scale: f64) -> u32 {
+    if !scale.is_finite() || scale <= 0.0 {
+        return DEFAULT_ICON_SIZE;
+    }
+    let wanted = f64::from(DEFAULT_ICON_SIZE) * scale;
+    ICON_SIZES
+        .into_iter()
+        .find(|size| f64::from(*size) >= wanted - 0.5)
+        .unwrap_or(ICON_SIZES[ICON_SIZES.len() - 1])
+}
 
 /// Convert embedded icon data to RGBA format
 ///
@@ -25,54 +124,310 @@ static ICON_BLOCK: &[u8] = include_bytes!("../icons/icon-block-32x32.png");
 /// Prepares icon data for display by Tauri tray icon API.
 ///
 /// ## Arguments
-/// * `is_awake` - Whether to return the "awake" or "sleep" icon
+/// * `state` - Which embedded icon to return
+/// * `scale` - Display scale factor, 1.0 for a standard display
 ///
 /// ## Returns
-/// RGBA pixel data on success, AppError::IconProcessing on failure
+/// RGBA pixel data with its width and height on success,
+/// AppError::IconProcessing on failure
 ///
 /// ## Failure Modes
 /// - Corrupted embedded data: Returns IconProcessing error
-pub fn get_icon_rgba(is_awake: bool) -> Result<Vec<u8>> {
-    let icon_data = if is_awake { ICON_BLOCK } else { ICON_ALLOW };
+pub fn get_icon_rgba(state: IconState, scale: f64) -> Result<IconRgba> {
+    let size = icon_size_for_scale(scale);
+    let icon_data = state.embedded(size);
 
     let img = image::load_from_memory(icon_data).map_err(|e| AppError::IconProcessing {
         message: format!(
-            "Failed to load {} icon from embedded data",
-            if is_awake { "awake" } else { "sleep" }
+            "Failed to load {}x{} {} icon from embedded data",
+            size,
+            size,
+            state.label()
         ),
         cause: e.to_string(),
         recovery_hint: "This is a bug. Icon data may be corrupted.",
     })?;
 
     let rgba = img.into_rgba8();
-    Ok(rgba.into_raw())
+    let (width, height) = rgba.dimensions();
+    Ok((rgba.into_raw(), width, height))
+}
+
+/// Accent variant of the "awake" icon, used for the press heartbeat
+///
+/// ## Design Intent
+/// Derived from the awake icon by lightening every pixel halfway towards
+/// white, so no extra asset has to be shipped and it stays recognisable.
+///
+/// ## Returns
+/// RGBA pixel data with its dimensions on success,
+/// AppError::IconProcessing on failure
+pub fn get_accent_icon_rgba(scale: f64) -> Result<IconRgba> {
+    let (mut rgba, width, height) = get_icon_rgba(IconState::Block, scale)?;
+    for pixel in rgba.chunks_exact_mut(4) {
+        for channel in &mut pixel[..3] {
+            *channel = 255 - (255 - *channel) / 2;
+        }
+    }
+    Ok((rgba, width, height))
+}
+
+/// Busy indicator variant of the "sleep" icon
+///
+/// ## Design Intent
+/// Tints the icon red so "do not disturb" reads at a glance, derived in
+/// code like the accent icon.
+///
+/// ## Returns
+/// RGBA pixel data with its dimensions on success,
+/// AppError::IconProcessing on failure
+pub fn get_busy_icon_rgba(scale: f64) -> Result<IconRgba> {
+    let (mut rgba, width, height) = get_icon_rgba(IconState::Allow, scale)?;
+    for pixel in rgba.chunks_exact_mut(4) {
+        let brightest = pixel[0].max(pixel[1]).max(pixel[2]);
+        pixel[0] = brightest;
+        pixel[1] /= 3;
+        pixel[2] /= 3;
+    }
+    Ok((rgba, width, height))
+}
+
+/// Wake icon with a status badge composed onto it
+///
+/// ## Design Intent
+/// Badges are drawn in code over the base icon so every combination of
+/// state and badge is available without shipping an asset for each. The
+/// badge covers the same share of the icon at every size.
+///
+/// ## Arguments
+/// * `base` - Icon the badge is drawn onto
+/// * `badge` - Badge drawn in the bottom-right corner
+/// * `scale` - Display scale factor, 1.0 for a standard display
+///
+/// ## Returns
+/// RGBA pixel data with its dimensions on success,
+/// AppError::IconProcessing on failure
+pub fn get_composed_icon_rgba(
+    base: TrayIconKind,
+    badge: IconBadge,
+    scale: f64,
+) -> Result<IconRgba> {
+    let (mut rgba, width, height) = get_icon_rgba_for(base, scale)?;
+    let size = width as usize;
+    let badge_size = size * BADGE_SIZE / DEFAULT_ICON_SIZE as usize;
+    let origin = size - badge_size;
+    for y in 0..badge_size {
+        for x in 0..badge_size {
+            // Sample the badge on its default-size grid
+            let (bx, by) = (x * BADGE_SIZE / badge_size, y * BADGE_SIZE / badge_size);
+            if let Some(color) = badge_pixel(badge, bx, by) {
+                let offset = ((origin + y) * size + origin + x) * 4;
+                if let Some(pixel) = rgba.get_mut(offset..offset + 4) {
+                    pixel.copy_from_slice(&color);
+                }
+            }
+        }
+    }
+    Ok((rgba, width, height))
+}
+
+/// Color of a badge pixel in badge-local coordinates, None if transparent
+fn badge_pixel(badge: IconBadge, x: usize, y: usize) -> Option<[u8; 4]> {
+    let (fx, fy) = (x as f32 + 0.5, y as f32 + 0.5);
+    let center = BADGE_SIZE as f32 / 2.0;
+    match badge {
+        IconBadge::Timer => {
+            // White clock face with a dark rim and hands at 12 and 3
+            let distance = ((fx - center).powi(2) + (fy - center).powi(2)).sqrt();
+            let rim = distance > center - 1.5;
+            let hands = (x == 5 || x == 6) && (2..=6).contains(&y)
+                || (y == 5 || y == 6) && (5..=8).contains(&x);
+            if distance > center {
+                None
+            } else if rim || hands {
+                Some(BADGE_INK)
+            } else {
+                Some([0xFF, 0xFF, 0xFF, 0xFF])
+            }
+        }
+        IconBadge::Degraded => {
+            // Amber triangle, apex at the top, with a dark exclamation mark
+            let half_width = fy / 2.0 + 0.5;
+            if (fx - center).abs() > half_width {
+                None
+            } else if (x == 5 || x == 6) && ((4..=7).contains(&y) || y == 9) {
+                Some(BADGE_INK)
+            } else {
+                Some([0xFF, 0xB0, 0x00, 0xFF])
+            }
+        }
+    }
+}
+
+/// RGBA data and dimensions for a tray icon kind
+pub fn get_icon_rgba_for(kind: TrayIconKind, scale: f64) -> Result<IconRgba> {
+    match kind {
+        TrayIconKind::Sleep => get_icon_rgba(IconState::Allow, scale),
+        TrayIconKind::Awake => get_icon_rgba(IconState::Block, scale),
+        TrayIconKind::Timed => get_icon_rgba(IconState::Timed, scale),
+        TrayIconKind::Busy => get_busy_icon_rgba(scale),
+    }
+}
+
+/// Verify every embedded icon decodes to RGBA at its bundled size
+///
+/// ## Design Intent
+/// Used by the startup self-check so a broken build is reported at launch
+/// rather than at the first toggle.
+///
+/// ## Returns
+/// Ok(()) if all icons are usable, AppError::IconProcessing otherwise
+pub fn verify_icons() -> Result<()> {
+    for state in IconState::ALL {
+        for size in ICON_SIZES {
+            let scale = f64::from(size) / f64::from(DEFAULT_ICON_SIZE);
+            let (rgba, width, height) = get_icon_rgba(state, scale)?;
+            if width != size || height != size || rgba.len() != (size * size * 4) as usize {
+                return Err(AppError::IconProcessing {
+                    message: format!("{} icon is not {}x{}", state.label(), size, size),
+                    cause: format!("decoded to {}x{} ({} bytes)", width, height, rgba.len()),
+                    recovery_hint: "This is a bug. Icon data may be corrupted.",
+                });
+            }
+        }
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Scale factor that selects a bundled size
+    fn scale_for(size: u32) -> f64 {
+        f64::from(size) / f64::from(DEFAULT_ICON_SIZE)
+    }
+
     #[test]
     fn test_get_icon_for_awake_state() {
-        let result = get_icon_rgba(true);
-        assert!(result.is_ok());
-        let data = result.unwrap();
-        // 32x32 RGBA = 4096 bytes
-        assert_eq!(data.len(), 32 * 32 * 4);
+        for size in ICON_SIZES {
+            let (data, width, height) = get_icon_rgba(IconState::Block, scale_for(size)).unwrap();
+            assert_eq!((width, height), (size, size));
+            assert_eq!(data.len(), (size * size * 4) as usize);
+        }
     }
 
     #[test]
     fn test_get_icon_for_sleep_state() {
-        let result = get_icon_rgba(false);
-        assert!(result.is_ok());
-        let data = result.unwrap();
+        for size in ICON_SIZES {
+            let (data, width, height) = get_icon_rgba(IconState::Allow, scale_for(size)).unwrap();
+            assert_eq!((width, height), (size, size));
+            assert_eq!(data.len(), (size * size * 4) as usize);
+        }
+    }
+
+    #[test]
+    fn test_default_scale_keeps_32x32() {
+        let (data, width, height) = get_icon_rgba(IconState::Block, 1.0).unwrap();
+        assert_eq!((width, height), (32, 32));
+        // 32x32 RGBA = 4096 bytes
         assert_eq!(data.len(), 32 * 32 * 4);
     }
 
+    #[test]
+    fn test_icon_size_for_scale() {
+        assert_eq!(icon_size_for_scale(1.0), 32);
+        assert_eq!(icon_size_for_scale(0.5), 16);
+        assert_eq!(icon_size_for_scale(1.25), 64);
+        assert_eq!(icon_size_for_scale(2.0), 64);
+        assert_eq!(icon_size_for_scale(3.0), 64);
+        assert_eq!(icon_size_for_scale(0.0), 32);
+        assert_eq!(icon_size_for_scale(f64::NAN), 32);
+    }
+
     #[test]
     fn test_icons_are_different() {
-        let awake_icon = get_icon_rgba(true).unwrap();
-        let sleep_icon = get_icon_rgba(false).unwrap();
+        let awake_icon = get_icon_rgba(IconState::Block, 1.0).unwrap();
+        let sleep_icon = get_icon_rgba(IconState::Allow, 1.0).unwrap();
         assert_ne!(awake_icon, sleep_icon);
     }
+
+    #[test]
+    fn test_all_icon_states_load_distinct() {
+        for size in ICON_SIZES {
+            let icons: Vec<IconRgba> = IconState::ALL
+                .into_iter()
+                .map(|state| get_icon_rgba(state, scale_for(size)).unwrap())
+                .collect();
+            for (i, icon) in icons.iter().enumerate() {
+                assert_eq!(icon.0.len(), (size * size * 4) as usize);
+                for other in &icons[i + 1..] {
+                    assert_ne!(icon, other);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_accent_icon_differs_from_awake_icon() {
+        let accent = get_accent_icon_rgba(1.0).unwrap();
+        let awake = get_icon_rgba(IconState::Block, 1.0).unwrap();
+        assert_eq!(accent.0.len(), awake.0.len());
+        assert_ne!(accent, awake);
+    }
+
+    #[test]
+    fn test_busy_icon_is_distinct() {
+        let busy = get_busy_icon_rgba(1.0).unwrap();
+        assert_eq!(busy.0.len(), 32 * 32 * 4);
+        assert_ne!(busy, get_icon_rgba(IconState::Allow, 1.0).unwrap());
+        assert_ne!(busy, get_icon_rgba(IconState::Block, 1.0).unwrap());
+    }
+
+    #[test]
+    fn test_composed_icon_keeps_size() {
+        for size in ICON_SIZES {
+            for badge in [IconBadge::Timer, IconBadge::Degraded] {
+                for base in [
+                    TrayIconKind::Sleep,
+                    TrayIconKind::Awake,
+                    TrayIconKind::Timed,
+                ] {
+                    let (composed, width, height) =
+                        get_composed_icon_rgba(base, badge, scale_for(size)).unwrap();
+                    assert_eq!((width, height), (size, size));
+                    assert_eq!(composed.len(), (size * size * 4) as usize);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_badges_compose_differently() {
+        let timer = get_composed_icon_rgba(TrayIconKind::Awake, IconBadge::Timer, 1.0).unwrap();
+        let degraded =
+            get_composed_icon_rgba(TrayIconKind::Awake, IconBadge::Degraded, 1.0).unwrap();
+        assert_ne!(timer, degraded);
+        assert_ne!(timer, get_icon_rgba(IconState::Block, 1.0).unwrap());
+        assert_ne!(degraded, get_icon_rgba(IconState::Block, 1.0).unwrap());
+    }
+
+    #[test]
+    fn test_badge_leaves_rest_of_icon_untouched() {
+        for size in ICON_SIZES {
+            let (base, _, _) = get_icon_rgba(IconState::Block, scale_for(size)).unwrap();
+            let (composed, _, _) =
+                get_composed_icon_rgba(TrayIconKind::Awake, IconBadge::Timer, scale_for(size))
+                    .unwrap();
+            // The top half is outside the badge area
+            let half = (size / 2 * size * 4) as usize;
+            assert_eq!(composed[..half], base[..half]);
+            assert_ne!(composed, base);
+        }
+    }
+
+    #[test]
+    fn test_verify_icons() {
+        assert!(verify_icons().is_ok());
+    }
 }
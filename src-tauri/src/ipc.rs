@@ -0,0 +1,497 @@
+//! Local control endpoint
+//!
+//! Lets scripts that are not a Tauri frontend (e.g. home automation on a
+//! media PC) turn wake on and off while the tray app runs, using the line
+//! protocol in `core::ipc`. Also holds the client `tea --enable`,
+//! `--disable` and `--status` use to reach the running instance.
+//!
+//! ## Design Intent
+//! Off unless `enable_ipc` is set (the Command Line Control tray checkbox
+//! or `set_enable_ipc`), since any process of the user can then change
+//! wake. `sync_server` starts and stops it to match the setting, at
+//! startup and whenever it changes. Each request goes through
+//! `commands::control_request_impl`, the same logic as the tray. The
+//! transport is local only.
+//!
+//! ## Platform Behavior
+//! - Windows: Named pipe `\\.\pipe\tea-control-<session>-<hash>`, named
+//!   after the session and config directory (`persistence::ipc_pipe_name`),
+//!   remote clients refused
+//! - Other: Unix domain socket `tea.sock` in the config directory,
+//!   created readable and writable by the owner only
+//!
+//! ## Side Effects
+//! - Spawns a Tokio task serving connections while the endpoint is on
+//! - May enable/disable wake, persist state and refresh the tray
+//!
+//! ## Failure Modes
+//! - Endpoint cannot be created: Logged, the app runs without it
+//! - A client fails to connect or be accepted: Logged, the endpoint keeps
+//!   serving
+//! - Malformed or oversized request: Error reply; oversized closes the
+//!   connection
+
+use crate::commands::{self, AppStateManager};
+use crate::core::ipc::{
+    format_response, parse_request, parse_response, IpcRequest, MAX_REQUEST_BYTES,
+};
+use crate::core::ScreenMode;
+use crate::notify;
+use crate::tray;
+use std::sync::atomic::Ordering;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+/// Carries out one request, returning the wake state and screen mode after it
+pub type RequestHandler =
+    Arc<dyn Fn(IpcRequest) -> Result<(bool, ScreenMode), String> + Send + Sync>;
+
+/// Task serving the endpoint, None while it is off
+static SERVER: Mutex<Option<tokio::task::JoinHandle<()>>> = Mutex::new(None);
+
+/// Start or stop the control endpoint to match `enable_ipc`
+///
+/// ## Design Intent
+/// Called at startup and after every change of the setting, so turning it
+/// on or off applies without a restart. A server that ended on an error is
+/// started again.
+///
+/// ## Side Effects
+/// - Spawns or aborts the serving task; connections already accepted are
+///   served until the client hangs up
+/// - Unix: Removes the socket file when stopping
+pub fn sync_server(app: &AppHandle) {
+    let enabled = app
+        .state::<AppStateManager>()
+        .control
+        .enable_ipc
+        .load(Ordering::SeqCst);
+    let mut server = match SERVER.lock() {
+        Ok(server) => server,
+        Err(e) => {
+            log::error!("Mutex poisoned during control endpoint sync: {}", e);
+            return;
+        }
+    };
+    let serving = server.as_ref().is_some_and(|task| !task.is_finished());
+    if enabled && !serving {
+        *server = Some(spawn_server(app.clone()));
+    } else if !enabled {
+        if let Some(task) = server.take() {
+            task.abort();
+            #[cfg(unix)]
+            if let Err(e) = std::fs::remove_file(crate::persistence::ipc_socket_path()) {
+                log::debug!("Control socket not removed: {}", e);
+            }
+            log::info!("Control endpoint turned off");
+        }
+    }
+}
+
+/// Serve the control endpoint in the background
+///
+/// ## Returns
+/// The serving task, which runs until aborted or the endpoint fails
+fn spawn_server(app: AppHandle) -> tokio::task::JoinHandle<()> {
+    let handler: RequestHandler = Arc::new(move |request| {
+        let state = app.state::<AppStateManager>();
+        let was_awake = state.is_awake.load(Ordering::SeqCst);
+        let outcome = commands::control_request_impl(&state, request)?;
+        if outcome.0 != was_awake {
+            tray::refresh(&app);
+            notify::notify_state_change(&app, outcome.0, Some("control socket"));
+        }
+        Ok(outcome)
+    });
+
+    tokio::spawn(async move {
+        if let Err(e) = serve(handler).await {
+            log::error!("Control endpoint stopped: {}", e);
+        }
+    })
+}
+
+/// Accept connections on the control pipe until it can no longer be created
+#[cfg(windows)]
+async fn serve(handler: RequestHandler) -> std::io::Result<()> {
+    use tokio::net::windows::named_pipe::ServerOptions;
+
+    let name = crate::persistence::ipc_pipe_name();
+    let mut server = ServerOptions::new()
+        .first_pipe_instance(true)
+        .create(&name)?;
+    log::info!("Control endpoint listening on {}", name);
+    loop {
+        let connected = server.connect().await;
+        // A new instance takes the next client while this one is served,
+        // or replaces it if the client went away while connecting
+        let client = std::mem::replace(&mut server, ServerOptions::new().create(&name)?);
+        match connected {
+            Ok(()) => {
+                tokio::spawn(handle_connection(client, handler.clone()));
+            }
+            Err(e) => log::warn!("Control client failed to connect: {}", e),
+        }
+    }
+}
+
+/// Accept connections on the control socket until it fails
+#[cfg(unix)]
+async fn serve(handler: RequestHandler) -> std::io::Result<()> {
+    let path = crate::persistence::ipc_socket_path();
+    let listener = bind(&path)?;
+    log::info!("Control endpoint listening on {}", path.display());
+    serve_listener(listener, handler).await
+}
+
+/// Bind the control socket, owner access only
+///
+/// ## Design Intent
+/// The socket is created under a `0o177` umask so it is `0600` from the
+/// moment it exists; narrowing it with `chmod` after `bind` would leave a
+/// window in which other users could connect. The umask is process-wide,
+/// so files other threads create meanwhile are at most more private.
+///
+/// ## Side Effects
+/// Removes a socket file left by an earlier run; the single instance
+/// plugin guarantees that run is gone.
+#[cfg(unix)]
+fn bind(path: &std::path::Path) -> std::io::Result<tokio::net::UnixListener> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    // SAFETY: umask only swaps the process file mode mask
+    let previous = unsafe { libc::umask(0o177) };
+    let bound = tokio::net::UnixListener::bind(path);
+    // SAFETY: as above, restoring the mask that was in place
+    unsafe { libc::umask(previous) };
+    bound
+}
+
+/// Serve every connection accepted on `listener`
+///
+/// ## Failure Modes
+/// - Accept fails (e.g. out of file descriptors): Logged, retried after
+///   `ACCEPT_RETRY` so a persistent error does not spin
+#[cfg(unix)]
+async fn serve_listener(
+    listener: tokio::net::UnixListener,
+    handler: RequestHandler,
+) -> std::io::Result<()> {
+    loop {
+        match listener.accept().await {
+            Ok((stream, _)) => {
+                tokio::spawn(handle_connection(stream, handler.clone()));
+            }
+            Err(e) => {
+                log::warn!("Failed to accept control client: {}", e);
+                tokio::time::sleep(ACCEPT_RETRY).await;
+            }
+        }
+    }
+}
+
+/// Pause after a failed accept before trying again
+#[cfg(unix)]
+const ACCEPT_RETRY: Duration = Duration::from_millis(100);
+
+/// How long the command line client waits to connect, send and be answered
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Send one request to the running instance's control endpoint
+///
+/// ## Design Intent
+/// Lets `tea --enable` / `--disable` / `--status` act on the tray app
+/// instead of a state file it would only read on its next start. Blocking
+/// I/O, since the command line exits as soon as it has the reply.
+///
+/// ## Returns
+/// - `None`: Nothing listening, because Tea is not running or
+///   `enable_ipc` is off
+/// - `Some(Ok(..))`: Wake state and screen mode after the request
+/// - `Some(Err(..))`: The request failed, or the endpoint could not be
+///   reached or did not answer
+///
+/// ## Platform Behavior
+/// Either way the client gives up after `CLIENT_TIMEOUT` rather than hang
+/// on an instance that does not answer.
+/// - Windows: Opens the pipe named by `persistence::ipc_pipe_name`,
+///   waiting for a free instance while it is busy. Pipe handles have no
+///   read timeout, so the exchange runs on a worker thread
+/// - Other: Connects to `persistence::ipc_socket_path`, with
+///   `CLIENT_TIMEOUT` as the socket's read and write timeout
+pub fn send_request(request: IpcRequest) -> Option<Result<(bool, ScreenMode), String>> {
+    #[cfg(windows)]
+    let stream = connect_pipe(&crate::persistence::ipc_pipe_name());
+    #[cfg(unix)]
+    let stream = connect_socket(&crate::persistence::ipc_socket_path());
+
+    match stream {
+        #[cfg(windows)]
+        Ok(Some(stream)) => Some(exchange_within(stream, request, CLIENT_TIMEOUT)),
+        #[cfg(unix)]
+        Ok(Some(stream)) => Some(exchange(stream, request)),
+        Ok(None) => None,
+        Err(e) => Some(Err(format!("Failed to reach the running instance: {}", e))),
+    }
+}
+
+/// Open the control pipe as a client
+///
+/// While every pipe instance is taken by another client, waits up to
+/// `CLIENT_TIMEOUT` in total for one to be free.
+///
+/// ## Returns
+/// The pipe, or None if no instance serves it
+#[cfg(windows)]
+fn connect_pipe(name: &str) -> std::io::Result<Option<std::fs::File>> {
+    use windows::core::HSTRING;
+    use windows::Win32::Foundation::ERROR_PIPE_BUSY;
+    use windows::Win32::System::Pipes::WaitNamedPipeW;
+
+    let deadline = std::time::Instant::now() + CLIENT_TIMEOUT;
+    loop {
+        match std::fs::OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(name)
+        {
+            Ok(pipe) => return Ok(Some(pipe)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) if e.raw_os_error() == Some(ERROR_PIPE_BUSY.0 as i32) => {
+                let left = deadline.saturating_duration_since(std::time::Instant::now());
+                let wait_ms = u32::try_from(left.as_millis()).unwrap_or(u32::MAX);
+                // SAFETY: The name is a valid, NUL terminated wide string
+                // for the duration of the call
+                if wait_ms == 0
+                    || !unsafe { WaitNamedPipeW(&HSTRING::from(name), wait_ms) }.as_bool()
+                {
+                    return Err(e);
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Connect to the control socket as a client
+///
+/// ## Returns
+/// The stream, or None if there is no socket or only a stale one left by
+/// an instance that has exited
+#[cfg(unix)]
+fn connect_socket(
+    path: &std::path::Path,
+) -> std::io::Result<Option<std::os::unix::net::UnixStream>> {
+    use std::io::ErrorKind;
+
+    match std::os::unix::net::UnixStream::connect(path) {
+        Ok(stream) => {
+            stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+            stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+            Ok(Some(stream))
+        }
+        Err(e) if matches!(e.kind(), ErrorKind::NotFound | ErrorKind::ConnectionRefused) => {
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// `exchange` on a worker thread, giving up after `timeout`
+///
+/// ## Design Intent
+/// For streams without a read timeout of their own. On timeout the worker
+/// stays blocked until the process exits, which the command line does
+/// right after.
+#[cfg(any(windows, test))]
+fn exchange_within<S>(
+    stream: S,
+    request: IpcRequest,
+    timeout: Duration,
+) -> Result<(bool, ScreenMode), String>
+where
+    S: std::io::Read + std::io::Write + Send + 'static,
+{
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        let _ = tx.send(exchange(stream, request));
+    });
+    rx.recv_timeout(timeout).unwrap_or_else(|_| {
+        Err(format!(
+            "No reply from the running instance within {}s",
+            timeout.as_secs()
+        ))
+    })
+}
+
+/// Send `request` on `stream` and read the reply line
+fn exchange<S>(mut stream: S, request: IpcRequest) -> Result<(bool, ScreenMode), String>
+where
+    S: std::io::Read + std::io::Write,
+{
+    use std::io::BufRead;
+
+    writeln!(stream, "{}", request.command())
+        .and_then(|_| stream.flush())
+        .map_err(|e| format!("Failed to send request: {}", e))?;
+    let mut line = String::new();
+    std::io::BufReader::new(stream)
+        .read_line(&mut line)
+        .map_err(|e| format!("No reply from the running instance: {}", e))?;
+    parse_response(&line)
+}
+
+/// Answer requests on one connection until the client hangs up
+///
+/// ## Failure Modes
+/// - Request longer than `MAX_REQUEST_BYTES`: Error reply, connection closed
+/// - Invalid UTF-8 or I/O error: Connection closed
+async fn handle_connection<S>(stream: S, handler: RequestHandler)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let mut reader = BufReader::new(reader);
+    loop {
+        let mut line = String::new();
+        let read = (&mut reader)
+            .take(MAX_REQUEST_BYTES as u64)
+            .read_line(&mut line)
+            .await;
+        match read {
+            Ok(0) => return,
+            Ok(_) => {}
+            Err(e) => {
+                log::debug!("Control connection closed: {}", e);
+                return;
+            }
+        }
+
+        let too_long = !line.ends_with('\n') && line.len() >= MAX_REQUEST_BYTES;
+        let response = if too_long {
+            format_response(Err(format!(
+                "Request longer than {} bytes",
+                MAX_REQUEST_BYTES
+            )))
+        } else {
+            format_response(parse_request(&line).and_then(|request| handler(request)))
+        };
+        if let Err(e) = writer.write_all(response.as_bytes()).await {
+            log::debug!("Control connection closed: {}", e);
+            return;
+        }
+        if too_long {
+            return;
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::PermissionsExt;
+    use tokio::net::UnixStream;
+
+    #[tokio::test]
+    async fn test_status_round_trip_over_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tea.sock");
+        let handler: RequestHandler = Arc::new(|request| match request {
+            IpcRequest::Status => Ok((true, ScreenMode::KeepScreenOn)),
+            _ => Err("read only".to_string()),
+        });
+        let listener = bind(&path).unwrap();
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        tokio::spawn(serve_listener(listener, handler));
+
+        let stream = UnixStream::connect(&path).await.unwrap();
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = BufReader::new(reader).lines();
+
+        writer.write_all(b"status\n").await.unwrap();
+        assert_eq!(
+            lines.next_line().await.unwrap().as_deref(),
+            Some("{\"sleep_disabled\":true,\"screen_mode\":\"KeepScreenOn\"}")
+        );
+        writer.write_all(b"ENABLE\nsleep\n").await.unwrap();
+        assert_eq!(
+            lines.next_line().await.unwrap().as_deref(),
+            Some("{\"error\":\"read only\"}")
+        );
+        assert!(lines
+            .next_line()
+            .await
+            .unwrap()
+            .is_some_and(|line| line.contains("Unknown command")));
+
+        writer.write_all(&[b'x'; MAX_REQUEST_BYTES]).await.unwrap();
+        assert!(lines
+            .next_line()
+            .await
+            .unwrap()
+            .is_some_and(|line| line.contains("longer than")));
+        assert_eq!(lines.next_line().await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn test_client_reaches_a_listening_instance_only() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tea.sock");
+        assert!(connect_socket(&path).unwrap().is_none());
+
+        let handler: RequestHandler = Arc::new(|request| match request {
+            IpcRequest::Enable => Ok((true, ScreenMode::AllowScreenOff)),
+            _ => Err("enable only".to_string()),
+        });
+        let listener = bind(&path).unwrap();
+        let server = tokio::spawn(serve_listener(listener, handler));
+
+        let client_path = path.clone();
+        let replies = tokio::task::spawn_blocking(move || {
+            [IpcRequest::Enable, IpcRequest::Status].map(|request| {
+                let stream = connect_socket(&client_path).unwrap().unwrap();
+                exchange(stream, request)
+            })
+        })
+        .await
+        .unwrap();
+        assert_eq!(
+            replies,
+            [
+                Ok((true, ScreenMode::AllowScreenOff)),
+                Err("enable only".to_string())
+            ]
+        );
+
+        // The socket file an exited instance leaves behind is not a listener
+        server.abort();
+        let _ = server.await;
+        assert!(connect_socket(&path).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_exchange_gives_up_on_a_silent_instance() {
+        use std::io::Write;
+
+        let (client, server) = std::os::unix::net::UnixStream::pair().unwrap();
+        let started = std::time::Instant::now();
+        let reply = exchange_within(client, IpcRequest::Status, Duration::from_millis(200));
+        assert!(reply.is_err_and(|e| e.contains("No reply")));
+        assert!(started.elapsed() < Duration::from_secs(2));
+
+        let (client, mut answering) = std::os::unix::net::UnixStream::pair().unwrap();
+        answering
+            .write_all(b"{\"sleep_disabled\":false,\"screen_mode\":\"KeepScreenOn\"}\n")
+            .unwrap();
+        assert_eq!(
+            exchange_within(client, IpcRequest::Status, CLIENT_TIMEOUT),
+            Ok((false, ScreenMode::KeepScreenOn))
+        );
+        drop(server);
+    }
+}
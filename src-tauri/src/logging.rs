@@ -0,0 +1,464 @@
+//! Logging setup and debug capture
+//!
+//! Installs the application logger and lets support temporarily raise the
+//! log level while recording to a dedicated file.
+//!
+//! ## Design Intent
+//! Release builds on Windows have no console, so every record also goes to
+//! a daily log file next to the state file (see `core::log_rotation`);
+//! stderr output via `env_logger` is kept for debug builds and command
+//! line actions, which report to the launching console. The level comes
+//! from `RUST_LOG`, else `AppState.log_level`, else `info`, so users can
+//! switch to `debug` without recompiling.
+//!
+//! On top of that, a capture file: while a capture is running the global
+//! level is raised to trace and every record is also written to that file,
+//! so a user reproducing "it slept anyway" can hand over just the relevant
+//! trace without restarting with different settings.
+//!
+//! The previous level is held by a `LevelGuard`, so it is restored even if
+//! the capture is cancelled part way.
+//!
+//! ## Side Effects
+//! - Sets the global logger once at startup
+//! - Writes and prunes `tea-YYYY-MM-DD.log` files in the config directory
+//! - Creates capture files in the system temp directory
+
+use crate::core::log_rotation::{
+    files_to_prune, is_log_file_name, log_file_name, log_timestamp, MAX_LOG_BYTES,
+};
+use crate::persistence;
+use log::{LevelFilter, Log, Metadata, Record};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Longest capture window accepted, in seconds
+pub const MAX_CAPTURE_SECS: u64 = 600;
+
+/// File receiving every record while a capture is running
+static CAPTURE: Mutex<Option<File>> = Mutex::new(None);
+
+/// Seconds since the UNIX epoch, 0 if the clock is before it
+fn unix_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+/// Today's log file, switched at UTC midnight
+struct LogFile {
+    dir: PathBuf,
+    name: String,
+    file: File,
+    size: u64,
+}
+
+impl LogFile {
+    /// Open (appending) the file for the day containing `now`
+    ///
+    /// ## Side Effects
+    /// Creates `dir` if needed and prunes old files beyond `MAX_LOG_BYTES`
+    fn open(dir: &Path, now: u64) -> std::io::Result<Self> {
+        std::fs::create_dir_all(dir)?;
+        let name = log_file_name(now);
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(&name))?;
+        let size = file.metadata()?.len();
+        let log_file = Self {
+            dir: dir.to_path_buf(),
+            name,
+            file,
+            size,
+        };
+        log_file.prune();
+        Ok(log_file)
+    }
+
+    /// Append one line, rotating first if the day changed
+    ///
+    /// ## Design Intent
+    /// Today's file alone may not outgrow the cap either (e.g. a day at
+    /// trace level); it then starts over rather than filling the disk.
+    fn write_line(&mut self, now: u64, line: &str) -> std::io::Result<()> {
+        if log_file_name(now) != self.name {
+            *self = Self::open(&self.dir, now)?;
+        }
+        let len = line.len() as u64 + 1;
+        if self.size + len > MAX_LOG_BYTES {
+            self.file = File::create(self.dir.join(&self.name))?;
+            self.size = 0;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.size += len;
+        Ok(())
+    }
+
+    /// Delete the oldest log files until all of them fit in the cap
+    fn prune(&self) {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            return;
+        };
+        let files: Vec<(String, u64)> = entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let size = entry.metadata().ok()?.len();
+                Some((entry.file_name().into_string().ok()?, size))
+            })
+            .collect();
+        for name in files_to_prune(&files, &self.name, MAX_LOG_BYTES) {
+            // A file that cannot be removed now is retried at the next rotation
+            let _ = std::fs::remove_file(self.dir.join(name));
+        }
+    }
+}
+
+/// Level from `AppState.log_level`, `info` when unset
+///
+/// ## Returns
+/// The level, or error string naming an unknown value
+fn configured_level(value: Option<&str>) -> Result<LevelFilter, String> {
+    match value {
+        None => Ok(LevelFilter::Info),
+        Some(value) => value
+            .trim()
+            .parse()
+            .map_err(|_| format!("Unknown log_level \"{}\", using info", value)),
+    }
+}
+
+/// `env_logger` plus the daily log file and an optional capture file
+struct TeaLogger {
+    /// Applies the `RUST_LOG` / `log_level` filter; prints only if `stderr`
+    console: env_logger::Logger,
+    stderr: bool,
+    file: Option<Mutex<LogFile>>,
+}
+
+impl Log for TeaLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+
+    fn log(&self, record: &Record) {
+        if self.console.enabled(record.metadata()) {
+            if self.stderr {
+                self.console.log(record);
+            }
+            if let Some(file) = &self.file {
+                if let Ok(mut file) = file.lock() {
+                    let now = unix_secs();
+                    let line = format!(
+                        "[{} {} {}] {}",
+                        log_timestamp(now),
+                        record.level(),
+                        record.target(),
+                        record.args()
+                    );
+                    // A failed write must never take logging down with it
+                    let _ = file.write_line(now, &line);
+                }
+            }
+        }
+        if let Ok(mut capture) = CAPTURE.lock() {
+            if let Some(file) = capture.as_mut() {
+                // A failed write must never take logging down with it
+                let _ = writeln!(
+                    file,
+                    "[{:?} {} {}] {}",
+                    std::time::SystemTime::now()
+                        .duration_since(std::time::UNIX_EPOCH)
+                        .unwrap_or_default(),
+                    record.level(),
+                    record.target(),
+                    record.args()
+                );
+            }
+        }
+    }
+
+    fn flush(&self) {
+        self.console.flush();
+        if let Some(file) = &self.file {
+            if let Ok(mut file) = file.lock() {
+                let _ = file.file.flush();
+            }
+        }
+        if let Ok(mut capture) = CAPTURE.lock() {
+            if let Some(file) = capture.as_mut() {
+                let _ = file.flush();
+            }
+        }
+    }
+}
+
+/// Install the application logger
+///
+/// ## Design Intent
+/// Level: `RUST_LOG` if set, else `AppState.log_level`, else `info`. A log
+/// file that cannot be opened leaves stderr (if enabled) as the only
+/// output rather than failing startup.
+///
+/// ## Arguments
+/// * `stderr` - Also print records to stderr; debug builds always do
+pub fn init(stderr: bool) {
+    let configured = configured_level(persistence::read_state().log_level.as_deref());
+    let default_level = configured.clone().unwrap_or(LevelFilter::Info);
+    let console = env_logger::Builder::from_env(
+        env_logger::Env::default().default_filter_or(default_level.to_string()),
+    )
+    .build();
+    let level = console.filter();
+
+    let log_dir = persistence::log_dir();
+    let file = match LogFile::open(&log_dir, unix_secs()) {
+        Ok(file) => Some(Mutex::new(file)),
+        Err(e) => {
+            eprintln!("Failed to open log file in {}: {}", log_dir.display(), e);
+            None
+        }
+    };
+    let logger = TeaLogger {
+        console,
+        stderr: stderr || cfg!(debug_assertions),
+        file,
+    };
+    match log::set_boxed_logger(Box::new(logger)) {
+        Ok(()) => log::set_max_level(level),
+        Err(e) => eprintln!("Failed to install logger: {}", e),
+    }
+    if let Err(e) = configured {
+        log::warn!("{}", e);
+    }
+}
+
+/// Set the global log level
+///
+/// ## Returns
+/// The level that was in effect before
+pub fn set_log_level(level: LevelFilter) -> LevelFilter {
+    let previous = log::max_level();
+    log::set_max_level(level);
+    previous
+}
+
+/// Restores the previous log level when dropped
+pub struct LevelGuard {
+    previous: LevelFilter,
+}
+
+impl LevelGuard {
+    /// Switch to `level` until the guard is dropped
+    pub fn raise(level: LevelFilter) -> Self {
+        Self {
+            previous: set_log_level(level),
+        }
+    }
+}
+
+impl Drop for LevelGuard {
+    fn drop(&mut self) {
+        set_log_level(self.previous);
+    }
+}
+
+/// Record trace-level logs to `path` for `window`
+///
+/// ## Design Intent
+/// Only one capture runs at a time: a second one would restore the level
+/// out of order and split the trace across files.
+///
+/// ## Returns
+/// Ok(()) once the window has elapsed and the level is restored, or error
+/// string if a capture is already running or the file cannot be created
+pub async fn capture_to(path: &Path, window: Duration) -> Result<(), String> {
+    {
+        let mut capture = CAPTURE
+            .lock()
+            .map_err(|e| format!("Mutex poisoned during capture_debug_window: {}", e))?;
+        if capture.is_some() {
+            return Err("A debug capture is already running".to_string());
+        }
+        let file = File::create(path)
+            .map_err(|e| format!("Failed to create {}: {}", path.display(), e))?;
+        *capture = Some(file);
+    }
+
+    let guard = LevelGuard::raise(LevelFilter::Trace);
+    log::info!("Debug capture started for {:?}", window);
+    tokio::time::sleep(window).await;
+    log::info!("Debug capture finished");
+    drop(guard);
+
+    let file = CAPTURE
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during capture_debug_window: {}", e))?
+        .take();
+    if let Some(mut file) = file {
+        file.flush()
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// Last `count` lines of the newest log file, oldest first
+///
+/// ## Returns
+/// The lines, empty if there is no readable log file
+pub fn recent_log_lines(count: usize) -> Vec<String> {
+    recent_log_lines_in(&persistence::log_dir(), count)
+}
+
+/// Directory-parameterised core of `recent_log_lines`
+fn recent_log_lines_in(dir: &Path, count: usize) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    // Dated names sort oldest to newest
+    let newest = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| is_log_file_name(name))
+        .max();
+    let Some(contents) = newest.and_then(|name| std::fs::read(dir.join(name)).ok()) else {
+        return Vec::new();
+    };
+    let contents = String::from_utf8_lossy(&contents);
+    let lines: Vec<&str> = contents.lines().collect();
+    lines[lines.len().saturating_sub(count)..]
+        .iter()
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// Path for a new capture file in the system temp directory
+pub fn capture_file_path() -> PathBuf {
+    let stamp = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default();
+    std::env::temp_dir().join(format!("tea-debug-{}.log", stamp))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The log level and capture slot are process-wide
+    static GLOBAL_LOGGING: Mutex<()> = Mutex::new(());
+
+    fn paused_runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .start_paused(true)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_configured_level() {
+        assert_eq!(configured_level(None), Ok(LevelFilter::Info));
+        assert_eq!(configured_level(Some("Debug ")), Ok(LevelFilter::Debug));
+        assert!(configured_level(Some("loud")).is_err());
+    }
+
+    #[test]
+    fn test_log_file_rotates_daily_and_prunes() {
+        let dir = tempfile::tempdir().unwrap();
+        let day = 86_400;
+        // Older days that together exceed the cap
+        for (n, name) in ["tea-1970-01-02.log", "tea-1970-01-03.log"]
+            .iter()
+            .enumerate()
+        {
+            let old = std::fs::File::create(dir.path().join(name)).unwrap();
+            old.set_len(MAX_LOG_BYTES / 2 + n as u64).unwrap();
+        }
+        std::fs::write(dir.path().join("state.json"), "{}").unwrap();
+
+        let mut file = LogFile::open(dir.path(), 3 * day).unwrap();
+        file.write_line(3 * day, "first").unwrap();
+        assert!(!dir.path().join("tea-1970-01-02.log").exists());
+        assert!(dir.path().join("tea-1970-01-03.log").exists());
+
+        file.write_line(4 * day + 5, "next day").unwrap();
+        let today = std::fs::read_to_string(dir.path().join("tea-1970-01-05.log")).unwrap();
+        assert_eq!(today, "next day\n");
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("tea-1970-01-04.log")).unwrap(),
+            "first\n"
+        );
+        assert!(dir.path().join("state.json").exists());
+    }
+
+    #[test]
+    fn test_recent_lines_come_from_newest_log() {
+        let dir = tempfile::tempdir().unwrap();
+        assert!(recent_log_lines_in(dir.path(), 2).is_empty());
+
+        std::fs::write(dir.path().join("tea-1970-01-02.log"), "old\n").unwrap();
+        std::fs::write(dir.path().join("tea-1970-01-03.log"), "a\nb\nc\n").unwrap();
+        std::fs::write(dir.path().join("state.json"), "{}").unwrap();
+        assert_eq!(recent_log_lines_in(dir.path(), 2), vec!["b", "c"]);
+        assert_eq!(recent_log_lines_in(dir.path(), 10), vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn test_level_guard_restores_previous_level() {
+        let _lock = GLOBAL_LOGGING.lock().unwrap();
+        set_log_level(LevelFilter::Warn);
+        {
+            let _guard = LevelGuard::raise(LevelFilter::Trace);
+            assert_eq!(log::max_level(), LevelFilter::Trace);
+        }
+        assert_eq!(log::max_level(), LevelFilter::Warn);
+    }
+
+    #[test]
+    fn test_capture_raises_then_restores_level() {
+        let _lock = GLOBAL_LOGGING.lock().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("capture.log");
+        set_log_level(LevelFilter::Info);
+
+        paused_runtime().block_on(async {
+            let capture = tokio::spawn({
+                let path = path.clone();
+                async move { capture_to(&path, Duration::from_secs(30)).await }
+            });
+            tokio::time::sleep(Duration::from_secs(10)).await;
+            assert_eq!(log::max_level(), LevelFilter::Trace);
+            capture.await.unwrap().unwrap();
+        });
+
+        assert_eq!(log::max_level(), LevelFilter::Info);
+        assert!(path.exists());
+        assert!(CAPTURE.lock().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_overlapping_capture_is_rejected() {
+        let _lock = GLOBAL_LOGGING.lock().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        set_log_level(LevelFilter::Info);
+
+        paused_runtime().block_on(async {
+            let first = tokio::spawn({
+                let path = dir.path().join("first.log");
+                async move { capture_to(&path, Duration::from_secs(30)).await }
+            });
+            tokio::time::sleep(Duration::from_secs(1)).await;
+            let second = capture_to(&dir.path().join("second.log"), Duration::from_secs(5)).await;
+            assert!(second.is_err());
+            assert_eq!(log::max_level(), LevelFilter::Trace);
+            first.await.unwrap().unwrap();
+        });
+
+        assert_eq!(log::max_level(), LevelFilter::Info);
+    }
+}
@@ -7,6 +7,9 @@
 //! - Persistence: State file I/O
 //! - Platform: OS-specific abstractions (Windows display control)
 //! - Wake Service: Background task for input simulation
+//! - Once: Headless `--once --for <duration>` session (no tray)
+//! - CLI: Headless `--enable` / `--disable` / `--status` (no tray)
+//! - Status Window: Optional window mirroring the tray state
 //! - UI: Tauri setup and menu event handling (this file)
 //!
 //! ## Design Principles
@@ -18,62 +21,420 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 #![deny(warnings)]
 
+mod autostart;
+mod awake_cap;
+mod battery_guard;
+mod cli;
 mod commands;
 mod core;
+mod device_watch;
 mod error;
+mod hotkey;
 mod icon;
+mod ipc;
+mod logging;
+mod net_watch;
+mod notify;
+mod once;
 mod persistence;
 mod platform;
+mod power_monitor;
+mod process_watch;
+mod remote_watch;
+mod schedule_monitor;
+mod scheduler;
+mod self_check;
+mod service_manager;
+mod session_watch;
+mod settings;
+mod status_window;
+#[cfg(test)]
+mod test_support;
+mod tray;
 mod wake_service;
+mod watcher;
+mod window_watch;
 
+use crate::autostart::Autostart;
 use crate::commands::AppStateManager;
-use crate::core::{ScreenMode, TooltipText};
-use crate::persistence::{read_state, AppState};
+use crate::core::profile;
+use crate::core::timed_awake::{
+    self, pause_preset_by_id, preset_by_id, RestoredDeadline, AWAKE_PRESETS, PAUSE_PRESETS,
+};
+use crate::core::wake_interval;
+use crate::core::{menu_layout, MenuEntry, MenuOptions, ScreenMode, TooltipText, WakeStrategy};
+use crate::persistence::AppState;
+use crate::service_manager::ServiceManager;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
-use tauri::{image::Image, menu::{MenuBuilder, MenuId, MenuItemBuilder}, tray::TrayIconBuilder, Manager};
-use tauri_plugin_autostart::{MacosLauncher, ManagerExt};
+use std::time::{Duration, Instant};
+use tauri::{
+    image::Image,
+    menu::{MenuBuilder, MenuId, MenuItemBuilder, SubmenuBuilder},
+    tray::TrayIconBuilder,
+    Manager,
+};
+use tauri_plugin_autostart::MacosLauncher;
+use tauri_plugin_dialog::{DialogExt, MessageDialogButtons, MessageDialogKind};
+use tauri_plugin_global_shortcut::ShortcutState;
+
+/// How long a "click again to disable" prompt stays valid
+const OVERRIDE_CONFIRM_WINDOW: Duration = Duration::from_secs(10);
+
+/// Upper bound on wake service cleanup when quitting
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Length of a debug capture started from the Diagnostics menu, in seconds
+const MENU_DEBUG_CAPTURE_SECS: u64 = 60;
 
 #[tokio::main]
 async fn main() {
+    // Select the config directory before anything reads state or opens the log
+    if let Some(dir) = core::config_dir_arg(std::env::args().skip(1)) {
+        persistence::set_config_dir_override(dir);
+    }
+
+    // Command line actions report to the launching console, so attach it
+    // before the logger binds stderr
+    let action = core::parse_cli_args(std::env::args().skip(1));
+    let console = !matches!(action, Ok(core::CliAction::Tray));
+    if console {
+        platform::attach_parent_console();
+    }
+
     // Initialize logging
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    logging::init(console);
+
+    let dry_run = core::dry_run_arg(std::env::args().skip(1));
+    let backend = if dry_run {
+        log::warn!("Dry run: no input or power calls will be made");
+        wake_service::WakeBackend::dry_run(Arc::new(wake_service::log_dry_run))
+    } else {
+        wake_service::WakeBackend::platform()
+    };
+
+    match action {
+        Ok(core::CliAction::Once(duration)) => {
+            std::process::exit(once::run(duration, backend).await)
+        }
+        Ok(core::CliAction::Tray) => {}
+        Ok(action) => std::process::exit(cli::run(action)),
+        Err(e) => {
+            log::error!("{}", e);
+            std::process::exit(once::EXIT_USAGE);
+        }
+    }
 
     log::info!("Starting Tea application");
 
-    // Load persisted state
-    let state = read_state();
+    // Carry settings over from builds that stored them elsewhere
+    if let Err(e) = persistence::import_legacy() {
+        log::warn!("Failed to import legacy settings: {}", e);
+    }
+
+    // Load persisted state; strict mode refuses to fall back to defaults
+    let mut state = match persistence::read_startup_state(persistence::strict_config_from_env()) {
+        Ok(state) => state,
+        Err(e) => {
+            log::error!(
+                "Config could not be loaded and {} is set, aborting: {}",
+                persistence::STRICT_CONFIG_ENV,
+                e
+            );
+            std::process::exit(once::EXIT_CONFIG_INVALID);
+        }
+    };
     log::info!(
         "Loaded state: sleep_disabled={}, screen_mode={:?}",
         state.sleep_disabled,
         state.screen_mode
     );
 
+    // Session-only wake ends with the session; otherwise a timed wake either
+    // ran out while Tea was not running or resumes with the time it has left
+    let restored_deadline = timed_awake::restore_on_startup(
+        state.sleep_disabled,
+        state.session_only,
+        state.disable_at,
+        commands::unix_now(),
+    );
+    // Persisted in setup, which a second instance never reaches
+    let wake_ended = match restored_deadline {
+        RestoredDeadline::Expired => Some("Timed sleep prevention ended while not running"),
+        RestoredDeadline::SessionEnded => Some("Sleep prevention was for the last session only"),
+        RestoredDeadline::None | RestoredDeadline::Remaining(_) => None,
+    };
+    if let Some(reason) = wake_ended {
+        log::info!("{}, starting with sleep allowed", reason);
+        state.sleep_disabled = false;
+        state.disable_at = None;
+        state.session_only = false;
+        state.resume_at = None;
+    }
+
+    // Likewise a pause either ended while Tea was not running or resumes
+    // with the time it has left
+    let restored_pause = if state.sleep_disabled {
+        RestoredDeadline::None
+    } else {
+        timed_awake::restore_deadline(state.resume_at, commands::unix_now())
+    };
+    if restored_pause == RestoredDeadline::Expired {
+        log::info!("Pause ended while not running, starting with sleep prevented");
+        state.sleep_disabled = true;
+        state.resume_at = None;
+    }
+
     // Shared state for wake control
-    let is_awake = Arc::new(AtomicBool::new(state.sleep_disabled));
-    let screen_mode = Arc::new(Mutex::new(state.screen_mode));
+    // Master off overrides a persisted wake preference
+    let is_awake = Arc::new(AtomicBool::new(state.sleep_disabled && !state.master_off));
+    let startup_check = self_check::run();
+    // Schedule, battery floor and max session length were sanitized on load
+    let wake =
+        settings::WakeSettings::from_state(&state, commands::compute_sleep_risk(state.screen_mode));
+    let power = settings::PowerSettings::from_state(&state);
+    let automation = settings::AutomationSettings::from_state(&state);
+    let tray_settings = settings::TraySettings::from_state(&state);
+    let control = settings::ControlSettings::from_state(&state);
+    let scheduled_actions = Arc::new(scheduler::ScheduledActions::new());
+    // A wake session restored from the last run starts the clock now
+    let awake_since = is_awake
+        .load(Ordering::SeqCst)
+        .then(tokio::time::Instant::now);
+    scheduled_actions.set_suspended(state.master_off);
 
-    // Clone for Tauri builder closure
+    // Decide the initial pause before any wake service can start
+    power_monitor::refresh(&power.only_on_ac, &automation.muted, &power.paused);
+
+    // Clone for Tauri builder closure; the groups move into the manager
+    let only_on_ac = power.only_on_ac.clone();
+    let automation_muted = automation.muted.clone();
+    let power_paused = power.paused.clone();
+    let toggle_shortcut = control.toggle_shortcut.clone();
     let is_awake_clone = is_awake.clone();
-    let screen_mode_clone = screen_mode.clone();
+    let screen_mode_clone = wake.screen_mode.clone();
     let initial_state = state;
+    // Separate from the menu's: a hotkey press does not confirm a click
+    let hotkey_confirm_pending: Mutex<Option<Instant>> = Mutex::new(None);
 
+    // Single instance goes first: a second launch hands its arguments to the
+    // running instance and exits before any other plugin or setup runs
     let result = tauri::Builder::default()
+        .plugin(tauri_plugin_single_instance::init(|app, argv, _cwd| {
+            handle_second_instance(app, argv)
+        }))
         .plugin(tauri_plugin_autostart::init(
             MacosLauncher::LaunchAgent,
             None,
         ))
+        .plugin(
+            tauri_plugin_global_shortcut::Builder::new()
+                .with_handler(move |app, _shortcut, event| {
+                    if event.state == ShortcutState::Pressed {
+                        handle_toggle_shortcut(app, &hotkey_confirm_pending);
+                    }
+                })
+                .build(),
+        )
+        .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_dialog::init())
         .manage(AppStateManager {
             is_awake: is_awake_clone.clone(),
-            screen_mode: screen_mode_clone.clone(),
+            awake_since: Arc::new(Mutex::new(awake_since)),
+            auto_disable_timer: Arc::new(Mutex::new(None)),
+            pause_timer: Arc::new(Mutex::new(None)),
+            scheduled_actions,
+            service: Arc::new(ServiceManager::new(
+                backend,
+                is_awake_clone.clone(),
+                wake.screen_mode.clone(),
+                power.paused.clone(),
+                wake.log_each_iteration.clone(),
+                wake.wake_key.clone(),
+                wake.wake_strategy.clone(),
+            )),
+            toggle_commit: Arc::new(commands::ToggleCommit::default()),
+            wake,
+            power,
+            automation,
+            tray: tray_settings,
+            control,
         })
+        .manage(startup_check)
         .invoke_handler(tauri::generate_handler![
             commands::toggle_sleep,
+            commands::toggle_sleep_for,
+            commands::pause_for,
+            commands::get_remaining_time,
+            commands::set_wake_interval,
+            commands::get_wake_interval,
+            commands::boost,
+            commands::set_interval_jitter,
+            commands::get_interval_jitter,
+            commands::set_use_power_requests,
+            commands::get_use_power_requests,
             commands::change_screen_mode,
             commands::get_state,
+            commands::get_state_tuple,
+            commands::get_instance_label,
+            commands::set_instance_label,
+            commands::get_only_on_ac,
+            commands::set_only_on_ac,
+            commands::get_refuse_on_battery,
+            commands::set_refuse_on_battery,
+            commands::get_tooltip_template,
+            commands::set_tooltip_template,
+            commands::get_left_click_toggles,
+            commands::set_left_click_toggles,
+            commands::list_scheduled_actions,
+            commands::cancel_scheduled_action,
+            commands::get_watch_window_title,
+            commands::set_watch_window_title,
+            commands::get_watch_device,
+            commands::set_watch_device,
+            commands::get_watch_process,
+            commands::set_watch_process,
+            commands::get_watch_network,
+            commands::set_watch_network,
+            commands::get_keep_awake_on_remote_session,
+            commands::set_keep_awake_on_remote_session,
+            commands::get_automation_muted,
+            commands::set_automation_muted,
+            commands::get_startup_check,
+            commands::get_show_advanced_menu,
+            commands::set_show_advanced_menu,
+            commands::get_master_enabled,
+            commands::set_master_enabled,
+            commands::get_build_info,
+            commands::capture_debug_window,
+            commands::reload_state,
+            commands::flush_state,
+            commands::collect_diagnostics,
+            commands::reset_state,
+            commands::get_capabilities,
+            commands::get_platform_capabilities,
+            commands::behavior_matrix,
+            commands::get_bootstrap,
+            commands::get_blink_on_press,
+            commands::set_blink_on_press,
+            commands::get_log_each_iteration,
+            commands::set_log_each_iteration,
+            commands::get_wake_key,
+            commands::set_wake_key,
+            commands::get_toggle_shortcut,
+            commands::get_notifications_enabled,
+            commands::get_enable_ipc,
+            commands::set_enable_ipc,
+            commands::set_notifications_enabled,
+            commands::get_session_only,
+            commands::set_session_only,
+            commands::get_schedule,
+            commands::set_schedule,
+            commands::get_profiles,
+            commands::get_active_profile,
+            commands::get_autostart,
+            commands::set_autostart,
+            commands::select_profile,
+            commands::save_profile,
+            commands::set_toggle_shortcut,
+            commands::list_wake_keys,
+            commands::get_wake_strategy,
+            commands::set_wake_strategy,
+            commands::assess_sleep_risk,
+            commands::set_busy_indicator,
+            commands::get_system_idle_secs,
+            commands::get_confirm_override_watchers,
+            commands::set_confirm_override_watchers,
+            commands::get_awake_reasons,
+            commands::get_on_condition_end,
+            commands::get_max_session_secs,
+            commands::get_battery_floor_percent,
+            commands::set_battery_floor_percent,
+            commands::set_max_session_secs,
+            commands::set_on_condition_end,
         ])
         .setup(move |app| {
-            setup_tray(app, initial_state, is_awake_clone, screen_mode_clone)
+            power_monitor::spawn_power_monitor(only_on_ac, automation_muted, power_paused);
+            let supervisor = watcher::default_supervisor(&app.state::<AppStateManager>());
+            watcher::spawn_supervisor(app.handle().clone(), supervisor);
+            awake_cap::spawn_awake_cap_monitor(app.handle().clone());
+            battery_guard::spawn_battery_guard(app.handle().clone());
+            session_watch::spawn_session_watch(app.state::<AppStateManager>().service.clone());
+            if matches!(
+                restored_deadline,
+                RestoredDeadline::Expired | RestoredDeadline::SessionEnded
+            ) {
+                let expired = persistence::update_state(|s| {
+                    s.sleep_disabled = false;
+                    s.disable_at = None;
+                    s.session_only = false;
+                    s.resume_at = None;
+                });
+                if let Err(e) = expired {
+                    log::warn!("Failed to persist expired auto-disable: {}", e);
+                }
+            }
+            if restored_pause == RestoredDeadline::Expired {
+                let ended = persistence::update_state(|s| {
+                    s.sleep_disabled = true;
+                    s.resume_at = None;
+                });
+                if let Err(e) = ended {
+                    log::warn!("Failed to persist ended pause: {}", e);
+                }
+            }
+            if let RestoredDeadline::Remaining(left) = restored_pause {
+                let resumed = commands::schedule_resume(
+                    &app.state::<AppStateManager>(),
+                    left,
+                    notify::on_pause_ended(app.handle().clone()),
+                );
+                if let Err(e) = resumed {
+                    log::warn!("Failed to restore pause: {}", e);
+                }
+            }
+            if let RestoredDeadline::Remaining(left) = restored_deadline {
+                let resumed = commands::toggle_sleep_for_impl(
+                    &app.state::<AppStateManager>(),
+                    Some(left),
+                    notify::on_timer_expired(app.handle().clone()),
+                );
+                if let Err(e) = resumed {
+                    log::warn!("Failed to resume timed sleep prevention: {}", e);
+                }
+            }
+            tray::spawn_countdown_refresh(app.handle().clone());
+            schedule_monitor::spawn_schedule_monitor(app.handle().clone());
+            ipc::sync_server(app.handle());
+            // A taken shortcut only costs the hotkey; the tray works without it
+            let shortcut = match toggle_shortcut.lock() {
+                Ok(configured) => hotkey::effective_shortcut(configured.as_deref()).to_string(),
+                Err(e) => {
+                    log::error!("Mutex poisoned during shortcut setup: {}", e);
+                    hotkey::DEFAULT_TOGGLE_SHORTCUT.to_string()
+                }
+            };
+            if let Err(e) = hotkey::register(app.handle(), &shortcut) {
+                log::warn!("Toggle shortcut unavailable: {}", e);
+            }
+            let state_handle = app.handle().clone();
+            commands::set_state_listener(Arc::new(move |change| {
+                status_window::emit_state(&state_handle, change)
+            }));
+            let cycle_handle = app.handle().clone();
+            wake_service::set_cycle_listener(Arc::new(move |outcome| {
+                tray::on_wake_cycle(&cycle_handle, outcome)
+            }));
+            let start_handle = app.handle().clone();
+            service_manager::set_start_failure_listener(Arc::new(move |error| {
+                tray::on_start_failed(&start_handle, error)
+            }));
+            let display_handle = app.handle().clone();
+            wake_service::set_display_status_listener(Arc::new(move |_| {
+                tray::refresh(&display_handle)
+            }));
+            setup_tray(app, initial_state, screen_mode_clone)
         })
         .run(tauri::generate_context!());
 
@@ -90,14 +451,14 @@ async fn main() {
 /// Contains no business logic, only UI rendering and event delegation.
 ///
 /// ## Platform Behavior
-/// Screen mode menu items (Keep Screen On / Allow Screen Off) are only shown
-/// on Windows where users have actual control choice. On macOS/Linux, F15
-/// simulation provides no screen control options, so menu items are omitted.
+/// Each screen mode menu item is shown only where `ScreenMode::is_supported`
+/// says the platform can honor it: Keep Screen On and Allow Screen Off on
+/// Windows, macOS and Linux, Away Mode on Windows only. Elsewhere F15
+/// simulation provides no screen control options, so the items are omitted.
 ///
 /// ## Arguments
 /// * `app` - Tauri application handle
 /// * `state` - Initial application state
-/// * `is_awake` - Shared flag for wake state
 /// * `screen_mode` - Shared screen mode preference
 ///
 /// ## Side Effects
@@ -110,209 +471,618 @@ async fn main() {
 fn setup_tray(
     app: &mut tauri::App,
     state: AppState,
-    is_awake: Arc<AtomicBool>,
     screen_mode: Arc<Mutex<ScreenMode>>,
 ) -> Result<(), Box<dyn std::error::Error>> {
     let handle = app.handle();
 
     // Menu item IDs
     let toggle_sleep_id = MenuId::new("toggle_sleep");
+    let master_id = MenuId::new("master");
     let toggle_autostart_id = MenuId::new("toggle_autostart");
+    let notifications_id = MenuId::new("notifications");
+    let session_only_id = MenuId::new("session_only");
+    let command_line_control_id = MenuId::new("command_line_control");
     let screen_on_id = MenuId::new("screen_on");
     let screen_off_id = MenuId::new("screen_off");
+    let away_mode_id = MenuId::new("away_mode");
+    let show_status_id = MenuId::new("show_status");
     let quit_id = MenuId::new("quit");
+    let watch_process_clear_id = MenuId::new("watch_process_clear");
+    let capture_debug_id = MenuId::new("capture_debug");
+    let reload_state_id = MenuId::new("reload_state");
+    let flush_state_id = MenuId::new("flush_state");
+    let copy_diagnostics_id = MenuId::new("copy_diagnostics");
+    let reset_defaults_id = MenuId::new("reset_defaults");
 
     // Build menu items
-    let toggle_sleep_text = if state.sleep_disabled {
-        "Enable Sleep"
-    } else {
-        "Disable Sleep"
-    };
+    let toggle_sleep_text = tray::toggle_sleep_text(state.sleep_disabled);
     let toggle_sleep_item =
         MenuItemBuilder::with_id(toggle_sleep_id.clone(), toggle_sleep_text).build(handle)?;
+    let master_item =
+        MenuItemBuilder::with_id(master_id.clone(), tray::master_text(state.master_off))
+            .build(handle)?;
+    let mut disable_for_builder = SubmenuBuilder::new(handle, "Disable Sleep For");
+    for preset in AWAKE_PRESETS {
+        disable_for_builder = disable_for_builder.text(preset.id, preset.label);
+    }
+    let disable_for_menu = disable_for_builder.build()?;
+    let mut pause_for_builder = SubmenuBuilder::new(handle, "Pause For");
+    for preset in PAUSE_PRESETS {
+        pause_for_builder = pause_for_builder.text(preset.id, preset.label);
+    }
+    let pause_for_menu = pause_for_builder.build()?;
 
     // Configure autostart
     // Uses tauri-plugin-autostart which provides platform-specific autostart:
     // - Windows: Creates registry entry in HKCU\Software\Microsoft\Windows\CurrentVersion\Run
     // - macOS: Creates LaunchAgent plist in ~/Library/LaunchAgents
     // - Linux: Creates .desktop file in ~/.config/autostart
-    let autostart_manager = handle.autolaunch();
+    let autostart_manager = autostart::PluginAutostart::new(handle);
     let is_autostart = autostart_manager.is_enabled().unwrap_or_else(|e| {
         log::warn!("Failed to check autostart status: {}", e);
         false
     });
 
     if is_autostart {
-        // Update autostart path if already enabled (ensures correct path after app updates)
-        if autostart_manager.disable().is_ok() {
-            if let Err(e) = autostart_manager.enable() {
-                log::error!("Failed to update autostart path: {}", e);
-            }
+        // Update autostart path only if it changed (e.g. after an app update)
+        if let Some(current) = autostart::expected_path() {
+            autostart::refresh_path(&autostart_manager, &current);
         }
     }
 
-    let autostart_text = if is_autostart {
-        "\u{2713} Start at Login"
-    } else {
-        "Start at Login"
-    };
-    let toggle_autostart_item =
-        MenuItemBuilder::with_id(toggle_autostart_id.clone(), autostart_text).build(handle)?;
+    let toggle_autostart_item = MenuItemBuilder::with_id(
+        toggle_autostart_id.clone(),
+        tray::start_at_login_text(is_autostart),
+    )
+    .build(handle)?;
+    let notifications_item = MenuItemBuilder::with_id(
+        notifications_id.clone(),
+        tray::notifications_text(state.notifications_enabled),
+    )
+    .build(handle)?;
+    let session_only_item = MenuItemBuilder::with_id(
+        session_only_id.clone(),
+        tray::session_only_text(state.session_only),
+    )
+    .build(handle)?;
+    let command_line_control_item = MenuItemBuilder::with_id(
+        command_line_control_id.clone(),
+        tray::command_line_control_text(state.enable_ipc),
+    )
+    .build(handle)?;
 
-    // Screen mode menu items are only shown on Windows where user has actual choice
-    // Non-Windows: F15 simulation provides no screen control options
+    // Screen mode menu items are only shown where the platform can honor them
     // Use core logic (is_supported) to determine platform capability
     let screen_on_item = if ScreenMode::KeepScreenOn.is_supported() {
-        let screen_on_text = if state.screen_mode == ScreenMode::KeepScreenOn {
-            "\u{2713} Keep Screen On"
-        } else {
-            "Keep Screen On"
-        };
+        let screen_on_text = tray::screen_mode_text(ScreenMode::KeepScreenOn, state.screen_mode);
         Some(MenuItemBuilder::with_id(screen_on_id.clone(), screen_on_text).build(handle)?)
     } else {
         None
     };
 
-    let screen_off_item = if ScreenMode::AllowScreenOff.is_supported() {
-        let screen_off_text = if state.screen_mode == ScreenMode::AllowScreenOff {
-            "\u{2713} Allow Screen Off"
-        } else {
-            "Allow Screen Off"
-        };
+    // Shares the check with the frontend so neither offers a dead toggle
+    let screen_off_item = if commands::get_platform_capabilities().allow_screen_off_supported {
+        let screen_off_text = tray::screen_mode_text(ScreenMode::AllowScreenOff, state.screen_mode);
         Some(MenuItemBuilder::with_id(screen_off_id.clone(), screen_off_text).build(handle)?)
     } else {
         None
     };
 
+    let away_mode_item = if commands::get_platform_capabilities().away_mode_supported {
+        let away_mode_text = tray::screen_mode_text(ScreenMode::AwayMode, state.screen_mode);
+        Some(MenuItemBuilder::with_id(away_mode_id.clone(), away_mode_text).build(handle)?)
+    } else {
+        None
+    };
+
+    let show_status_item =
+        MenuItemBuilder::with_id(show_status_id.clone(), "Show Status").build(handle)?;
     let quit_item = MenuItemBuilder::with_id(quit_id.clone(), "Quit").build(handle)?;
 
-    // Build tray menu - conditionally include screen mode items (Windows only)
-    let mut menu_builder = MenuBuilder::new(handle).item(&toggle_sleep_item);
-    
-    // Add screen mode section only if items exist (Windows)
-    if screen_on_item.is_some() || screen_off_item.is_some() {
-        menu_builder = menu_builder.separator();
-        
-        if let Some(ref item) = screen_on_item {
-            menu_builder = menu_builder.item(item);
-        }
-        if let Some(ref item) = screen_off_item {
-            menu_builder = menu_builder.item(item);
-        }
-    }
-    
-    let tray_menu = menu_builder
+    // Advanced entries are built even when hidden so they can be shown at runtime
+    let capture_debug_item =
+        MenuItemBuilder::with_id(capture_debug_id.clone(), "Capture Debug Log (1 min)")
+            .build(handle)?;
+    let reload_state_item =
+        MenuItemBuilder::with_id(reload_state_id.clone(), "Reload State File").build(handle)?;
+    let flush_state_item =
+        MenuItemBuilder::with_id(flush_state_id.clone(), "Write State File").build(handle)?;
+    let copy_diagnostics_item =
+        MenuItemBuilder::with_id(copy_diagnostics_id.clone(), "Copy Diagnostics").build(handle)?;
+    let reset_defaults_item =
+        MenuItemBuilder::with_id(reset_defaults_id.clone(), "Reset to Defaults").build(handle)?;
+    let diagnostics_menu = SubmenuBuilder::new(handle, "Diagnostics")
+        .item(&capture_debug_item)
+        .item(&reload_state_item)
+        .item(&flush_state_item)
+        .item(&copy_diagnostics_item)
         .separator()
-        .item(&toggle_autostart_item)
-        .separator()
-        .item(&quit_item)
+        .item(&reset_defaults_item)
         .build()?;
 
-    // Wrap menu items for event handler
-    let toggle_sleep_item = Arc::new(toggle_sleep_item);
-    let toggle_sleep_item_clone = toggle_sleep_item.clone();
-    let toggle_autostart_item = Arc::new(toggle_autostart_item);
-    let screen_on_item = screen_on_item.map(Arc::new);
-    let screen_on_item_clone = screen_on_item.clone();
-    let screen_off_item = screen_off_item.map(Arc::new);
-    let screen_off_item_clone = screen_off_item.clone();
+    let active_interval = state
+        .wake_interval_secs
+        .unwrap_or(wake_interval::DEFAULT_WAKE_INTERVAL_SECS);
+    let mut wake_interval_items = Vec::new();
+    for secs in wake_interval::WAKE_INTERVAL_PRESETS {
+        let text = wake_interval::wake_interval_menu_text(secs, secs == active_interval);
+        let item = MenuItemBuilder::with_id(wake_interval::wake_interval_menu_id(secs), text)
+            .build(handle)?;
+        wake_interval_items.push((secs, item));
+    }
+    let mut wake_interval_builder = SubmenuBuilder::new(handle, "Wake Interval");
+    for (_, item) in &wake_interval_items {
+        wake_interval_builder = wake_interval_builder.item(item);
+    }
+    let wake_interval_menu = wake_interval_builder.build()?;
+
+    let mut profile_items = Vec::new();
+    for name in profile::profile_names(&state.profiles) {
+        let active = state.active_profile.as_deref() == Some(name.as_str());
+        let item = MenuItemBuilder::with_id(
+            profile::profile_menu_id(&name),
+            profile::profile_menu_text(&name, active),
+        )
+        .build(handle)?;
+        profile_items.push((name, item));
+    }
+    let mut profile_builder = SubmenuBuilder::new(handle, "Profile");
+    for (_, item) in &profile_items {
+        profile_builder = profile_builder.item(item);
+    }
+    let profile_menu = profile_builder.build()?;
+
+    let mut wake_strategy_items = Vec::new();
+    for strategy in WakeStrategy::ALL {
+        let text = strategy.menu_text(strategy == state.wake_strategy);
+        let item = MenuItemBuilder::with_id(strategy.menu_id(), text).build(handle)?;
+        wake_strategy_items.push((strategy, item));
+    }
+    let mut wake_strategy_builder = SubmenuBuilder::new(handle, "Wake Strategy");
+    for (_, item) in &wake_strategy_items {
+        wake_strategy_builder = wake_strategy_builder.item(item);
+    }
+    let wake_strategy_menu = wake_strategy_builder.build()?;
+
+    // A tray menu cannot take text input; the name is set via set_watch_process
+    let watch_process_status =
+        MenuItemBuilder::new(tray::watch_process_text(state.watch_process.as_deref()))
+            .enabled(false)
+            .build(handle)?;
+    let watch_process_clear =
+        MenuItemBuilder::with_id(watch_process_clear_id.clone(), "Stop Watching")
+            .enabled(state.watch_process.is_some())
+            .build(handle)?;
+    let watch_process_menu = SubmenuBuilder::new(handle, "Watch Process")
+        .item(&watch_process_status)
+        .item(&watch_process_clear)
+        .build()?;
+
+    // Build tray menu - screen mode items only where supported
+    let menu_options = MenuOptions {
+        screen_on: screen_on_item.is_some(),
+        screen_off: screen_off_item.is_some(),
+        away_mode: away_mode_item.is_some(),
+        show_advanced: state.show_advanced_menu,
+    };
+    let mut menu_builder = MenuBuilder::new(handle);
+    for entry in menu_layout(menu_options) {
+        menu_builder = match (entry, &screen_on_item, &screen_off_item) {
+            (MenuEntry::ToggleSleep, _, _) => menu_builder.item(&toggle_sleep_item),
+            (MenuEntry::DisableSleepFor, _, _) => menu_builder.item(&disable_for_menu),
+            (MenuEntry::PauseFor, _, _) => menu_builder.item(&pause_for_menu),
+            (MenuEntry::Master, _, _) => menu_builder.item(&master_item),
+            (MenuEntry::ScreenOn, Some(item), _) => menu_builder.item(item),
+            (MenuEntry::ScreenOff, _, Some(item)) => menu_builder.item(item),
+            (MenuEntry::ScreenOn | MenuEntry::ScreenOff, _, _) => menu_builder,
+            (MenuEntry::AwayMode, _, _) => match &away_mode_item {
+                Some(item) => menu_builder.item(item),
+                None => menu_builder,
+            },
+            (MenuEntry::StartAtLogin, _, _) => menu_builder.item(&toggle_autostart_item),
+            (MenuEntry::SessionOnly, _, _) => menu_builder.item(&session_only_item),
+            (MenuEntry::Notifications, _, _) => menu_builder.item(&notifications_item),
+            (MenuEntry::CommandLineControl, _, _) => menu_builder.item(&command_line_control_item),
+            (MenuEntry::Profile, _, _) => menu_builder.item(&profile_menu),
+            (MenuEntry::WakeInterval, _, _) => menu_builder.item(&wake_interval_menu),
+            (MenuEntry::WakeStrategy, _, _) => menu_builder.item(&wake_strategy_menu),
+            (MenuEntry::WatchProcess, _, _) => menu_builder.item(&watch_process_menu),
+            (MenuEntry::Diagnostics, _, _) => menu_builder.item(&diagnostics_menu),
+            (MenuEntry::ShowStatus, _, _) => menu_builder.item(&show_status_item),
+            (MenuEntry::Quit, _, _) => menu_builder.item(&quit_item),
+            (MenuEntry::Separator, _, _) => menu_builder.separator(),
+        };
+    }
+    let tray_menu = menu_builder.build()?;
+
+    // Let background tasks refresh state-dependent menu text
+    app.manage(tray::TrayMenu {
+        toggle_sleep: toggle_sleep_item.clone(),
+        master: master_item.clone(),
+        screen_on: screen_on_item,
+        screen_off: screen_off_item,
+        away_mode: away_mode_item,
+        watch_process_status,
+        watch_process_clear,
+        notifications: notifications_item,
+        session_only: session_only_item,
+        command_line_control: command_line_control_item,
+        start_at_login: toggle_autostart_item,
+        profile_menu,
+        profile_items: Mutex::new(profile_items),
+        menu: tray_menu.clone(),
+        diagnostics: diagnostics_menu,
+        options: menu_options,
+    });
 
     // Generate initial tooltip
-    let current_mode = *screen_mode.lock().expect(
-        "Mutex poisoned during initial tooltip generation. This indicates a critical bug."
+    let current_mode = *commands::lock_screen_mode(&screen_mode, "initial tooltip generation");
+    let tooltip = TooltipText::for_state_templated(
+        state.tooltip_template.as_deref(),
+        state.sleep_disabled,
+        current_mode,
+        commands::configured_wake_interval(current_mode),
+        state.instance_label.as_deref(),
     );
-    let tooltip = TooltipText::for_state(state.sleep_disabled, current_mode);
 
     // Load icon
-    let icon_data = icon::get_icon_rgba(state.sleep_disabled)?;
+    let (icon_data, icon_width, icon_height) = icon::get_icon_rgba(
+        icon::IconState::for_awake(state.sleep_disabled),
+        tray::icon_scale(handle),
+    )?;
+    // Left click toggles wake unless the user prefers it to open the menu
+    // (see tray::is_toggle_click for the platform differences)
+    let left_click_toggles = app
+        .state::<AppStateManager>()
+        .tray
+        .left_click_toggles
+        .load(Ordering::SeqCst);
+    let click_confirm_pending: Mutex<Option<Instant>> = Mutex::new(None);
     let tray = TrayIconBuilder::new()
-        .icon(Image::new(icon_data.as_slice(), 32, 32))
+        .icon(Image::new(icon_data.as_slice(), icon_width, icon_height))
         .menu(&tray_menu)
         .tooltip(tooltip.as_str())
+        .show_menu_on_left_click(!left_click_toggles)
+        .on_tray_icon_event(move |tray, event| {
+            let app = tray.app_handle();
+            let app_state = app.state::<AppStateManager>();
+            if tray::is_toggle_click(&event)
+                && app_state.tray.left_click_toggles.load(Ordering::SeqCst)
+            {
+                handle_toggle_sleep(app, &app_state, &click_confirm_pending);
+            }
+        })
         .build(handle)?;
 
     // Start wake service if needed
-    if state.sleep_disabled {
+    if state.sleep_disabled && !state.master_off {
         log::info!("Starting wake service on startup");
-        commands::start_wake_service(is_awake.clone(), current_mode);
+        app.state::<AppStateManager>().service.request_sync();
     }
 
-    let tray_handle = tray.clone();
+    // When a disable needs confirmation, the next click within
+    // OVERRIDE_CONFIRM_WINDOW confirms it
+    let disable_confirm_pending: Mutex<Option<Instant>> = Mutex::new(None);
 
     // Register menu event handler
     tray.on_menu_event(move |app, event| {
+        let app_state = app.state::<AppStateManager>();
         if *event.id() == toggle_sleep_id {
-            handle_toggle_sleep(
-                is_awake.clone(),
-                screen_mode.clone(),
-                &toggle_sleep_item_clone,
-                &tray_handle,
-            );
-        } else if *event.id() == screen_on_id && screen_on_item_clone.is_some() {
-            handle_screen_mode_change(
-                ScreenMode::KeepScreenOn,
-                is_awake.clone(),
-                screen_mode.clone(),
-                &screen_on_item_clone,
-                &screen_off_item_clone,
-                &tray_handle,
-            );
-        } else if *event.id() == screen_off_id && screen_off_item_clone.is_some() {
-            handle_screen_mode_change(
-                ScreenMode::AllowScreenOff,
-                is_awake.clone(),
-                screen_mode.clone(),
-                &screen_on_item_clone,
-                &screen_off_item_clone,
-                &tray_handle,
-            );
+            handle_toggle_sleep(app, &app_state, &disable_confirm_pending);
+        } else if *event.id() == master_id {
+            handle_toggle_master(app, &app_state);
+        } else if *event.id() == screen_on_id {
+            handle_screen_mode_change(app, ScreenMode::KeepScreenOn, &app_state);
+        } else if *event.id() == screen_off_id {
+            handle_screen_mode_change(app, ScreenMode::AllowScreenOff, &app_state);
+        } else if *event.id() == away_mode_id {
+            handle_screen_mode_change(app, ScreenMode::AwayMode, &app_state);
         } else if *event.id() == toggle_autostart_id {
-            handle_toggle_autostart(app, &toggle_autostart_item);
+            handle_toggle_autostart(app);
+        } else if *event.id() == notifications_id {
+            handle_toggle_notifications(app, &app_state);
+        } else if *event.id() == session_only_id {
+            handle_toggle_session_only(app, &app_state);
+        } else if *event.id() == command_line_control_id {
+            handle_toggle_command_line_control(app, &app_state);
+        } else if *event.id() == capture_debug_id {
+            handle_capture_debug();
+        } else if *event.id() == reload_state_id {
+            handle_reload_state(app, &app_state);
+        } else if *event.id() == flush_state_id {
+            handle_flush_state(&app_state);
+        } else if *event.id() == copy_diagnostics_id {
+            handle_copy_diagnostics(app);
+        } else if *event.id() == reset_defaults_id {
+            handle_reset_defaults(app, &wake_interval_items, &wake_strategy_items);
+        } else if *event.id() == watch_process_clear_id {
+            handle_clear_watch_process(app, &app_state);
+        } else if *event.id() == show_status_id {
+            handle_show_status(app);
         } else if *event.id() == quit_id {
-            handle_quit(app, is_awake.clone());
+            handle_quit(app);
+        } else if let Some(preset) = preset_by_id(event.id().as_ref()) {
+            handle_toggle_sleep_for(app, &app_state, preset.duration);
+        } else if let Some(preset) = pause_preset_by_id(event.id().as_ref()) {
+            handle_pause_for(app, &app_state, preset.duration);
+        } else if let Some(secs) = wake_interval::wake_interval_from_menu_id(event.id().as_ref()) {
+            handle_wake_interval_change(&app_state, secs, &wake_interval_items);
+        } else if let Some(strategy) = WakeStrategy::from_menu_id(event.id().as_ref()) {
+            handle_wake_strategy_change(&app_state, strategy, &wake_strategy_items);
+        } else if let Some(name) = profile::profile_from_menu_id(event.id().as_ref()) {
+            handle_select_profile(app, &app_state, name, &wake_interval_items);
         }
     });
 
     app.manage(tray);
+
+    // Surface a failed startup check right away
+    tray::refresh(handle);
     Ok(())
 }
 
-/// Handle toggle sleep menu event
+/// Handle toggle sleep menu event or global shortcut press
 ///
 /// ## Design Intent
-/// Delegates to shared business logic, updates UI based on result.
+/// Delegates to shared business logic, then re-renders the tray from state.
 ///
 /// ## Side Effects
 /// - Updates menu item text
 /// - Updates tray icon and tooltip
+/// - May ask for a confirming second click instead of disabling
 fn handle_toggle_sleep(
-    is_awake: Arc<AtomicBool>,
-    screen_mode: Arc<Mutex<ScreenMode>>,
-    toggle_item: &Arc<tauri::menu::MenuItem<tauri::Wry>>,
-    tray: &tauri::tray::TrayIcon<tauri::Wry>,
+    app: &tauri::AppHandle,
+    state: &AppStateManager,
+    confirm_pending: &Mutex<Option<Instant>>,
 ) {
+    let Ok(mut pending) = confirm_pending.lock() else {
+        log::error!("Mutex poisoned during toggle confirmation");
+        return;
+    };
+    let confirmed = pending
+        .take()
+        .is_some_and(|asked| asked.elapsed() < OVERRIDE_CONFIRM_WINDOW);
+
     // Delegate to shared business logic
-    let (new_awake, current_mode) = match commands::toggle_sleep_impl(&is_awake, &screen_mode) {
-        Ok(result) => result,
+    match commands::manual_toggle_impl(state, confirmed) {
+        Ok(commands::ToggleOutcome::Toggled(..)) => {}
+        Ok(commands::ToggleOutcome::NeedsConfirmation(prompt)) => {
+            *pending = Some(Instant::now());
+            if let Some(menu) = app.try_state::<tray::TrayMenu>() {
+                let _ = menu.toggle_sleep.set_text("Click again to disable");
+            }
+            log::info!("{}", prompt);
+            return;
+        }
         Err(e) => {
-            log::error!("Toggle sleep failed: {}", e);
+            log::warn!("Toggle sleep not applied: {}", e);
+            notify::notify_error(app, &e);
             return;
         }
+    }
+
+    tray::refresh(app);
+}
+
+/// Handle a global shortcut press
+///
+/// ## Design Intent
+/// Same as clicking the toggle item, but there is no menu in view, so the
+/// resulting change is also announced as a notification.
+fn handle_toggle_shortcut(app: &tauri::AppHandle, confirm_pending: &Mutex<Option<Instant>>) {
+    let state = app.state::<AppStateManager>();
+    let was_awake = state.is_awake.load(Ordering::SeqCst);
+    handle_toggle_sleep(app, &state, confirm_pending);
+    let awake = state.is_awake.load(Ordering::SeqCst);
+    if awake != was_awake {
+        notify::notify_state_change(app, awake, Some("shortcut"));
+    }
+}
+
+/// Handle a second launch of Tea
+///
+/// ## Design Intent
+/// Runs in the first instance with the arguments of the second, which has
+/// already exited. A plain relaunch or `--toggle` flips wake like a click
+/// on the toggle item; with no menu in view the change is announced.
+///
+/// ## Side Effects
+/// - May toggle wake, persist state and refresh the tray
+fn handle_second_instance(app: &tauri::AppHandle, argv: Vec<String>) {
+    log::info!("Second instance launched with {:?}", argv);
+    if !core::cli::is_toggle_request(argv.into_iter().skip(1)) {
+        log::info!("Ignoring second instance arguments");
+        return;
+    }
+
+    let state = app.state::<AppStateManager>();
+    // Launching again is deliberate; there is no prompt to confirm through
+    match commands::manual_toggle_impl(&state, true) {
+        Ok(commands::ToggleOutcome::Toggled(awake, _)) => {
+            tray::refresh(app);
+            notify::notify_state_change(app, awake, Some("launched again"));
+        }
+        Ok(commands::ToggleOutcome::NeedsConfirmation(prompt)) => log::info!("{}", prompt),
+        Err(e) => log::warn!("Toggle from second instance not applied: {}", e),
+    }
+}
+
+/// Handle the Notifications checkbox menu event
+///
+/// ## Side Effects
+/// - Persists the flipped preference
+/// - Updates the checkmark
+fn handle_toggle_notifications(app: &tauri::AppHandle, state: &AppStateManager) {
+    let enabled = !state.tray.notifications_enabled.load(Ordering::SeqCst);
+    if let Err(e) = commands::set_notifications_enabled_impl(state, enabled) {
+        log::warn!("Notifications preference not applied: {}", e);
+        return;
+    }
+    tray::refresh(app);
+}
+
+/// Handle the This Session Only checkbox menu event
+///
+/// ## Side Effects
+/// - Persists the flipped flag, read at the next startup
+/// - Updates the checkmark
+fn handle_toggle_session_only(app: &tauri::AppHandle, state: &AppStateManager) {
+    let enabled = !state.wake.session_only.load(Ordering::SeqCst);
+    if let Err(e) = commands::set_session_only_impl(state, enabled) {
+        log::warn!("Session only flag not applied: {}", e);
+        return;
+    }
+    tray::refresh(app);
+}
+
+/// Handle the Command Line Control checkbox menu event
+///
+/// ## Side Effects
+/// - Persists the flipped setting
+/// - Starts or stops the control endpoint
+/// - Updates the checkmark
+fn handle_toggle_command_line_control(app: &tauri::AppHandle, state: &AppStateManager) {
+    let enabled = !state.control.enable_ipc.load(Ordering::SeqCst);
+    if let Err(e) = commands::set_enable_ipc_impl(state, enabled) {
+        log::warn!("Command line control not applied: {}", e);
+        return;
+    }
+    ipc::sync_server(app);
+    tray::refresh(app);
+}
+
+/// Handle a Pause For preset menu event
+///
+/// ## Side Effects
+/// - Turns wake off and schedules it back on
+/// - Updates tray icon, tooltip and menu text now and when it resumes
+fn handle_pause_for(app: &tauri::AppHandle, state: &AppStateManager, duration: Option<Duration>) {
+    let Some(duration) = duration else {
+        return;
     };
+    if let Err(e) = commands::pause_for_impl(state, duration, notify::on_pause_ended(app.clone())) {
+        log::warn!("Pause not applied: {}", e);
+        return;
+    }
+    tray::refresh(app);
+}
 
-    // Update UI based on result
-    let menu_text = if new_awake {
-        "Enable Sleep"
-    } else {
-        "Disable Sleep"
+/// Handle a Disable Sleep For preset menu event
+///
+/// ## Side Effects
+/// - Enables wake and schedules its auto-disable
+/// - Updates tray icon, tooltip and menu text now and when it expires
+fn handle_toggle_sleep_for(
+    app: &tauri::AppHandle,
+    state: &AppStateManager,
+    duration: Option<Duration>,
+) {
+    if let Err(e) =
+        commands::toggle_sleep_for_impl(state, duration, notify::on_timer_expired(app.clone()))
+    {
+        log::warn!("Timed sleep prevention not applied: {}", e);
+        return;
+    }
+
+    tray::refresh(app);
+}
+
+/// Handle a Wake Interval preset menu event
+///
+/// ## Side Effects
+/// - Persists the interval and restarts a running wake loop with it
+/// - Moves the checkmark to the chosen preset
+fn handle_wake_interval_change(
+    state: &AppStateManager,
+    secs: u64,
+    items: &[(u64, tauri::menu::MenuItem<tauri::Wry>)],
+) {
+    if let Err(e) = commands::set_wake_interval_impl(state, Some(secs)) {
+        log::error!("Set wake interval failed: {}", e);
+        return;
+    }
+
+    for (preset, item) in items {
+        let text = wake_interval::wake_interval_menu_text(*preset, *preset == secs);
+        let _ = item.set_text(text);
+    }
+}
+
+/// Handle a Profile menu event
+///
+/// ## Side Effects
+/// - Applies and persists the profile's settings, restarting a running
+///   wake loop
+/// - Moves the profile, screen mode and wake interval checkmarks
+fn handle_select_profile(
+    app: &tauri::AppHandle,
+    state: &AppStateManager,
+    name: &str,
+    interval_items: &[(u64, tauri::menu::MenuItem<tauri::Wry>)],
+) {
+    let settings = match commands::select_profile_impl(state, name) {
+        Ok(settings) => settings,
+        Err(e) => {
+            log::error!("Select profile failed: {}", e);
+            return;
+        }
     };
-    let _ = toggle_item.set_text(menu_text);
 
-    if let Ok(icon_data) = icon::get_icon_rgba(new_awake) {
-        let tooltip = TooltipText::for_state(new_awake, current_mode);
-        let _ = tray.set_icon(Some(Image::new(icon_data.as_slice(), 32, 32)));
-        let _ = tray.set_tooltip(Some(tooltip.as_str()));
+    let active_interval = settings
+        .wake_interval_secs
+        .unwrap_or(wake_interval::DEFAULT_WAKE_INTERVAL_SECS);
+    for (preset, item) in interval_items {
+        let text = wake_interval::wake_interval_menu_text(*preset, *preset == active_interval);
+        let _ = item.set_text(text);
+    }
+    tray::refresh(app);
+}
+
+/// Handle a Wake Strategy menu event
+///
+/// ## Side Effects
+/// - Persists the strategy; the running loop uses it from its next cycle
+/// - Moves the checkmark to the chosen strategy
+fn handle_wake_strategy_change(
+    state: &AppStateManager,
+    strategy: WakeStrategy,
+    items: &[(WakeStrategy, tauri::menu::MenuItem<tauri::Wry>)],
+) {
+    if let Err(e) = commands::set_wake_strategy_impl(state, strategy) {
+        log::error!("Set wake strategy failed: {}", e);
+        return;
+    }
+
+    for (choice, item) in items {
+        let _ = item.set_text(choice.menu_text(*choice == strategy));
+    }
+}
+
+/// Handle Stop Watching menu event
+///
+/// ## Side Effects
+/// - Clears the persisted process watch
+/// - Updates the Watch Process submenu
+fn handle_clear_watch_process(app: &tauri::AppHandle, state: &AppStateManager) {
+    if let Err(e) = commands::set_watch_process_impl(state, None) {
+        log::error!("Clear process watch failed: {}", e);
+    }
+
+    tray::refresh(app);
+}
+
+/// Handle master switch menu event
+///
+/// ## Side Effects
+/// - Stops wake and suspends automation when pausing
+/// - Updates tray icon, tooltip and menu text
+fn handle_toggle_master(app: &tauri::AppHandle, state: &AppStateManager) {
+    let enable = state.automation.master_off.load(Ordering::SeqCst);
+    if let Err(e) = commands::set_master_enabled_impl(state, enable) {
+        log::error!("Toggle master switch failed: {}", e);
     }
+
+    tray::refresh(app);
 }
 
 /// Handle screen mode change menu event
@@ -325,48 +1095,137 @@ fn handle_toggle_sleep(
 /// - Updates menu item checkmarks
 /// - Updates tooltip
 fn handle_screen_mode_change(
+    app: &tauri::AppHandle,
     new_mode: ScreenMode,
-    is_awake: Arc<AtomicBool>,
-    screen_mode: Arc<Mutex<ScreenMode>>,
-    screen_on_item: &Option<Arc<tauri::menu::MenuItem<tauri::Wry>>>,
-    screen_off_item: &Option<Arc<tauri::menu::MenuItem<tauri::Wry>>>,
-    tray: &tauri::tray::TrayIcon<tauri::Wry>,
+    state: &AppStateManager,
 ) {
     // Delegate to shared business logic
-    if let Err(e) = commands::change_screen_mode_impl(&is_awake, &screen_mode, new_mode) {
+    if let Err(e) = commands::change_screen_mode_impl(state, new_mode) {
         log::error!("Change screen mode failed: {}", e);
         return;
     }
 
-    // Update UI based on result (items only exist on Windows)
-    if let Some(ref item) = screen_on_item {
-        let _ = item.set_text(if new_mode == ScreenMode::KeepScreenOn {
-            "\u{2713} Keep Screen On"
-        } else {
-            "Keep Screen On"
-        });
+    // Moves the screen mode checkmarks (items only exist on Windows)
+    tray::refresh(app);
+}
+
+/// Handle the Show Status menu event
+///
+/// ## Side Effects
+/// Creates or shows the status window
+fn handle_show_status(app: &tauri::AppHandle) {
+    if let Err(e) = status_window::show(app) {
+        log::error!("Show status failed: {}", e);
     }
-    
-    if let Some(ref item) = screen_off_item {
-        let _ = item.set_text(if new_mode == ScreenMode::AllowScreenOff {
-            "\u{2713} Allow Screen Off"
-        } else {
-            "Allow Screen Off"
-        });
+}
+
+/// Handle the Diagnostics > Capture Debug Log menu event
+///
+/// ## Side Effects
+/// Spawns a Tokio task recording a trace-level log for
+/// `MENU_DEBUG_CAPTURE_SECS`; the file path is logged when done.
+fn handle_capture_debug() {
+    tokio::spawn(async {
+        match commands::capture_debug_window(MENU_DEBUG_CAPTURE_SECS).await {
+            Ok(path) => log::info!("Debug capture from menu saved to {}", path),
+            Err(e) => log::error!("Debug capture from menu failed: {}", e),
+        }
+    });
+}
+
+/// Handle the Diagnostics > Reload State File menu event
+///
+/// ## Side Effects
+/// - Applies the wake state and screen mode from the state file
+/// - Updates tray icon, tooltip and menu text
+fn handle_reload_state(app: &tauri::AppHandle, state: &AppStateManager) {
+    if let Err(e) = commands::reload_state_impl(state) {
+        log::error!("Failed to reload state: {}", e);
+        return;
+    }
+    tray::refresh(app);
+}
+
+/// Handle the Diagnostics > Write State File menu event
+///
+/// ## Side Effects
+/// Persists the in-memory wake state and screen mode
+fn handle_flush_state(state: &AppStateManager) {
+    if let Err(e) = commands::flush_state_impl(state) {
+        log::error!("Failed to write state: {}", e);
     }
+}
 
-    // Update tooltip if currently awake
-    let awake = is_awake.load(Ordering::SeqCst);
-    if awake {
-        let tooltip = TooltipText::for_state(true, new_mode);
-        let _ = tray.set_tooltip(Some(tooltip.as_str()));
+/// Handle the Diagnostics > Copy Diagnostics menu event
+///
+/// ## Side Effects
+/// Replaces the clipboard contents with the diagnostics report
+fn handle_copy_diagnostics(app: &tauri::AppHandle) {
+    match commands::collect_diagnostics(app.clone(), app.state::<AppStateManager>()) {
+        Ok(_) => log::info!("Diagnostics copied to clipboard"),
+        Err(e) => {
+            log::error!("Failed to copy diagnostics: {}", e);
+            notify::notify_error(app, &e);
+        }
     }
 }
 
+/// Handle the Diagnostics > Reset to Defaults menu event
+///
+/// ## Design Intent
+/// A reset cannot be undone, so it asks first. The dialog does not block
+/// the menu handler; the reset runs from its callback.
+///
+/// ## Side Effects
+/// - Once confirmed: stops wake, overwrites the state file with defaults
+///   and brings the whole menu in line, including the wake interval and
+///   wake strategy checkmarks
+/// - On failure, shows an error notification (if enabled)
+fn handle_reset_defaults(
+    app: &tauri::AppHandle,
+    interval_items: &[(u64, tauri::menu::MenuItem<tauri::Wry>)],
+    strategy_items: &[(WakeStrategy, tauri::menu::MenuItem<tauri::Wry>)],
+) {
+    let app = app.clone();
+    let interval_items = interval_items.to_vec();
+    let strategy_items = strategy_items.to_vec();
+    app.dialog()
+        .message("Reset all Tea settings to their defaults? Wake will be turned off.")
+        .title("Reset to Defaults")
+        .kind(MessageDialogKind::Warning)
+        .buttons(MessageDialogButtons::OkCancelCustom(
+            "Reset".to_string(),
+            "Cancel".to_string(),
+        ))
+        .show(move |confirmed| {
+            if !confirmed {
+                return;
+            }
+            if let Err(e) = commands::reset_state(app.clone(), app.state::<AppStateManager>()) {
+                log::error!("Failed to reset settings: {}", e);
+                notify::notify_error(&app, &format!("Settings were not fully reset: {}", e));
+            }
+
+            let defaults = AppState::default();
+            let active_interval = defaults
+                .wake_interval_secs
+                .unwrap_or(wake_interval::DEFAULT_WAKE_INTERVAL_SECS);
+            for (preset, item) in &interval_items {
+                let text =
+                    wake_interval::wake_interval_menu_text(*preset, *preset == active_interval);
+                let _ = item.set_text(text);
+            }
+            for (choice, item) in &strategy_items {
+                let _ = item.set_text(choice.menu_text(*choice == defaults.wake_strategy));
+            }
+        });
+}
+
 /// Handle toggle autostart menu event
 ///
 /// ## Design Intent
-/// Toggles autostart preference via Tauri plugin.
+/// Toggles autostart preference via Tauri plugin, through the same
+/// commands a frontend uses.
 ///
 /// ## Platform Behavior
 /// - Windows: Modifies registry at HKCU\Software\Microsoft\Windows\CurrentVersion\Run
@@ -375,40 +1234,35 @@ fn handle_screen_mode_change(
 ///
 /// ## Side Effects
 /// - Enables or disables autostart
-/// - Updates menu item text
-fn handle_toggle_autostart(
-    app: &tauri::AppHandle,
-    toggle_item: &Arc<tauri::menu::MenuItem<tauri::Wry>>,
-) {
-    let autostart_manager = app.autolaunch();
-    let is_enabled = autostart_manager.is_enabled().unwrap_or_else(|e| {
-        log::warn!("Failed to check autostart status during toggle: {}", e);
-        false
-    });
-
-    log::info!("Toggling autostart: {} -> {}", is_enabled, !is_enabled);
-
-    if is_enabled {
-        let _ = autostart_manager.disable();
-        let _ = toggle_item.set_text("Start at Login");
-    } else {
-        let _ = autostart_manager.enable();
-        let _ = toggle_item.set_text("✓ Start at Login");
+/// - Sets the menu checkmark to the setting actually in effect
+/// - On failure, shows an error notification (if enabled)
+fn handle_toggle_autostart(app: &tauri::AppHandle) {
+    let result = commands::get_autostart(app.clone())
+        .and_then(|enabled| commands::set_autostart(app.clone(), !enabled));
+    if let Err(e) = result {
+        log::error!("Failed to toggle autostart: {}", e);
+        notify::notify_error(app, &format!("Start at Login was not changed: {}", e));
     }
 }
 
 /// Handle quit menu event
 ///
 /// ## Design Intent
-/// Clean shutdown - stop wake service and exit.
+/// Clean shutdown - stop wake service and exit. Cleanup is bounded so a
+/// stuck service can never make quit hang.
 ///
 /// ## Side Effects
-/// - Stops wake service
+/// - Stops wake service, waiting up to `SHUTDOWN_TIMEOUT` for cleanup
 /// - Exits application
-fn handle_quit(app: &tauri::AppHandle, is_awake: Arc<AtomicBool>) {
+fn handle_quit(app: &tauri::AppHandle) {
     log::info!("Quit requested");
-    is_awake.store(false, Ordering::SeqCst);
+    let state = app.state::<AppStateManager>();
+    commands::flush_toggle(&state);
+    if !state.service.stop_blocking(SHUTDOWN_TIMEOUT) {
+        log::warn!(
+            "Wake service cleanup timed out after {:?}; power flags may not have been restored",
+            SHUTDOWN_TIMEOUT
+        );
+    }
     app.exit(0);
 }
-
-
@@ -0,0 +1,96 @@
+//! Network watcher
+//!
+//! Keeps the system awake while a network is connected, e.g. a work VPN
+//! that backups run over, and lets it sleep again once it disconnects.
+//!
+//! ## Design Intent
+//! The watched target is an interface name (a VPN adapter is up) or a
+//! `host:port` (a server behind the VPN answers), since some VPN clients
+//! leave their adapter up while disconnected. Parsing and matching live in
+//! `core::net_watch`. A missing interface or unreachable host is the normal
+//! "disconnected" state, so it is logged at debug only; a reconnect is
+//! bridged by debouncing (see `core::automation`).
+//!
+//! ## Platform Behavior
+//! - Interfaces: See `platform::network_interface_names`; elsewhere the
+//!   watcher never matches an interface
+//! - Hosts: TCP connect on every platform
+//!
+//! ## Failure Modes
+//! - Host lookup fails or the connection is refused or times out: Does not
+//!   hold. Name resolution is not bounded by `CONNECT_TIMEOUT`.
+
+use crate::core::automation::{PatternCondition, PatternProbe};
+use crate::core::net_watch::{any_interface_matches, NetTarget};
+use crate::platform;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::Duration;
+
+/// How long a connection to the watched host may take
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// The configured interface is up or the configured host is reachable
+pub type NetworkCondition = PatternCondition<NetworkProbe>;
+
+/// Samples interfaces or connects to the watched host
+#[derive(Default)]
+pub struct NetworkProbe;
+
+impl PatternProbe for NetworkProbe {
+    const NAME: &'static str = "network";
+    const NOUN: &'static str = "network";
+
+    fn matches(&self, target: &str) -> Option<bool> {
+        let Some(target) = NetTarget::parse(target) else {
+            return Some(false);
+        };
+        match target {
+            NetTarget::Interface(name) => {
+                let interfaces = platform::network_interface_names()?;
+                Some(any_interface_matches(
+                    interfaces.iter().map(String::as_str),
+                    &name,
+                ))
+            }
+            NetTarget::Host { host, port } => Some(host_reachable(&host, port)),
+        }
+    }
+}
+
+/// Whether a TCP connection to `host:port` succeeds
+fn host_reachable(host: &str, port: u16) -> bool {
+    let addrs = match (host, port).to_socket_addrs() {
+        Ok(addrs) => addrs,
+        Err(e) => {
+            log::debug!("Network watch could not resolve {}: {}", host, e);
+            return false;
+        }
+    };
+    addrs
+        .into_iter()
+        .any(|addr| TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT).is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_holds_while_host_accepts_connections() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let target = Arc::new(Mutex::new(Some(format!("127.0.0.1:{}", port))));
+        let condition = NetworkCondition::new(target.clone());
+        assert!(condition.holds());
+
+        drop(listener);
+        assert!(!condition.holds());
+
+        *target.lock().unwrap() = Some("no-such-interface-0".to_string());
+        assert!(!condition.holds());
+        *target.lock().unwrap() = None;
+        assert!(!condition.holds());
+    }
+}
@@ -0,0 +1,134 @@
+//! Desktop notifications for wake changes nobody clicked
+//!
+//! Shows a toast when wake turns on or off by itself (timer expiry,
+//! watched condition, awake cap, global shortcut), where the tray change is
+//! easy to miss, and when a tray action fails without a visible change.
+//!
+//! ## Design Intent
+//! Opt-in via `AppState.notifications_enabled` (off by default); every
+//! call is a no-op while it is off. A notification is a courtesy, so a
+//! failure to show one is logged and otherwise ignored.
+//!
+//! ## Side Effects
+//! Shows OS notifications through `tauri-plugin-notification`.
+
+use crate::commands::AppStateManager;
+use crate::core::{label_prefixed, APP_NAME};
+use crate::tray;
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+/// Reason given when a timed wake session runs out
+pub const REASON_TIMER_EXPIRED: &str = "timer expired";
+
+/// Reason given when a pause ends and wake turns back on
+pub const REASON_PAUSE_ENDED: &str = "pause ended";
+
+/// Notification text for a wake change
+pub fn state_change_body(is_awake: bool, reason: Option<&str>) -> String {
+    let action = if is_awake {
+        "Awake enabled"
+    } else {
+        "Awake disabled"
+    };
+    match reason {
+        Some(reason) => format!("{} ({})", action, reason),
+        None => action.to_string(),
+    }
+}
+
+/// Notification title, prefixed with the instance label like the tooltip
+pub fn notification_title(label: Option<&str>) -> String {
+    label_prefixed(APP_NAME, label)
+}
+
+/// Announce a wake change, if notifications are enabled
+///
+/// ## Arguments
+/// * `app` - Application handle
+/// * `is_awake` - New wake state
+/// * `reason` - Why it changed, shown in parentheses
+pub fn notify_state_change(app: &AppHandle, is_awake: bool, reason: Option<&str>) {
+    show(app, &state_change_body(is_awake, reason));
+}
+
+/// Report a failed tray action, if notifications are enabled
+///
+/// ## Arguments
+/// * `app` - Application handle
+/// * `body` - What failed, in words the user can act on
+pub fn notify_error(app: &AppHandle, body: &str) {
+    show(app, body);
+}
+
+/// Show a toast, if notifications are enabled
+fn show(app: &AppHandle, body: &str) {
+    let Some(state) = app.try_state::<AppStateManager>() else {
+        return;
+    };
+    if !state.tray.notifications_enabled.load(Ordering::SeqCst) {
+        return;
+    }
+    let label = state
+        .tray
+        .instance_label
+        .lock()
+        .ok()
+        .and_then(|label| label.clone());
+
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(notification_title(label.as_deref()))
+        .body(body)
+        .show()
+    {
+        log::warn!("Failed to show notification \"{}\": {}", body, e);
+    }
+}
+
+/// Callback for the end of a timed wake session
+///
+/// Re-renders the tray and announces the auto-disable; pass it as
+/// `on_expire` to `commands::toggle_sleep_for_impl`.
+pub fn on_timer_expired(app: AppHandle) -> impl FnOnce() + Send + 'static {
+    move || {
+        tray::refresh(&app);
+        notify_state_change(&app, false, Some(REASON_TIMER_EXPIRED));
+    }
+}
+
+/// Callback for the end of a pause
+///
+/// Re-renders the tray and announces the resume; pass it as `on_resume`
+/// to `commands::pause_for_impl`.
+pub fn on_pause_ended(app: AppHandle) -> impl FnOnce() + Send + 'static {
+    move || {
+        tray::refresh(&app);
+        notify_state_change(&app, true, Some(REASON_PAUSE_ENDED));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_state_change_body() {
+        assert_eq!(state_change_body(true, None), "Awake enabled");
+        assert_eq!(
+            state_change_body(false, Some(REASON_TIMER_EXPIRED)),
+            "Awake disabled (timer expired)"
+        );
+    }
+
+    #[test]
+    fn test_labelled_instance_prefixes_notification_title() {
+        assert_eq!(notification_title(None), APP_NAME);
+        assert_eq!(
+            notification_title(Some("Build box")),
+            format!("[Build box] {}", APP_NAME)
+        );
+    }
+}
@@ -0,0 +1,233 @@
+//! Headless single-session runner
+//!
+//! Implements `tea --once --for <duration>` for scripts wrapping one task.
+//!
+//! ## Design Intent
+//! Reuses the wake service unchanged, without a tray or any Tauri setup.
+//! The session ends when the duration elapses or on Ctrl+C / SIGINT, and
+//! the wake loop's own cleanup restores normal power behavior. The saved
+//! screen mode and wake interval are read but nothing is ever written, so
+//! a scripted run leaves the tray app's state untouched.
+//!
+//! ## Exit Codes
+//! - `EXIT_OK` (0): Duration elapsed or interrupted, power state restored
+//! - `EXIT_INPUT_FAILED` (1): Input simulation could not start
+//! - `EXIT_USAGE` (2): Invalid command line
+//! - `EXIT_CONFIG_INVALID` (3): Config unreadable in strict mode
+//!
+//! ## Side Effects
+//! - Keeps the system awake for the session
+//! - Reads (never writes) the state file; with `AWAKE_STRICT_CONFIG=1` an
+//!   unreadable one aborts the session instead of using defaults
+
+use crate::core::{resolve_wake_interval, ScreenMode};
+use crate::persistence::{read_startup_state, strict_config_from_env};
+use crate::wake_service::{PauseFlag, RunningFlag, WakeBackend, WakeInput, WakeService};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Session completed normally
+pub const EXIT_OK: i32 = 0;
+/// Input simulation could not be started
+pub const EXIT_INPUT_FAILED: i32 = 1;
+/// Invalid command line arguments
+pub const EXIT_USAGE: i32 = 2;
+/// Config could not be loaded in strict mode (`AWAKE_STRICT_CONFIG=1`)
+pub const EXIT_CONFIG_INVALID: i32 = 3;
+
+/// Run one headless wake session for `duration`
+///
+/// ## Arguments
+/// * `duration` - How long to keep awake
+/// * `backend` - The platform, or a dry run with `--dry-run`
+///
+/// ## Returns
+/// Process exit code (see module docs)
+pub async fn run(duration: Duration, backend: WakeBackend) -> i32 {
+    let config = match read_startup_state(strict_config_from_env()) {
+        Ok(config) => config,
+        Err(e) => {
+            log::error!("Cannot start headless session, config invalid: {}", e);
+            return EXIT_CONFIG_INVALID;
+        }
+    };
+    let interval = resolve_wake_interval(
+        config.screen_mode,
+        &config.interval_per_mode,
+        config.wake_interval_secs,
+    );
+
+    let input = match (backend.input)(config.screen_mode) {
+        Ok(input) => input,
+        Err(e) => {
+            log::error!("Cannot start headless session: {}", e);
+            return EXIT_INPUT_FAILED;
+        }
+    };
+
+    let running = Arc::new(RunningFlag::new());
+    let service = WakeService::new(
+        running.clone(),
+        Arc::new(PauseFlag::default()),
+        (backend.display)(config.use_power_requests),
+    )
+    .with_wake_key(Arc::new(std::sync::Mutex::new(config.wake_key)))
+    .with_wake_strategy(Arc::new(std::sync::Mutex::new(config.wake_strategy)));
+
+    let interrupted = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            log::error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    run_session(
+        service,
+        &running,
+        config.screen_mode,
+        interval,
+        input,
+        duration,
+        interrupted,
+    )
+    .await;
+    EXIT_OK
+}
+
+/// Run the wake loop until `duration` elapses or `stop` completes
+///
+/// ## Design Intent
+/// Separated from `run` so the duration-to-exit flow can be tested with a
+/// paused Tokio clock and a mock keyboard.
+///
+/// ## Side Effects
+/// Clears `running` and waits for the wake loop's cleanup before returning.
+async fn run_session<F>(
+    service: WakeService,
+    running: &RunningFlag,
+    screen_mode: ScreenMode,
+    interval: Duration,
+    input: Option<Box<dyn WakeInput>>,
+    duration: Duration,
+    stop: F,
+) where
+    F: Future<Output = ()>,
+{
+    log::info!("Headless session started for {:?}", duration);
+    let session = service.run(screen_mode, interval, input);
+    tokio::pin!(session);
+
+    tokio::select! {
+        _ = &mut session => {
+            log::warn!("Wake loop ended before the session did");
+            return;
+        }
+        _ = tokio::time::sleep(duration) => log::info!("Headless session complete"),
+        _ = stop => log::info!("Headless session interrupted"),
+    }
+
+    running.stop();
+    session.await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{CountingInput, RecordingDisplay};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    /// A wake service wired to mocks, plus handles to observe them
+    struct MockSession {
+        service: WakeService,
+        running: Arc<RunningFlag>,
+        presses: Arc<AtomicUsize>,
+        calls: Arc<Mutex<Vec<String>>>,
+        keyboard: Box<dyn WakeInput>,
+    }
+
+    fn mock_session() -> MockSession {
+        let running = Arc::new(RunningFlag::new());
+        let presses = Arc::new(AtomicUsize::new(0));
+        let (display, calls) = RecordingDisplay::new();
+        let service = WakeService::new(
+            running.clone(),
+            Arc::new(PauseFlag::default()),
+            Box::new(display),
+        );
+        let keyboard = Box::new(CountingInput {
+            presses: presses.clone(),
+        });
+        MockSession {
+            service,
+            running,
+            presses,
+            calls,
+            keyboard,
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_session_exits_after_duration() {
+        let MockSession {
+            service,
+            running,
+            presses,
+            calls,
+            keyboard,
+        } = mock_session();
+        let started = tokio::time::Instant::now();
+
+        run_session(
+            service,
+            &running,
+            ScreenMode::KeepScreenOn,
+            Duration::from_secs(60),
+            Some(keyboard),
+            Duration::from_secs(150),
+            std::future::pending(),
+        )
+        .await;
+
+        // Presses at 0s, 60s and 120s, then the session ends at 150s
+        assert_eq!(presses.load(Ordering::SeqCst), 3);
+        assert!(started.elapsed() >= Duration::from_secs(150));
+        assert!(started.elapsed() < Duration::from_secs(151));
+        assert!(!running.is_running());
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["set_display_mode(KeepScreenOn)", "restore_normal_mode"]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_stop_signal_ends_session_early() {
+        let MockSession {
+            service,
+            running,
+            presses,
+            calls,
+            keyboard,
+        } = mock_session();
+        let started = tokio::time::Instant::now();
+
+        run_session(
+            service,
+            &running,
+            ScreenMode::KeepScreenOn,
+            Duration::from_secs(60),
+            Some(keyboard),
+            Duration::from_secs(2 * 3600),
+            tokio::time::sleep(Duration::from_secs(90)),
+        )
+        .await;
+
+        assert_eq!(presses.load(Ordering::SeqCst), 2);
+        assert!(started.elapsed() < Duration::from_secs(91));
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec!["set_display_mode(KeepScreenOn)", "restore_normal_mode"]
+        );
+    }
+}
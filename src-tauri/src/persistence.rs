@@ -4,7 +4,9 @@
 //!
 //! ## Design Intent
 //! Isolates all filesystem I/O for state management. Provides clear error
-//! handling and recovery paths.
+//! handling and recovery paths. `read_state` and `write_state` go through
+//! a `StateStore`: the state file in the app, an in-memory store in tests
+//! that set one with `set_test_store`.
 //!
 //! ## Side Effects
 //! - Reads from config directory
@@ -15,83 +17,374 @@
 //! - Disk full: Returns StateIo error with recovery hint to free space
 //! - Permission denied: Returns StateIo error with recovery hint to check permissions
 //! - Corrupted state: Returns default state (defensive design)
+//! - Out-of-range values: Replaced on load (see `AppState::sanitized`)
+//!
+//! ## Schema Versions
+//! The state file records the `STATE_VERSION` it was written with. Files
+//! from older versions are upgraded on load by `migrate_state_value`, and
+//! the upgraded file is written back by the next `read_state`. New fields
+//! need no migration step, they default on load; renamed or retyped ones
+//! do, along with a version bump.
 
-use crate::core::ScreenMode;
+use crate::core::awake_cap::{hours_to_session_secs, max_session_duration};
+use crate::core::power::validate_battery_floor;
+use crate::core::profile::DEFAULT_PROFILE;
+use crate::core::wake_interval::MIN_WAKE_INTERVAL_SECS;
+use crate::core::{
+    ConditionEndAction, ProfileSettings, Schedule, ScreenMode, WakeKey, WakeStrategy,
+};
 use crate::error::{AppError, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::OsString;
 use std::fs;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+#[cfg(test)]
+use std::sync::{Arc, Mutex};
+
+/// Name of the config directory used by builds branded "Awake"
+const LEGACY_DIR_NAME: &str = "awake";
+
+/// Environment variable naming a config directory to use instead of the
+/// platform one (e.g. for portable installs)
+pub const CONFIG_DIR_ENV: &str = "AWAKE_CONFIG_DIR";
+
+/// Config directory given by `--config-dir`, set once at startup
+static CONFIG_DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Schema version of the state files this build writes
+///
+/// - 0: No `version` field (everything before versioning, back to the
+///   original `{sleep_disabled, screen_mode}` file)
+/// - 1: `version` recorded
+/// - 2: `max_awake_hours` replaced by `max_session_secs`
+pub const STATE_VERSION: u32 = 2;
+
+/// Schema version of a state file, `STATE_VERSION` unless loaded from an
+/// older or newer file
+///
+/// A plain number on disk; a type of its own so new states default to the
+/// current version rather than 0.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(transparent)]
+pub struct StateVersion(pub u32);
+
+impl Default for StateVersion {
+    fn default() -> Self {
+        StateVersion(STATE_VERSION)
+    }
+}
 
 /// Application state persisted between sessions
-#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
 pub struct AppState {
     /// Whether system wake is currently active
     pub sleep_disabled: bool,
+    /// UNIX timestamp at which a timed wake turns off, None without a limit
+    #[serde(default)]
+    pub disable_at: Option<i64>,
     /// User's screen mode preference
     pub screen_mode: ScreenMode,
+    /// Optional label prefixed to the tooltip to tell instances apart
+    #[serde(default)]
+    pub instance_label: Option<String>,
+    /// Keep awake only while external power is connected
+    #[serde(default)]
+    pub only_on_ac: bool,
+    /// Keep awake while a window title contains this pattern
+    #[serde(default)]
+    pub watch_window_title: Option<String>,
+    /// Keep awake while a device matching this name or VID:PID is present
+    #[serde(default)]
+    pub watch_device: Option<String>,
+    /// Keep awake while a process with this executable name is running
+    #[serde(default)]
+    pub watch_process: Option<String>,
+    /// Keep awake while an interactive remote session (SSH, RDP) exists
+    #[serde(default)]
+    pub keep_awake_on_remote_session: bool,
+    /// Show advanced entries (diagnostics) in the tray menu
+    #[serde(default)]
+    pub show_advanced_menu: bool,
+    /// Master switch is off: wake and all automation are suspended
+    #[serde(default)]
+    pub master_off: bool,
+    /// Read every write back and compare it (see `write_state_verified_to`)
+    #[serde(default)]
+    pub verify_writes: bool,
+    /// Global wake interval in seconds (None uses the built-in default)
+    #[serde(default)]
+    pub wake_interval_secs: Option<u64>,
+    /// Per-screen-mode wake interval overrides in seconds
+    #[serde(default)]
+    pub interval_per_mode: HashMap<ScreenMode, u64>,
+    /// Briefly flash the tray icon on every F15 press
+    #[serde(default)]
+    pub blink_on_press: bool,
+    /// Require a second confirmation to disable wake while a watcher is active
+    #[serde(default)]
+    pub confirm_override_watchers: bool,
+    /// What to do when a watched condition ends
+    #[serde(default)]
+    pub on_condition_end: ConditionEndAction,
+    /// Delay before a system action on condition end (None uses the default)
+    #[serde(default)]
+    pub condition_end_delay_secs: Option<u64>,
+    /// Turn wake off after this many continuous seconds (None for no cap)
+    #[serde(default)]
+    pub max_session_secs: Option<u64>,
+    /// Log a heartbeat on every wake cycle (see `wake_service::Heartbeat`)
+    #[serde(default)]
+    pub log_each_iteration: bool,
+    /// Key pressed every wake cycle
+    #[serde(default)]
+    pub wake_key: WakeKey,
+    /// Global shortcut toggling wake, None for the built-in default
+    #[serde(default)]
+    pub toggle_shortcut: Option<String>,
+    /// Show a desktop notification when wake changes by itself
+    #[serde(default)]
+    pub notifications_enabled: bool,
+    /// Turn wake off on battery below this charge, None to never
+    #[serde(default)]
+    pub battery_floor_percent: Option<u8>,
+    /// Key press or mouse jiggle each wake cycle
+    #[serde(default)]
+    pub wake_strategy: WakeStrategy,
+    /// Log level (`error` to `trace`), None for `info`; `RUST_LOG` overrides
+    #[serde(default)]
+    pub log_level: Option<String>,
+    /// Start the next launch with sleep allowed, then clear this flag
+    #[serde(default)]
+    pub session_only: bool,
+    /// UNIX timestamp at which a pause ends and wake turns back on
+    #[serde(default)]
+    pub resume_at: Option<i64>,
+    /// Times of day at which wake turns on and off by itself
+    #[serde(default)]
+    pub schedule: Schedule,
+    /// Named settings bundles selectable from the tray
+    #[serde(default)]
+    pub profiles: HashMap<String, ProfileSettings>,
+    /// Profile last selected or saved, None before any profile exists
+    #[serde(default)]
+    pub active_profile: Option<String>,
+    /// Refuse to turn wake on while running on battery
+    #[serde(default)]
+    pub refuse_on_battery: bool,
+    /// Custom tooltip wording (see `core::tooltip`), None for the built-in
+    #[serde(default)]
+    pub tooltip_template: Option<String>,
+    /// Left click on the tray icon toggles wake instead of opening the
+    /// menu, None for the default (on)
+    #[serde(default)]
+    pub left_click_toggles: Option<bool>,
+    /// Serve the local control endpoint (see `crate::ipc`)
+    #[serde(default)]
+    pub enable_ipc: bool,
+    /// Vary the wake interval by up to 10% so machines do not press in step
+    #[serde(default)]
+    pub interval_jitter: bool,
+    /// Keep awake while this interface is up or this `host:port` is reachable
+    #[serde(default)]
+    pub watch_network: Option<String>,
+    /// Windows: hold power requests (listed by `powercfg /requests`)
+    /// instead of calling `SetThreadExecutionState`
+    #[serde(default)]
+    pub use_power_requests: bool,
+    /// Schema version the state was written with (see `STATE_VERSION`)
+    #[serde(default)]
+    pub version: StateVersion,
 }
 
-/// Get the path to the state file
+impl AppState {
+    /// This state with every value the app cannot use replaced
+    ///
+    /// ## Design Intent
+    /// The state file can be edited by hand, so nothing read from it is
+    /// trusted: every load passes through here (see `read_state_from`).
+    /// Values are repaired rather than rejected so one bad field does not
+    /// throw away the rest:
+    /// - Screen modes the platform cannot honor become Keep Screen On
+    /// - Wake intervals of zero mean the default, shorter ones are raised
+    ///   to `MIN_WAKE_INTERVAL_SECS` so the wake loop cannot spin
+    /// - An invalid max awake hours, battery floor or schedule is dropped
+    /// - A blank tooltip template means the built-in tooltip
+    ///
+    /// ## Side Effects
+    /// Logs a warning for every value it changes
+    pub fn sanitized(self) -> AppState {
+        self.sanitized_for(&ScreenMode::supported())
+    }
+
+    /// `sanitized` against an explicit list of supported screen modes
+    fn sanitized_for(mut self, supported: &[ScreenMode]) -> AppState {
+        let coerced = self.screen_mode.coerce_to(supported);
+        if coerced != self.screen_mode {
+            log::warn!(
+                "Screen mode {:?} is not supported on this platform, using {:?}",
+                self.screen_mode,
+                coerced
+            );
+            self.screen_mode = coerced;
+        }
+
+        self.wake_interval_secs = sanitize_interval("wake_interval_secs", self.wake_interval_secs);
+        self.interval_per_mode = self
+            .interval_per_mode
+            .into_iter()
+            .filter_map(|(mode, secs)| {
+                let name = format!("interval_per_mode {:?}", mode);
+                sanitize_interval(&name, Some(secs)).map(|secs| (mode, secs))
+            })
+            .collect();
+        for (name, profile) in self.profiles.iter_mut() {
+            let coerced = profile.screen_mode.coerce_to(supported);
+            if coerced != profile.screen_mode {
+                log::warn!(
+                    "Profile \"{}\" screen mode {:?} is not supported, using {:?}",
+                    name,
+                    profile.screen_mode,
+                    coerced
+                );
+                profile.screen_mode = coerced;
+            }
+            profile.wake_interval_secs = sanitize_interval(
+                &format!("profile \"{}\" wake_interval_secs", name),
+                profile.wake_interval_secs,
+            );
+        }
+
+        if let Err(e) = max_session_duration(self.max_session_secs) {
+            log::warn!("Ignoring max_session_secs: {}", e);
+            self.max_session_secs = None;
+        }
+        if let Err(e) = validate_battery_floor(self.battery_floor_percent) {
+            log::warn!("Ignoring battery_floor_percent: {}", e);
+            self.battery_floor_percent = None;
+        }
+        if let Err(e) = self.schedule.validate() {
+            log::warn!("Ignoring schedule: {}", e);
+            self.schedule = Schedule::default();
+        }
+        if self
+            .tooltip_template
+            .as_deref()
+            .is_some_and(|template| template.trim().is_empty())
+        {
+            log::warn!("Ignoring blank tooltip_template");
+            self.tooltip_template = None;
+        }
+        self
+    }
+}
+
+/// Wake interval made usable by the wake loop
+///
+/// ## Returns
+/// None for zero (use the default), `MIN_WAKE_INTERVAL_SECS` for anything
+/// shorter, otherwise the interval unchanged
+fn sanitize_interval(name: &str, secs: Option<u64>) -> Option<u64> {
+    match secs {
+        Some(0) => {
+            log::warn!("Ignoring {} of 0, using the default", name);
+            None
+        }
+        Some(secs) if secs < MIN_WAKE_INTERVAL_SECS => {
+            log::warn!(
+                "{} of {}s is below the minimum, using {}s",
+                name,
+                secs,
+                MIN_WAKE_INTERVAL_SECS
+            );
+            Some(MIN_WAKE_INTERVAL_SECS)
+        }
+        secs => secs,
+    }
+}
+
+/// Use `dir` as the config directory for the rest of the process
 ///
 /// ## Design Intent
-/// Centralizes path logic to ensure consistency across load/save operations.
+/// Called from `main` with the `--config-dir` value before anything reads
+/// the state or opens the log, so every entry point sees the same
+/// directory. Takes precedence over `AWAKE_CONFIG_DIR`.
+pub fn set_config_dir_override(dir: PathBuf) {
+    if let Err(dir) = CONFIG_DIR_OVERRIDE.set(dir) {
+        log::warn!("Config directory already set, ignoring {}", dir.display());
+    }
+}
+
+/// Config directory chosen by `--config-dir` or `AWAKE_CONFIG_DIR`, if any
 ///
-/// ## Platform Behavior
-/// - Windows: Uses %LOCALAPPDATA%\tea\state.json
-/// - Linux: Uses XDG_CONFIG_HOME or ~/.config/tea/state.json
-/// - macOS: Uses ~/Library/Application Support/tea/state.json
+/// ## Design Intent
+/// An empty variable counts as unset. Relative paths are used as given,
+/// i.e. resolved against the working directory.
+fn config_dir_override() -> Option<PathBuf> {
+    if let Some(dir) = CONFIG_DIR_OVERRIDE.get() {
+        return Some(dir.clone());
+    }
+    match std::env::var_os(CONFIG_DIR_ENV) {
+        Some(dir) if !dir.is_empty() => Some(PathBuf::from(dir)),
+        _ => None,
+    }
+}
+
+/// Get the directory holding the state file
 ///
-/// ## Side Effects
-/// Creates parent directories if they don't exist.
+/// ## Design Intent
+/// An override (see `config_dir_override`) wins over the platform
+/// directory.
+fn config_dir() -> PathBuf {
+    config_dir_override().unwrap_or_else(platform_config_dir)
+}
+
+/// Get the platform's default config directory
 ///
-/// ## Returns
-/// Result with path to state file. Parent directories are guaranteed to exist
-/// if function succeeds. Returns StateIo error if directory creation fails.
-fn get_state_file_path() -> Result<PathBuf> {
+/// ## Design Intent
+/// Builds the path from `OsString` environment values so home and config
+/// directories containing non-UTF-8 bytes are used as-is rather than
+/// rejected or lossily converted.
+///
+/// ## Platform Behavior
+/// - Windows: Uses %LOCALAPPDATA%\tea (falls back to %APPDATA%)
+/// - Linux: Uses XDG_CONFIG_HOME/tea or ~/.config/tea
+/// - macOS: Uses ~/Library/Application Support/tea
+/// - Other: Uses a `config` directory next to the executable
+fn platform_config_dir() -> PathBuf {
     #[cfg(target_os = "windows")]
     {
-        let local_app_data = std::env::var("LOCALAPPDATA")
-            .or_else(|_| std::env::var("APPDATA"))
-            .unwrap_or_else(|_| ".".to_string());
+        let local_app_data = std::env::var_os("LOCALAPPDATA")
+            .or_else(|| std::env::var_os("APPDATA"))
+            .unwrap_or_else(|| ".".into());
         let mut path = PathBuf::from(local_app_data);
         path.push("tea");
-        fs::create_dir_all(&path).map_err(|e| AppError::StateIo {
-            message: format!("Failed to create config directory at {}", path.display()),
-            cause: e.to_string(),
-            recovery_hint: "Ensure you have write permissions to the AppData directory.",
-        })?;
-        path.push("state.json");
-        Ok(path)
+        path
     }
     #[cfg(target_os = "linux")]
     {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        let xdg_config = std::env::var("XDG_CONFIG_HOME")
-            .unwrap_or_else(|_| format!("{}/.config", home));
-        let mut path = PathBuf::from(xdg_config);
+        let mut path = match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(xdg_config) if !xdg_config.is_empty() => PathBuf::from(xdg_config),
+            _ => {
+                let mut home =
+                    PathBuf::from(std::env::var_os("HOME").unwrap_or_else(|| ".".into()));
+                home.push(".config");
+                home
+            }
+        };
         path.push("tea");
-        fs::create_dir_all(&path).map_err(|e| AppError::StateIo {
-            message: format!("Failed to create config directory at {}", path.display()),
-            cause: e.to_string(),
-            recovery_hint: "Ensure you have write permissions to the config directory.",
-        })?;
-        path.push("state.json");
-        Ok(path)
+        path
     }
     #[cfg(target_os = "macos")]
     {
-        let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
-        let mut path = PathBuf::from(home);
+        let mut path = PathBuf::from(std::env::var_os("HOME").unwrap_or_else(|| ".".into()));
         path.push("Library");
         path.push("Application Support");
         path.push("tea");
-        fs::create_dir_all(&path).map_err(|e| AppError::StateIo {
-            message: format!("Failed to create config directory at {}", path.display()),
-            cause: e.to_string(),
-            recovery_hint: "Ensure you have write permissions to the Application Support directory.",
-        })?;
-        path.push("state.json");
-        Ok(path)
+        path
     }
     #[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
     {
@@ -102,16 +395,311 @@ fn get_state_file_path() -> Result<PathBuf> {
             .unwrap_or_else(|| std::path::Path::new("."))
             .to_path_buf();
         path.push("config");
-        fs::create_dir_all(&path).map_err(|e| AppError::StateIo {
-            message: format!("Failed to create config directory at {}", path.display()),
+        path
+    }
+}
+
+/// Get the directory holding the daily log files
+///
+/// ## Design Intent
+/// Logs sit next to the state file, so support finds both in one place.
+pub fn log_dir() -> PathBuf {
+    config_dir()
+}
+
+/// Get the path of the local control socket
+///
+/// ## Design Intent
+/// Kept in the config directory, which only the user can write to, so
+/// `--config-dir` instances each get their own. Windows uses a named
+/// pipe instead.
+#[cfg(unix)]
+pub fn ipc_socket_path() -> PathBuf {
+    config_dir().join("tea.sock")
+}
+
+/// Get the name of the local control pipe
+///
+/// ## Design Intent
+/// The Windows counterpart of `ipc_socket_path`: unique to the session and
+/// the config directory, so other users and `--config-dir` instances each
+/// get their own (see `core::ipc::pipe_name`).
+#[cfg(windows)]
+pub fn ipc_pipe_name() -> String {
+    crate::core::ipc::pipe_name(
+        crate::platform::current_session_id(),
+        &config_dir().to_string_lossy(),
+    )
+}
+
+/// Get the path to the state file
+///
+/// ## Design Intent
+/// Centralizes path logic to ensure consistency across load/save operations.
+///
+/// ## Side Effects
+/// Creates parent directories if they don't exist.
+///
+/// ## Returns
+/// Result with path to state file. Parent directories are guaranteed to exist
+/// if function succeeds. Returns StateIo error if directory creation fails.
+///
+/// ## Configuration
+/// `--config-dir` or `AWAKE_CONFIG_DIR` replace the platform directory.
+/// Legacy migration only applies to the platform directory, since an
+/// explicitly chosen directory has no `awake` sibling to carry over.
+fn get_state_file_path() -> Result<PathBuf> {
+    #[cfg(test)]
+    if let Some(dir) = TEST_CONFIG_DIR.with(|dir| dir.borrow().clone()) {
+        return state_file_path_in(dir);
+    }
+    if let Some(dir) = config_dir_override() {
+        return state_file_path_in(dir);
+    }
+    let dir = platform_config_dir();
+    migrate_legacy_dir(&dir);
+    state_file_path_in(dir)
+}
+
+/// Copy the state from an `awake` directory next to `dir`, once
+///
+/// ## Design Intent
+/// The config directory is `tea` on every platform, but builds branded
+/// "Awake" used an `awake` directory in the same place, so upgrading would
+/// silently start from defaults. Unlike `import_legacy`, which only knows
+/// the old two-field format, this carries the whole state over. It runs
+/// from every state lookup, so the first read by any entry point (tray,
+/// CLI, logging setup) migrates, and it is a single existence check once
+/// a current state file exists. The legacy directory is left in place so
+/// an older build still finds its settings.
+///
+/// ## Side Effects
+/// Writes `dir/state.json` when a readable legacy state is found.
+///
+/// ## Returns
+/// The legacy file migrated from, None if nothing was migrated
+fn migrate_legacy_dir(dir: &Path) -> Option<PathBuf> {
+    let current = dir.join("state.json");
+    if current.exists() {
+        return None;
+    }
+    let legacy = dir.parent()?.join(LEGACY_DIR_NAME).join("state.json");
+    if !legacy.is_file() {
+        return None;
+    }
+
+    let state = match load_state_from(&legacy) {
+        Ok(state) => state,
+        Err(e) => {
+            log::warn!("Not migrating legacy state: {}", e);
+            return None;
+        }
+    };
+    let written = fs::create_dir_all(dir)
+        .map_err(|e| e.to_string())
+        .and_then(|()| write_state_to(&current, &state).map_err(|e| e.to_string()));
+    match written {
+        Ok(()) => {
+            log::info!(
+                "Migrated state from {} to {}",
+                legacy.display(),
+                current.display()
+            );
+            Some(legacy)
+        }
+        Err(e) => {
+            log::warn!("Failed to migrate legacy state {}: {}", legacy.display(), e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    /// Per-thread config directory override for tests
+    static TEST_CONFIG_DIR: std::cell::RefCell<Option<PathBuf>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Redirect state reads and writes on this thread to `dir` (tests only)
+///
+/// ## Design Intent
+/// Lets command-level tests exercise persistence without touching the
+/// real config directory. Thread-local so parallel tests stay isolated;
+/// single-threaded Tokio tests run their spawned tasks on the same thread.
+#[cfg(test)]
+pub fn set_test_config_dir(dir: Option<PathBuf>) {
+    TEST_CONFIG_DIR.with(|current| *current.borrow_mut() = dir);
+}
+
+/// Resolve the state file inside `dir`, creating `dir` if needed
+///
+/// ## Design Intent
+/// Path-parameterised core of `get_state_file_path` so tests can point it at
+/// a temporary directory. Error messages use `Path::display`, which replaces
+/// invalid UTF-8 rather than failing.
+fn state_file_path_in(mut dir: PathBuf) -> Result<PathBuf> {
+    fs::create_dir_all(&dir).map_err(|e| AppError::StateIo {
+        message: format!("Failed to create config directory at {}", dir.display()),
+        cause: e.to_string(),
+        recovery_hint: "Ensure you have write permissions to the config directory.",
+        io_kind: e.kind(),
+    })?;
+    dir.push("state.json");
+    Ok(dir)
+}
+
+/// Verify the config directory exists and accepts writes
+///
+/// ## Design Intent
+/// Used by the startup self-check so an unwritable config directory is
+/// reported at launch instead of at the first state change.
+///
+/// ## Side Effects
+/// Creates the config directory if missing and briefly writes a probe file.
+///
+/// ## Returns
+/// Ok(()) if writable, AppError::StateIo otherwise
+pub fn verify_config_writable() -> Result<()> {
+    verify_writable_in(config_dir())
+}
+
+/// Path-parameterised core of `verify_config_writable`
+fn verify_writable_in(dir: PathBuf) -> Result<()> {
+    let mut probe = state_file_path_in(dir)?;
+    probe.set_file_name(".write-test");
+    fs::write(&probe, b"").map_err(|e| AppError::StateIo {
+        message: format!("Config directory is not writable: {}", probe.display()),
+        cause: e.to_string(),
+        recovery_hint: "Ensure you have write permissions to the config directory.",
+        io_kind: e.kind(),
+    })?;
+    if let Err(e) = fs::remove_file(&probe) {
+        log::warn!("Failed to remove write probe {}: {}", probe.display(), e);
+    }
+    Ok(())
+}
+
+/// Where application state is kept
+///
+/// ## Design Intent
+/// Lets tests of command logic keep state in memory instead of a temp
+/// directory. File-specific behavior (startup repair, legacy import,
+/// atomic replacement) stays on paths and is tested there.
+pub trait StateStore: Send + Sync {
+    /// Load the stored state, unsanitized
+    ///
+    /// ## Returns
+    /// The state (default if none is stored yet), or the error that kept
+    /// it from being read
+    fn read(&self) -> Result<AppState>;
+
+    /// Replace the stored state
+    fn write(&self, state: &AppState) -> Result<()>;
+}
+
+/// `state.json` in the config directory
+///
+/// The path is resolved on every call, so `--config-dir` and the legacy
+/// directory migration apply as before.
+pub struct FileStateStore;
+
+impl StateStore for FileStateStore {
+    fn read(&self) -> Result<AppState> {
+        let path = get_state_file_path()?;
+        let (state, version) = load_versioned_from(&path)?;
+        if version < STATE_VERSION {
+            match write_state_to(&path, &state) {
+                Ok(()) => log::info!(
+                    "Upgraded {} from state version {} to {}",
+                    path.display(),
+                    version,
+                    STATE_VERSION
+                ),
+                Err(e) => log::warn!("Failed to persist upgraded state: {}", e),
+            }
+        }
+        Ok(state)
+    }
+
+    fn write(&self, state: &AppState) -> Result<()> {
+        let path = get_state_file_path()?;
+        if state.verify_writes {
+            write_state_verified_to(&path, state)
+        } else {
+            write_state_to(&path, state)
+        }
+    }
+}
+
+/// State held in memory (tests only)
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemoryStateStore {
+    state: Mutex<Option<AppState>>,
+    writes: std::sync::atomic::AtomicUsize,
+}
+
+#[cfg(test)]
+impl MemoryStateStore {
+    /// Number of writes so far
+    pub fn writes(&self) -> usize {
+        self.writes.load(Ordering::SeqCst)
+    }
+}
+
+#[cfg(test)]
+impl StateStore for MemoryStateStore {
+    fn read(&self) -> Result<AppState> {
+        let state = self.state.lock().map_err(|e| AppError::StateIo {
+            message: "Memory state store poisoned".to_string(),
+            cause: e.to_string(),
+            recovery_hint: "A previous test panicked while writing state.",
+            io_kind: std::io::ErrorKind::Other,
+        })?;
+        Ok(state.clone().unwrap_or_default())
+    }
+
+    fn write(&self, state: &AppState) -> Result<()> {
+        let mut stored = self.state.lock().map_err(|e| AppError::StateIo {
+            message: "Memory state store poisoned".to_string(),
             cause: e.to_string(),
-            recovery_hint: "Ensure you have write permissions to the application directory.",
+            recovery_hint: "A previous test panicked while writing state.",
+            io_kind: std::io::ErrorKind::Other,
         })?;
-        path.push("state.json");
-        Ok(path)
+        *stored = Some(state.clone());
+        self.writes.fetch_add(1, Ordering::SeqCst);
+        Ok(())
     }
 }
 
+#[cfg(test)]
+thread_local! {
+    /// Per-thread state store for tests
+    static TEST_STORE: std::cell::RefCell<Option<Arc<dyn StateStore>>> =
+        const { std::cell::RefCell::new(None) };
+}
+
+/// Send state reads and writes on this thread to `store` (tests only)
+///
+/// ## Design Intent
+/// Like `set_test_config_dir`, thread-local so parallel tests stay
+/// isolated; single-threaded Tokio tests run their spawned tasks on the
+/// same thread. Takes precedence over the test config directory.
+#[cfg(test)]
+pub fn set_test_store(store: Option<Arc<dyn StateStore>>) {
+    TEST_STORE.with(|current| *current.borrow_mut() = store);
+}
+
+/// Run `f` with the store state is read from and written to
+fn with_store<T>(f: impl FnOnce(&dyn StateStore) -> T) -> T {
+    #[cfg(test)]
+    if let Some(store) = TEST_STORE.with(|store| store.borrow().clone()) {
+        return f(store.as_ref());
+    }
+    f(&FileStateStore)
+}
+
 /// Write application state to disk
 ///
 /// ## Design Intent
@@ -127,23 +715,146 @@ fn get_state_file_path() -> Result<PathBuf> {
 /// ## Returns
 /// Ok(()) on success, AppError::StateIo or AppError::StateSerialization on failure
 pub fn write_state(state: &AppState) -> Result<()> {
-    let path = get_state_file_path()?;
-    
-    let json = serde_json::to_string_pretty(state).map_err(|e| AppError::StateSerialization {
-        message: "Failed to serialize application state".to_string(),
+    with_store(|store| store.write(state))
+}
+
+/// Write application state to `path` and confirm it by reading it back
+///
+/// ## Design Intent
+/// For deployments that must know a preference really reached the disk.
+/// Detects writes that reported success but did not stick (e.g. a full disk
+/// that only failed on flush). Opt-in via `AppState.verify_writes` because
+/// it doubles the I/O of every save.
+///
+/// ## Returns
+/// Ok(()) if the read-back matches, AppError::StateIo otherwise
+fn write_state_verified_to(path: &Path, state: &AppState) -> Result<()> {
+    write_state_to(path, state)?;
+    verify_read_back(path, state, |path| fs::read_to_string(path))
+}
+
+/// Compare the state read back through `read` with `expected`
+///
+/// ## Design Intent
+/// Takes the reader as a parameter so tests can simulate a mismatching
+/// read-back without corrupting a real file system.
+fn verify_read_back<R>(path: &Path, expected: &AppState, read: R) -> Result<()>
+where
+    R: FnOnce(&Path) -> std::io::Result<String>,
+{
+    let content = read(path).map_err(|e| AppError::StateIo {
+        message: format!("Failed to read back state from {}", path.display()),
         cause: e.to_string(),
-        recovery_hint: "This is a bug. Please report it with your state configuration.",
+        recovery_hint: "Ensure the disk is healthy and has sufficient space.",
+        io_kind: e.kind(),
     })?;
 
-    fs::write(&path, json).map_err(|e| AppError::StateIo {
-        message: format!("Failed to write state to {}", path.display()),
+    let actual: AppState = serde_json::from_str(&content).map_err(|e| AppError::StateIo {
+        message: format!("State read back from {} is unreadable", path.display()),
         cause: e.to_string(),
-        recovery_hint: "Ensure you have write permissions and sufficient disk space.",
+        recovery_hint: "Ensure the disk is healthy and has sufficient space.",
+        io_kind: std::io::ErrorKind::InvalidData,
     })?;
 
+    if actual != *expected {
+        return Err(AppError::StateIo {
+            message: format!("State read back from {} does not match", path.display()),
+            cause: "written and read-back state differ".to_string(),
+            recovery_hint: "Ensure the disk is healthy and has sufficient space.",
+            io_kind: std::io::ErrorKind::InvalidData,
+        });
+    }
+
     Ok(())
 }
 
+/// Write application state to a specific file
+///
+/// ## Returns
+/// Ok(()) on success, AppError::StateIo or AppError::StateSerialization on failure
+fn write_state_to(path: &Path, state: &AppState) -> Result<()> {
+    let json = serde_json::to_string_pretty(state)?;
+
+    write_atomically(path, json.as_bytes(), |file, bytes| file.write_all(bytes)).map_err(|e| {
+        AppError::StateIo {
+            message: format!("Failed to write state to {}", path.display()),
+            cause: e.to_string(),
+            recovery_hint: "Ensure you have write permissions and sufficient disk space.",
+            io_kind: e.kind(),
+        }
+    })
+}
+
+/// Replace `path` with `bytes` so readers see the old or the new content,
+/// never a truncated mix
+///
+/// ## Design Intent
+/// A crash or power loss during a plain `fs::write` leaves a truncated
+/// `state.json`, which `read_state` then discards as corrupt. The bytes go
+/// to a temporary file in the same directory (same file system), are
+/// flushed to disk, and only then renamed over the target. Each write uses
+/// its own temporary name, so concurrent saves never mix their bytes.
+/// `write` is a parameter so tests can simulate a write that dies part way.
+///
+/// ## Platform Behavior
+/// - Unix: `rename` atomically replaces the target
+/// - Windows: `fs::rename` uses `MoveFileExW` with
+///   `MOVEFILE_REPLACE_EXISTING`, so an existing target is replaced
+///   without removing it first
+///
+/// ## Side Effects
+/// Creates and removes a temporary file next to `path`; on failure the
+/// target is left untouched.
+fn write_atomically<W>(path: &Path, bytes: &[u8], write: W) -> std::io::Result<()>
+where
+    W: FnOnce(&mut fs::File, &[u8]) -> std::io::Result<()>,
+{
+    static NEXT_TEMP: AtomicU64 = AtomicU64::new(0);
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(format!(
+        ".{}.{}.tmp",
+        std::process::id(),
+        NEXT_TEMP.fetch_add(1, Ordering::Relaxed)
+    ));
+    let temp = path.with_file_name(temp_name);
+
+    let result = fs::File::create(&temp)
+        .and_then(|mut file| {
+            write(&mut file, bytes)?;
+            file.sync_all()
+        })
+        .and_then(|()| fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp);
+    }
+    result
+}
+
+/// Update persisted state in place
+///
+/// ## Design Intent
+/// Read-modify-write helper so callers only touch the fields they own.
+/// Writing a freshly constructed `AppState` would silently reset every
+/// other persisted preference.
+///
+/// ## Arguments
+/// * `update` - Closure applying the change to the current state
+///
+/// ## Side Effects
+/// - Reads and rewrites the state file
+///
+/// ## Returns
+/// The state as written, or the error from `write_state`
+pub fn update_state<F>(update: F) -> Result<AppState>
+where
+    F: FnOnce(&mut AppState),
+{
+    let mut state = read_state();
+    update(&mut state);
+    write_state(&state)?;
+    Ok(state)
+}
+
 /// Read application state from disk
 ///
 /// ## Design Intent
@@ -154,38 +865,383 @@ pub fn write_state(state: &AppState) -> Result<()> {
 ///
 /// ## Returns
 /// Loaded state on success, or default state if file doesn't exist or is corrupted.
-/// Never fails - returns default state as fallback.
+/// Never fails - returns default state as fallback. A state left in a legacy
+/// `awake` directory is migrated first (see `migrate_legacy_dir`).
 pub fn read_state() -> AppState {
-    let path = match get_state_file_path() {
-        Ok(p) => p,
+    with_store(|store| store.read())
+        .map(AppState::sanitized)
+        .unwrap_or_else(|e| {
+            log::warn!("{}; using defaults", e);
+            AppState::default()
+        })
+}
+
+/// Read application state from a specific file
+///
+/// ## Returns
+/// Loaded and sanitized state, or default state if the file is missing or
+/// corrupted
+fn read_state_from(path: &Path) -> AppState {
+    load_state_from(path)
+        .map(AppState::sanitized)
+        .unwrap_or_else(|e| {
+            log::warn!("{}; using defaults", e);
+            AppState::default()
+        })
+}
+
+/// Load application state from a specific file without falling back
+///
+/// ## Returns
+/// Loaded state (default if the file does not exist yet, as on first
+/// run), AppError::StateIo if it cannot be read, or
+/// AppError::StateSerialization if it is corrupted
+fn load_state_from(path: &Path) -> Result<AppState> {
+    load_versioned_from(path).map(|(state, _)| state)
+}
+
+/// `load_state_from`, also returning the version the file was written with
+///
+/// ## Returns
+/// The state upgraded to `STATE_VERSION` and the file's own version
+/// (`STATE_VERSION` for a missing file), or the errors of `load_state_from`
+fn load_versioned_from(path: &Path) -> Result<(AppState, u32)> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            return Ok((AppState::default(), STATE_VERSION))
+        }
         Err(e) => {
-            log::error!("Failed to get state file path, using defaults: {}", e);
-            return AppState::default();
+            return Err(AppError::StateIo {
+                message: format!("Failed to read state file {}", path.display()),
+                cause: e.to_string(),
+                recovery_hint: "Ensure you have read permissions to the config directory.",
+                io_kind: e.kind(),
+            })
         }
     };
-    
-    match fs::read_to_string(&path) {
-        Ok(content) => {
-            match serde_json::from_str(&content) {
-                Ok(state) => state,
-                Err(e) => {
-                    log::warn!(
-                        "State file corrupted ({}), using defaults: {}",
-                        path.display(),
-                        e
-                    );
-                    AppState::default()
+    let corrupted = |cause: String| AppError::StateSerialization {
+        message: format!("State file {} is corrupted", path.display()),
+        cause,
+        recovery_hint: "Fix or delete the state file to start from defaults.",
+    };
+    let mut value: serde_json::Value =
+        serde_json::from_str(&content).map_err(|e| corrupted(e.to_string()))?;
+    let version = migrate_state_value(&mut value).map_err(corrupted)?;
+    let state = serde_json::from_value(value).map_err(|e| corrupted(e.to_string()))?;
+    Ok((state, version))
+}
+
+/// Upgrade the JSON of a state file to `STATE_VERSION`
+///
+/// ## Design Intent
+/// Works on the raw JSON, before it is deserialized, so a step can rename
+/// or reshape fields that the current `AppState` could not read. Each step
+/// upgrades one version, so a file of any age takes the same path. A file
+/// from a newer build is loaded as far as this build understands it and
+/// is not written back on load; the next save drops what it did not know.
+///
+/// ## Returns
+/// The version the file was written with, or why it is not a state file
+fn migrate_state_value(value: &mut serde_json::Value) -> std::result::Result<u32, String> {
+    let fields = value
+        .as_object_mut()
+        .ok_or_else(|| "state is not a JSON object".to_string())?;
+    let written = match fields.get("version") {
+        None => 0,
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| format!("invalid state version {}", version))?,
+    };
+    if written > STATE_VERSION {
+        log::warn!(
+            "State file is from a newer version ({} > {}), loading what this version knows",
+            written,
+            STATE_VERSION
+        );
+    }
+
+    if written < 1 {
+        // v0 -> v1: the oldest files may lack the two original settings,
+        // which have no serde default
+        fields
+            .entry("sleep_disabled")
+            .or_insert(serde_json::Value::Bool(false));
+        fields
+            .entry("screen_mode")
+            .or_insert_with(|| serde_json::json!(ScreenMode::default()));
+    }
+    if written < 2 {
+        // v1 -> v2: the awake cap moved from hours to seconds; a cap that
+        // is not a valid number of hours is dropped, as it was on load
+        if let Some(hours) = fields.remove("max_awake_hours") {
+            match hours_to_session_secs(hours.as_f64()) {
+                Ok(Some(secs)) => {
+                    fields
+                        .entry("max_session_secs")
+                        .or_insert_with(|| secs.into());
                 }
+                Ok(None) => {}
+                Err(e) => log::warn!("Dropping max_awake_hours: {}", e),
             }
         }
-        Err(e) => {
-            // File not existing is normal on first run
-            if e.kind() != std::io::ErrorKind::NotFound {
-                log::warn!("Failed to read state file, using defaults: {}", e);
-            }
-            AppState::default()
+    }
+    fields.insert("version".to_string(), STATE_VERSION.into());
+    Ok(written)
+}
+
+/// Back up a corrupt state file and replace it with defaults
+///
+/// ## Design Intent
+/// `read_state` already falls back to defaults for a corrupt file, but
+/// leaves the garbage in place for external tooling (and the user) to
+/// trip over. Repair keeps the bad bytes in `state.json.bak` for
+/// inspection and writes a valid default `state.json`. Unreadable files
+/// (permissions, disk errors) are not corruption and are left alone.
+///
+/// ## Side Effects
+/// Copies the corrupt file to `state.json.bak`, replacing an older backup,
+/// and rewrites `state.json`.
+///
+/// ## Returns
+/// True if the file was corrupt and has been repaired, false if it was
+/// valid, missing or unreadable
+fn repair_state(path: &Path) -> Result<bool> {
+    let corruption = match load_state_from(path) {
+        Err(e @ AppError::StateSerialization { .. }) => e,
+        _ => return Ok(false),
+    };
+
+    let backup = backup_path(path);
+    fs::copy(path, &backup).map_err(|e| AppError::StateIo {
+        message: format!("Failed to back up corrupt state to {}", backup.display()),
+        cause: e.to_string(),
+        recovery_hint: "Ensure you have write permissions to the config directory.",
+        io_kind: e.kind(),
+    })?;
+    write_state_to(path, &AppState::default())?;
+
+    log::warn!(
+        "{}; backed up to {} and reset to defaults",
+        corruption,
+        backup.display()
+    );
+    Ok(true)
+}
+
+/// Write back a state file whose values had to be sanitized
+///
+/// ## Design Intent
+/// `read_state` sanitizes on every load, but an unchanged file would be
+/// sanitized (and warned about) again on every read until some setting
+/// is changed. Correcting it once at startup keeps the log quiet and the
+/// file honest about the values in use.
+///
+/// ## Returns
+/// True if the file has been rewritten, false if it was valid, missing,
+/// unreadable or corrupt (see `repair_state`)
+fn correct_state(path: &Path) -> Result<bool> {
+    let Ok(loaded) = load_state_from(path) else {
+        return Ok(false);
+    };
+    let sanitized = loaded.clone().sanitized();
+    if sanitized == loaded {
+        return Ok(false);
+    }
+
+    write_state_to(path, &sanitized)?;
+    log::info!("Corrected out-of-range values in {}", path.display());
+    Ok(true)
+}
+
+/// Backup location of a corrupt state file (`state.json.bak`)
+fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".bak");
+    path.with_file_name(name)
+}
+
+/// Environment variable that turns on strict config loading
+pub const STRICT_CONFIG_ENV: &str = "AWAKE_STRICT_CONFIG";
+
+/// Whether strict config loading is requested by `AWAKE_STRICT_CONFIG`
+///
+/// ## Returns
+/// True for `1`, `true` or `yes` (case-insensitive)
+pub fn strict_config_from_env() -> bool {
+    is_strict_config(std::env::var_os(STRICT_CONFIG_ENV).as_deref())
+}
+
+/// Parse the value of `AWAKE_STRICT_CONFIG`
+fn is_strict_config(value: Option<&std::ffi::OsStr>) -> bool {
+    value
+        .and_then(|value| value.to_str())
+        .map(|value| {
+            matches!(
+                value.trim().to_ascii_lowercase().as_str(),
+                "1" | "true" | "yes"
+            )
+        })
+        .unwrap_or(false)
+}
+
+/// Read state for startup, honoring strict config mode
+///
+/// ## Design Intent
+/// By default a corrupt or unreadable config falls back to defaults so the
+/// app always starts; a corrupt file is also repaired (see `repair_state`).
+/// Managed deployments can set `AWAKE_STRICT_CONFIG=1` to fail loudly
+/// instead and catch misconfiguration, leaving the file untouched.
+///
+/// ## Arguments
+/// * `strict` - Fail instead of defaulting
+///
+/// ## Returns
+/// Loaded state, or the load error in strict mode
+pub fn read_startup_state(strict: bool) -> Result<AppState> {
+    let path = get_state_file_path()?;
+    startup_state_from(&path, strict)
+}
+
+/// Path-parameterised core of `read_startup_state`
+fn startup_state_from(path: &Path, strict: bool) -> Result<AppState> {
+    let mut state = if strict {
+        load_state_from(path)?.sanitized()
+    } else {
+        if let Err(e) = repair_state(path) {
+            log::error!("Failed to repair state file: {}", e);
+        }
+        if let Err(e) = correct_state(path) {
+            log::error!("Failed to correct state file: {}", e);
         }
+        read_state_from(path)
+    };
+    ensure_default_profile(&mut state);
+    Ok(state)
+}
+
+/// Give a state file without profiles a "Default" profile
+///
+/// ## Design Intent
+/// State files from before profiles keep their flat settings, which become
+/// the "Default" profile so the tray always lists at least one. It is
+/// written with the next change to the file; until then it is synthesized
+/// again on every load, from the same settings.
+pub fn ensure_default_profile(state: &mut AppState) {
+    if !state.profiles.is_empty() {
+        return;
+    }
+    state.profiles.insert(
+        DEFAULT_PROFILE.to_string(),
+        ProfileSettings {
+            screen_mode: state.screen_mode,
+            wake_interval_secs: state.wake_interval_secs,
+            wake_key: state.wake_key,
+        },
+    );
+    state.active_profile = Some(DEFAULT_PROFILE.to_string());
+}
+
+/// State file written by builds from before the rename to `tea`
+#[derive(Deserialize)]
+struct LegacyState {
+    sleep_disabled: bool,
+    #[serde(default)]
+    screen_mode: ScreenMode,
+}
+
+/// Carry settings over from a legacy state file
+///
+/// ## Design Intent
+/// Older builds kept `{sleep_disabled, screen_mode}` in a different place,
+/// so users upgrading would otherwise silently start from defaults. Only
+/// runs while no current state file exists, so it never overrides
+/// settings made since.
+///
+/// ## Platform Behavior
+/// - Linux: `awake/state.json` under XDG_CONFIG_HOME (or ~/.config)
+/// - Other: `config/state.json` next to the executable
+///
+/// ## Side Effects
+/// Writes the current state file when a legacy one is imported.
+///
+/// ## Returns
+/// The legacy file imported from, None if nothing was imported, or
+/// AppError::StateIo if the imported state cannot be written
+pub fn import_legacy() -> Result<Option<PathBuf>> {
+    let path = get_state_file_path()?;
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf));
+    let candidates = legacy_state_paths(
+        cfg!(target_os = "linux"),
+        std::env::var_os("XDG_CONFIG_HOME"),
+        std::env::var_os("HOME"),
+        exe_dir,
+    );
+    import_legacy_into(&path, &candidates)
+}
+
+/// Legacy state file locations, most specific first
+///
+/// ## Arguments
+/// * `xdg_layout` - Legacy build used the XDG layout (Linux)
+/// * `xdg_config_home` - Value of XDG_CONFIG_HOME
+/// * `home` - Value of HOME
+/// * `exe_dir` - Directory containing the executable
+fn legacy_state_paths(
+    xdg_layout: bool,
+    xdg_config_home: Option<OsString>,
+    home: Option<OsString>,
+    exe_dir: Option<PathBuf>,
+) -> Vec<PathBuf> {
+    if !xdg_layout {
+        return exe_dir
+            .map(|dir| dir.join("config").join("state.json"))
+            .into_iter()
+            .collect();
     }
+    let config = match (xdg_config_home, home) {
+        (Some(xdg_config), _) if !xdg_config.is_empty() => PathBuf::from(xdg_config),
+        (_, Some(home)) => PathBuf::from(home).join(".config"),
+        _ => return Vec::new(),
+    };
+    vec![config.join("awake").join("state.json")]
+}
+
+/// Import the first readable legacy file into `path` if `path` is absent
+fn import_legacy_into(path: &Path, candidates: &[PathBuf]) -> Result<Option<PathBuf>> {
+    if path.exists() {
+        return Ok(None);
+    }
+    for candidate in candidates {
+        let content = match fs::read_to_string(candidate) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+        let legacy: LegacyState = match serde_json::from_str(&content) {
+            Ok(legacy) => legacy,
+            Err(e) => {
+                log::warn!(
+                    "Ignoring unreadable legacy state {}: {}",
+                    candidate.display(),
+                    e
+                );
+                continue;
+            }
+        };
+
+        let state = AppState {
+            sleep_disabled: legacy.sleep_disabled,
+            screen_mode: legacy.screen_mode,
+            ..AppState::default()
+        };
+        write_state_to(path, &state)?;
+        log::info!("Imported legacy settings from {}", candidate.display());
+        return Ok(Some(candidate.clone()));
+    }
+    Ok(None)
 }
 
 #[cfg(test)]
@@ -203,7 +1259,54 @@ mod tests {
     fn test_state_serialization() {
         let state = AppState {
             sleep_disabled: true,
+            disable_at: Some(1_700_000_000),
             screen_mode: ScreenMode::KeepScreenOn,
+            instance_label: Some("Build Server".to_string()),
+            only_on_ac: true,
+            watch_window_title: Some("Render".to_string()),
+            watch_device: Some("17EF:A396".to_string()),
+            watch_process: Some("blender".to_string()),
+            keep_awake_on_remote_session: true,
+            show_advanced_menu: true,
+            master_off: true,
+            verify_writes: true,
+            wake_interval_secs: Some(30),
+            interval_per_mode: HashMap::from([(ScreenMode::KeepScreenOn, 15)]),
+            blink_on_press: true,
+            confirm_override_watchers: true,
+            on_condition_end: ConditionEndAction::Hibernate,
+            condition_end_delay_secs: Some(60),
+            max_session_secs: Some(45_000),
+            log_each_iteration: true,
+            wake_key: WakeKey::ScrollLock,
+            toggle_shortcut: Some("Ctrl+Shift+F12".to_string()),
+            notifications_enabled: true,
+            battery_floor_percent: Some(20),
+            wake_strategy: WakeStrategy::MouseJiggle,
+            log_level: Some("debug".to_string()),
+            session_only: true,
+            resume_at: Some(1_700_000_600),
+            schedule: serde_json::from_str(
+                r#"{"windows":[{"days":31,"start":"09:00","end":"18:00"}]}"#,
+            )
+            .unwrap(),
+            profiles: HashMap::from([(
+                "Work".to_string(),
+                ProfileSettings {
+                    screen_mode: ScreenMode::KeepScreenOn,
+                    wake_interval_secs: Some(30),
+                    wake_key: WakeKey::F13,
+                },
+            )]),
+            active_profile: Some("Work".to_string()),
+            refuse_on_battery: true,
+            tooltip_template: Some("{label}: {state}".to_string()),
+            left_click_toggles: Some(false),
+            enable_ipc: true,
+            interval_jitter: true,
+            watch_network: Some("wg0".to_string()),
+            use_power_requests: true,
+            version: StateVersion(STATE_VERSION),
         };
 
         let json = serde_json::to_string(&state).unwrap();
@@ -211,4 +1314,544 @@ mod tests {
 
         assert_eq!(state, deserialized);
     }
+
+    #[test]
+    fn test_v1_awake_hours_migrate_to_session_secs() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("state.json");
+
+        fs::write(
+            &path,
+            r#"{"sleep_disabled":false,"screen_mode":"KeepScreenOn","version":1,"max_awake_hours":0.5}"#,
+        )
+        .unwrap();
+        let (state, written) = load_versioned_from(&path).unwrap();
+        assert_eq!(written, 1);
+        assert_eq!(state.max_session_secs, Some(1800));
+
+        // An invalid cap is dropped rather than failing the load
+        fs::write(
+            &path,
+            r#"{"sleep_disabled":false,"screen_mode":"KeepScreenOn","version":1,"max_awake_hours":-2}"#,
+        )
+        .unwrap();
+        let (state, _) = load_versioned_from(&path).unwrap();
+        assert_eq!(state.max_session_secs, None);
+    }
+
+    #[test]
+    fn test_v0_state_migrates_to_current_version() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("state.json");
+
+        fs::write(
+            &path,
+            r#"{"sleep_disabled":true,"screen_mode":"KeepScreenOn"}"#,
+        )
+        .unwrap();
+        let (state, written) = load_versioned_from(&path).unwrap();
+        assert_eq!(written, 0);
+        assert_eq!(
+            state,
+            AppState {
+                sleep_disabled: true,
+                screen_mode: ScreenMode::KeepScreenOn,
+                ..AppState::default()
+            }
+        );
+        assert_eq!(state.version, StateVersion(STATE_VERSION));
+
+        // The oldest files may not have a screen mode at all
+        fs::write(&path, r#"{"sleep_disabled":true}"#).unwrap();
+        let (state, _) = load_versioned_from(&path).unwrap();
+        assert!(state.sleep_disabled);
+        assert_eq!(state.screen_mode, ScreenMode::default());
+
+        fs::write(&path, r#"{"sleep_disabled":true,"version":"one"}"#).unwrap();
+        assert!(matches!(
+            load_versioned_from(&path),
+            Err(AppError::StateSerialization { .. })
+        ));
+    }
+
+    #[test]
+    fn test_read_state_persists_migrated_file() {
+        let temp = tempfile::tempdir().unwrap();
+        set_test_config_dir(Some(temp.path().to_path_buf()));
+        let path = get_state_file_path().unwrap();
+        let on_disk = || -> serde_json::Value {
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap()
+        };
+
+        fs::write(
+            &path,
+            r#"{"sleep_disabled":true,"screen_mode":"KeepScreenOn"}"#,
+        )
+        .unwrap();
+        assert!(read_state().sleep_disabled);
+        assert_eq!(on_disk()["version"], STATE_VERSION);
+        assert_eq!(on_disk()["sleep_disabled"], true);
+
+        // A newer file loads without being rewritten
+        let newer =
+            r#"{"sleep_disabled":true,"screen_mode":"KeepScreenOn","version":99,"future":1}"#;
+        fs::write(&path, newer).unwrap();
+        assert_eq!(read_state().version, StateVersion(STATE_VERSION));
+        assert_eq!(fs::read_to_string(&path).unwrap(), newer);
+        set_test_config_dir(None);
+    }
+
+    #[test]
+    fn test_state_without_label_loads() {
+        let json = r#"{"sleep_disabled":true,"screen_mode":"KeepScreenOn"}"#;
+        let state: AppState = serde_json::from_str(json).unwrap();
+        assert!(state.sleep_disabled);
+        assert_eq!(state.instance_label, None);
+        assert!(!state.only_on_ac);
+        assert!(!state.master_off);
+        assert_eq!(state.wake_interval_secs, None);
+        assert!(state.interval_per_mode.is_empty());
+        assert_eq!(state.wake_key, WakeKey::F15);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_non_utf8_config_dir_round_trip() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join(OsStr::from_bytes(b"caf\xe9-config"));
+        assert!(dir.to_str().is_none(), "test path must not be valid UTF-8");
+
+        let path = state_file_path_in(dir.clone()).unwrap();
+        assert!(dir.is_dir());
+
+        let state = AppState {
+            sleep_disabled: true,
+            screen_mode: ScreenMode::KeepScreenOn,
+            ..AppState::default()
+        };
+        write_state_to(&path, &state).unwrap();
+        assert_eq!(read_state_from(&path), state);
+    }
+
+    #[test]
+    fn test_config_dir_env_override() {
+        // Tests that redirect with `set_test_config_dir` take precedence over
+        // the process-wide variable, so setting it here does not leak into them
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("portable");
+        std::env::set_var(CONFIG_DIR_ENV, &dir);
+
+        let written = update_state(|s| s.sleep_disabled = true);
+        let read_back = read_state();
+        let blocker = temp.path().join("file");
+        fs::write(&blocker, b"").unwrap();
+        std::env::set_var(CONFIG_DIR_ENV, blocker.join("config"));
+        let unusable = get_state_file_path();
+        std::env::remove_var(CONFIG_DIR_ENV);
+
+        assert!(written.unwrap().sleep_disabled);
+        assert!(read_back.sleep_disabled);
+        assert!(dir.join("state.json").is_file());
+        assert!(matches!(unusable, Err(AppError::StateIo { .. })));
+    }
+
+    #[test]
+    fn test_verify_writable_creates_dir_and_cleans_up() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("tea");
+
+        verify_writable_in(dir.clone()).unwrap();
+        assert!(dir.is_dir());
+        assert!(!dir.join(".write-test").exists());
+    }
+
+    #[test]
+    fn test_verified_write_succeeds() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("state.json");
+        let state = AppState {
+            sleep_disabled: true,
+            verify_writes: true,
+            ..AppState::default()
+        };
+
+        write_state_verified_to(&path, &state).unwrap();
+        assert_eq!(read_state_from(&path), state);
+    }
+
+    #[test]
+    fn test_read_back_mismatch_is_state_io_error() {
+        let expected = AppState {
+            sleep_disabled: true,
+            ..AppState::default()
+        };
+        let stale = serde_json::to_string(&AppState::default()).unwrap();
+
+        let result = verify_read_back(Path::new("state.json"), &expected, |_| Ok(stale));
+        assert!(matches!(result, Err(AppError::StateIo { .. })));
+    }
+
+    /// Write a legacy state file at `path`, creating its directory
+    fn write_legacy(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(
+            path,
+            r#"{"sleep_disabled":true,"screen_mode":"KeepScreenOn"}"#,
+        )
+        .unwrap();
+    }
+
+    fn assert_imported(new_path: &Path, candidates: &[PathBuf], legacy: &Path) {
+        let imported = import_legacy_into(new_path, candidates).unwrap();
+        assert_eq!(imported.as_deref(), Some(legacy));
+        let state = read_state_from(new_path);
+        assert!(state.sleep_disabled);
+        assert_eq!(state.screen_mode, ScreenMode::KeepScreenOn);
+    }
+
+    #[test]
+    fn test_import_from_xdg_config_home() {
+        let temp = tempfile::tempdir().unwrap();
+        let xdg = temp.path().join("xdg");
+        let legacy = xdg.join("awake").join("state.json");
+        write_legacy(&legacy);
+
+        let candidates = legacy_state_paths(
+            true,
+            Some(xdg.into_os_string()),
+            Some(temp.path().join("home").into_os_string()),
+            None,
+        );
+        let new_path = state_file_path_in(temp.path().join("tea")).unwrap();
+        assert_imported(&new_path, &candidates, &legacy);
+    }
+
+    #[test]
+    fn test_import_from_home_config_without_xdg() {
+        let temp = tempfile::tempdir().unwrap();
+        let home = temp.path().join("home");
+        let legacy = home.join(".config").join("awake").join("state.json");
+        write_legacy(&legacy);
+
+        let candidates = legacy_state_paths(
+            true,
+            Some(OsString::new()),
+            Some(home.into_os_string()),
+            None,
+        );
+        let new_path = state_file_path_in(temp.path().join("tea")).unwrap();
+        assert_imported(&new_path, &candidates, &legacy);
+    }
+
+    #[test]
+    fn test_import_from_exe_adjacent_config() {
+        let temp = tempfile::tempdir().unwrap();
+        let exe_dir = temp.path().join("bin");
+        let legacy = exe_dir.join("config").join("state.json");
+        write_legacy(&legacy);
+
+        let candidates = legacy_state_paths(false, None, None, Some(exe_dir));
+        let new_path = state_file_path_in(temp.path().join("tea")).unwrap();
+        assert_imported(&new_path, &candidates, &legacy);
+    }
+
+    #[test]
+    fn test_import_skipped_when_state_exists() {
+        let temp = tempfile::tempdir().unwrap();
+        let exe_dir = temp.path().join("bin");
+        write_legacy(&exe_dir.join("config").join("state.json"));
+        let new_path = state_file_path_in(temp.path().join("tea")).unwrap();
+        write_state_to(&new_path, &AppState::default()).unwrap();
+
+        let candidates = legacy_state_paths(false, None, None, Some(exe_dir));
+        assert_eq!(import_legacy_into(&new_path, &candidates).unwrap(), None);
+        assert!(!read_state_from(&new_path).sleep_disabled);
+    }
+
+    #[test]
+    fn test_startup_coerces_unsupported_screen_mode() {
+        let state = AppState {
+            sleep_disabled: true,
+            screen_mode: ScreenMode::AllowScreenOff,
+            ..AppState::default()
+        };
+        let coerced = state.clone().sanitized_for(&[ScreenMode::KeepScreenOn]);
+        assert_eq!(coerced.screen_mode, ScreenMode::KeepScreenOn);
+        assert!(coerced.sleep_disabled);
+        assert_eq!(state.clone().sanitized_for(&ScreenMode::ALL), state);
+    }
+
+    #[test]
+    fn test_state_round_trips_through_test_store() {
+        let store = Arc::new(MemoryStateStore::default());
+        set_test_store(Some(store.clone()));
+        assert_eq!(read_state(), AppState::default());
+
+        let written = update_state(|s| {
+            s.sleep_disabled = true;
+            s.wake_interval_secs = Some(0);
+            s.verify_writes = true;
+        })
+        .unwrap();
+        assert_eq!(store.read().unwrap(), written);
+        // Reads are sanitized whatever the store
+        let state = read_state();
+        assert!(state.sleep_disabled);
+        assert_eq!(state.wake_interval_secs, None);
+
+        set_test_store(None);
+    }
+
+    #[test]
+    fn test_sanitized_repairs_out_of_range_values() {
+        let bad = AppState {
+            sleep_disabled: true,
+            wake_interval_secs: Some(0),
+            interval_per_mode: HashMap::from([
+                (ScreenMode::KeepScreenOn, 1),
+                (ScreenMode::AllowScreenOff, 0),
+                (ScreenMode::AwayMode, 90),
+            ]),
+            profiles: HashMap::from([(
+                "Work".to_string(),
+                ProfileSettings {
+                    screen_mode: ScreenMode::AwayMode,
+                    wake_interval_secs: Some(2),
+                    wake_key: WakeKey::F13,
+                },
+            )]),
+            max_session_secs: Some(0),
+            battery_floor_percent: Some(150),
+            schedule: serde_json::from_str(
+                r#"{"windows":[{"days":0,"start":"09:00","end":"18:00"}]}"#,
+            )
+            .unwrap(),
+            tooltip_template: Some("  ".to_string()),
+            ..AppState::default()
+        };
+
+        let sane = bad.sanitized_for(&[ScreenMode::KeepScreenOn]);
+        assert!(sane.sleep_disabled);
+        assert_eq!(sane.wake_interval_secs, None);
+        assert_eq!(
+            sane.interval_per_mode,
+            HashMap::from([
+                (ScreenMode::KeepScreenOn, MIN_WAKE_INTERVAL_SECS),
+                (ScreenMode::AwayMode, 90),
+            ])
+        );
+        let work = &sane.profiles["Work"];
+        assert_eq!(work.screen_mode, ScreenMode::KeepScreenOn);
+        assert_eq!(work.wake_interval_secs, Some(MIN_WAKE_INTERVAL_SECS));
+        assert_eq!(work.wake_key, WakeKey::F13);
+        assert_eq!(sane.max_session_secs, None);
+        assert_eq!(sane.battery_floor_percent, None);
+        assert_eq!(sane.schedule, Schedule::default());
+        assert_eq!(sane.tooltip_template, None);
+
+        // Valid values pass through untouched
+        let good = AppState {
+            wake_interval_secs: Some(MIN_WAKE_INTERVAL_SECS),
+            max_session_secs: Some(8 * 3600),
+            battery_floor_percent: Some(20),
+            ..AppState::default()
+        };
+        assert_eq!(good.clone().sanitized_for(&ScreenMode::ALL), good);
+    }
+
+    #[test]
+    fn test_startup_corrects_state_file() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("state.json");
+        fs::write(
+            &path,
+            r#"{"sleep_disabled":true,"screen_mode":"KeepScreenOn","wake_interval_secs":0}"#,
+        )
+        .unwrap();
+
+        let state = startup_state_from(&path, false).unwrap();
+        assert!(state.sleep_disabled);
+        assert_eq!(state.wake_interval_secs, None);
+        assert_eq!(load_state_from(&path).unwrap().wake_interval_secs, None);
+        assert!(!correct_state(&path).unwrap());
+    }
+
+    #[test]
+    fn test_default_profile_from_legacy_settings() {
+        let mut state = AppState {
+            screen_mode: ScreenMode::KeepScreenOn,
+            wake_interval_secs: Some(30),
+            wake_key: WakeKey::ScrollLock,
+            ..AppState::default()
+        };
+        ensure_default_profile(&mut state);
+        assert_eq!(
+            state.profiles.get(DEFAULT_PROFILE),
+            Some(&ProfileSettings {
+                screen_mode: ScreenMode::KeepScreenOn,
+                wake_interval_secs: Some(30),
+                wake_key: WakeKey::ScrollLock,
+            })
+        );
+        assert_eq!(state.active_profile.as_deref(), Some(DEFAULT_PROFILE));
+
+        // Existing profiles are left alone
+        let mut state = AppState {
+            profiles: HashMap::from([("Home".to_string(), ProfileSettings::default())]),
+            ..AppState::default()
+        };
+        ensure_default_profile(&mut state);
+        assert_eq!(state.profiles.len(), 1);
+        assert_eq!(state.active_profile, None);
+    }
+
+    #[test]
+    fn test_interrupted_write_keeps_previous_state() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("state.json");
+        let good = AppState {
+            sleep_disabled: true,
+            screen_mode: ScreenMode::KeepScreenOn,
+            ..AppState::default()
+        };
+        write_state_to(&path, &good).unwrap();
+
+        // Half the bytes reach the disk, then the write dies
+        let result = write_atomically(&path, b"{\"sleep_disabled\": false}", |file, bytes| {
+            file.write_all(&bytes[..bytes.len() / 2])?;
+            Err(std::io::Error::other("simulated power loss"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(load_state_from(&path).unwrap(), good);
+        let leftovers: Vec<_> = fs::read_dir(temp.path()).unwrap().collect();
+        assert_eq!(leftovers.len(), 1);
+    }
+
+    #[test]
+    fn test_atomic_write_replaces_existing_state() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("state.json");
+        write_state_to(&path, &AppState::default()).unwrap();
+        let updated = AppState {
+            sleep_disabled: true,
+            ..AppState::default()
+        };
+
+        write_state_to(&path, &updated).unwrap();
+        assert_eq!(load_state_from(&path).unwrap(), updated);
+    }
+
+    #[test]
+    fn test_migrate_legacy_awake_dir() {
+        let temp = tempfile::tempdir().unwrap();
+        let legacy = AppState {
+            sleep_disabled: true,
+            screen_mode: ScreenMode::KeepScreenOn,
+            wake_interval_secs: Some(120),
+            ..AppState::default()
+        };
+        let legacy_path = state_file_path_in(temp.path().join("awake")).unwrap();
+        write_state_to(&legacy_path, &legacy).unwrap();
+        let dir = temp.path().join("tea");
+
+        assert_eq!(migrate_legacy_dir(&dir), Some(legacy_path.clone()));
+        assert_eq!(read_state_from(&dir.join("state.json")), legacy);
+        assert!(legacy_path.exists());
+
+        // Settings made since are never overwritten
+        write_state_to(&dir.join("state.json"), &AppState::default()).unwrap();
+        assert_eq!(migrate_legacy_dir(&dir), None);
+        assert_eq!(
+            read_state_from(&dir.join("state.json")),
+            AppState::default()
+        );
+    }
+
+    #[test]
+    fn test_migrate_skips_missing_or_corrupt_legacy_state() {
+        let temp = tempfile::tempdir().unwrap();
+        let dir = temp.path().join("tea");
+        assert_eq!(migrate_legacy_dir(&dir), None);
+
+        let legacy_path = state_file_path_in(temp.path().join("awake")).unwrap();
+        fs::write(&legacy_path, "{not json").unwrap();
+        assert_eq!(migrate_legacy_dir(&dir), None);
+        assert!(!dir.join("state.json").exists());
+    }
+
+    #[test]
+    fn test_strict_mode_rejects_invalid_config() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("state.json");
+        fs::write(&path, "{not json").unwrap();
+
+        assert!(matches!(
+            startup_state_from(&path, true),
+            Err(AppError::StateSerialization { .. })
+        ));
+        assert_eq!(
+            startup_state_from(&path, false).unwrap(),
+            with_default_profile(AppState::default())
+        );
+    }
+
+    #[test]
+    fn test_repair_backs_up_corrupt_state() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("state.json");
+        let backup = temp.path().join("state.json.bak");
+        fs::write(&path, "{not json").unwrap();
+
+        assert!(startup_state_from(&path, true).is_err());
+        assert!(!backup.exists(), "strict mode must leave the file alone");
+
+        assert!(repair_state(&path).unwrap());
+        assert_eq!(fs::read_to_string(&backup).unwrap(), "{not json");
+        assert_eq!(load_state_from(&path).unwrap(), AppState::default());
+
+        // A valid or missing file needs no repair
+        assert!(!repair_state(&path).unwrap());
+        assert!(!repair_state(&temp.path().join("missing.json")).unwrap());
+    }
+
+    /// `state` as startup loads it, with the synthesized "Default" profile
+    fn with_default_profile(mut state: AppState) -> AppState {
+        ensure_default_profile(&mut state);
+        state
+    }
+
+    #[test]
+    fn test_strict_mode_accepts_missing_and_valid_config() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("state.json");
+        assert_eq!(
+            startup_state_from(&path, true).unwrap(),
+            with_default_profile(AppState::default())
+        );
+
+        let state = AppState {
+            sleep_disabled: true,
+            ..AppState::default()
+        };
+        write_state_to(&path, &state).unwrap();
+        assert_eq!(
+            startup_state_from(&path, true).unwrap(),
+            with_default_profile(state)
+        );
+    }
+
+    #[test]
+    fn test_strict_config_env_values() {
+        use std::ffi::OsStr;
+
+        assert!(is_strict_config(Some(OsStr::new("1"))));
+        assert!(is_strict_config(Some(OsStr::new("TRUE"))));
+        assert!(!is_strict_config(Some(OsStr::new("0"))));
+        assert!(!is_strict_config(Some(OsStr::new(""))));
+        assert!(!is_strict_config(None));
+    }
 }
@@ -11,7 +11,91 @@
 //! ## Side Effects
 //! Implementations may modify system power settings via platform APIs.
 
-use crate::core::ScreenMode;
+use crate::core::device_match::DeviceInfo;
+use crate::core::remote_session::SessionInfo;
+use crate::core::session_event::SessionEvent;
+pub use crate::core::WakeMethod;
+use crate::core::{ConditionEndAction, PowerSource, ScreenMode};
+use std::time::Duration;
+
+/// Wake methods available on the current platform
+pub fn wake_methods() -> Vec<WakeMethod> {
+    let mut methods = vec![WakeMethod::F15Simulation];
+    if cfg!(windows) {
+        methods.push(WakeMethod::ExecutionState);
+    }
+    if cfg!(target_os = "macos") {
+        methods.push(WakeMethod::PowerAssertion);
+    }
+    if cfg!(target_os = "linux") && supports_sleep_inhibit() {
+        methods.push(WakeMethod::LogindInhibit);
+    }
+    methods
+}
+
+/// Whether logind grants Tea an inhibitor lock
+///
+/// ## Design Intent
+/// Checked once per process by taking and releasing a `sleep` lock, so a
+/// missing system bus, a stopped logind or a policy refusing the lock all
+/// count as unavailable. Without it the Linux display controller does
+/// nothing and wake falls back to F15 alone.
+pub fn supports_sleep_inhibit() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        static AVAILABLE: std::sync::OnceLock<bool> = std::sync::OnceLock::new();
+        *AVAILABLE.get_or_init(
+            || match logind_inhibit("sleep", "Checking inhibitor support") {
+                Ok(_lock) => true,
+                Err(e) => {
+                    log::debug!("logind inhibitor locks unavailable: {}", e);
+                    false
+                }
+            },
+        )
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    {
+        false
+    }
+}
+
+/// Whether a platform API keeps the system awake without key presses
+///
+/// ## Platform Behavior
+/// - Windows: `SetThreadExecutionState` or power requests
+/// - macOS: IOKit power assertions
+/// - Linux: logind inhibitor locks, where systemd is available
+/// - Other: None, F15 simulation does everything
+pub fn native_wake_available() -> bool {
+    cfg!(any(windows, target_os = "macos")) || supports_sleep_inhibit()
+}
+
+/// Whether `visible_window_titles` is implemented on this platform
+pub fn supports_window_enumeration() -> bool {
+    cfg!(windows)
+}
+
+/// Whether `connected_devices` is implemented on this platform
+pub fn supports_device_enumeration() -> bool {
+    cfg!(windows)
+}
+
+/// Whether `login_sessions` is implemented on this platform
+pub fn supports_session_enumeration() -> bool {
+    cfg!(any(windows, target_os = "linux"))
+}
+
+/// Whether `running_process_names` is implemented on this platform
+pub fn supports_process_enumeration() -> bool {
+    cfg!(any(windows, target_os = "linux", target_os = "macos"))
+}
+
+/// Whether `network_interface_names` is implemented on this platform
+pub fn supports_interface_enumeration() -> bool {
+    cfg!(any(windows, target_os = "linux", target_os = "macos"))
+}
 
 /// Platform-specific display power control
 ///
@@ -27,7 +111,12 @@ pub trait DisplayControl {
     ///
     /// ## Side Effects
     /// May set platform power flags that affect display sleep behavior.
-    fn set_display_mode(&self, screen_mode: ScreenMode);
+    ///
+    /// ## Returns
+    /// AppError::DisplayControl if the platform refused the request. The
+    /// caller keeps waking by its other means and reports display control
+    /// as unavailable.
+    fn set_display_mode(&self, screen_mode: ScreenMode) -> crate::error::Result<()>;
 
     /// Restore normal display power behavior
     ///
@@ -44,10 +133,21 @@ pub trait DisplayControl {
 /// ## Behavior
 /// - KeepScreenOn: Sets ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED
 /// - AllowScreenOff: Sets ES_CONTINUOUS | ES_SYSTEM_REQUIRED (allows display sleep)
+/// - AwayMode: Sets ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_AWAYMODE_REQUIRED
 ///
 /// ## Design Intent
 /// ES_CONTINUOUS must be combined with ES_SYSTEM_REQUIRED to prevent system sleep.
 /// ES_DISPLAY_REQUIRED additionally prevents display from sleeping.
+/// Every call is made on the one `execution_state_thread`, since the state
+/// belongs to the calling thread and sessions apply, reassert and restore
+/// it from whichever Tokio worker they run on.
+///
+/// ## Failure Modes
+/// `SetThreadExecutionState` returns 0 when it rejects the flags, which
+/// mostly happens for away mode on editions or power policies without it.
+/// The failure is returned as AppError::DisplayControl; wake keeps its
+/// other mechanisms. So is an execution state thread that cannot be
+/// started.
 ///
 /// ## Safety
 /// Uses unsafe Windows API calls. Platform guarantees these are safe when
@@ -57,46 +157,549 @@ pub struct WindowsDisplayControl;
 
 #[cfg(windows)]
 impl DisplayControl for WindowsDisplayControl {
-    fn set_display_mode(&self, screen_mode: ScreenMode) {
+    fn set_display_mode(&self, screen_mode: ScreenMode) -> crate::error::Result<()> {
         use windows::Win32::System::Power::{
-            SetThreadExecutionState, ES_CONTINUOUS, ES_DISPLAY_REQUIRED, ES_SYSTEM_REQUIRED,
+            SetThreadExecutionState, ES_AWAYMODE_REQUIRED, ES_CONTINUOUS, ES_DISPLAY_REQUIRED,
+            ES_SYSTEM_REQUIRED,
         };
 
-        unsafe {
-            if screen_mode.should_keep_display_on() {
+        let flags = match screen_mode {
+            ScreenMode::KeepScreenOn => {
                 log::debug!("Setting Windows display mode: keep screen on (system + display)");
-                SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED);
-            } else {
+                ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_DISPLAY_REQUIRED
+            }
+            ScreenMode::AllowScreenOff => {
                 log::debug!("Setting Windows display mode: allow screen off (system only)");
-                SetThreadExecutionState(ES_CONTINUOUS | ES_SYSTEM_REQUIRED);
+                ES_CONTINUOUS | ES_SYSTEM_REQUIRED
+            }
+            ScreenMode::AwayMode => {
+                log::debug!("Setting Windows display mode: away mode (system, appears asleep)");
+                ES_CONTINUOUS | ES_SYSTEM_REQUIRED | ES_AWAYMODE_REQUIRED
             }
+        };
+
+        // SAFETY: SetThreadExecutionState only takes the flags by value
+        let previous = execution_state_thread()
+            .and_then(|thread| thread.run(move || unsafe { SetThreadExecutionState(flags) }))
+            .map_err(|e| crate::error::AppError::DisplayControl {
+                message: format!("Failed to set execution state for {:?}", screen_mode),
+                cause: e,
+                recovery_hint: "Restart Tea, or turn on use_power_requests.",
+            })?;
+        if previous.0 == 0 {
+            return Err(crate::error::AppError::DisplayControl {
+                message: format!("SetThreadExecutionState rejected {:?}", screen_mode),
+                cause: format!("flags {:#x}", flags.0),
+                recovery_hint: "Away mode needs a desktop edition and a power policy that allows it; try another screen mode.",
+            });
         }
+        Ok(())
     }
 
     fn restore_normal_mode(&self) {
         use windows::Win32::System::Power::{SetThreadExecutionState, ES_CONTINUOUS};
 
-        unsafe {
-            log::debug!("Restoring Windows normal power mode");
-            SetThreadExecutionState(ES_CONTINUOUS);
+        log::debug!("Restoring Windows normal power mode");
+        // SAFETY: as in `set_display_mode`
+        let restored = execution_state_thread()
+            .and_then(|thread| thread.run(|| unsafe { SetThreadExecutionState(ES_CONTINUOUS) }));
+        if let Err(e) = restored {
+            log::error!("Failed to restore execution state: {}", e);
+        }
+    }
+}
+
+/// The thread every `SetThreadExecutionState` call is made on
+///
+/// ## Design Intent
+/// Started on first use and kept for the lifetime of the process: the
+/// execution state it holds would be dropped with it.
+#[cfg(windows)]
+fn execution_state_thread() -> Result<&'static PinnedThread, String> {
+    static THREAD: std::sync::OnceLock<std::io::Result<PinnedThread>> = std::sync::OnceLock::new();
+    THREAD
+        .get_or_init(|| PinnedThread::spawn("execution-state"))
+        .as_ref()
+        .map_err(|e| format!("Failed to start execution state thread: {}", e))
+}
+
+/// A job for a `PinnedThread`
+#[cfg(any(windows, test))]
+type PinnedJob = Box<dyn FnOnce() + Send>;
+
+/// A dedicated OS thread running jobs one at a time, in the order sent
+///
+/// ## Design Intent
+/// For thread-affine OS state that async code, which may resume on any
+/// worker, needs to set and clear consistently.
+#[cfg(any(windows, test))]
+struct PinnedThread {
+    jobs: std::sync::mpsc::Sender<PinnedJob>,
+}
+
+#[cfg(any(windows, test))]
+impl PinnedThread {
+    /// Start the thread; it ends once this handle is dropped
+    fn spawn(name: &str) -> std::io::Result<Self> {
+        let (jobs, queue) = std::sync::mpsc::channel::<PinnedJob>();
+        std::thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || queue.into_iter().for_each(|job| job()))?;
+        Ok(Self { jobs })
+    }
+
+    /// Run `job` on the thread, blocking until it returns
+    ///
+    /// ## Failure Modes
+    /// - The thread is gone, after an earlier job panicked: Err
+    fn run<R, F>(&self, job: F) -> Result<R, String>
+    where
+        R: Send + 'static,
+        F: FnOnce() -> R + Send + 'static,
+    {
+        let (result_tx, result_rx) = std::sync::mpsc::channel();
+        self.jobs
+            .send(Box::new(move || {
+                let _ = result_tx.send(job());
+            }))
+            .map_err(|_| "Pinned thread has exited".to_string())?;
+        result_rx
+            .recv()
+            .map_err(|_| "Pinned thread exited during the call".to_string())
+    }
+}
+
+/// Windows display control using power requests
+///
+/// ## Platform
+/// Windows only. Uses `PowerCreateRequest`/`PowerSetRequest`.
+///
+/// ## Behavior
+/// - KeepScreenOn: `PowerRequestSystemRequired` and
+///   `PowerRequestDisplayRequired`
+/// - AllowScreenOff: `PowerRequestSystemRequired` (allows display sleep)
+/// - AwayMode: `PowerRequestSystemRequired` and
+///   `PowerRequestAwayModeRequired`
+///
+/// ## Design Intent
+/// Opt-in alternative to `WindowsDisplayControl` (`use_power_requests` in
+/// the state file). A power request is not tied to the calling thread and
+/// is listed with its reason by `powercfg /requests`, so an administrator
+/// can see why the machine stays awake. The controller owns the request
+/// handle for the session: a mode change replaces it, and
+/// `restore_normal_mode` or drop clears and closes it.
+///
+/// ## Failure Modes
+/// A request that cannot be created or set is returned as
+/// AppError::DisplayControl, with nothing left held.
+#[cfg(windows)]
+#[derive(Default)]
+pub struct WindowsPowerRequestControl {
+    /// Request held for the current mode, if any
+    request: std::sync::Mutex<Option<PowerRequest>>,
+}
+
+/// Power request handle and the request types set on it
+///
+/// Clears every set type and closes the handle on drop, so no path leaks
+/// a request.
+#[cfg(windows)]
+struct PowerRequest {
+    handle: windows::Win32::Foundation::HANDLE,
+    set: Vec<windows::Win32::System::Power::POWER_REQUEST_TYPE>,
+}
+
+// SAFETY: The handle is a kernel object handle, usable from any thread;
+// it is only used behind the controller's mutex
+#[cfg(windows)]
+unsafe impl Send for PowerRequest {}
+
+#[cfg(windows)]
+impl PowerRequest {
+    /// Create a request carrying `reason` and set each of `types` on it
+    fn create(
+        reason: &str,
+        types: &[windows::Win32::System::Power::POWER_REQUEST_TYPE],
+    ) -> windows::core::Result<Self> {
+        use windows::core::PWSTR;
+        use windows::Win32::System::Power::{PowerCreateRequest, PowerSetRequest};
+        use windows::Win32::System::Threading::{
+            POWER_REQUEST_CONTEXT_SIMPLE_STRING, REASON_CONTEXT, REASON_CONTEXT_0,
+        };
+
+        /// `POWER_REQUEST_CONTEXT_VERSION`
+        const CONTEXT_VERSION: u32 = 0;
+
+        let mut wide: Vec<u16> = reason.encode_utf16().chain(std::iter::once(0)).collect();
+        let context = REASON_CONTEXT {
+            Version: CONTEXT_VERSION,
+            Flags: POWER_REQUEST_CONTEXT_SIMPLE_STRING,
+            Reason: REASON_CONTEXT_0 {
+                SimpleReasonString: PWSTR(wide.as_mut_ptr()),
+            },
+        };
+        // SAFETY: `context` and the string it points at outlive the call;
+        // the system copies the reason
+        let handle = unsafe { PowerCreateRequest(&context) }?;
+        let mut request = Self {
+            handle,
+            set: Vec::new(),
+        };
+        for &kind in types {
+            // SAFETY: `handle` is the live request created above
+            unsafe { PowerSetRequest(request.handle, kind) }?;
+            request.set.push(kind);
+        }
+        Ok(request)
+    }
+}
+
+#[cfg(windows)]
+impl Drop for PowerRequest {
+    fn drop(&mut self) {
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::System::Power::PowerClearRequest;
+
+        for &kind in &self.set {
+            // SAFETY: `handle` is live until closed below
+            if let Err(e) = unsafe { PowerClearRequest(self.handle, kind) } {
+                log::warn!("PowerClearRequest failed for {:?}: {}", kind, e);
+            }
+        }
+        // SAFETY: `handle` is owned by this request and not used afterwards
+        if let Err(e) = unsafe { CloseHandle(self.handle) } {
+            log::warn!("Failed to close power request: {}", e);
+        }
+    }
+}
+
+#[cfg(windows)]
+impl DisplayControl for WindowsPowerRequestControl {
+    fn set_display_mode(&self, screen_mode: ScreenMode) -> crate::error::Result<()> {
+        use windows::Win32::System::Power::{
+            PowerRequestAwayModeRequired, PowerRequestDisplayRequired, PowerRequestSystemRequired,
+        };
+
+        let Ok(mut held) = self.request.lock() else {
+            log::error!("Mutex poisoned during Windows power request change");
+            return Ok(());
+        };
+        // Release the previous mode's request before taking the new one
+        held.take();
+
+        let types = match screen_mode {
+            ScreenMode::KeepScreenOn => {
+                log::debug!("Setting Windows power request: keep screen on (system + display)");
+                vec![PowerRequestSystemRequired, PowerRequestDisplayRequired]
+            }
+            ScreenMode::AllowScreenOff => {
+                log::debug!("Setting Windows power request: allow screen off (system only)");
+                vec![PowerRequestSystemRequired]
+            }
+            ScreenMode::AwayMode => {
+                log::debug!("Setting Windows power request: away mode (system, appears asleep)");
+                vec![PowerRequestSystemRequired, PowerRequestAwayModeRequired]
+            }
+        };
+        let reason = format!("{} is keeping the system awake", crate::core::APP_NAME);
+        match PowerRequest::create(&reason, &types) {
+            Ok(request) => {
+                *held = Some(request);
+                Ok(())
+            }
+            Err(e) => Err(crate::error::AppError::DisplayControl {
+                message: format!("Power request rejected for {:?}", screen_mode),
+                cause: e.to_string(),
+                recovery_hint: "Away mode needs a desktop edition and a power policy that allows it; try another screen mode or turn off use_power_requests.",
+            }),
+        }
+    }
+
+    fn restore_normal_mode(&self) {
+        log::debug!("Restoring Windows normal power mode");
+        match self.request.lock() {
+            Ok(mut held) => drop(held.take()),
+            Err(e) => log::error!("Mutex poisoned during Windows power restore: {}", e),
+        }
+    }
+}
+
+/// macOS display control using IOKit power assertions
+///
+/// ## Platform
+/// macOS only. Uses `IOPMAssertionCreateWithName` from IOKit.
+///
+/// ## Behavior
+/// - KeepScreenOn: Holds `PreventUserIdleDisplaySleep` (display, and with it
+///   the system)
+/// - AllowScreenOff: Holds `PreventUserIdleSystemSleep` (allows display sleep)
+///
+/// ## Design Intent
+/// An assertion lives until released, so the controller keeps its id and
+/// releases it on mode change and in `restore_normal_mode`. The kernel also
+/// drops it if the process dies.
+#[cfg(target_os = "macos")]
+#[derive(Default)]
+pub struct MacOsDisplayControl {
+    /// Id of the held assertion, if any
+    assertion: std::sync::Mutex<Option<macos_power::AssertionId>>,
+}
+
+#[cfg(target_os = "macos")]
+impl DisplayControl for MacOsDisplayControl {
+    fn set_display_mode(&self, screen_mode: ScreenMode) -> crate::error::Result<()> {
+        let Ok(mut held) = self.assertion.lock() else {
+            log::error!("Mutex poisoned during macOS display mode change");
+            return Ok(());
+        };
+        if let Some(id) = held.take() {
+            macos_power::release(id);
+        }
+
+        let kind = if screen_mode.should_keep_display_on() {
+            log::debug!("Setting macOS display mode: keep screen on (display assertion)");
+            macos_power::PREVENT_DISPLAY_SLEEP
+        } else {
+            log::debug!("Setting macOS display mode: allow screen off (system assertion)");
+            macos_power::PREVENT_SYSTEM_SLEEP
+        };
+        let reason = format!("{} is keeping the system awake", crate::core::APP_NAME);
+        match macos_power::create(kind, &reason) {
+            Ok(id) => {
+                *held = Some(id);
+                Ok(())
+            }
+            Err(e) => Err(crate::error::AppError::DisplayControl {
+                message: format!("Failed to create power assertion {}", kind),
+                cause: e.to_string(),
+                recovery_hint: "Check that no configuration profile blocks power assertions.",
+            }),
+        }
+    }
+
+    fn restore_normal_mode(&self) {
+        log::debug!("Restoring macOS normal power mode");
+        match self.assertion.lock() {
+            Ok(mut held) => {
+                if let Some(id) = held.take() {
+                    macos_power::release(id);
+                }
+            }
+            Err(e) => log::error!("Mutex poisoned during macOS power restore: {}", e),
+        }
+    }
+}
+
+/// Minimal IOKit power assertion bindings
+#[cfg(target_os = "macos")]
+mod macos_power {
+    use std::ffi::{c_char, c_void, CString};
+
+    pub type AssertionId = u32;
+
+    /// `kIOPMAssertionTypePreventUserIdleSystemSleep`
+    pub const PREVENT_SYSTEM_SLEEP: &str = "PreventUserIdleSystemSleep";
+    /// `kIOPMAssertionTypePreventUserIdleDisplaySleep`
+    pub const PREVENT_DISPLAY_SLEEP: &str = "PreventUserIdleDisplaySleep";
+
+    type CFStringRef = *const c_void;
+    const K_CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+    const K_IOPM_ASSERTION_LEVEL_ON: u32 = 255;
+    const K_IO_RETURN_SUCCESS: i32 = 0;
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    extern "C" {
+        fn CFStringCreateWithCString(
+            alloc: *const c_void,
+            c_str: *const c_char,
+            encoding: u32,
+        ) -> CFStringRef;
+        fn CFRelease(cf: *const c_void);
+    }
+
+    #[link(name = "IOKit", kind = "framework")]
+    extern "C" {
+        fn IOPMAssertionCreateWithName(
+            assertion_type: CFStringRef,
+            level: u32,
+            name: CFStringRef,
+            id: *mut AssertionId,
+        ) -> i32;
+        fn IOPMAssertionRelease(id: AssertionId) -> i32;
+    }
+
+    /// Owned CFString, released on drop
+    struct CfString(CFStringRef);
+
+    impl CfString {
+        fn new(text: &str) -> Result<Self, String> {
+            let c_text = CString::new(text).map_err(|e| e.to_string())?;
+            // SAFETY: c_text is a valid NUL-terminated string for the call
+            let string = unsafe {
+                CFStringCreateWithCString(
+                    std::ptr::null(),
+                    c_text.as_ptr(),
+                    K_CF_STRING_ENCODING_UTF8,
+                )
+            };
+            if string.is_null() {
+                return Err(format!("CFStringCreateWithCString failed for {}", text));
+            }
+            Ok(CfString(string))
+        }
+    }
+
+    impl Drop for CfString {
+        fn drop(&mut self) {
+            // SAFETY: created by CFStringCreateWithCString and released once
+            unsafe { CFRelease(self.0) }
+        }
+    }
+
+    /// Create an assertion of `kind`, shown to the user as `reason`
+    pub fn create(kind: &str, reason: &str) -> Result<AssertionId, String> {
+        let kind = CfString::new(kind)?;
+        let reason = CfString::new(reason)?;
+        let mut id: AssertionId = 0;
+        // SAFETY: both strings are live CFStrings and id is a valid out pointer
+        let result = unsafe {
+            IOPMAssertionCreateWithName(kind.0, K_IOPM_ASSERTION_LEVEL_ON, reason.0, &mut id)
+        };
+        if result != K_IO_RETURN_SUCCESS {
+            return Err(format!("IOReturn {:#x}", result));
+        }
+        Ok(id)
+    }
+
+    /// Release an assertion created by `create`
+    pub fn release(id: AssertionId) {
+        // SAFETY: id came from a successful IOPMAssertionCreateWithName
+        let result = unsafe { IOPMAssertionRelease(id) };
+        if result != K_IO_RETURN_SUCCESS {
+            log::error!(
+                "Failed to release power assertion {}: IOReturn {:#x}",
+                id,
+                result
+            );
+        }
+    }
+}
+
+/// Linux display control using logind inhibitor locks
+///
+/// ## Platform
+/// Linux with systemd. Takes the lock with `logind_inhibit` and holds the
+/// file descriptor logind returns; the lock lasts until it is closed.
+///
+/// ## Behavior
+/// - KeepScreenOn: Blocks `idle` and `sleep`
+/// - AllowScreenOff: Blocks `sleep` only (allows the display to blank)
+///
+/// ## Design Intent
+/// The lock is released by closing the descriptor, on mode change and in
+/// `restore_normal_mode`. The kernel closes it if Tea dies without
+/// cleanup, so no lock outlives the process. Without systemd this does
+/// nothing and wake relies on F15 alone.
+#[cfg(target_os = "linux")]
+#[derive(Default)]
+pub struct LinuxDisplayControl {
+    /// Descriptor holding the lock, if any
+    inhibitor: std::sync::Mutex<Option<zbus::zvariant::OwnedFd>>,
+}
+
+#[cfg(target_os = "linux")]
+impl DisplayControl for LinuxDisplayControl {
+    fn set_display_mode(&self, screen_mode: ScreenMode) -> crate::error::Result<()> {
+        if !supports_sleep_inhibit() {
+            return Ok(());
+        }
+        let Ok(mut inhibitor) = self.inhibitor.lock() else {
+            log::error!("Mutex poisoned during Linux display mode change");
+            return Ok(());
+        };
+        // Release first: holding both locks would briefly block idle in
+        // AllowScreenOff for nothing
+        *inhibitor = None;
+
+        let what = logind_inhibit_what(screen_mode);
+        log::debug!("Setting Linux display mode: inhibit {}", what);
+        match logind_inhibit(what, "Keeping the system awake") {
+            Ok(lock) => {
+                *inhibitor = Some(lock);
+                Ok(())
+            }
+            Err(e) => Err(crate::error::AppError::DisplayControl {
+                message: "Failed to take logind inhibitor lock".to_string(),
+                cause: e.to_string(),
+                recovery_hint: "Ensure logind is running and the system bus is reachable.",
+            }),
+        }
+    }
+
+    fn restore_normal_mode(&self) {
+        log::debug!("Restoring Linux normal power mode");
+        match self.inhibitor.lock() {
+            Ok(mut inhibitor) => *inhibitor = None,
+            Err(e) => log::error!("Mutex poisoned during Linux power restore: {}", e),
         }
     }
 }
 
+/// How long logind may take to answer an inhibit call
+#[cfg(target_os = "linux")]
+const INHIBIT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Take a logind inhibitor lock
+///
+/// ## Design Intent
+/// Calls `org.freedesktop.login1.Manager.Inhibit` on the system bus
+/// directly rather than running `systemd-inhibit`, so there is no helper
+/// process to start, watch or clean up. A call logind does not answer
+/// fails after `INHIBIT_TIMEOUT` instead of stalling the wake loop.
+///
+/// ## Returns
+/// The descriptor holding the lock until it is dropped, or why no system
+/// bus, no logind or a refusing policy kept it from being granted
+#[cfg(target_os = "linux")]
+fn logind_inhibit(what: &str, why: &str) -> zbus::Result<zbus::zvariant::OwnedFd> {
+    let connection = zbus::blocking::connection::Builder::system()?
+        .method_timeout(INHIBIT_TIMEOUT)
+        .build()?;
+    let reply = connection.call_method(
+        Some("org.freedesktop.login1"),
+        "/org/freedesktop/login1",
+        Some("org.freedesktop.login1.Manager"),
+        "Inhibit",
+        &(what, crate::core::APP_NAME, why, "block"),
+    )?;
+    reply.body().deserialize()
+}
+
+/// logind inhibitor lock types for a screen mode
+#[cfg(any(target_os = "linux", test))]
+fn logind_inhibit_what(screen_mode: ScreenMode) -> &'static str {
+    if screen_mode.should_keep_display_on() {
+        "idle:sleep"
+    } else {
+        "sleep"
+    }
+}
+
 /// No-op display control for platforms without specific support
 ///
 /// ## Platform
-/// Non-Windows platforms
+/// Platforms other than Windows, macOS and Linux
 ///
 /// ## Behavior
 /// Does nothing. Screen behavior is controlled by F15 simulation only.
-#[cfg(not(windows))]
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
 pub struct NoOpDisplayControl;
 
-#[cfg(not(windows))]
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
 impl DisplayControl for NoOpDisplayControl {
-    fn set_display_mode(&self, _screen_mode: ScreenMode) {
+    fn set_display_mode(&self, _screen_mode: ScreenMode) -> crate::error::Result<()> {
         // No platform-specific display control available
+        Ok(())
     }
 
     fn restore_normal_mode(&self) {
@@ -110,14 +713,1465 @@ impl DisplayControl for NoOpDisplayControl {
 /// Factory function that returns the correct implementation for current platform.
 /// Allows platform-agnostic code to obtain a display controller without
 /// conditional compilation at call sites.
-pub fn get_display_controller() -> Box<dyn DisplayControl + Send> {
+///
+/// ## Arguments
+/// * `power_requests` - On Windows, use power requests instead of
+///   `SetThreadExecutionState`; ignored elsewhere
+pub fn get_display_controller(power_requests: bool) -> Box<dyn DisplayControl + Send> {
     #[cfg(windows)]
     {
-        Box::new(WindowsDisplayControl)
+        let controller: Box<dyn DisplayControl + Send> = if power_requests {
+            Box::new(WindowsPowerRequestControl::default())
+        } else {
+            Box::new(WindowsDisplayControl)
+        };
+        controller
     }
 
     #[cfg(not(windows))]
+    let _ = power_requests;
+
+    #[cfg(target_os = "macos")]
+    {
+        Box::new(MacOsDisplayControl::default())
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        Box::new(LinuxDisplayControl::default())
+    }
+
+    #[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
     {
         Box::new(NoOpDisplayControl)
     }
 }
+
+/// Detect the current power source
+///
+/// ## Design Intent
+/// Single query point for AC/battery detection so policy code never needs
+/// conditional compilation.
+///
+/// ## Platform Behavior
+/// - Windows: `GetSystemPowerStatus` AC line status
+/// - Linux: `/sys/class/power_supply` mains/battery entries
+/// - macOS: `pmset -g ps` output
+/// - Other: Always `Unknown`
+///
+/// ## Failure Modes
+/// Any query failure yields `PowerSource::Unknown`, which policy treats as AC.
+pub fn power_source() -> PowerSource {
+    #[cfg(windows)]
+    {
+        use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+        let mut status = SYSTEM_POWER_STATUS::default();
+        // SAFETY: `status` is a valid, writable SYSTEM_POWER_STATUS for the call duration
+        if let Err(e) = unsafe { GetSystemPowerStatus(&mut status) } {
+            log::debug!("GetSystemPowerStatus failed: {}", e);
+            return PowerSource::Unknown;
+        }
+        match status.ACLineStatus {
+            0 => PowerSource::Battery,
+            1 => PowerSource::Ac,
+            _ => PowerSource::Unknown,
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let entries = match std::fs::read_dir("/sys/class/power_supply") {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::debug!("Cannot read /sys/class/power_supply: {}", e);
+                return PowerSource::Unknown;
+            }
+        };
+        let supplies: Vec<(String, Option<bool>)> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| {
+                let dir = entry.path();
+                let kind = std::fs::read_to_string(dir.join("type")).unwrap_or_default();
+                let online = std::fs::read_to_string(dir.join("online"))
+                    .ok()
+                    .map(|v| v.trim() == "1");
+                (kind.trim().to_string(), online)
+            })
+            .collect();
+        classify_linux_power_supplies(&supplies)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        match std::process::Command::new("pmset")
+            .args(["-g", "ps"])
+            .output()
+        {
+            Ok(output) => {
+                let text = String::from_utf8_lossy(&output.stdout);
+                if text.contains("'AC Power'") {
+                    PowerSource::Ac
+                } else if text.contains("'Battery Power'") {
+                    PowerSource::Battery
+                } else {
+                    PowerSource::Unknown
+                }
+            }
+            Err(e) => {
+                log::debug!("pmset query failed: {}", e);
+                PowerSource::Unknown
+            }
+        }
+    }
+
+    #[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+    {
+        PowerSource::Unknown
+    }
+}
+
+/// Read the battery charge level
+///
+/// ## Platform Behavior
+/// - Windows: `GetSystemPowerStatus` battery life percent
+/// - Linux: Average `capacity` of `/sys/class/power_supply` batteries
+/// - macOS: `pmset -g batt` output
+/// - Other: Always None
+///
+/// ## Returns
+/// Charge in percent, or None without a battery or on query failure
+pub fn battery_percent() -> Option<u8> {
+    #[cfg(windows)]
+    {
+        use windows::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+
+        /// `BatteryFlag` bit for "no system battery"
+        const NO_SYSTEM_BATTERY: u8 = 128;
+
+        let mut status = SYSTEM_POWER_STATUS::default();
+        // SAFETY: `status` is a valid, writable SYSTEM_POWER_STATUS for the call duration
+        if let Err(e) = unsafe { GetSystemPowerStatus(&mut status) } {
+            log::debug!("GetSystemPowerStatus failed: {}", e);
+            return None;
+        }
+        // 255 means the percentage is unknown
+        if status.BatteryFlag & NO_SYSTEM_BATTERY != 0 || status.BatteryLifePercent > 100 {
+            return None;
+        }
+        Some(status.BatteryLifePercent)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let entries = match std::fs::read_dir("/sys/class/power_supply") {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::debug!("Cannot read /sys/class/power_supply: {}", e);
+                return None;
+            }
+        };
+        let levels: Vec<u32> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|dir| {
+                std::fs::read_to_string(dir.join("type")).is_ok_and(|kind| kind.trim() == "Battery")
+            })
+            .filter_map(|dir| std::fs::read_to_string(dir.join("capacity")).ok())
+            .filter_map(|capacity| capacity.trim().parse::<u32>().ok())
+            .collect();
+        if levels.is_empty() {
+            return None;
+        }
+        let average = levels.iter().sum::<u32>() / levels.len() as u32;
+        u8::try_from(average.min(100)).ok()
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        match std::process::Command::new("pmset")
+            .args(["-g", "batt"])
+            .output()
+        {
+            Ok(output) => parse_pmset_battery_percent(&String::from_utf8_lossy(&output.stdout)),
+            Err(e) => {
+                log::debug!("pmset query failed: {}", e);
+                None
+            }
+        }
+    }
+
+    #[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// List the titles of visible top-level windows
+///
+/// ## Design Intent
+/// Feeds the window-title watcher. Matching is done by the pure predicate in
+/// `core::window_title`, so this only enumerates.
+///
+/// ## Platform Behavior
+/// - Windows: `EnumWindows` + `GetWindowTextW`, visible windows with a title
+/// - Other: Not supported yet, returns `None`
+///
+/// ## Returns
+/// Window titles, or None if enumeration is unsupported or failed
+pub fn visible_window_titles() -> Option<Vec<String>> {
+    #[cfg(windows)]
+    {
+        use windows::Win32::Foundation::{BOOL, HWND, LPARAM};
+        use windows::Win32::UI::WindowsAndMessaging::{
+            EnumWindows, GetWindowTextLengthW, GetWindowTextW, IsWindowVisible,
+        };
+
+        unsafe extern "system" fn collect_title(hwnd: HWND, lparam: LPARAM) -> BOOL {
+            // SAFETY: lparam carries the &mut Vec<String> passed to EnumWindows
+            // below, which outlives the synchronous enumeration
+            let titles = &mut *(lparam.0 as *mut Vec<String>);
+            if IsWindowVisible(hwnd).as_bool() {
+                let len = GetWindowTextLengthW(hwnd);
+                if len > 0 {
+                    let mut buf = vec![0u16; len as usize + 1];
+                    let copied = GetWindowTextW(hwnd, &mut buf);
+                    if copied > 0 {
+                        titles.push(String::from_utf16_lossy(&buf[..copied as usize]));
+                    }
+                }
+            }
+            BOOL(1) // Continue enumeration
+        }
+
+        let mut titles: Vec<String> = Vec::new();
+        // SAFETY: the callback only touches `titles` through lparam during this call
+        let result = unsafe {
+            EnumWindows(
+                Some(collect_title),
+                LPARAM(&mut titles as *mut Vec<String> as isize),
+            )
+        };
+        match result {
+            Ok(()) => Some(titles),
+            Err(e) => {
+                log::debug!("EnumWindows failed: {}", e);
+                None
+            }
+        }
+    }
+
+    #[cfg(not(windows))]
+    {
+        None
+    }
+}
+
+/// List the devices currently present
+///
+/// ## Design Intent
+/// Feeds the device watcher. Matching is done by the pure predicate in
+/// `core::device_match`, so this only enumerates.
+///
+/// ## Platform Behavior
+/// - Windows: SetupAPI over all present devices, with the friendly name
+///   (or description) and hardware ids of each
+/// - Other: Not supported yet, returns `None`
+///
+/// ## Returns
+/// Present devices, or None if enumeration is unsupported or failed
+pub fn connected_devices() -> Option<Vec<DeviceInfo>> {
+    #[cfg(windows)]
+    {
+        use windows::core::PCWSTR;
+        use windows::Win32::Devices::DeviceAndDriverInstallation::{
+            SetupDiDestroyDeviceInfoList, SetupDiEnumDeviceInfo, SetupDiGetClassDevsW,
+            SetupDiGetDeviceRegistryPropertyW, DIGCF_ALLCLASSES, DIGCF_PRESENT, HDEVINFO,
+            SETUP_DI_REGISTRY_PROPERTY, SPDRP_DEVICEDESC, SPDRP_FRIENDLYNAME, SPDRP_HARDWAREID,
+            SP_DEVINFO_DATA,
+        };
+        use windows::Win32::Foundation::HWND;
+
+        /// Read a string or multi-string property, one entry per string
+        fn read_property(
+            set: HDEVINFO,
+            data: &SP_DEVINFO_DATA,
+            property: SETUP_DI_REGISTRY_PROPERTY,
+        ) -> Vec<String> {
+            let mut buf = vec![0u8; 512];
+            let mut required = 0u32;
+            for _ in 0..2 {
+                // SAFETY: `data` was filled by SetupDiEnumDeviceInfo on `set`
+                // and `buf` is a live, writable buffer
+                let result = unsafe {
+                    SetupDiGetDeviceRegistryPropertyW(
+                        set,
+                        data,
+                        property,
+                        None,
+                        Some(&mut buf),
+                        Some(&mut required),
+                    )
+                };
+                if result.is_ok() {
+                    let len = (required as usize).min(buf.len());
+                    let wide: Vec<u16> = buf[..len]
+                        .chunks_exact(2)
+                        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+                        .collect();
+                    return wide
+                        .split(|&c| c == 0)
+                        .filter(|s| !s.is_empty())
+                        .map(String::from_utf16_lossy)
+                        .collect();
+                }
+                if required as usize <= buf.len() {
+                    break; // Property missing, not a short buffer
+                }
+                buf.resize(required as usize, 0);
+            }
+            Vec::new()
+        }
+
+        // SAFETY: no class GUID, enumerator or parent window is passed
+        let set = match unsafe {
+            SetupDiGetClassDevsW(
+                None,
+                PCWSTR::null(),
+                HWND::default(),
+                DIGCF_PRESENT | DIGCF_ALLCLASSES,
+            )
+        } {
+            Ok(set) => set,
+            Err(e) => {
+                log::debug!("SetupDiGetClassDevsW failed: {}", e);
+                return None;
+            }
+        };
+
+        let mut devices = Vec::new();
+        let mut data = SP_DEVINFO_DATA {
+            cbSize: std::mem::size_of::<SP_DEVINFO_DATA>() as u32,
+            ..Default::default()
+        };
+        // SAFETY: `data` has its size set and the set stays open until
+        // destroyed below; enumeration stops at the first failed index
+        while unsafe { SetupDiEnumDeviceInfo(set, devices.len() as u32, &mut data) }.is_ok() {
+            let name = read_property(set, &data, SPDRP_FRIENDLYNAME)
+                .into_iter()
+                .next()
+                .or_else(|| {
+                    read_property(set, &data, SPDRP_DEVICEDESC)
+                        .into_iter()
+                        .next()
+                })
+                .unwrap_or_default();
+            devices.push(DeviceInfo {
+                name,
+                hardware_ids: read_property(set, &data, SPDRP_HARDWAREID),
+            });
+        }
+
+        // SAFETY: `set` came from SetupDiGetClassDevsW and is not used again
+        if let Err(e) = unsafe { SetupDiDestroyDeviceInfoList(set) } {
+            log::debug!("SetupDiDestroyDeviceInfoList failed: {}", e);
+        }
+        Some(devices)
+    }
+
+    #[cfg(not(windows))]
+    {
+        None
+    }
+}
+
+/// List the login sessions on this machine
+///
+/// ## Design Intent
+/// Feeds the remote session watcher. Filtering is done by the pure
+/// predicate in `core::remote_session`, so this only enumerates.
+///
+/// ## Platform Behavior
+/// - Windows: `WTSEnumerateSessionsW`; `RDP-Tcp#` stations are reported as
+///   remote `rdp` sessions
+/// - Linux: logind sessions via `loginctl` (Type, Remote, State, Class)
+/// - Other: Not supported yet, returns `None`
+///
+/// ## Returns
+/// Sessions, or None if enumeration is unsupported or failed
+pub fn login_sessions() -> Option<Vec<SessionInfo>> {
+    #[cfg(windows)]
+    {
+        use windows::Win32::System::RemoteDesktop::{
+            WTSActive, WTSEnumerateSessionsW, WTSFreeMemory, WTS_CURRENT_SERVER_HANDLE,
+            WTS_SESSION_INFOW,
+        };
+
+        let mut info: *mut WTS_SESSION_INFOW = std::ptr::null_mut();
+        let mut count = 0u32;
+        // SAFETY: both out pointers are valid; the returned buffer is freed
+        // with WTSFreeMemory below
+        if let Err(e) =
+            unsafe { WTSEnumerateSessionsW(WTS_CURRENT_SERVER_HANDLE, 0, 1, &mut info, &mut count) }
+        {
+            log::debug!("WTSEnumerateSessionsW failed: {}", e);
+            return None;
+        }
+
+        // SAFETY: on success `info` points at `count` session entries
+        let entries = unsafe { std::slice::from_raw_parts(info, count as usize) };
+        let sessions = entries
+            .iter()
+            .map(|entry| {
+                // SAFETY: station names are NUL-terminated strings in the buffer
+                let station = unsafe { entry.pWinStationName.to_string() }.unwrap_or_default();
+                let rdp = station.to_ascii_uppercase().starts_with("RDP-TCP#");
+                SessionInfo {
+                    kind: if rdp { "rdp" } else { "console" }.to_string(),
+                    remote: rdp,
+                    state: if entry.State == WTSActive {
+                        "active"
+                    } else {
+                        "inactive"
+                    }
+                    .to_string(),
+                    class: "user".to_string(),
+                }
+            })
+            .collect();
+
+        // SAFETY: `info` came from WTSEnumerateSessionsW and is not used again
+        unsafe { WTSFreeMemory(info.cast()) };
+        Some(sessions)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        use crate::core::remote_session::parse_logind_properties;
+        use std::process::Command;
+
+        let output = match Command::new("loginctl")
+            .args(["list-sessions", "--no-legend"])
+            .output()
+        {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => {
+                log::debug!("loginctl list-sessions exited with {}", output.status);
+                return None;
+            }
+            Err(e) => {
+                log::debug!("Failed to run loginctl: {}", e);
+                return None;
+            }
+        };
+
+        let sessions = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| line.split_whitespace().next())
+            .filter_map(|id| {
+                let output = Command::new("loginctl")
+                    .args(["show-session", id, "-p", "Type", "-p", "Remote"])
+                    .args(["-p", "State", "-p", "Class"])
+                    .output()
+                    .ok()?;
+                output
+                    .status
+                    .success()
+                    .then(|| parse_logind_properties(&String::from_utf8_lossy(&output.stdout)))
+            })
+            .collect();
+        Some(sessions)
+    }
+
+    #[cfg(not(any(windows, target_os = "linux")))]
+    {
+        None
+    }
+}
+
+/// Enumerate the executable names of running processes
+///
+/// ## Design Intent
+/// Used by the process watcher. Names may contain duplicates (one per
+/// process) and, on macOS, full paths; matching normalizes both.
+///
+/// ## Platform Behavior
+/// - Windows: `CreateToolhelp32Snapshot` executable names
+/// - Linux: `/proc/<pid>/comm` plus the first `cmdline` argument, since
+///   `comm` is cut at 15 characters
+/// - macOS: `ps -axo comm=`
+/// - Other: Not supported, returns `None`
+///
+/// ## Returns
+/// Process names, or None if enumeration is unsupported or failed
+pub fn running_process_names() -> Option<Vec<String>> {
+    #[cfg(windows)]
+    {
+        use windows::Win32::Foundation::CloseHandle;
+        use windows::Win32::System::Diagnostics::ToolHelp::{
+            CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+            TH32CS_SNAPPROCESS,
+        };
+
+        // SAFETY: Plain snapshot of the process list, closed below
+        let snapshot = match unsafe { CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0) } {
+            Ok(snapshot) => snapshot,
+            Err(e) => {
+                log::debug!("CreateToolhelp32Snapshot failed: {}", e);
+                return None;
+            }
+        };
+
+        let mut names = Vec::new();
+        let mut entry = PROCESSENTRY32W {
+            dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+            ..Default::default()
+        };
+        // SAFETY: `entry` is a live PROCESSENTRY32W with dwSize set
+        let mut more = unsafe { Process32FirstW(snapshot, &mut entry) }.is_ok();
+        while more {
+            let exe = &entry.szExeFile;
+            let len = exe.iter().position(|&c| c == 0).unwrap_or(exe.len());
+            names.push(String::from_utf16_lossy(&exe[..len]));
+            // SAFETY: as above
+            more = unsafe { Process32NextW(snapshot, &mut entry) }.is_ok();
+        }
+
+        // SAFETY: `snapshot` is a valid handle owned by this function
+        unsafe {
+            let _ = CloseHandle(snapshot);
+        }
+        Some(names)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let entries = match std::fs::read_dir("/proc") {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::debug!("Failed to read /proc: {}", e);
+                return None;
+            }
+        };
+
+        let mut names = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_pid = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.bytes().all(|b| b.is_ascii_digit()));
+            if !is_pid {
+                continue;
+            }
+            // Processes may exit between listing and reading
+            if let Ok(comm) = std::fs::read_to_string(path.join("comm")) {
+                names.push(comm.trim_end().to_string());
+            }
+            if let Ok(cmdline) = std::fs::read(path.join("cmdline")) {
+                if let Some(arg0) = cmdline.split(|&b| b == 0).next().filter(|a| !a.is_empty()) {
+                    names.push(String::from_utf8_lossy(arg0).into_owned());
+                }
+            }
+        }
+        Some(names)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let output = match std::process::Command::new("ps")
+            .args(["-axo", "comm="])
+            .output()
+        {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => {
+                log::debug!("ps exited with {}", output.status);
+                return None;
+            }
+            Err(e) => {
+                log::debug!("Failed to run ps: {}", e);
+                return None;
+            }
+        };
+        Some(
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(|line| line.trim().to_string())
+                .filter(|line| !line.is_empty())
+                .collect(),
+        )
+    }
+
+    #[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// Names of the network interfaces that are up
+///
+/// ## Design Intent
+/// Used by the network watcher. Only interfaces that are up are listed,
+/// since e.g. a VPN adapter on Windows exists while disconnected.
+///
+/// ## Platform Behavior
+/// - Windows: `GetAdaptersAddresses` friendly names and descriptions of
+///   adapters whose status is up
+/// - Linux: `/sys/class/net` entries with `IFF_UP` set
+/// - macOS: `ifconfig -lu`
+/// - Other: Not supported, returns `None`
+///
+/// ## Returns
+/// Interface names, or None if enumeration is unsupported or failed
+pub fn network_interface_names() -> Option<Vec<String>> {
+    #[cfg(windows)]
+    {
+        use windows::Win32::Foundation::{ERROR_BUFFER_OVERFLOW, ERROR_SUCCESS};
+        use windows::Win32::NetworkManagement::IpHelper::{
+            GetAdaptersAddresses, GAA_FLAG_SKIP_ANYCAST, GAA_FLAG_SKIP_DNS_SERVER,
+            GAA_FLAG_SKIP_MULTICAST, GAA_FLAG_SKIP_UNICAST, IP_ADAPTER_ADDRESSES_LH,
+        };
+        use windows::Win32::NetworkManagement::Ndis::IfOperStatusUp;
+        use windows::Win32::Networking::WinSock::AF_UNSPEC;
+
+        let flags = GAA_FLAG_SKIP_UNICAST
+            | GAA_FLAG_SKIP_ANYCAST
+            | GAA_FLAG_SKIP_MULTICAST
+            | GAA_FLAG_SKIP_DNS_SERVER;
+        let mut size: u32 = 16 * 1024;
+        // u64 elements keep the buffer aligned for IP_ADAPTER_ADDRESSES_LH
+        let mut buffer: Vec<u64>;
+        // Adapters may be added between sizing and filling, so retry once
+        let mut attempts = 0;
+        loop {
+            buffer = vec![0; (size as usize).div_ceil(8)];
+            // SAFETY: `buffer` holds at least `size` writable, aligned bytes
+            let result = unsafe {
+                GetAdaptersAddresses(
+                    AF_UNSPEC.0 as u32,
+                    flags,
+                    None,
+                    Some(buffer.as_mut_ptr().cast::<IP_ADAPTER_ADDRESSES_LH>()),
+                    &mut size,
+                )
+            };
+            attempts += 1;
+            if result == ERROR_SUCCESS.0 {
+                break;
+            }
+            if result != ERROR_BUFFER_OVERFLOW.0 || attempts > 2 {
+                log::debug!("GetAdaptersAddresses failed: {}", result);
+                return None;
+            }
+        }
+
+        let mut names = Vec::new();
+        let mut adapter = buffer.as_ptr().cast::<IP_ADAPTER_ADDRESSES_LH>();
+        while !adapter.is_null() {
+            // SAFETY: The list was filled in by GetAdaptersAddresses and lives
+            // in `buffer`; its strings are NUL-terminated
+            unsafe {
+                let entry = &*adapter;
+                if entry.OperStatus == IfOperStatusUp {
+                    for name in [entry.FriendlyName, entry.Description] {
+                        if let Ok(name) = name.to_string() {
+                            names.push(name);
+                        }
+                    }
+                }
+                adapter = entry.Next;
+            }
+        }
+        Some(names)
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        const IFF_UP: u32 = 0x1;
+
+        let entries = match std::fs::read_dir("/sys/class/net") {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::debug!("Failed to read /sys/class/net: {}", e);
+                return None;
+            }
+        };
+
+        let mut names = Vec::new();
+        for entry in entries.flatten() {
+            // Interfaces may go away between listing and reading
+            let Ok(flags) = std::fs::read_to_string(entry.path().join("flags")) else {
+                continue;
+            };
+            let flags = flags.trim().trim_start_matches("0x");
+            if u32::from_str_radix(flags, 16).is_ok_and(|flags| flags & IFF_UP != 0) {
+                names.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+        Some(names)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let output = match std::process::Command::new("ifconfig").arg("-lu").output() {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => {
+                log::debug!("ifconfig exited with {}", output.status);
+                return None;
+            }
+            Err(e) => {
+                log::debug!("Failed to run ifconfig: {}", e);
+                return None;
+            }
+        };
+        Some(
+            String::from_utf8_lossy(&output.stdout)
+                .split_whitespace()
+                .map(str::to_string)
+                .collect(),
+        )
+    }
+
+    #[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// Get the Remote Desktop Services session Tea runs in
+///
+/// ## Failure Modes
+/// - Lookup fails: Logs a warning and returns 0, the services session,
+///   which no interactive Tea runs in
+#[cfg(windows)]
+pub fn current_session_id() -> u32 {
+    use windows::Win32::System::RemoteDesktop::ProcessIdToSessionId;
+    use windows::Win32::System::Threading::GetCurrentProcessId;
+
+    let mut session_id = 0;
+    // SAFETY: `session_id` is a valid out pointer for the call
+    if let Err(e) = unsafe { ProcessIdToSessionId(GetCurrentProcessId(), &mut session_id) } {
+        log::warn!("Failed to read session id: {}", e);
+        return 0;
+    }
+    session_id
+}
+
+/// Route stdout and stderr to the console Tea was launched from
+///
+/// ## Design Intent
+/// Release builds on Windows use the GUI subsystem, so a command line
+/// action would otherwise print its output and errors nowhere. Call this
+/// before anything writes to the console.
+///
+/// ## Platform Behavior
+/// - Windows: `AttachConsole(ATTACH_PARENT_PROCESS)`. The shell does not
+///   wait for a GUI program, so output may land after the next prompt
+/// - Other: No-op, the process already has its terminal
+///
+/// ## Failure Modes
+/// - No parent console (started from Explorer or a shortcut): Silently
+///   does nothing, as there is nowhere to report to
+pub fn attach_parent_console() {
+    #[cfg(windows)]
+    {
+        use windows::Win32::System::Console::{AttachConsole, ATTACH_PARENT_PROCESS};
+
+        // SAFETY: No pointers are passed; failure only means no console
+        let _ = unsafe { AttachConsole(ATTACH_PARENT_PROCESS) };
+    }
+}
+
+/// Call `on_event` for every session change of Tea's session
+///
+/// ## Design Intent
+/// Feeds the session watcher, which reasserts wake after an unlock or an
+/// RDP reconnect. Only decodes events; what they mean for wake is decided
+/// by `core::session_event`.
+///
+/// ## Platform Behavior
+/// - Windows: `WM_WTSSESSION_CHANGE` on the `notification_window`,
+///   registered with `WTSRegisterSessionNotification`
+/// - Other: Not supported, returns false
+///
+/// ## Failure Modes
+/// - Already watching, or the window or registration fails: Logs a
+///   warning and returns false
+///
+/// ## Returns
+/// Whether session changes are being watched
+pub fn watch_session_changes<F>(on_event: F) -> bool
+where
+    F: Fn(SessionEvent) + Send + Sync + 'static,
+{
+    #[cfg(windows)]
+    {
+        if ON_SESSION_EVENT.set(Box::new(on_event)).is_err() {
+            log::warn!("Session changes already watched, ignoring");
+            return false;
+        }
+        match notification_window() {
+            Ok(window) => window.sessions,
+            Err(e) => {
+                log::warn!("Session change notifications unavailable: {}", e);
+                false
+            }
+        }
+    }
+
+    #[cfg(not(windows))]
+    {
+        drop(on_event);
+        false
+    }
+}
+
+/// Call `on_change` whenever the system switches between AC and battery
+///
+/// ## Design Intent
+/// Lets the power monitor react to unplugging at once instead of polling
+/// `power_source`. Carries no value: the callback queries `power_source`,
+/// the one place the source is decoded.
+///
+/// ## Platform Behavior
+/// - Windows: `WM_POWERBROADCAST` / `PBT_POWERSETTINGCHANGE` for
+///   `GUID_ACDC_POWER_SOURCE` on the `notification_window`. Windows also
+///   sends one right after registering.
+/// - Other: Not supported, returns false; callers poll instead
+///
+/// ## Failure Modes
+/// - Already watching, or the window or registration fails: Logs a
+///   warning and returns false
+///
+/// ## Returns
+/// Whether power source changes are being watched
+pub fn watch_power_source_changes<F>(on_change: F) -> bool
+where
+    F: Fn() + Send + Sync + 'static,
+{
+    #[cfg(windows)]
+    {
+        if ON_POWER_SOURCE_CHANGE.set(Box::new(on_change)).is_err() {
+            log::warn!("Power source changes already watched, ignoring");
+            return false;
+        }
+        match notification_window() {
+            Ok(window) => window.power_source,
+            Err(e) => {
+                log::warn!("Power source notifications unavailable: {}", e);
+                false
+            }
+        }
+    }
+
+    #[cfg(not(windows))]
+    {
+        drop(on_change);
+        false
+    }
+}
+
+/// Callback for `WM_WTSSESSION_CHANGE`, installed by `watch_session_changes`
+///
+/// The window procedure has no context pointer of ours, so callbacks are
+/// process-wide, like the one window.
+#[cfg(windows)]
+static ON_SESSION_EVENT: std::sync::OnceLock<Box<dyn Fn(SessionEvent) + Send + Sync>> =
+    std::sync::OnceLock::new();
+
+/// Callback for AC/DC changes, installed by `watch_power_source_changes`
+#[cfg(windows)]
+static ON_POWER_SOURCE_CHANGE: std::sync::OnceLock<Box<dyn Fn() + Send + Sync>> =
+    std::sync::OnceLock::new();
+
+/// Notifications the `notification_window` was registered for
+#[cfg(windows)]
+#[derive(Debug, Clone, Copy)]
+struct NotificationWindow {
+    sessions: bool,
+    power_source: bool,
+}
+
+/// Get the hidden window receiving session and power notifications
+///
+/// ## Design Intent
+/// One message-only window, on its own `session-watch` thread for the
+/// lifetime of the process, serves both `watch_session_changes` and
+/// `watch_power_source_changes`. Whichever starts first creates it,
+/// registered for both kinds of notification; each is dispatched to its
+/// callback once that is installed. A registration that fails only turns
+/// off its own kind.
+///
+/// ## Failure Modes
+/// - Window class, window or thread cannot be created: Err, for every
+///   caller
+#[cfg(windows)]
+fn notification_window() -> Result<NotificationWindow, String> {
+    use windows::core::{w, PCWSTR};
+    use windows::Win32::Foundation::{HANDLE, HINSTANCE, HWND, LPARAM, LRESULT, WPARAM};
+    use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+    use windows::Win32::System::Power::{RegisterPowerSettingNotification, POWERBROADCAST_SETTING};
+    use windows::Win32::System::RemoteDesktop::{
+        WTSRegisterSessionNotification, NOTIFY_FOR_THIS_SESSION,
+    };
+    use windows::Win32::System::SystemServices::GUID_ACDC_POWER_SOURCE;
+    use windows::Win32::UI::WindowsAndMessaging::{
+        CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, RegisterClassW,
+        TranslateMessage, DEVICE_NOTIFY_WINDOW_HANDLE, HMENU, HWND_MESSAGE, MSG,
+        PBT_POWERSETTINGCHANGE, WINDOW_EX_STYLE, WINDOW_STYLE, WM_POWERBROADCAST,
+        WM_WTSSESSION_CHANGE, WNDCLASSW,
+    };
+
+    static WINDOW: std::sync::OnceLock<Result<NotificationWindow, String>> =
+        std::sync::OnceLock::new();
+
+    unsafe extern "system" fn window_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+    ) -> LRESULT {
+        if msg == WM_WTSSESSION_CHANGE {
+            match SessionEvent::from_wts_code(wparam.0 as u32) {
+                Some(event) => {
+                    if let Some(on_event) = ON_SESSION_EVENT.get() {
+                        on_event(event);
+                    }
+                }
+                None => log::trace!("Ignoring session change code {}", wparam.0),
+            }
+            return LRESULT(0);
+        }
+        if msg == WM_POWERBROADCAST && wparam.0 as u32 == PBT_POWERSETTINGCHANGE {
+            // SAFETY: for PBT_POWERSETTINGCHANGE, lParam points at a
+            // POWERBROADCAST_SETTING valid for the duration of the message
+            let setting = lparam.0 as *const POWERBROADCAST_SETTING;
+            if !setting.is_null() && (*setting).PowerSetting == GUID_ACDC_POWER_SOURCE {
+                if let Some(on_change) = ON_POWER_SOURCE_CHANGE.get() {
+                    on_change();
+                }
+            }
+            // TRUE grants the request, as WM_POWERBROADCAST expects
+            return LRESULT(1);
+        }
+        DefWindowProcW(hwnd, msg, wparam, lparam)
+    }
+
+    fn create_window() -> windows::core::Result<NotificationWindow> {
+        let class = w!("TeaSessionWatch");
+        // SAFETY: the class and window are created once and live, with
+        // this thread's message loop, until the process exits
+        unsafe {
+            let instance = HINSTANCE(GetModuleHandleW(PCWSTR::null())?.0);
+            let window_class = WNDCLASSW {
+                lpfnWndProc: Some(window_proc),
+                hInstance: instance,
+                lpszClassName: class,
+                ..Default::default()
+            };
+            if RegisterClassW(&window_class) == 0 {
+                return Err(windows::core::Error::from_win32());
+            }
+            let hwnd = CreateWindowExW(
+                WINDOW_EX_STYLE::default(),
+                class,
+                w!("Tea session watch"),
+                WINDOW_STYLE::default(),
+                0,
+                0,
+                0,
+                0,
+                HWND_MESSAGE,
+                HMENU::default(),
+                instance,
+                None,
+            )?;
+            let sessions = WTSRegisterSessionNotification(hwnd, NOTIFY_FOR_THIS_SESSION)
+                .inspect_err(|e| log::warn!("WTSRegisterSessionNotification failed: {}", e))
+                .is_ok();
+            let power_source = RegisterPowerSettingNotification(
+                HANDLE(hwnd.0),
+                &GUID_ACDC_POWER_SOURCE,
+                DEVICE_NOTIFY_WINDOW_HANDLE,
+            )
+            .inspect_err(|e| log::warn!("RegisterPowerSettingNotification failed: {}", e))
+            .is_ok();
+            Ok(NotificationWindow {
+                sessions,
+                power_source,
+            })
+        }
+    }
+
+    WINDOW
+        .get_or_init(|| {
+            let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+            std::thread::Builder::new()
+                .name("session-watch".to_string())
+                .spawn(move || {
+                    match create_window() {
+                        Ok(window) => {
+                            let _ = ready_tx.send(Ok(window));
+                        }
+                        Err(e) => {
+                            let _ = ready_tx.send(Err(e.to_string()));
+                            return;
+                        }
+                    }
+
+                    let mut msg = MSG::default();
+                    // SAFETY: `msg` is a valid out pointer; GetMessageW
+                    // returns 0 on WM_QUIT and -1 on failure, both of which
+                    // end the loop
+                    while unsafe { GetMessageW(&mut msg, HWND::default(), 0, 0) }.0 > 0 {
+                        unsafe {
+                            let _ = TranslateMessage(&msg);
+                            DispatchMessageW(&msg);
+                        }
+                    }
+                    log::debug!("Session watch message loop ended");
+                })
+                .map_err(|e| format!("Failed to start session watch thread: {}", e))?;
+            ready_rx
+                .recv()
+                .map_err(|e| format!("Session watch thread ended during setup: {}", e))?
+        })
+        .clone()
+}
+
+/// Read the command registered to start `app_name` at login
+///
+/// ## Design Intent
+/// The autostart plugin can enable and disable the entry but not report
+/// what it points at. Reading it back lets startup skip rewriting an entry
+/// that is already correct.
+///
+/// ## Platform Behavior
+/// - Windows: `HKCU\Software\Microsoft\Windows\CurrentVersion\Run` value
+/// - Linux: `Exec=` line of `~/.config/autostart/<app_name>.desktop`
+/// - macOS: First `ProgramArguments` entry of the LaunchAgent plist
+/// - Other: Not supported, returns `None`
+///
+/// ## Returns
+/// The registered command line, or None if missing or unreadable
+pub fn registered_autostart_command(app_name: &str) -> Option<String> {
+    #[cfg(windows)]
+    {
+        use windows::core::HSTRING;
+        use windows::Win32::System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_SZ};
+
+        let subkey = HSTRING::from("Software\\Microsoft\\Windows\\CurrentVersion\\Run");
+        let value = HSTRING::from(app_name);
+        let mut buf = vec![0u16; 1024];
+        let mut size = (buf.len() * std::mem::size_of::<u16>()) as u32;
+        // SAFETY: `buf` is writable for `size` bytes for the call duration
+        let status = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                &subkey,
+                &value,
+                RRF_RT_REG_SZ,
+                None,
+                Some(buf.as_mut_ptr().cast()),
+                Some(&mut size),
+            )
+        };
+        if status.is_err() {
+            log::debug!("Autostart registry value not readable: {:?}", status);
+            return None;
+        }
+        let len = (size as usize / std::mem::size_of::<u16>()).saturating_sub(1);
+        Some(String::from_utf16_lossy(&buf[..len]))
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let mut path = std::path::PathBuf::from(std::env::var_os("HOME")?);
+        path.push(".config");
+        path.push("autostart");
+        path.push(format!("{}.desktop", app_name));
+        let content = std::fs::read_to_string(path).ok()?;
+        content
+            .lines()
+            .find_map(|line| line.strip_prefix("Exec="))
+            .map(str::to_string)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let mut path = std::path::PathBuf::from(std::env::var_os("HOME")?);
+        path.push("Library");
+        path.push("LaunchAgents");
+        path.push(format!("{}.plist", app_name));
+        let content = std::fs::read_to_string(path).ok()?;
+        let (_, args) = content.split_once("<key>ProgramArguments</key>")?;
+        let (_, rest) = args.split_once("<string>")?;
+        let (program, _) = rest.split_once("</string>")?;
+        Some(program.to_string())
+    }
+
+    #[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+    {
+        let _ = app_name;
+        None
+    }
+}
+
+/// Query the OS idle-to-sleep timeout for the current power source
+///
+/// ## Platform Behavior
+/// - Windows: Not queried; `SetThreadExecutionState` holds the system awake
+///   regardless of the timeout, so `None` is returned
+/// - Linux: GNOME `sleep-inactive-{ac,battery}-timeout` via `gsettings`
+/// - macOS: `sleep` minutes from `pmset -g`
+/// - Other: `None`
+///
+/// ## Returns
+/// The timeout, `Some(Duration::ZERO)` if the system never idle-sleeps, or
+/// None if it could not be determined
+pub fn idle_sleep_timeout() -> Option<Duration> {
+    #[cfg(target_os = "linux")]
+    {
+        let suffix = if power_source() == PowerSource::Battery {
+            "battery"
+        } else {
+            "ac"
+        };
+        let schema = "org.gnome.settings-daemon.plugins.power";
+        let query = |key: String| {
+            std::process::Command::new("gsettings")
+                .args(["get", schema, &key])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+        };
+
+        if query(format!("sleep-inactive-{}-type", suffix))? == "'nothing'" {
+            return Some(Duration::ZERO);
+        }
+        let timeout = query(format!("sleep-inactive-{}-timeout", suffix))?;
+        parse_gsettings_uint(&timeout).map(Duration::from_secs)
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let output = std::process::Command::new("pmset")
+            .arg("-g")
+            .output()
+            .ok()?;
+        let minutes = parse_pmset_sleep_minutes(&String::from_utf8_lossy(&output.stdout))?;
+        Some(Duration::from_secs(minutes * 60))
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "macos")))]
+    {
+        None
+    }
+}
+
+/// Parse a `gsettings get` integer such as `uint32 1200` or `1200`
+#[cfg(target_os = "linux")]
+fn parse_gsettings_uint(value: &str) -> Option<u64> {
+    value.split_whitespace().last()?.parse().ok()
+}
+
+/// Parse the `sleep` minutes from `pmset -g` output
+#[cfg(any(target_os = "macos", test))]
+fn parse_pmset_sleep_minutes(output: &str) -> Option<u64> {
+    output.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        if fields.next()? != "sleep" {
+            return None;
+        }
+        fields.next()?.parse().ok()
+    })
+}
+
+/// Parse the charge of the first battery from `pmset -g batt` output
+///
+/// Battery lines look like
+/// `-InternalBattery-0 (id=1234)\t85%; discharging; 4:12 remaining`.
+#[cfg(any(target_os = "macos", test))]
+fn parse_pmset_battery_percent(output: &str) -> Option<u8> {
+    output
+        .lines()
+        .filter(|line| line.contains("InternalBattery"))
+        .find_map(|line| {
+            let (before, _) = line.split_once('%')?;
+            let digits = before.rsplit(|c: char| !c.is_ascii_digit()).next()?;
+            digits.parse::<u8>().ok().filter(|percent| *percent <= 100)
+        })
+}
+
+/// Query how long the user has been idle
+///
+/// ## Platform Behavior
+/// - Windows: `GetLastInputInfo` relative to `GetTickCount`
+/// - macOS: `HIDIdleTime` from `ioreg -c IOHIDSystem`
+/// - Other: Unsupported (no portable idle API without a display server
+///   dependency), returns an error
+///
+/// ## Returns
+/// Idle time in whole seconds, or error string
+pub fn system_idle_secs() -> Result<u64, String> {
+    #[cfg(windows)]
+    {
+        use windows::Win32::System::SystemInformation::GetTickCount;
+        use windows::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        // SAFETY: `info` is a valid LASTINPUTINFO with cbSize set
+        if !unsafe { GetLastInputInfo(&mut info) }.as_bool() {
+            return Err("GetLastInputInfo failed".to_string());
+        }
+        // SAFETY: GetTickCount has no preconditions
+        let now = unsafe { GetTickCount() };
+        Ok(idle_secs_from_ticks(now, info.dwTime))
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let output = std::process::Command::new("ioreg")
+            .args(["-c", "IOHIDSystem"])
+            .output()
+            .map_err(|e| format!("Failed to run ioreg: {}", e))?;
+        let text = String::from_utf8_lossy(&output.stdout);
+        let nanos: u64 = text
+            .lines()
+            .find_map(|line| {
+                let (_, value) = line.split_once("\"HIDIdleTime\" = ")?;
+                value.trim().parse().ok()
+            })
+            .ok_or_else(|| "HIDIdleTime not found in ioreg output".to_string())?;
+        Ok(nanos / 1_000_000_000)
+    }
+
+    #[cfg(not(any(windows, target_os = "macos")))]
+    {
+        Err("Reading system idle time is not supported on this platform".to_string())
+    }
+}
+
+/// Put the system to sleep, hibernate or shut it down
+///
+/// ## Platform Behavior
+/// - Windows: `SetSuspendState` for sleep and hibernate, `shutdown /s`
+/// - Linux: `systemctl suspend`, `hibernate` or `poweroff`
+/// - macOS: `pmset sleepnow` and System Events shut down; hibernate is
+///   unsupported (it depends on the `hibernatemode` setting)
+///
+/// ## Side Effects
+/// Suspends or powers off the machine. `Disable` does nothing.
+///
+/// ## Returns
+/// Ok once the request was issued, or error string
+pub fn perform_power_action(action: ConditionEndAction) -> Result<(), String> {
+    if action == ConditionEndAction::Disable {
+        return Ok(());
+    }
+    log::warn!("Performing system power action: {:?}", action);
+
+    #[cfg(windows)]
+    {
+        use windows::Win32::Foundation::BOOLEAN;
+        use windows::Win32::System::Power::SetSuspendState;
+
+        if action == ConditionEndAction::Shutdown {
+            return run_power_command("shutdown", &["/s", "/t", "0"]);
+        }
+        let hibernate = action == ConditionEndAction::Hibernate;
+        // SAFETY: SetSuspendState takes plain flags and has no preconditions
+        let suspended = unsafe {
+            SetSuspendState(
+                BOOLEAN::from(hibernate),
+                BOOLEAN::from(false),
+                BOOLEAN::from(false),
+            )
+        };
+        if suspended.as_bool() {
+            Ok(())
+        } else {
+            Err("SetSuspendState failed".to_string())
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let verb = match action {
+            ConditionEndAction::Hibernate => "hibernate",
+            ConditionEndAction::Shutdown => "poweroff",
+            _ => "suspend",
+        };
+        run_power_command("systemctl", &[verb])
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        match action {
+            ConditionEndAction::Hibernate => Err("Hibernate is not supported on macOS".to_string()),
+            ConditionEndAction::Shutdown => run_power_command(
+                "osascript",
+                &["-e", "tell application \"System Events\" to shut down"],
+            ),
+            _ => run_power_command("pmset", &["sleepnow"]),
+        }
+    }
+
+    #[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+    {
+        Err("System power actions are not supported on this platform".to_string())
+    }
+}
+
+/// Run a power management command and check its exit status
+#[cfg(any(windows, target_os = "linux", target_os = "macos"))]
+fn run_power_command(program: &str, args: &[&str]) -> Result<(), String> {
+    let status = std::process::Command::new(program)
+        .args(args)
+        .status()
+        .map_err(|e| format!("Failed to run {}: {}", program, e))?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(format!("{} exited with {}", program, status))
+    }
+}
+
+/// Convert Windows tick counts to idle seconds
+///
+/// ## Design Intent
+/// Tick counts are milliseconds in a `u32` that wraps every ~49.7 days;
+/// wrapping subtraction keeps the difference correct across the wrap.
+#[cfg(any(windows, test))]
+fn idle_secs_from_ticks(now: u32, last_input: u32) -> u64 {
+    u64::from(now.wrapping_sub(last_input)) / 1000
+}
+
+/// Classify Linux power supplies as reported by sysfs
+///
+/// ## Arguments
+/// * `supplies` - `(type, online)` pairs, one per `/sys/class/power_supply` entry
+///
+/// ## Returns
+/// `Ac` if any mains supply is online, `Battery` if a battery exists with no
+/// online mains, otherwise `Unknown` (e.g. desktops without a battery)
+#[cfg(target_os = "linux")]
+fn classify_linux_power_supplies(supplies: &[(String, Option<bool>)]) -> PowerSource {
+    let mains_online = supplies
+        .iter()
+        .any(|(kind, online)| kind == "Mains" && *online == Some(true));
+    let has_battery = supplies.iter().any(|(kind, _)| kind == "Battery");
+
+    if mains_online {
+        PowerSource::Ac
+    } else if has_battery {
+        PowerSource::Battery
+    } else {
+        PowerSource::Unknown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pinned_thread_runs_every_job_on_one_thread() {
+        let pinned = std::sync::Arc::new(PinnedThread::spawn("pinned-test").unwrap());
+        let callers: Vec<_> = (0..4)
+            .map(|_| {
+                let pinned = pinned.clone();
+                std::thread::spawn(move || pinned.run(|| std::thread::current().id()).unwrap())
+            })
+            .collect();
+        let ids: Vec<_> = callers.into_iter().map(|c| c.join().unwrap()).collect();
+        assert!(ids.iter().all(|id| *id == ids[0]));
+        assert_ne!(ids[0], std::thread::current().id());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_linux_mains_online_is_ac() {
+        let supplies = vec![
+            ("Mains".to_string(), Some(true)),
+            ("Battery".to_string(), None),
+        ];
+        assert_eq!(classify_linux_power_supplies(&supplies), PowerSource::Ac);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_linux_mains_offline_is_battery() {
+        let supplies = vec![
+            ("Mains".to_string(), Some(false)),
+            ("Battery".to_string(), None),
+        ];
+        assert_eq!(
+            classify_linux_power_supplies(&supplies),
+            PowerSource::Battery
+        );
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_linux_no_supplies_is_unknown() {
+        assert_eq!(classify_linux_power_supplies(&[]), PowerSource::Unknown);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_parse_gsettings_uint() {
+        assert_eq!(parse_gsettings_uint("uint32 1200"), Some(1200));
+        assert_eq!(parse_gsettings_uint("900"), Some(900));
+        assert_eq!(parse_gsettings_uint("'suspend'"), None);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    fn test_logind_inhibit_answers_within_timeout() {
+        // Granted, refused or no system bus: either way without hanging
+        let started = std::time::Instant::now();
+        drop(logind_inhibit("sleep", "Testing inhibitor support"));
+        assert!(started.elapsed() < INHIBIT_TIMEOUT + Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_logind_inhibit_what() {
+        assert_eq!(logind_inhibit_what(ScreenMode::KeepScreenOn), "idle:sleep");
+        assert_eq!(logind_inhibit_what(ScreenMode::AllowScreenOff), "sleep");
+    }
+
+    #[test]
+    fn test_parse_pmset_battery_percent() {
+        let output = "Now drawing from 'Battery Power'\n \
+                      -InternalBattery-0 (id=4653155)\t85%; discharging; 4:12 remaining present: true\n";
+        assert_eq!(parse_pmset_battery_percent(output), Some(85));
+        assert_eq!(
+            parse_pmset_battery_percent("Now drawing from 'AC Power'\n"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_pmset_sleep_minutes() {
+        let output = "System-wide power settings:\nCurrently in use:\n displaysleep         10\n sleep                15 (sleep prevented by coreaudiod)\n";
+        assert_eq!(parse_pmset_sleep_minutes(output), Some(15));
+        assert_eq!(parse_pmset_sleep_minutes(" displaysleep 10\n"), None);
+    }
+
+    #[test]
+    fn test_idle_secs_from_ticks() {
+        assert_eq!(idle_secs_from_ticks(10_000, 10_000), 0);
+        assert_eq!(idle_secs_from_ticks(75_500, 63_000), 12);
+    }
+
+    #[test]
+    fn test_idle_secs_from_ticks_across_wrap() {
+        // Last input 1s before the counter wrapped, now 2s after
+        assert_eq!(idle_secs_from_ticks(2_000, u32::MAX - 999), 3);
+    }
+}
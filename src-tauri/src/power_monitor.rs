@@ -0,0 +1,100 @@
+//! Power source monitor
+//!
+//! Background task that pauses the wake service while on battery when the
+//! user has asked to keep awake only on external power.
+//!
+//! ## Design Intent
+//! Bridges platform power detection and the pure policy in `core::power`.
+//! The monitor only ever writes the shared `PauseFlag`; the user's enabled
+//! flag (`is_awake`) is left untouched so wake resumes by itself once AC
+//! power returns.
+//!
+//! ## Side Effects
+//! - Re-evaluates on every power source change, or polls where changes
+//!   are not reported
+//! - Toggles the shared pause flag read by the wake service
+//!
+//! ## Platform Behavior
+//! - Windows: Notified of AC/DC switches by
+//!   `platform::watch_power_source_changes`
+//! - Other: Polls every `POLL_INTERVAL`
+//!
+//! ## Failure Modes
+//! - Power source unavailable: Treated as AC (never pauses)
+//! - Change notifications unavailable: Falls back to polling
+
+use crate::core::power::should_pause_for_power;
+use crate::core::{resolve_desired_state, AutomationInputs};
+use crate::platform;
+use crate::wake_service::PauseFlag;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often the power source is polled without change notifications
+///
+/// Unplugging is then noticed within one poll interval.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Re-evaluate the power pause decision once
+///
+/// ## Arguments
+/// * `only_on_ac` - Shared "only keep awake on AC" preference
+/// * `muted` - Shared automation mute flag; muting lifts any power pause
+/// * `paused` - Shared pause flag consumed by the wake service
+///
+/// ## Side Effects
+/// Queries the platform power source only when the option is enabled. A
+/// change of the pause state wakes the running wake loop.
+///
+/// ## Returns
+/// The new pause state
+pub fn refresh(only_on_ac: &AtomicBool, muted: &AtomicBool, paused: &PauseFlag) -> bool {
+    let enabled = only_on_ac.load(Ordering::SeqCst);
+    let power_pause = enabled && should_pause_for_power(enabled, platform::power_source());
+    let pause = resolve_desired_state(AutomationInputs {
+        muted: muted.load(Ordering::SeqCst),
+        power_pause,
+        ..Default::default()
+    })
+    .paused;
+
+    let was_paused = paused.set(pause);
+    if was_paused != pause {
+        if pause {
+            log::info!("Running on battery, pausing sleep prevention");
+        } else {
+            log::info!("Power pause lifted, resuming sleep prevention");
+        }
+    }
+
+    pause
+}
+
+/// Start the power monitor in the background
+///
+/// ## Side Effects
+/// Registers for power source changes or, failing that, spawns a Tokio
+/// task polling for the lifetime of the application.
+pub fn spawn_power_monitor(
+    only_on_ac: Arc<AtomicBool>,
+    muted: Arc<AtomicBool>,
+    paused: Arc<PauseFlag>,
+) {
+    let on_change = (only_on_ac.clone(), muted.clone(), paused.clone());
+    let watching = platform::watch_power_source_changes(move || {
+        let (only_on_ac, muted, paused) = &on_change;
+        refresh(only_on_ac, muted, paused);
+    });
+    if watching {
+        log::info!("Watching power source changes");
+        return;
+    }
+
+    tokio::spawn(async move {
+        loop {
+            refresh(&only_on_ac, &muted, &paused);
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
@@ -0,0 +1,41 @@
+//! Process watcher
+//!
+//! Keeps the system awake while a named process is running (e.g. a build
+//! or render job), and lets it sleep again once the process exits.
+//!
+//! ## Design Intent
+//! Build tools often run as a chain of short-lived processes, so a name
+//! can vanish for a moment between steps; debouncing (see
+//! `core::automation`) bridges that gap. The name must match a whole
+//! executable name, ignoring case, path and `.exe`, so the same setting
+//! works on every platform; those rules live in `core::process_watch`.
+//!
+//! ## Platform Behavior
+//! - Windows: Toolhelp process snapshot
+//! - Linux: `/proc`
+//! - macOS: `ps`
+//! - Other: Enumeration unsupported, watcher never matches
+
+use crate::core::automation::{PatternCondition, PatternProbe};
+use crate::core::process_watch::any_process_matches;
+use crate::platform;
+
+/// A running process has the configured name
+pub type ProcessCondition = PatternCondition<RunningProcesses>;
+
+/// Samples the running processes' names
+#[derive(Default)]
+pub struct RunningProcesses;
+
+impl PatternProbe for RunningProcesses {
+    const NAME: &'static str = "process";
+    const NOUN: &'static str = "process";
+
+    fn matches(&self, name: &str) -> Option<bool> {
+        let processes = platform::running_process_names()?;
+        Some(any_process_matches(
+            processes.iter().map(String::as_str),
+            name,
+        ))
+    }
+}
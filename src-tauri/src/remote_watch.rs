@@ -0,0 +1,58 @@
+//! Remote session watcher
+//!
+//! Keeps the system awake while anyone is connected over SSH or Remote
+//! Desktop, for servers that are worked on remotely.
+//!
+//! ## Design Intent
+//! An on/off setting rather than a pattern: any interactive remote session
+//! counts, and which ones do is decided by `core::remote_session`. This is
+//! about other sessions on this machine, not whether Tea itself runs in a
+//! remote session. A dropped RDP connection that is re-established within
+//! a poll or two is bridged by debouncing (see `core::automation`).
+//!
+//! ## Platform Behavior
+//! - Windows: Remote Desktop sessions (`WTSEnumerateSessionsW`)
+//! - Linux: Remote logind sessions (e.g. SSH) via `loginctl`
+//! - Other: Enumeration unsupported, watcher never matches
+
+use crate::core::automation::{Condition, ConditionResult, EvalContext};
+use crate::core::remote_session::any_interactive_remote;
+use crate::platform;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// An interactive remote session exists
+pub struct RemoteSessionCondition {
+    enabled: Arc<AtomicBool>,
+}
+
+impl RemoteSessionCondition {
+    /// Watch sessions while the shared `keep_awake_on_remote_session` is set
+    pub fn new(enabled: Arc<AtomicBool>) -> Self {
+        Self { enabled }
+    }
+
+    /// Sample whether an interactive remote session exists
+    fn holds(&self) -> bool {
+        if !self.enabled.load(Ordering::SeqCst) {
+            return false;
+        }
+        match platform::login_sessions() {
+            Some(sessions) => any_interactive_remote(&sessions),
+            None => {
+                log::debug!("Session enumeration unavailable, remote session watch inactive");
+                false
+            }
+        }
+    }
+}
+
+impl Condition for RemoteSessionCondition {
+    fn name(&self) -> &str {
+        "remote session"
+    }
+
+    fn evaluate(&self, _ctx: &EvalContext) -> ConditionResult {
+        ConditionResult::keep_awake_if(self.holds())
+    }
+}
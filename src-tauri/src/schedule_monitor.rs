@@ -0,0 +1,48 @@
+//! Wake schedule monitor
+//!
+//! Turns wake on and off at the times configured in `schedule`.
+//!
+//! ## Design Intent
+//! Checks once a minute against the local wall clock, which is the
+//! resolution a schedule is written in, and catches DST jumps and a
+//! resumed machine on the next check without tracking instants. The
+//! decision is `commands::apply_schedule_impl`; rules for windows and
+//! manual overrides live in `core::schedule`.
+//!
+//! ## Side Effects
+//! - Spawns a Tokio task for the application lifetime
+//! - May enable/disable wake, persist state and refresh the tray
+
+use crate::commands::{self, AppStateManager};
+use crate::notify;
+use crate::tray;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// How often the schedule is checked
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Start the schedule monitor in the background
+///
+/// The first check runs right away, so a launch inside a window starts
+/// awake.
+///
+/// ## Side Effects
+/// Spawns a Tokio task that runs for the lifetime of the application.
+pub fn spawn_schedule_monitor(app: AppHandle) {
+    tokio::spawn(async move {
+        loop {
+            let state = app.state::<AppStateManager>();
+            match commands::apply_schedule_impl(&state, commands::local_now()) {
+                Ok(Some(awake)) => {
+                    tray::refresh(&app);
+                    notify::notify_state_change(&app, awake, Some("schedule"));
+                }
+                Ok(None) => {}
+                Err(e) => log::error!("Schedule check failed: {}", e),
+            }
+
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
@@ -0,0 +1,299 @@
+//! Scheduled one-shot actions
+//!
+//! Tracks every pending timer so users can see and cancel them.
+//!
+//! ## Design Intent
+//! Timed features (auto-disable, pause/resume, ...) each need a delayed
+//! action. Routing them through one registry gives every action a stable id
+//! and a single place to list or cancel them, instead of detached tasks that
+//! nobody can reach.
+//!
+//! ## Side Effects
+//! - Spawns one Tokio task per scheduled action
+//! - Cancelling aborts the task before its action runs
+//! - While suspended, due actions are held back and run on resume
+//!
+//! ## Failure Modes
+//! - Unknown id on cancel: Returns an error string, registry unchanged
+
+use crate::core::format_duration_human;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::task::AbortHandle;
+
+/// A pending one-shot action, as shown to the user
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct ScheduledAction {
+    /// Stable identifier, valid until the action runs or is cancelled
+    pub id: String,
+    /// Human-readable description (e.g. "Auto-disable")
+    pub label: String,
+    /// When the action fires, as a UNIX timestamp in seconds
+    pub due_at: u64,
+}
+
+/// Registry entry pairing the visible action with its task
+struct Entry {
+    action: ScheduledAction,
+    /// Monotonic due time, for countdowns
+    deadline: tokio::time::Instant,
+    abort: AbortHandle,
+}
+
+/// An action that came due while the registry was suspended
+type DeferredAction = (String, Pin<Box<dyn Future<Output = ()> + Send>>);
+
+/// Registry of pending one-shot actions
+///
+/// ## Design Intent
+/// Shared behind an `Arc` so spawned tasks can remove their own entry once
+/// they fire.
+#[derive(Default)]
+pub struct ScheduledActions {
+    next_id: AtomicU64,
+    entries: Mutex<HashMap<String, Entry>>,
+    suspended: AtomicBool,
+    deferred: Mutex<Vec<DeferredAction>>,
+}
+
+impl ScheduledActions {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Schedule `action` to run after `delay`
+    ///
+    /// ## Arguments
+    /// * `label` - Description shown when listing
+    /// * `delay` - Time until the action runs
+    /// * `action` - Future executed when the delay elapses
+    ///
+    /// ## Side Effects
+    /// Spawns a Tokio task. The entry is removed when the action starts.
+    ///
+    /// ## Returns
+    /// The id of the new action
+    pub fn schedule<F>(self: &Arc<Self>, label: &str, delay: Duration, action: F) -> String
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let id = format!("timer-{}", self.next_id.fetch_add(1, Ordering::SeqCst) + 1);
+        let due_at = SystemTime::now()
+            .checked_add(delay)
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map_or(0, |d| d.as_secs());
+
+        // Hold the lock across spawn so the task cannot remove its entry
+        // before it has been inserted
+        let mut entries = self.lock_entries();
+
+        let registry = Arc::clone(self);
+        let task_id = id.clone();
+        let task = tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            registry.lock_entries().remove(&task_id);
+            if registry.suspended.load(Ordering::SeqCst) {
+                log::info!("Holding scheduled action {} until resumed", task_id);
+                registry.lock_deferred().push((task_id, Box::pin(action)));
+                return;
+            }
+            log::info!("Running scheduled action {}", task_id);
+            action.await;
+        });
+
+        log::info!(
+            "Scheduled action {} ({}) in {}",
+            id,
+            label,
+            format_duration_human(delay)
+        );
+        entries.insert(
+            id.clone(),
+            Entry {
+                action: ScheduledAction {
+                    id: id.clone(),
+                    label: label.to_string(),
+                    due_at,
+                },
+                deadline: tokio::time::Instant::now() + delay,
+                abort: task.abort_handle(),
+            },
+        );
+
+        id
+    }
+
+    /// List pending actions, soonest first
+    pub fn list(&self) -> Vec<ScheduledAction> {
+        let mut actions: Vec<ScheduledAction> = self
+            .lock_entries()
+            .values()
+            .map(|entry| entry.action.clone())
+            .collect();
+        actions.sort_by(|a, b| a.due_at.cmp(&b.due_at).then_with(|| a.id.cmp(&b.id)));
+        actions
+    }
+
+    /// Time left until a pending action runs
+    ///
+    /// ## Returns
+    /// The remaining time, or None for an unknown id (already run or
+    /// cancelled)
+    pub fn remaining(&self, id: &str) -> Option<Duration> {
+        let entries = self.lock_entries();
+        let deadline = entries.get(id)?.deadline;
+        Some(deadline.saturating_duration_since(tokio::time::Instant::now()))
+    }
+
+    /// Cancel a pending action
+    ///
+    /// ## Returns
+    /// Ok(()) if the action was pending, or error string for an unknown id
+    pub fn cancel(&self, id: &str) -> Result<(), String> {
+        let entry = self
+            .lock_entries()
+            .remove(id)
+            .ok_or_else(|| format!("No scheduled action with id '{}'", id))?;
+        entry.abort.abort();
+        log::info!("Cancelled scheduled action {} ({})", id, entry.action.label);
+        Ok(())
+    }
+
+    /// Suspend or resume running due actions
+    ///
+    /// ## Design Intent
+    /// Timers keep counting while suspended; an action that comes due is
+    /// held back rather than dropped, and runs as soon as the registry is
+    /// resumed.
+    ///
+    /// ## Side Effects
+    /// Resuming spawns a Tokio task for each held-back action.
+    pub fn set_suspended(&self, suspended: bool) {
+        self.suspended.store(suspended, Ordering::SeqCst);
+        if suspended {
+            return;
+        }
+
+        let deferred: Vec<DeferredAction> = self.lock_deferred().drain(..).collect();
+        for (id, action) in deferred {
+            log::info!("Running held-back scheduled action {}", id);
+            tokio::spawn(action);
+        }
+    }
+
+    /// Lock the held-back actions, recovering from poisoning
+    fn lock_deferred(&self) -> std::sync::MutexGuard<'_, Vec<DeferredAction>> {
+        self.deferred.lock().unwrap_or_else(|poisoned| {
+            log::warn!("Deferred action list mutex was poisoned, recovering");
+            poisoned.into_inner()
+        })
+    }
+
+    /// Lock the entry map, recovering from poisoning
+    ///
+    /// The map holds no invariants a panicking holder could break halfway.
+    fn lock_entries(&self) -> std::sync::MutexGuard<'_, HashMap<String, Entry>> {
+        self.entries.lock().unwrap_or_else(|poisoned| {
+            log::warn!("Scheduled action registry mutex was poisoned, recovering");
+            poisoned.into_inner()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_schedule_lists_action() {
+        let registry = Arc::new(ScheduledActions::new());
+        let id = registry.schedule("Auto-disable", Duration::from_secs(3600), async {});
+
+        let actions = registry.list();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].id, id);
+        assert_eq!(actions[0].label, "Auto-disable");
+        assert!(actions[0].due_at > 0);
+    }
+
+    #[tokio::test]
+    async fn test_ids_are_unique() {
+        let registry = Arc::new(ScheduledActions::new());
+        let first = registry.schedule("a", Duration::from_secs(3600), async {});
+        let second = registry.schedule("b", Duration::from_secs(3600), async {});
+        assert_ne!(first, second);
+        assert_eq!(registry.list().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_prevents_action() {
+        let registry = Arc::new(ScheduledActions::new());
+        let fired = Arc::new(AtomicBool::new(false));
+        let fired_clone = fired.clone();
+        let id = registry.schedule("Resume", Duration::from_millis(50), async move {
+            fired_clone.store(true, Ordering::SeqCst);
+        });
+
+        assert!(registry.cancel(&id).is_ok());
+        assert!(registry.list().is_empty());
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(!fired.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_remaining_counts_down() {
+        let registry = Arc::new(ScheduledActions::new());
+        let id = registry.schedule("Auto-disable", Duration::from_secs(600), async {});
+
+        tokio::time::sleep(Duration::from_secs(240)).await;
+        assert_eq!(registry.remaining(&id), Some(Duration::from_secs(360)));
+        registry.cancel(&id).unwrap();
+        assert_eq!(registry.remaining(&id), None);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_unknown_id_fails() {
+        let registry = Arc::new(ScheduledActions::new());
+        assert!(registry.cancel("timer-42").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fired_action_is_removed() {
+        let registry = Arc::new(ScheduledActions::new());
+        let fired = Arc::new(AtomicBool::new(false));
+        let fired_clone = fired.clone();
+        registry.schedule("Quick", Duration::from_millis(10), async move {
+            fired_clone.store(true, Ordering::SeqCst);
+        });
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(fired.load(Ordering::SeqCst));
+        assert!(registry.list().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_suspended_action_runs_on_resume() {
+        let registry = Arc::new(ScheduledActions::new());
+        let fired = Arc::new(AtomicBool::new(false));
+        let fired_clone = fired.clone();
+        registry.set_suspended(true);
+        registry.schedule("Resume", Duration::from_millis(10), async move {
+            fired_clone.store(true, Ordering::SeqCst);
+        });
+
+        tokio::time::sleep(Duration::from_millis(150)).await;
+        assert!(!fired.load(Ordering::SeqCst));
+        assert!(registry.list().is_empty());
+
+        registry.set_suspended(false);
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(fired.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,62 @@
+//! Startup self-check
+//!
+//! Runs quick health checks at launch and aggregates them with
+//! `core::StartupCheck`.
+//!
+//! ## Design Intent
+//! Problems such as corrupted icons, an unwritable config directory or
+//! missing input permissions otherwise only surface at the first toggle.
+//! Checking up front lets the tray show the problem immediately.
+//!
+//! ## Side Effects
+//! - Creates the config directory and briefly writes a probe file
+//! - Briefly creates an input simulator (no input is sent)
+//!
+//! ## Failure Modes
+//! None - individual failures are recorded in the returned summary.
+
+use crate::core::{CheckResult, StartupCheck};
+use crate::{icon, persistence, wake_service};
+
+/// Run all startup checks and log the summary
+///
+/// ## Platform Behavior
+/// Input simulation is critical everywhere except Windows, where the
+/// default AllowScreenOff mode keeps the system awake without it.
+pub fn run() -> StartupCheck {
+    let input_critical = cfg!(not(windows));
+
+    let checks = vec![
+        to_check_result("icons", true, icon::verify_icons()),
+        to_check_result("config", true, persistence::verify_config_writable()),
+        to_check_result(
+            "input",
+            input_critical,
+            wake_service::create_input_simulator().map(drop),
+        ),
+    ];
+
+    for check in checks.iter().filter(|check| !check.passed) {
+        log::warn!(
+            "Startup check '{}' failed: {}",
+            check.name,
+            check.detail.as_deref().unwrap_or("unknown error")
+        );
+    }
+
+    let result = StartupCheck::from_results(checks);
+    if result.ok {
+        log::info!("{}", result.summary());
+    } else {
+        log::error!("{}", result.summary());
+    }
+    result
+}
+
+/// Convert a check outcome into a `CheckResult`
+fn to_check_result(name: &str, critical: bool, outcome: crate::error::Result<()>) -> CheckResult {
+    match outcome {
+        Ok(()) => CheckResult::pass(name, critical),
+        Err(e) => CheckResult::fail(name, critical, e.to_string()),
+    }
+}
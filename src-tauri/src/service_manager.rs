@@ -0,0 +1,731 @@
+//! Wake service lifecycle
+//!
+//! Serializes starting, stopping and restarting the wake service.
+//!
+//! ## Design Intent
+//! Manual toggles, screen mode changes, watchers and the master switch can
+//! all ask for a lifecycle change in the same instant. Every change goes
+//! through one `tokio::sync::Mutex` that also owns the live service, so two
+//! operations never interleave and at most one wake loop runs. Each loop
+//! has its own running flag, and a stop awaits the loop's task, which ends
+//! only after cleanup, before the next operation may start another. Quit
+//! waits on the same task through its abort handle.
+//!
+//! Synchronous callers use `request_sync`, which queues a `sync` against
+//! the shared desired state (`is_awake` and `screen_mode`) rather than a
+//! fixed start or stop. Whichever request takes the lock last reads the
+//! latest state, so the outcome never depends on the order queued tasks
+//! happen to run in.
+//!
+//! ## Start Failures
+//! `sync` returns the error when a service cannot start (input simulation
+//! unavailable, e.g. no permission on Wayland), and remembers it for the
+//! tooltip until a start succeeds. A queued `request_sync` has no caller
+//! to return to, so it reports the error to a process-wide listener
+//! registered at setup, which turns wake back off.
+//!
+//! ## Boost
+//! `boost` shortens the interval of the live service, and of any started
+//! while it lasts, for a set time. It goes to the loops over a `watch`
+//! channel rather than through `sync`, so it never restarts a service. A
+//! later boost replaces an earlier one, including its end time.
+//!
+//! ## Side Effects
+//! Spawns and stops wake service tasks.
+
+use crate::commands;
+use crate::core::{resolve_wake_interval, ScreenMode, WakeKey, WakeStrategy};
+use crate::error::{AppError, Result};
+use crate::persistence::read_state;
+use crate::wake_service::{self, PauseFlag, PowerAction, RunningFlag, WakeBackend, WakeService};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+use tokio::sync::{watch, Notify};
+use tokio::task::{AbortHandle, JoinHandle};
+
+/// The wake loop currently running
+struct LiveService {
+    mode: ScreenMode,
+    interval: Duration,
+    jitter: bool,
+    power_requests: bool,
+    running: Arc<RunningFlag>,
+    task: JoinHandle<()>,
+}
+
+/// Running flag and task of the live service, reachable without awaiting
+#[derive(Default)]
+struct QuitHandle {
+    live: Option<(Arc<RunningFlag>, AbortHandle)>,
+    /// Quit has begun; no further services may start
+    closed: bool,
+}
+
+/// Temporarily shortened wake interval shared with every service
+struct Boost {
+    interval: watch::Sender<Option<Duration>>,
+    /// Bumped by every boost, so only the latest one's timer ends it
+    generation: AtomicU64,
+}
+
+/// Callback told when a queued start fails
+pub type StartFailureListener = Arc<dyn Fn(&AppError) + Send + Sync>;
+
+/// Listener registered by `set_start_failure_listener`
+static START_FAILURE_LISTENER: OnceLock<StartFailureListener> = OnceLock::new();
+
+/// Register the process-wide start failure listener
+///
+/// ## Failure Modes
+/// - Already registered: Logs a warning, the first listener is kept
+pub fn set_start_failure_listener(listener: StartFailureListener) {
+    if START_FAILURE_LISTENER.set(listener).is_err() {
+        log::warn!("Start failure listener already registered, ignoring");
+    }
+}
+
+/// Report a failed start to the listener, if any
+fn notify_start_failure(error: &AppError) {
+    if let Some(listener) = START_FAILURE_LISTENER.get() {
+        listener(error);
+    }
+}
+
+/// Owner of the wake service lifecycle
+pub struct ServiceManager {
+    backend: WakeBackend,
+    desired_awake: Arc<AtomicBool>,
+    desired_mode: Arc<Mutex<ScreenMode>>,
+    paused: Arc<PauseFlag>,
+    log_each_iteration: Arc<AtomicBool>,
+    wake_key: Arc<Mutex<WakeKey>>,
+    wake_strategy: Arc<Mutex<WakeStrategy>>,
+    /// Set by the live service while the platform refuses its display request
+    display_unavailable: Arc<AtomicBool>,
+    /// The live service runs without input simulation, which failed to start
+    input_unavailable: AtomicBool,
+    /// Handed to each service to re-apply its display mode on demand
+    reassert: Arc<Notify>,
+    boost: Arc<Boost>,
+    /// Why the last start failed, cleared by the next successful start
+    start_error: Mutex<Option<String>>,
+    /// Serializes every lifecycle operation
+    live: tokio::sync::Mutex<Option<LiveService>>,
+    quit: Mutex<QuitHandle>,
+}
+
+impl ServiceManager {
+    /// Create a manager with no service running
+    ///
+    /// ## Arguments
+    /// * `backend` - Display and input used by wake sessions
+    /// * `desired_awake` - Shared wake state read by `sync`
+    /// * `desired_mode` - Shared screen mode read by `sync`
+    /// * `paused` - Shared power pause flag handed to each service
+    /// * `log_each_iteration` - Shared heartbeat flag handed to each service
+    /// * `wake_key` - Shared key choice handed to each service
+    /// * `wake_strategy` - Shared key press / mouse jiggle choice handed to
+    ///   each service
+    pub fn new(
+        backend: WakeBackend,
+        desired_awake: Arc<AtomicBool>,
+        desired_mode: Arc<Mutex<ScreenMode>>,
+        paused: Arc<PauseFlag>,
+        log_each_iteration: Arc<AtomicBool>,
+        wake_key: Arc<Mutex<WakeKey>>,
+        wake_strategy: Arc<Mutex<WakeStrategy>>,
+    ) -> Self {
+        Self {
+            backend,
+            desired_awake,
+            desired_mode,
+            paused,
+            log_each_iteration,
+            wake_key,
+            wake_strategy,
+            display_unavailable: Arc::new(AtomicBool::new(false)),
+            input_unavailable: AtomicBool::new(false),
+            reassert: Arc::new(Notify::new()),
+            boost: Arc::new(Boost {
+                interval: watch::Sender::new(None),
+                generation: AtomicU64::new(0),
+            }),
+            start_error: Mutex::new(None),
+            live: tokio::sync::Mutex::new(None),
+            quit: Mutex::new(QuitHandle::default()),
+        }
+    }
+
+    /// Bring the service in line with the shared wake state and screen mode
+    ///
+    /// ## Design Intent
+    /// The one lifecycle operation: starts a stopped service, stops one
+    /// that is no longer wanted, and restarts one running in another mode
+    /// or with another configured interval.
+    ///
+    /// ## Failure Modes
+    /// - Input simulation fails to start where the platform API keeps the
+    ///   system awake by itself: Logs a warning and starts the service
+    ///   without input; `input_unavailable` reports the degraded state
+    ///
+    /// ## Returns
+    /// AppError::InputSimulation if a wanted service could not start; no
+    /// service is running then
+    pub async fn sync(&self) -> Result<()> {
+        let mut live = self.live.lock().await;
+        if !self.desired_awake.load(Ordering::SeqCst) {
+            Self::stop_locked(&mut live).await;
+            self.input_unavailable.store(false, Ordering::SeqCst);
+            return Ok(());
+        }
+        let mode = *commands::lock_screen_mode(&self.desired_mode, "service sync");
+        let result = self.start_locked(&mut live, mode).await;
+        match self.start_error.lock() {
+            Ok(mut start_error) => {
+                *start_error = result.as_ref().err().map(AppError::message);
+            }
+            Err(e) => log::error!("Mutex poisoned during start error update: {}", e),
+        }
+        result
+    }
+
+    /// Why the last start failed, None if it succeeded
+    pub fn start_error(&self) -> Option<String> {
+        match self.start_error.lock() {
+            Ok(start_error) => start_error.clone(),
+            Err(e) => {
+                log::error!("Mutex poisoned during start error read: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Whether the running service's display request was refused
+    ///
+    /// ## Design Intent
+    /// Wake keeps going by input simulation, so this is a degraded state
+    /// for the tooltip rather than an error. Cleared when the service stops.
+    pub fn display_unavailable(&self) -> bool {
+        self.display_unavailable.load(Ordering::SeqCst)
+    }
+
+    /// Whether the running service fell back to the platform API alone
+    ///
+    /// ## Design Intent
+    /// Like `display_unavailable`, a degraded state for the tooltip: the
+    /// system is still kept awake, but e.g. Keep Screen On may not hold
+    /// the screen on where that relies on key presses.
+    pub fn input_unavailable(&self) -> bool {
+        self.input_unavailable.load(Ordering::SeqCst)
+    }
+
+    /// Runs condition end actions, or only reports them in a dry run
+    pub fn power_action(&self) -> PowerAction {
+        self.backend.power_action.clone()
+    }
+
+    /// Ask the running service to apply its display mode again
+    ///
+    /// ## Design Intent
+    /// For events after which the platform may have dropped the display
+    /// request, such as unlocking the session (see `session_watch`). Does
+    /// nothing without a running service, so no stale request is left for
+    /// the next one; a service mid-cycle picks it up at its next wait.
+    ///
+    /// ## Returns
+    /// Whether a service was running to reassert
+    pub fn reassert_display(&self) -> bool {
+        let live = match self.quit.lock() {
+            Ok(quit) => quit
+                .live
+                .as_ref()
+                .is_some_and(|(running, _)| running.is_running()),
+            Err(e) => {
+                log::error!("Mutex poisoned during display reassert: {}", e);
+                false
+            }
+        };
+        if live {
+            self.reassert.notify_one();
+        }
+        live
+    }
+
+    /// Wake at least every `interval` for the next `duration`
+    ///
+    /// ## Design Intent
+    /// For critical stretches (e.g. a long upload) where one missed press
+    /// could let the screen sleep. Applies to the running loop at once,
+    /// without a restart, and is not persisted. An interval longer than
+    /// the configured one has no effect.
+    ///
+    /// ## Side Effects
+    /// Spawns a task that ends the boost after `duration`, unless another
+    /// boost has replaced it by then.
+    pub fn boost(&self, interval: Duration, duration: Duration) {
+        let generation = self.boost.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        self.boost.interval.send_replace(Some(interval));
+        log::info!(
+            "Boosting wake interval to {:?} for {:?}",
+            interval,
+            duration
+        );
+
+        let boost = self.boost.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(duration).await;
+            if boost.generation.load(Ordering::SeqCst) == generation {
+                boost.interval.send_replace(None);
+                log::info!("Wake interval boost ended");
+            }
+        });
+    }
+
+    /// The boosted interval in force, None outside a boost
+    pub fn boost_interval(&self) -> Option<Duration> {
+        *self.boost.interval.borrow()
+    }
+
+    /// Queue a `sync` from synchronous code
+    ///
+    /// ## Side Effects
+    /// Spawns a Tokio task; a failed start goes to the start failure
+    /// listener
+    pub fn request_sync(self: &Arc<Self>) {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = manager.sync().await {
+                log::error!("Wake service failed to start: {}", e);
+                notify_start_failure(&e);
+            }
+        });
+    }
+
+    /// Stop the service from synchronous code and refuse further starts
+    ///
+    /// ## Design Intent
+    /// Quit runs on the UI thread and must not await the lifecycle lock, so
+    /// it signals the live loop directly and waits, bounded, for its task
+    /// to finish; the display is restored by then.
+    ///
+    /// ## Returns
+    /// True if the live loop finished cleanup, false on timeout
+    pub fn stop_blocking(&self, timeout: Duration) -> bool {
+        let live = match self.quit.lock() {
+            Ok(mut quit) => {
+                quit.closed = true;
+                quit.live.take()
+            }
+            Err(e) => {
+                log::error!("Mutex poisoned during service shutdown: {}", e);
+                None
+            }
+        };
+        match live {
+            Some((running, task)) => {
+                wake_service::stop_wake_service_blocking(&running, &task, timeout)
+            }
+            None => true,
+        }
+    }
+
+    async fn start_locked(&self, live: &mut Option<LiveService>, mode: ScreenMode) -> Result<()> {
+        let config = read_state();
+        let interval =
+            resolve_wake_interval(mode, &config.interval_per_mode, config.wake_interval_secs);
+        let jitter = config.interval_jitter;
+        let power_requests = config.use_power_requests;
+        if let Some(service) = live.as_ref() {
+            if service.mode == mode
+                && service.interval == interval
+                && service.jitter == jitter
+                && service.power_requests == power_requests
+                && !service.task.is_finished()
+            {
+                return Ok(());
+            }
+        }
+        Self::stop_locked(live).await;
+
+        let input = match (self.backend.input)(mode) {
+            Ok(input) => {
+                self.input_unavailable.store(false, Ordering::SeqCst);
+                input
+            }
+            Err(e) if self.backend.native_wake => {
+                log::warn!(
+                    "Input simulation unavailable, keeping awake by platform API only: {}",
+                    e
+                );
+                self.input_unavailable.store(true, Ordering::SeqCst);
+                None
+            }
+            Err(e) => return Err(e),
+        };
+        let running = Arc::new(RunningFlag::new());
+        let service = WakeService::new(
+            running.clone(),
+            self.paused.clone(),
+            (self.backend.display)(power_requests),
+        )
+        .with_heartbeat(
+            self.log_each_iteration.clone(),
+            Arc::new(wake_service::log_heartbeat),
+        )
+        .with_wake_key(self.wake_key.clone())
+        .with_wake_strategy(self.wake_strategy.clone())
+        .with_display_status(self.display_unavailable.clone())
+        .with_jitter(jitter)
+        .with_reassert(self.reassert.clone())
+        .with_boost(self.boost.interval.subscribe());
+
+        // Hold the quit lock across the spawn so quit always sees the task
+        let task = match self.quit.lock() {
+            Ok(mut quit) if !quit.closed => {
+                let task = tokio::spawn(service.run(mode, interval, input));
+                quit.live = Some((running.clone(), task.abort_handle()));
+                task
+            }
+            Ok(_) => {
+                log::info!("Quitting, not starting the wake service");
+                return Ok(());
+            }
+            Err(e) => {
+                log::error!("Mutex poisoned during service start: {}", e);
+                return Ok(());
+            }
+        };
+        *live = Some(LiveService {
+            mode,
+            interval,
+            jitter,
+            power_requests,
+            running,
+            task,
+        });
+        Ok(())
+    }
+
+    async fn stop_locked(live: &mut Option<LiveService>) {
+        if let Some(service) = live.take() {
+            service.running.stop();
+            if let Err(e) = service.task.await {
+                log::error!("Wake service task failed: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{
+        counting_backend, recording_backend, service_manager, CountingDisplay, LiveCount,
+        SilentInput,
+    };
+
+    fn counting_manager() -> (Arc<ServiceManager>, Arc<Mutex<LiveCount>>) {
+        let (backend, count) = counting_backend();
+        let manager = service_manager(
+            backend,
+            Arc::new(AtomicBool::new(false)),
+            ScreenMode::default(),
+        );
+        (Arc::new(manager), count)
+    }
+
+    /// Screen mode of the running service, None if stopped
+    async fn live_mode(manager: &ServiceManager) -> Option<ScreenMode> {
+        let live = manager.live.lock().await;
+        live.as_ref()
+            .filter(|service| !service.task.is_finished())
+            .map(|service| service.mode)
+    }
+
+    fn set_desired(manager: &ServiceManager, awake: bool, mode: ScreenMode) {
+        manager.desired_awake.store(awake, Ordering::SeqCst);
+        *manager.desired_mode.lock().unwrap() = mode;
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sync_starts_restarts_and_stops() {
+        let (manager, count) = counting_manager();
+
+        set_desired(&manager, true, ScreenMode::AllowScreenOff);
+        manager.sync().await.unwrap();
+        manager.sync().await.unwrap();
+        tokio::task::yield_now().await;
+        assert_eq!(count.lock().unwrap().starts, 1);
+
+        set_desired(&manager, true, ScreenMode::KeepScreenOn);
+        manager.sync().await.unwrap();
+        tokio::task::yield_now().await;
+        assert_eq!(count.lock().unwrap().starts, 2);
+        assert_eq!(count.lock().unwrap().current, 1);
+        assert_eq!(live_mode(&manager).await, Some(ScreenMode::KeepScreenOn));
+
+        set_desired(&manager, false, ScreenMode::KeepScreenOn);
+        manager.sync().await.unwrap();
+        assert_eq!(count.lock().unwrap().current, 0);
+        assert_eq!(live_mode(&manager).await, None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_concurrent_lifecycle_ops_leave_one_service() {
+        let (manager, count) = counting_manager();
+        let modes = [ScreenMode::AllowScreenOff, ScreenMode::KeepScreenOn];
+
+        let mut tasks = Vec::new();
+        for i in 0..24 {
+            let manager = manager.clone();
+            tasks.push(tokio::spawn(async move {
+                set_desired(&manager, i % 3 != 0, modes[i % 2]);
+                if i % 2 == 0 {
+                    manager.request_sync();
+                }
+                manager.sync().await.unwrap();
+            }));
+        }
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        set_desired(&manager, true, ScreenMode::KeepScreenOn);
+        manager.request_sync();
+        // Syncs still queued by the tasks run too, and read the same state
+        tokio::time::sleep(Duration::from_secs(5)).await;
+
+        assert!(count.lock().unwrap().starts > 1);
+        assert_eq!(count.lock().unwrap().max, 1);
+        assert_eq!(count.lock().unwrap().current, 1);
+        assert_eq!(live_mode(&manager).await, Some(ScreenMode::KeepScreenOn));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sync_restarts_on_interval_change() {
+        crate::persistence::set_test_store(Some(Arc::new(
+            crate::persistence::MemoryStateStore::default(),
+        )));
+        let (manager, count) = counting_manager();
+        set_desired(&manager, true, ScreenMode::KeepScreenOn);
+        manager.sync().await.unwrap();
+
+        crate::persistence::update_state(|s| s.wake_interval_secs = Some(120)).unwrap();
+        manager.sync().await.unwrap();
+        manager.sync().await.unwrap();
+        tokio::task::yield_now().await;
+        assert_eq!(count.lock().unwrap().starts, 2);
+        assert_eq!(count.lock().unwrap().current, 1);
+        let interval = manager.live.lock().await.as_ref().map(|s| s.interval);
+        assert_eq!(interval, Some(Duration::from_secs(120)));
+
+        set_desired(&manager, false, ScreenMode::KeepScreenOn);
+        manager.sync().await.unwrap();
+        crate::persistence::set_test_store(None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sync_restarts_on_jitter_change() {
+        crate::persistence::set_test_store(Some(Arc::new(
+            crate::persistence::MemoryStateStore::default(),
+        )));
+        let (manager, count) = counting_manager();
+        set_desired(&manager, true, ScreenMode::KeepScreenOn);
+        manager.sync().await.unwrap();
+
+        crate::persistence::update_state(|s| s.interval_jitter = true).unwrap();
+        manager.sync().await.unwrap();
+        manager.sync().await.unwrap();
+        tokio::task::yield_now().await;
+        assert_eq!(count.lock().unwrap().starts, 2);
+        assert_eq!(count.lock().unwrap().current, 1);
+        let jitter = manager.live.lock().await.as_ref().map(|s| s.jitter);
+        assert_eq!(jitter, Some(true));
+
+        set_desired(&manager, false, ScreenMode::KeepScreenOn);
+        manager.sync().await.unwrap();
+        crate::persistence::set_test_store(None);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_stop_blocking_waits_for_cleanup() {
+        let (manager, count) = counting_manager();
+        set_desired(&manager, true, ScreenMode::KeepScreenOn);
+        manager.sync().await.unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(count.lock().unwrap().current, 1);
+
+        // Quit blocks like the UI thread does
+        let quitting = manager.clone();
+        let stopped =
+            tokio::task::spawn_blocking(move || quitting.stop_blocking(Duration::from_secs(5)))
+                .await
+                .unwrap();
+
+        assert!(stopped);
+        assert_eq!(count.lock().unwrap().current, 0);
+        let live = manager.live.lock().await;
+        assert!(live
+            .as_ref()
+            .is_some_and(|service| service.task.is_finished()));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_failed_start_is_returned_and_remembered() {
+        let fail = Arc::new(AtomicBool::new(true));
+        let failing = fail.clone();
+        let backend = WakeBackend {
+            display: Arc::new(|_| {
+                Box::new(CountingDisplay {
+                    count: Arc::new(Mutex::new(LiveCount::default())),
+                })
+            }),
+            input: Arc::new(move |_| {
+                if failing.load(Ordering::SeqCst) {
+                    Err(AppError::from(enigo::NewConError::NoPermission))
+                } else {
+                    Ok(Some(Box::new(SilentInput)))
+                }
+            }),
+            native_wake: false,
+            power_action: Arc::new(|_| Ok(())),
+        };
+        let manager = service_manager(
+            backend,
+            Arc::new(AtomicBool::new(true)),
+            ScreenMode::KeepScreenOn,
+        );
+
+        let error = manager.sync().await.unwrap_err();
+        assert!(matches!(error, AppError::InputSimulation { .. }));
+        assert_eq!(live_mode(&manager).await, None);
+        assert_eq!(manager.start_error(), Some(error.message()));
+
+        fail.store(false, Ordering::SeqCst);
+        manager.sync().await.unwrap();
+        assert_eq!(live_mode(&manager).await, Some(ScreenMode::KeepScreenOn));
+        assert_eq!(manager.start_error(), None);
+
+        set_desired(&manager, false, ScreenMode::KeepScreenOn);
+        manager.sync().await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_sync_restarts_on_power_request_change() {
+        crate::persistence::set_test_store(Some(Arc::new(
+            crate::persistence::MemoryStateStore::default(),
+        )));
+        let (manager, count) = counting_manager();
+        set_desired(&manager, true, ScreenMode::KeepScreenOn);
+        manager.sync().await.unwrap();
+
+        crate::persistence::update_state(|s| s.use_power_requests = true).unwrap();
+        manager.sync().await.unwrap();
+        tokio::task::yield_now().await;
+        assert_eq!(count.lock().unwrap().starts, 2);
+        assert_eq!(count.lock().unwrap().current, 1);
+        let power_requests = manager.live.lock().await.as_ref().map(|s| s.power_requests);
+        assert_eq!(power_requests, Some(true));
+
+        set_desired(&manager, false, ScreenMode::KeepScreenOn);
+        manager.sync().await.unwrap();
+        crate::persistence::set_test_store(None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_failed_input_falls_back_to_platform_api() {
+        let count = Arc::new(Mutex::new(LiveCount::default()));
+        let display_count = count.clone();
+        let backend = WakeBackend {
+            display: Arc::new(move |_| {
+                Box::new(CountingDisplay {
+                    count: display_count.clone(),
+                })
+            }),
+            input: Arc::new(|_| Err(AppError::from(enigo::NewConError::NoPermission))),
+            native_wake: true,
+            power_action: Arc::new(|_| Ok(())),
+        };
+        let manager = service_manager(
+            backend,
+            Arc::new(AtomicBool::new(true)),
+            ScreenMode::KeepScreenOn,
+        );
+
+        manager.sync().await.unwrap();
+        tokio::task::yield_now().await;
+        assert_eq!(live_mode(&manager).await, Some(ScreenMode::KeepScreenOn));
+        assert_eq!(count.lock().unwrap().current, 1);
+        assert!(manager.input_unavailable());
+        assert_eq!(manager.start_error(), None);
+
+        set_desired(&manager, false, ScreenMode::KeepScreenOn);
+        manager.sync().await.unwrap();
+        assert!(!manager.input_unavailable());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_no_start_after_quit() {
+        let (manager, count) = counting_manager();
+        set_desired(&manager, true, ScreenMode::KeepScreenOn);
+        manager.sync().await.unwrap();
+
+        manager.quit.lock().unwrap().closed = true;
+        set_desired(&manager, true, ScreenMode::AllowScreenOff);
+        manager.sync().await.unwrap();
+        assert_eq!(live_mode(&manager).await, None);
+        assert_eq!(count.lock().unwrap().current, 0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_boost_shortens_interval_then_reverts_without_restart() {
+        crate::persistence::set_test_store(Some(Arc::new(
+            crate::persistence::MemoryStateStore::default(),
+        )));
+        let (backend, log) = recording_backend();
+        let manager = Arc::new(service_manager(
+            backend,
+            Arc::new(AtomicBool::new(true)),
+            ScreenMode::KeepScreenOn,
+        ));
+        let pressed = || log.presses.load(Ordering::SeqCst);
+
+        // Default 60s interval: the first press is at 0s
+        manager.sync().await.unwrap();
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(pressed(), 1);
+
+        // Boosted to 10s from 1s until 56s: presses at 10s, 20s, ... 50s
+        manager.boost(Duration::from_secs(10), Duration::from_secs(55));
+        assert_eq!(manager.boost_interval(), Some(Duration::from_secs(10)));
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        assert_eq!(pressed(), 6);
+        assert_eq!(manager.boost_interval(), None);
+
+        // Reverted: the next press is a full interval after the last, at 110s
+        tokio::time::sleep(Duration::from_secs(48)).await;
+        assert_eq!(pressed(), 6);
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert_eq!(pressed(), 7);
+        assert_eq!(log.applied(), 1);
+
+        manager.desired_awake.store(false, Ordering::SeqCst);
+        manager.sync().await.unwrap();
+        crate::persistence::set_test_store(None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_later_boost_replaces_earlier_end() {
+        let (manager, _count) = counting_manager();
+        manager.boost(Duration::from_secs(10), Duration::from_secs(30));
+        tokio::time::sleep(Duration::from_secs(20)).await;
+        manager.boost(Duration::from_secs(5), Duration::from_secs(30));
+
+        // The first boost's timer must not end the second one
+        tokio::time::sleep(Duration::from_secs(15)).await;
+        assert_eq!(manager.boost_interval(), Some(Duration::from_secs(5)));
+        tokio::time::sleep(Duration::from_secs(20)).await;
+        assert_eq!(manager.boost_interval(), None);
+    }
+}
@@ -0,0 +1,101 @@
+//! Session change watcher
+//!
+//! Reasserts the running wake session's display request when the user
+//! comes back to the session: unlocking it, or reconnecting over Remote
+//! Desktop.
+//!
+//! ## Design Intent
+//! Windows can drop or stop honouring an execution state or power request
+//! across a lock or an RDP hand-over, leaving the machine to sleep although
+//! Tea shows it awake. Rather than a new lifecycle path, session events are
+//! fed to `ServiceManager::reassert_display`, which makes the live wake
+//! loop apply its screen mode again. Which events count is decided by
+//! `core::session_event`.
+//!
+//! ## Platform Behavior
+//! - Windows: `WM_WTSSESSION_CHANGE` via `platform::watch_session_changes`
+//! - Other: No session notifications, nothing is watched
+//!
+//! ## Manual Test (Windows)
+//! 1. Enable Tea with Keep Screen On, debug logging on
+//! 2. Lock (Win+L), wait a few seconds, unlock: the log shows
+//!    `Session Unlock, reasserting wake` then `Reasserting display mode`
+//! 3. `powercfg /requests` lists Tea's request under DISPLAY and SYSTEM
+//! 4. Disconnect an RDP session to the machine, reconnect: the log shows
+//!    `Session RemoteConnect, reasserting wake`
+//! 5. With wake off, lock and unlock: only the event is logged at debug
+
+use crate::core::session_event::SessionEvent;
+use crate::platform;
+use crate::service_manager::ServiceManager;
+use std::sync::Arc;
+
+/// React to one session change
+///
+/// ## Returns
+/// Whether a running wake session was asked to reassert
+pub fn handle_session_event(service: &ServiceManager, event: SessionEvent) -> bool {
+    if !event.reasserts_wake() {
+        log::debug!("Session {:?}, nothing to reassert", event);
+        return false;
+    }
+    let reasserted = service.reassert_display();
+    if reasserted {
+        log::info!("Session {:?}, reasserting wake", event);
+    } else {
+        log::debug!("Session {:?}, wake not running", event);
+    }
+    reasserted
+}
+
+/// Start watching session changes for the lifetime of the process
+///
+/// ## Failure Modes
+/// - Unsupported platform or the hook fails to register: Logged by
+///   `platform::watch_session_changes`; wake works as before
+pub fn spawn_session_watch(service: Arc<ServiceManager>) {
+    let watching = platform::watch_session_changes(move |event| {
+        handle_session_event(&service, event);
+    });
+    if watching {
+        log::info!("Watching session changes to reassert wake");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ScreenMode;
+    use crate::test_support::{recording_backend, service_manager};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::time::Duration;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_unlock_reasserts_running_service_only() {
+        let (backend, log) = recording_backend();
+        let desired_awake = Arc::new(AtomicBool::new(false));
+        let service = service_manager(backend, desired_awake.clone(), ScreenMode::KeepScreenOn);
+
+        // Wake off: nothing to reassert
+        assert!(!handle_session_event(&service, SessionEvent::Unlock));
+
+        desired_awake.store(true, Ordering::SeqCst);
+        service.sync().await.unwrap();
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(log.applied(), 1);
+
+        assert!(!handle_session_event(&service, SessionEvent::Lock));
+        assert!(handle_session_event(&service, SessionEvent::Unlock));
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(log.applied(), 2);
+
+        assert!(handle_session_event(&service, SessionEvent::RemoteConnect));
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(log.applied(), 3);
+
+        // Stopped again: later unlocks are ignored
+        desired_awake.store(false, Ordering::SeqCst);
+        service.sync().await.unwrap();
+        assert!(!handle_session_event(&service, SessionEvent::Unlock));
+    }
+}
@@ -0,0 +1,294 @@
+//! Live settings of the running app, grouped by feature
+//!
+//! Each group holds the shared handles `AppStateManager` hands to commands,
+//! the tray and background services, and knows how to load itself from a
+//! persisted `AppState`.
+//!
+//! ## Design Intent
+//! A flat manager of forty fields made every new setting one more line to
+//! remember in `reset_state_impl`. Here each group builds itself and resets
+//! itself from the same `AppState`, and every `reset_from` destructures the
+//! group without `..`, so a field added later does not compile until its
+//! reset is decided.
+//!
+//! ## Failure Modes
+//! - Poisoned mutex during a reset: Error string, later groups untouched
+
+use crate::commands::{compute_sleep_risk, lock_screen_mode};
+use crate::core::schedule::Schedule;
+use crate::core::{ScreenMode, SleepRisk, WakeKey, WakeStrategy};
+use crate::persistence::AppState;
+use crate::wake_service::PauseFlag;
+use chrono::NaiveDateTime;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Replace a shared value during a reset
+fn store<T>(field: &Mutex<T>, value: T) -> Result<(), String> {
+    *field
+        .lock()
+        .map_err(|e| format!("Mutex poisoned during reset_state: {}", e))? = value;
+    Ok(())
+}
+
+/// How the system is kept awake
+#[derive(Clone)]
+pub struct WakeSettings {
+    pub screen_mode: Arc<Mutex<ScreenMode>>,
+    /// Key pressed every wake cycle, shared with the running service
+    pub wake_key: Arc<Mutex<WakeKey>>,
+    /// Key press or mouse jiggle, shared with the running service
+    pub wake_strategy: Arc<Mutex<WakeStrategy>>,
+    pub blink_on_press: Arc<AtomicBool>,
+    /// Heartbeat logging on every wake cycle, shared with the service manager
+    pub log_each_iteration: Arc<AtomicBool>,
+    /// Safety cap on continuous wake, in seconds
+    pub max_session_secs: Arc<Mutex<Option<u64>>>,
+    /// The next launch starts with sleep allowed
+    pub session_only: Arc<AtomicBool>,
+    /// Sleep risk of the current screen mode and interval
+    pub sleep_risk: Arc<Mutex<SleepRisk>>,
+}
+
+impl WakeSettings {
+    /// Load the wake settings
+    ///
+    /// ## Arguments
+    /// * `state` - Loaded state
+    /// * `sleep_risk` - Risk of the saved mode, usually `compute_sleep_risk`
+    pub fn from_state(state: &AppState, sleep_risk: SleepRisk) -> Self {
+        Self {
+            screen_mode: Arc::new(Mutex::new(state.screen_mode)),
+            wake_key: Arc::new(Mutex::new(state.wake_key)),
+            wake_strategy: Arc::new(Mutex::new(state.wake_strategy)),
+            blink_on_press: Arc::new(AtomicBool::new(state.blink_on_press)),
+            log_each_iteration: Arc::new(AtomicBool::new(state.log_each_iteration)),
+            max_session_secs: Arc::new(Mutex::new(state.max_session_secs)),
+            session_only: Arc::new(AtomicBool::new(state.session_only)),
+            sleep_risk: Arc::new(Mutex::new(sleep_risk)),
+        }
+    }
+
+    /// Take every wake setting from `state`, assessing the sleep risk of
+    /// its screen mode
+    pub fn reset_from(&self, state: &AppState) -> Result<(), String> {
+        let Self {
+            screen_mode,
+            wake_key,
+            wake_strategy,
+            blink_on_press,
+            log_each_iteration,
+            max_session_secs,
+            session_only,
+            sleep_risk,
+        } = self;
+        *lock_screen_mode(screen_mode, "reset_state") = state.screen_mode;
+        store(wake_key, state.wake_key)?;
+        store(wake_strategy, state.wake_strategy)?;
+        blink_on_press.store(state.blink_on_press, Ordering::SeqCst);
+        log_each_iteration.store(state.log_each_iteration, Ordering::SeqCst);
+        store(max_session_secs, state.max_session_secs)?;
+        session_only.store(state.session_only, Ordering::SeqCst);
+        store(sleep_risk, compute_sleep_risk(state.screen_mode))
+    }
+}
+
+/// When the power source holds wake back
+#[derive(Clone)]
+pub struct PowerSettings {
+    pub only_on_ac: Arc<AtomicBool>,
+    /// Manual enables are refused while on battery
+    pub refuse_on_battery: Arc<AtomicBool>,
+    /// Charge below which wake is turned off while on battery
+    pub battery_floor_percent: Arc<Mutex<Option<u8>>>,
+    /// Wake is held back by the power source (see `power_monitor`)
+    pub paused: Arc<PauseFlag>,
+}
+
+impl PowerSettings {
+    /// Load the power settings; nothing is paused until the first
+    /// `power_monitor::refresh`
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            only_on_ac: Arc::new(AtomicBool::new(state.only_on_ac)),
+            refuse_on_battery: Arc::new(AtomicBool::new(state.refuse_on_battery)),
+            battery_floor_percent: Arc::new(Mutex::new(state.battery_floor_percent)),
+            paused: Arc::new(PauseFlag::default()),
+        }
+    }
+
+    /// Take every power setting from `state`
+    pub fn reset_from(&self, state: &AppState) -> Result<(), String> {
+        let Self {
+            only_on_ac,
+            refuse_on_battery,
+            battery_floor_percent,
+            // Follows the settings: the caller runs `power_monitor::refresh`
+            paused: _,
+        } = self;
+        only_on_ac.store(state.only_on_ac, Ordering::SeqCst);
+        refuse_on_battery.store(state.refuse_on_battery, Ordering::SeqCst);
+        store(battery_floor_percent, state.battery_floor_percent)
+    }
+}
+
+/// Watchers and the schedule, which turn wake on and off by themselves
+#[derive(Clone)]
+pub struct AutomationSettings {
+    pub master_off: Arc<AtomicBool>,
+    pub watch_window_title: Arc<Mutex<Option<String>>>,
+    pub watch_device: Arc<Mutex<Option<String>>>,
+    /// Process name the process watcher looks for
+    pub watch_process: Arc<Mutex<Option<String>>>,
+    /// Interface or `host:port` the network watcher looks for
+    pub watch_network: Arc<Mutex<Option<String>>>,
+    pub keep_awake_on_remote_session: Arc<AtomicBool>,
+    pub confirm_override_watchers: Arc<AtomicBool>,
+    /// Watched condition currently keeping wake on, for override warnings
+    pub active_watch: Arc<Mutex<Option<String>>>,
+    /// The current wake session was started by a watcher, not the user
+    pub enabled_by_watcher: Arc<AtomicBool>,
+    pub muted: Arc<AtomicBool>,
+    pub unmute_timer: Arc<Mutex<Option<String>>>,
+    /// Times of day at which wake turns on and off by itself
+    pub schedule: Arc<Mutex<Schedule>>,
+    /// Local time until which a manual change holds against the schedule
+    pub schedule_override: Arc<Mutex<Option<NaiveDateTime>>>,
+}
+
+impl AutomationSettings {
+    /// Load the automation settings, with no watcher active and not muted
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            master_off: Arc::new(AtomicBool::new(state.master_off)),
+            watch_window_title: Arc::new(Mutex::new(state.watch_window_title.clone())),
+            watch_device: Arc::new(Mutex::new(state.watch_device.clone())),
+            watch_process: Arc::new(Mutex::new(state.watch_process.clone())),
+            watch_network: Arc::new(Mutex::new(state.watch_network.clone())),
+            keep_awake_on_remote_session: Arc::new(AtomicBool::new(
+                state.keep_awake_on_remote_session,
+            )),
+            confirm_override_watchers: Arc::new(AtomicBool::new(state.confirm_override_watchers)),
+            active_watch: Arc::new(Mutex::new(None)),
+            enabled_by_watcher: Arc::new(AtomicBool::new(false)),
+            muted: Arc::new(AtomicBool::new(false)),
+            unmute_timer: Arc::new(Mutex::new(None)),
+            schedule: Arc::new(Mutex::new(state.schedule.clone())),
+            schedule_override: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Take every automation setting from `state` and forget what the
+    /// watchers and the schedule were doing
+    ///
+    /// Muting is not touched: it owns a timer, so the caller unmutes with
+    /// `set_automation_muted_impl` first.
+    pub fn reset_from(&self, state: &AppState) -> Result<(), String> {
+        let Self {
+            master_off,
+            watch_window_title,
+            watch_device,
+            watch_process,
+            watch_network,
+            keep_awake_on_remote_session,
+            confirm_override_watchers,
+            active_watch,
+            enabled_by_watcher,
+            muted: _,
+            unmute_timer: _,
+            schedule,
+            schedule_override,
+        } = self;
+        master_off.store(state.master_off, Ordering::SeqCst);
+        store(watch_window_title, state.watch_window_title.clone())?;
+        store(watch_device, state.watch_device.clone())?;
+        store(watch_process, state.watch_process.clone())?;
+        store(watch_network, state.watch_network.clone())?;
+        keep_awake_on_remote_session.store(state.keep_awake_on_remote_session, Ordering::SeqCst);
+        confirm_override_watchers.store(state.confirm_override_watchers, Ordering::SeqCst);
+        store(active_watch, None)?;
+        enabled_by_watcher.store(false, Ordering::SeqCst);
+        store(schedule, state.schedule.clone())?;
+        store(schedule_override, None)
+    }
+}
+
+/// How the tray icon looks and behaves
+#[derive(Clone)]
+pub struct TraySettings {
+    pub instance_label: Arc<Mutex<Option<String>>>,
+    /// Custom tooltip wording, None for the built-in
+    pub tooltip_template: Arc<Mutex<Option<String>>>,
+    /// Label of the tool keeping wake on, set with `set_busy_indicator`
+    pub busy_label: Arc<Mutex<Option<String>>>,
+    /// Left click on the tray icon toggles wake instead of opening the menu
+    pub left_click_toggles: Arc<AtomicBool>,
+    /// Profile last selected or saved, for the tray checkmark
+    pub active_profile: Arc<Mutex<Option<String>>>,
+    /// Announce wake changes nobody clicked (see `notify`)
+    pub notifications_enabled: Arc<AtomicBool>,
+}
+
+impl TraySettings {
+    /// Load the tray settings, with no busy label
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            instance_label: Arc::new(Mutex::new(state.instance_label.clone())),
+            tooltip_template: Arc::new(Mutex::new(state.tooltip_template.clone())),
+            busy_label: Arc::new(Mutex::new(None)),
+            left_click_toggles: Arc::new(AtomicBool::new(state.left_click_toggles.unwrap_or(true))),
+            active_profile: Arc::new(Mutex::new(state.active_profile.clone())),
+            notifications_enabled: Arc::new(AtomicBool::new(state.notifications_enabled)),
+        }
+    }
+
+    /// Take every tray setting from `state` and clear the busy label
+    pub fn reset_from(&self, state: &AppState) -> Result<(), String> {
+        let Self {
+            instance_label,
+            tooltip_template,
+            busy_label,
+            left_click_toggles,
+            active_profile,
+            notifications_enabled,
+        } = self;
+        store(instance_label, state.instance_label.clone())?;
+        store(tooltip_template, state.tooltip_template.clone())?;
+        store(busy_label, None)?;
+        left_click_toggles.store(state.left_click_toggles.unwrap_or(true), Ordering::SeqCst);
+        store(active_profile, state.active_profile.clone())?;
+        notifications_enabled.store(state.notifications_enabled, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// Ways to toggle wake from outside the tray menu
+#[derive(Clone)]
+pub struct ControlSettings {
+    /// Configured global toggle shortcut, None for the default
+    pub toggle_shortcut: Arc<Mutex<Option<String>>>,
+    /// Serve the local control endpoint (see `ipc::sync_server`)
+    pub enable_ipc: Arc<AtomicBool>,
+}
+
+impl ControlSettings {
+    /// Load the shortcut and control endpoint settings
+    pub fn from_state(state: &AppState) -> Self {
+        Self {
+            toggle_shortcut: Arc::new(Mutex::new(state.toggle_shortcut.clone())),
+            enable_ipc: Arc::new(AtomicBool::new(state.enable_ipc)),
+        }
+    }
+
+    /// Take both settings from `state`; registering the shortcut and
+    /// starting or stopping the endpoint is up to the caller
+    pub fn reset_from(&self, state: &AppState) -> Result<(), String> {
+        let Self {
+            toggle_shortcut,
+            enable_ipc,
+        } = self;
+        store(toggle_shortcut, state.toggle_shortcut.clone())?;
+        enable_ipc.store(state.enable_ipc, Ordering::SeqCst);
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+//! Optional status window
+//!
+//! A small window showing whether wake is on and the screen mode, with the
+//! same toggle and screen mode controls as the tray. Opened from the tray's
+//! "Show Status" entry.
+//!
+//! ## Design Intent
+//! The tray stays the primary UI and works the same whether or not the
+//! window exists. The page drives the existing `get_state`, `toggle_sleep`
+//! and `change_screen_mode` commands. `main` registers `emit_state` as the
+//! `commands` state listener, so every window follows changes made from
+//! the tray, the shortcut or a watcher. Closing the window only hides it;
+//! Tea keeps running in the tray.
+//!
+//! ## Side Effects
+//! Creates, shows and hides the webview window and emits
+//! `STATE_CHANGED_EVENT` to all windows.
+
+use crate::commands::StateChanged;
+use crate::core::APP_NAME;
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+/// Label of the status window, the one the default capability covers
+pub const STATUS_WINDOW_LABEL: &str = "main";
+
+/// Event emitted to every window after a state change, carrying
+/// `StateChanged` as `{ is_awake, screen_mode }`
+pub const STATE_CHANGED_EVENT: &str = "state-changed";
+
+/// Show the status window, creating it on first use
+///
+/// ## Side Effects
+/// - Creates the window and its close-to-hide handler the first time
+/// - Shows and focuses it
+///
+/// ## Returns
+/// Error string if the window could not be created or shown
+pub fn show(app: &AppHandle) -> Result<(), String> {
+    if let Some(window) = app.get_webview_window(STATUS_WINDOW_LABEL) {
+        window
+            .show()
+            .map_err(|e| format!("Failed to show status window: {}", e))?;
+        if let Err(e) = window.set_focus() {
+            log::debug!("Failed to focus status window: {}", e);
+        }
+        return Ok(());
+    }
+
+    let window = WebviewWindowBuilder::new(
+        app,
+        STATUS_WINDOW_LABEL,
+        WebviewUrl::App("index.html".into()),
+    )
+    .title(APP_NAME)
+    .inner_size(320.0, 280.0)
+    .resizable(false)
+    .build()
+    .map_err(|e| format!("Failed to create status window: {}", e))?;
+
+    // Hide instead of closing so quitting stays a tray action
+    let handle = window.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::CloseRequested { api, .. } = event {
+            api.prevent_close();
+            if let Err(e) = handle.hide() {
+                log::warn!("Failed to hide status window: {}", e);
+            }
+        }
+    });
+    log::info!("Status window opened");
+    Ok(())
+}
+
+/// Send a state change to every window
+pub fn emit_state(app: &AppHandle, change: StateChanged) {
+    if let Err(e) = app.emit(STATE_CHANGED_EVENT, change) {
+        log::warn!("Failed to emit {}: {}", STATE_CHANGED_EVENT, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::ScreenMode;
+
+    #[test]
+    fn test_state_changed_json() {
+        let status = StateChanged {
+            is_awake: true,
+            screen_mode: ScreenMode::AllowScreenOff,
+        };
+        assert_eq!(
+            serde_json::to_string(&status).unwrap(),
+            r#"{"is_awake":true,"screen_mode":"AllowScreenOff"}"#
+        );
+    }
+}
@@ -0,0 +1,188 @@
+//! Shared test fixtures
+//!
+//! Mock display controllers, inputs and wake backends used by the unit
+//! tests of every module that starts wake sessions.
+//!
+//! ## Design Intent
+//! Wake sessions are exercised against these instead of the real OS (see
+//! `WakeBackend`). Keeping them in one place means a change to
+//! `DisplayControl`, `WakeInput` or `ServiceManager::new` is made once
+//! rather than in each test module.
+
+use crate::core::{ScreenMode, WakeKey, WakeStrategy};
+use crate::error::Result;
+use crate::platform::DisplayControl;
+use crate::service_manager::ServiceManager;
+use crate::wake_service::{PauseFlag, WakeBackend, WakeInput};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Display controller that records calls instead of touching the OS
+pub struct RecordingDisplay {
+    pub calls: Arc<Mutex<Vec<String>>>,
+}
+
+impl RecordingDisplay {
+    /// A recorder plus the handle its calls are read back from
+    pub fn new() -> (Self, Arc<Mutex<Vec<String>>>) {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        (
+            Self {
+                calls: calls.clone(),
+            },
+            calls,
+        )
+    }
+}
+
+impl DisplayControl for RecordingDisplay {
+    fn set_display_mode(&self, screen_mode: ScreenMode) -> Result<()> {
+        self.calls
+            .lock()
+            .unwrap()
+            .push(format!("set_display_mode({:?})", screen_mode));
+        Ok(())
+    }
+
+    fn restore_normal_mode(&self) {
+        self.calls
+            .lock()
+            .unwrap()
+            .push("restore_normal_mode".to_string());
+    }
+}
+
+/// Counts services between `set_display_mode` and `restore_normal_mode`
+#[derive(Default)]
+pub struct LiveCount {
+    pub current: usize,
+    pub max: usize,
+    pub starts: usize,
+}
+
+/// Display controller feeding a `LiveCount`
+pub struct CountingDisplay {
+    pub count: Arc<Mutex<LiveCount>>,
+}
+
+impl DisplayControl for CountingDisplay {
+    fn set_display_mode(&self, _screen_mode: ScreenMode) -> Result<()> {
+        let mut count = self.count.lock().unwrap();
+        count.current += 1;
+        count.starts += 1;
+        count.max = count.max.max(count.current);
+        Ok(())
+    }
+
+    fn restore_normal_mode(&self) {
+        self.count.lock().unwrap().current -= 1;
+    }
+}
+
+/// Input that accepts every press and jiggle and does nothing
+pub struct SilentInput;
+
+impl WakeInput for SilentInput {
+    fn press(&mut self, _key: WakeKey) -> std::result::Result<(), String> {
+        Ok(())
+    }
+
+    fn jiggle(&mut self) -> std::result::Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Input that counts presses and jiggles without sending any
+pub struct CountingInput {
+    pub presses: Arc<AtomicUsize>,
+}
+
+impl WakeInput for CountingInput {
+    fn press(&mut self, _key: WakeKey) -> std::result::Result<(), String> {
+        self.presses.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn jiggle(&mut self) -> std::result::Result<(), String> {
+        self.presses.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+}
+
+/// What wake sessions started through `recording_backend` did
+pub struct BackendLog {
+    pub display: Arc<Mutex<Vec<String>>>,
+    pub presses: Arc<AtomicUsize>,
+}
+
+impl BackendLog {
+    /// Number of display modes applied, by starts and reasserts alike
+    pub fn applied(&self) -> usize {
+        self.display
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|call| call.starts_with("set_display_mode"))
+            .count()
+    }
+}
+
+/// Backend recording display calls and counting input
+pub fn recording_backend() -> (WakeBackend, BackendLog) {
+    let display = Arc::new(Mutex::new(Vec::new()));
+    let presses = Arc::new(AtomicUsize::new(0));
+    let calls = display.clone();
+    let counter = presses.clone();
+    let backend = WakeBackend {
+        display: Arc::new(move |_| {
+            Box::new(RecordingDisplay {
+                calls: calls.clone(),
+            })
+        }),
+        input: Arc::new(move |_| {
+            Ok(Some(Box::new(CountingInput {
+                presses: counter.clone(),
+            })))
+        }),
+        native_wake: false,
+        power_action: Arc::new(|_| Ok(())),
+    };
+    (backend, BackendLog { display, presses })
+}
+
+/// Backend counting live services, with silent input
+pub fn counting_backend() -> (WakeBackend, Arc<Mutex<LiveCount>>) {
+    let count = Arc::new(Mutex::new(LiveCount::default()));
+    let display_count = count.clone();
+    let backend = WakeBackend {
+        display: Arc::new(move |_| {
+            Box::new(CountingDisplay {
+                count: display_count.clone(),
+            })
+        }),
+        input: Arc::new(|_| Ok(Some(Box::new(SilentInput)))),
+        native_wake: false,
+        power_action: Arc::new(|_| Ok(())),
+    };
+    (backend, count)
+}
+
+/// Service manager over `backend` wanting `desired_awake` in `screen_mode`
+///
+/// Every other shared setting starts at its default and is not shared with
+/// the test; tests needing those build an `AppStateManager` instead.
+pub fn service_manager(
+    backend: WakeBackend,
+    desired_awake: Arc<AtomicBool>,
+    screen_mode: ScreenMode,
+) -> ServiceManager {
+    ServiceManager::new(
+        backend,
+        desired_awake,
+        Arc::new(Mutex::new(screen_mode)),
+        Arc::new(PauseFlag::default()),
+        Arc::new(AtomicBool::new(false)),
+        Arc::new(Mutex::new(WakeKey::default())),
+        Arc::new(Mutex::new(WakeStrategy::default())),
+    )
+}
@@ -0,0 +1,551 @@
+//! Tray refresh helpers
+//!
+//! Re-renders the tray icon, tooltip and toggle text from shared state.
+//!
+//! ## Design Intent
+//! Menu handlers update the tray right after the user acts, but background
+//! tasks (watchers, timers) and frontend commands also change state. They
+//! all call `refresh` so the tray always reflects `AppStateManager`,
+//! whichever path caused the change.
+//!
+//! ## Side Effects
+//! Updates tray icon, tooltip and menu item text. A failed startup check
+//! stays visible as a tooltip warning, and while awake as a badge. Missing tray or menu (e.g. before
+//! setup completes) is silently skipped.
+
+use crate::commands::{self, AppStateManager};
+use crate::core::profile::{profile_menu_id, profile_menu_text, DEFAULT_PROFILE};
+use crate::core::{
+    menu_layout, MenuOptions, ScreenMode, SleepRisk, StartupCheck, TooltipText, TrayAppearance,
+};
+use crate::error::AppError;
+use crate::icon;
+use crate::notify;
+use crate::persistence::AppState;
+use crate::wake_service::CycleOutcome;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{
+    image::Image,
+    menu::{Menu, MenuItem, MenuItemBuilder, Submenu},
+    tray::{MouseButton, MouseButtonState, TrayIcon, TrayIconEvent},
+    AppHandle, Manager, Wry,
+};
+
+/// How long the accent icon is shown for a heartbeat blink
+const BLINK_DURATION: Duration = Duration::from_millis(300);
+
+/// How often the countdowns and time awake in the tooltip are updated
+const COUNTDOWN_REFRESH: Duration = Duration::from_secs(60);
+
+/// Menu items whose text depends on state, managed by Tauri after setup
+pub struct TrayMenu {
+    pub toggle_sleep: MenuItem<Wry>,
+    pub master: MenuItem<Wry>,
+    /// Keep Screen On item, where the platform offers it
+    pub screen_on: Option<MenuItem<Wry>>,
+    /// Allow Screen Off item, where the platform offers it
+    pub screen_off: Option<MenuItem<Wry>>,
+    /// Away Mode item, where the platform offers it
+    pub away_mode: Option<MenuItem<Wry>>,
+    /// Disabled entry naming the watched process
+    pub watch_process_status: MenuItem<Wry>,
+    /// Clears the watched process, enabled while one is set
+    pub watch_process_clear: MenuItem<Wry>,
+    /// Checkbox for wake change notifications
+    pub notifications: MenuItem<Wry>,
+    /// Checkbox for "start the next launch with sleep allowed"
+    pub session_only: MenuItem<Wry>,
+    /// Checkbox for the control endpoint `tea --enable` talks to
+    pub command_line_control: MenuItem<Wry>,
+    /// Checkbox for autostart, updated by `show_autostart`
+    pub start_at_login: MenuItem<Wry>,
+    /// Profile submenu, grown when a profile is saved
+    pub profile_menu: Submenu<Wry>,
+    /// One entry per profile, checked for the active one
+    pub profile_items: Mutex<Vec<(String, MenuItem<Wry>)>>,
+    /// The whole tray menu, for showing or hiding advanced entries
+    pub menu: Menu<Wry>,
+    /// Advanced diagnostics submenu, kept while hidden
+    pub diagnostics: Submenu<Wry>,
+    /// Options the menu was laid out with
+    pub options: MenuOptions,
+}
+
+/// Text for the sleep toggle menu item
+pub fn toggle_sleep_text(is_awake: bool) -> &'static str {
+    if is_awake {
+        "Enable Sleep"
+    } else {
+        "Disable Sleep"
+    }
+}
+
+/// Text for the master switch menu item
+pub fn master_text(master_off: bool) -> &'static str {
+    if master_off {
+        "Resume Tea"
+    } else {
+        "Pause Tea"
+    }
+}
+
+/// Text for a screen mode menu item, checked when it is the active mode
+pub fn screen_mode_text(item: ScreenMode, active: ScreenMode) -> &'static str {
+    match (item, item == active) {
+        (ScreenMode::KeepScreenOn, true) => "\u{2713} Keep Screen On",
+        (ScreenMode::KeepScreenOn, false) => "Keep Screen On",
+        (ScreenMode::AllowScreenOff, true) => "\u{2713} Allow Screen Off",
+        (ScreenMode::AllowScreenOff, false) => "Allow Screen Off",
+        (ScreenMode::AwayMode, true) => "\u{2713} Away Mode",
+        (ScreenMode::AwayMode, false) => "Away Mode",
+    }
+}
+
+/// Text for the notifications checkbox menu item
+pub fn notifications_text(enabled: bool) -> &'static str {
+    if enabled {
+        "\u{2713} Notifications"
+    } else {
+        "Notifications"
+    }
+}
+
+/// Text for the session only checkbox menu item
+pub fn session_only_text(enabled: bool) -> &'static str {
+    if enabled {
+        "\u{2713} This Session Only"
+    } else {
+        "This Session Only"
+    }
+}
+
+/// Text for the command line control checkbox menu item
+pub fn command_line_control_text(enabled: bool) -> &'static str {
+    if enabled {
+        "\u{2713} Command Line Control"
+    } else {
+        "Command Line Control"
+    }
+}
+
+/// Text for the Start at Login checkbox menu item
+pub fn start_at_login_text(enabled: bool) -> &'static str {
+    if enabled {
+        "\u{2713} Start at Login"
+    } else {
+        "Start at Login"
+    }
+}
+
+/// Text for the watched process status entry
+pub fn watch_process_text(name: Option<&str>) -> String {
+    match name {
+        Some(name) => format!("Watching: {}", name),
+        None => "Not watching a process".to_string(),
+    }
+}
+
+/// Add a newly saved profile to the Profile submenu
+///
+/// ## Failure Modes
+/// - Missing menu (before setup) is skipped, menu errors are logged
+pub fn add_profile_item(app: &AppHandle, name: &str) {
+    let Some(menu) = app.try_state::<TrayMenu>() else {
+        return;
+    };
+    let Ok(mut items) = menu.profile_items.lock() else {
+        log::error!("Mutex poisoned during profile menu update");
+        return;
+    };
+    if items.iter().any(|(existing, _)| existing == name) {
+        return;
+    }
+
+    let item = MenuItemBuilder::with_id(profile_menu_id(name), profile_menu_text(name, false))
+        .build(app)
+        .and_then(|item| menu.profile_menu.append(&item).map(|()| item));
+    match item {
+        Ok(item) => items.push((name.to_string(), item)),
+        Err(e) => log::error!("Failed to add profile \"{}\" to the menu: {}", name, e),
+    }
+}
+
+/// Check or uncheck Start at Login
+///
+/// ## Design Intent
+/// Autostart lives outside `AppStateManager`, so `refresh` cannot read it
+/// cheaply; whoever changes it reports the new setting here instead.
+pub fn show_autostart(app: &AppHandle, enabled: bool) {
+    if let Some(menu) = app.try_state::<TrayMenu>() {
+        let _ = menu.start_at_login.set_text(start_at_login_text(enabled));
+    }
+}
+
+/// Make a left click on the tray icon toggle wake or open the menu
+///
+/// ## Platform Behavior
+/// - Windows, macOS: Applied at once
+/// - Linux: The tray reports no clicks, so left click always opens the
+///   menu and this has no effect
+pub fn show_left_click(app: &AppHandle, toggles: bool) {
+    if let Some(tray) = app.try_state::<TrayIcon>() {
+        if let Err(e) = tray.set_show_menu_on_left_click(!toggles) {
+            log::warn!("Failed to update tray left click: {}", e);
+        }
+    }
+}
+
+/// Whether a tray icon event is the left click that toggles wake
+///
+/// ## Platform Behavior
+/// Windows and macOS report a press and a release for each click; only
+/// the release counts, so one click toggles once. A Windows double click
+/// arrives as two clicks and toggles twice, like two single clicks.
+pub fn is_toggle_click(event: &TrayIconEvent) -> bool {
+    matches!(
+        event,
+        TrayIconEvent::Click {
+            button: MouseButton::Left,
+            button_state: MouseButtonState::Up,
+            ..
+        }
+    )
+}
+
+/// Show or hide the advanced menu entries without rebuilding the menu
+///
+/// ## Design Intent
+/// The advanced submenu is built at startup either way; this inserts it
+/// where `menu_layout` places it, or removes it again.
+///
+/// ## Failure Modes
+/// - Missing menu (before setup) is skipped, menu errors are logged
+pub fn set_advanced_menu(app: &AppHandle, show: bool) {
+    let Some(tray_menu) = app.try_state::<TrayMenu>() else {
+        return;
+    };
+    let shown = tray_menu.menu.get(tray_menu.diagnostics.id()).is_some();
+    if shown == show {
+        return;
+    }
+
+    let result = if show {
+        let layout = menu_layout(MenuOptions {
+            show_advanced: true,
+            ..tray_menu.options
+        });
+        match layout.iter().position(|entry| entry.is_advanced()) {
+            Some(position) => tray_menu.menu.insert(&tray_menu.diagnostics, position),
+            None => Ok(()),
+        }
+    } else {
+        tray_menu.menu.remove(&tray_menu.diagnostics)
+    };
+    if let Err(e) = result {
+        log::error!("Failed to update advanced menu entries: {}", e);
+    }
+}
+
+/// Re-render tray icon, tooltip and toggle text from shared state
+///
+/// ## Failure Modes
+/// - Poisoned mutex or icon failure: Logged, remaining updates still applied
+pub fn refresh(app: &AppHandle) {
+    let state = app.state::<AppStateManager>();
+    let awake = state.is_awake.load(Ordering::SeqCst);
+    let master_off = state.automation.master_off.load(Ordering::SeqCst);
+    let mode = *commands::lock_screen_mode(&state.wake.screen_mode, "tray refresh");
+    let label = state
+        .tray
+        .instance_label
+        .lock()
+        .ok()
+        .and_then(|label| label.clone());
+
+    if let Some(menu) = app.try_state::<TrayMenu>() {
+        let _ = menu.toggle_sleep.set_text(toggle_sleep_text(awake));
+        let _ = menu.toggle_sleep.set_enabled(!master_off);
+        let _ = menu.master.set_text(master_text(master_off));
+        if let Some(item) = &menu.screen_on {
+            let _ = item.set_text(screen_mode_text(ScreenMode::KeepScreenOn, mode));
+        }
+        if let Some(item) = &menu.screen_off {
+            let _ = item.set_text(screen_mode_text(ScreenMode::AllowScreenOff, mode));
+        }
+        if let Some(item) = &menu.away_mode {
+            let _ = item.set_text(screen_mode_text(ScreenMode::AwayMode, mode));
+        }
+        let watched = state
+            .automation
+            .watch_process
+            .lock()
+            .ok()
+            .and_then(|name| name.clone());
+        let _ = menu
+            .watch_process_status
+            .set_text(watch_process_text(watched.as_deref()));
+        let _ = menu.watch_process_clear.set_enabled(watched.is_some());
+        let _ = menu.notifications.set_text(notifications_text(
+            state.tray.notifications_enabled.load(Ordering::SeqCst),
+        ));
+        let _ = menu.session_only.set_text(session_only_text(
+            state.wake.session_only.load(Ordering::SeqCst),
+        ));
+        let _ = menu
+            .command_line_control
+            .set_text(command_line_control_text(
+                state.control.enable_ipc.load(Ordering::SeqCst),
+            ));
+        let active = state
+            .tray
+            .active_profile
+            .lock()
+            .ok()
+            .and_then(|active| active.clone());
+        if let Ok(items) = menu.profile_items.lock() {
+            for (name, item) in items.iter() {
+                let _ = item.set_text(profile_menu_text(name, active.as_deref() == Some(name)));
+            }
+        }
+    }
+
+    let Some(tray) = app.try_state::<TrayIcon>() else {
+        return;
+    };
+
+    let template = state
+        .tray
+        .tooltip_template
+        .lock()
+        .ok()
+        .and_then(|template| template.clone());
+    let mut tooltip = if master_off {
+        TooltipText::master_off(label.as_deref())
+    } else {
+        // Only a template shows the interval; skip the config read otherwise
+        let interval = match template {
+            Some(_) => commands::configured_wake_interval(mode),
+            None => Duration::ZERO,
+        };
+        TooltipText::for_state_templated(
+            template.as_deref(),
+            awake,
+            mode,
+            interval,
+            label.as_deref(),
+        )
+    };
+    match commands::get_remaining_time_impl(&state) {
+        Ok(Some(left)) if awake => tooltip = tooltip.with_time_left(left),
+        Ok(_) => {}
+        Err(e) => log::error!("Failed to read auto-disable countdown: {}", e),
+    }
+    match commands::get_pause_remaining_impl(&state) {
+        Ok(Some(left)) if !awake && !master_off => tooltip = tooltip.with_resume_in(left),
+        Ok(_) => {}
+        Err(e) => log::error!("Failed to read pause countdown: {}", e),
+    }
+    match commands::get_awake_elapsed_impl(&state) {
+        Ok(Some(elapsed)) if awake && !master_off => tooltip = tooltip.with_elapsed(elapsed),
+        Ok(_) => {}
+        Err(e) => log::error!("Failed to read awake time: {}", e),
+    }
+    let mut degraded = false;
+    if let Some(check) = app.try_state::<StartupCheck>() {
+        if !check.ok {
+            tooltip = tooltip.with_warning(&check.summary());
+            degraded = true;
+        }
+    }
+    if awake && !master_off {
+        match commands::get_awake_reasons_impl(&state) {
+            Ok(reasons) => {
+                if let Some(top) = reasons.first() {
+                    tooltip = tooltip.with_reason(&top.describe());
+                }
+            }
+            Err(e) => log::error!("Failed to resolve awake reasons: {}", e),
+        }
+    }
+    if awake
+        && state
+            .wake
+            .sleep_risk
+            .lock()
+            .is_ok_and(|risk| *risk == SleepRisk::Unsafe)
+    {
+        tooltip = tooltip.with_warning("Wake interval exceeds OS sleep timeout");
+        degraded = true;
+    }
+    if awake && state.service.display_unavailable() {
+        tooltip = tooltip.with_warning("Display control unavailable");
+        degraded = true;
+    }
+    if awake && state.service.input_unavailable() {
+        tooltip = tooltip.with_warning("Input simulation unavailable");
+        degraded = true;
+    }
+    if !awake && state.service.start_error().is_some() {
+        tooltip = tooltip.with_warning("Wake could not start");
+    }
+    let timer = awake && !state.scheduled_actions.list().is_empty();
+
+    let busy_label = state
+        .tray
+        .busy_label
+        .lock()
+        .ok()
+        .and_then(|busy| busy.clone());
+    let appearance = TrayAppearance::for_wake(awake, tooltip)
+        .with_badge(timer, degraded && awake)
+        .with_busy(busy_label.as_deref());
+
+    let _ = tray.set_tooltip(Some(appearance.tooltip.as_str()));
+    let _ = tray.set_title(appearance.title.as_deref());
+
+    let scale = icon_scale(app);
+    let icon_data = match appearance.badge {
+        Some(badge) => icon::get_composed_icon_rgba(appearance.icon, badge, scale),
+        None => icon::get_icon_rgba_for(appearance.icon, scale),
+    };
+    match icon_data {
+        Ok((icon_data, width, height)) => {
+            let _ = tray.set_icon(Some(Image::new(icon_data.as_slice(), width, height)));
+        }
+        Err(e) => log::error!("Failed to refresh tray icon: {}", e),
+    }
+}
+
+/// Display scale factor used to pick the tray icon size
+///
+/// ## Design Intent
+/// The tray has no scale factor of its own, so the primary monitor's is
+/// used. Without one (e.g. headless) the standard 1.0 keeps the 32x32 icon.
+pub fn icon_scale(app: &AppHandle) -> f64 {
+    app.primary_monitor()
+        .ok()
+        .flatten()
+        .map_or(1.0, |monitor| monitor.scale_factor())
+}
+
+/// Keep the countdowns and the time awake in the tooltip current
+///
+/// ## Design Intent
+/// One task for the application lifetime rather than one per session, so
+/// there is nothing to cancel on disable or quit: it only refreshes while
+/// there is something to count, and ends with the runtime.
+///
+/// ## Side Effects
+/// Spawns a Tokio task that refreshes the tray every `COUNTDOWN_REFRESH`
+/// while wake is on or a resume is pending.
+pub fn spawn_countdown_refresh(app: AppHandle) {
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(COUNTDOWN_REFRESH).await;
+            let state = app.state::<AppStateManager>();
+            let pending = matches!(commands::get_awake_elapsed_impl(&state), Ok(Some(_)))
+                || matches!(commands::get_pause_remaining_impl(&state), Ok(Some(_)));
+            if pending {
+                refresh(&app);
+            }
+        }
+    });
+}
+
+/// Turn wake back off after its service failed to start
+///
+/// ## Design Intent
+/// Registered as the start failure listener. Enabling returns before the
+/// service starts, so an input simulation failure (e.g. no permission on
+/// Wayland) arrives here afterwards; reverting keeps the tray from
+/// claiming the system is kept awake. The tooltip shows the failure until
+/// a start succeeds.
+pub fn on_start_failed(app: &AppHandle, error: &AppError) {
+    let state = app.state::<AppStateManager>();
+    if let Err(e) = commands::set_awake_impl(&state, false) {
+        log::error!("Failed to turn wake off after a failed start: {}", e);
+    }
+    notify::notify_error(
+        app,
+        &format!(
+            "Wake could not start: {} {}",
+            error.message(),
+            error.recovery_hint()
+        ),
+    );
+    refresh(app);
+}
+
+/// Bring the menu in line with settings just reset to defaults
+///
+/// ## Design Intent
+/// `refresh` covers the state-dependent text; this also handles what it
+/// does not re-read: the advanced entries, the left click and the Profile
+/// submenu, where only the "Default" profile remains.
+pub fn on_reset(app: &AppHandle) {
+    let defaults = AppState::default();
+    set_advanced_menu(app, defaults.show_advanced_menu);
+    show_left_click(app, defaults.left_click_toggles.unwrap_or(true));
+
+    if let Some(menu) = app.try_state::<TrayMenu>() {
+        match menu.profile_items.lock() {
+            Ok(mut items) => items.retain(|(name, item)| {
+                if name == DEFAULT_PROFILE {
+                    return true;
+                }
+                if let Err(e) = menu.profile_menu.remove(item) {
+                    log::error!("Failed to remove profile \"{}\" from the menu: {}", name, e);
+                }
+                false
+            }),
+            Err(_) => log::error!("Mutex poisoned during profile menu update"),
+        }
+    }
+    add_profile_item(app, DEFAULT_PROFILE);
+    refresh(app);
+}
+
+/// Blink the tray icon after a wake cycle, if enabled
+///
+/// ## Design Intent
+/// Registered as the wake cycle listener. Shows the accent icon briefly,
+/// then re-renders from state, giving a heartbeat for each F15 press.
+///
+/// ## Side Effects
+/// Spawns a short Tokio task to restore the icon.
+pub fn on_wake_cycle(app: &AppHandle, outcome: CycleOutcome) {
+    let state = app.state::<AppStateManager>();
+    if !outcome.should_blink(state.wake.blink_on_press.load(Ordering::SeqCst)) {
+        return;
+    }
+    // The busy overlay takes precedence over the heartbeat
+    if state
+        .tray
+        .busy_label
+        .lock()
+        .map_or(true, |busy| busy.is_some())
+    {
+        return;
+    }
+    let Some(tray) = app.try_state::<TrayIcon>() else {
+        return;
+    };
+
+    match icon::get_accent_icon_rgba(icon_scale(app)) {
+        Ok((icon_data, width, height)) => {
+            let _ = tray.set_icon(Some(Image::new(icon_data.as_slice(), width, height)));
+        }
+        Err(e) => {
+            log::error!("Failed to load accent icon: {}", e);
+            return;
+        }
+    }
+
+    let app = app.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(BLINK_DURATION).await;
+        refresh(&app);
+    });
+}
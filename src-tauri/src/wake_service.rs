@@ -17,20 +17,287 @@
 //! ## Side Effects
 //! - On Windows with AllowScreenOff mode: Uses ES_CONTINUOUS API only (no F15)
 //! - On Windows with KeepScreenOn mode: Uses ES_DISPLAY_REQUIRED + F15 for redundancy
-//! - On non-Windows platforms: Simulates F15 key press every 60 seconds
+//! - On macOS: Same split, with IOKit system or display power assertions
+//! - On Linux with systemd: Same split, with logind inhibitor locks
+//! - On other platforms: Simulates F15 key press every wake interval
+//!   (60 seconds unless configured, see `core::wake_interval`)
 //! - May set platform display power flags
 //!
 //! ## Failure Modes
 //! - Input simulation initialization fails: Returns InputSimulation error (non-Windows or Windows KeepScreenOn)
 //! - Key press fails: Logs error but continues running (transient failure)
+//! - Platform refuses the display request: Logs a warning, sets the shared
+//!   `display_unavailable` flag and keeps waking by input simulation
+//!
+//! ## Cycle Listener
+//! A process-wide listener registered at setup is told the outcome of every
+//! wake cycle (used for the tray heartbeat blink). The service is started
+//! from several business-logic paths that have no UI handle, so the
+//! listener is registered once rather than threaded through each of them.
+//!
+//! ## Heartbeat
+//! With `log_each_iteration` on, every cycle also emits a structured
+//! `Heartbeat` at info level, for diagnosing reports of the system
+//! sleeping hours into a session. Off by default because it is chatty.
+//!
+//! ## Wake Key
+//! F15 unless the user picked another `WakeKey`. The choice is shared and
+//! read every cycle, so a change applies from the next press.
+//!
+//! ## Pausing
+//! A separate `PauseFlag` suspends input simulation and restores normal
+//! power behavior without ending the session, so automatic pauses (e.g. on
+//! battery) never touch the user's enabled preference. The loop waits on it
+//! like on the running flag, so a pause releases the display request, and
+//! a resume takes it again, at once rather than at the next cycle.
+//!
+//! ## Stopping
+//! The loop waits between cycles on a `RunningFlag`, which wakes it the
+//! moment it is stopped, so disabling and quitting restore normal power
+//! behavior right away rather than after the rest of an interval.
+//!
+//! ## Boost
+//! A shared `watch` channel can shorten the interval for a while (see
+//! `ServiceManager::boost`). The loop waits on it too: a change re-times
+//! the current wait from the last cycle, so a boost takes effect at once
+//! and its end lets the next cycle fall back to the normal interval, all
+//! without restarting the task.
+//!
+//! ## Dry Run
+//! With `--dry-run` the backend is `WakeBackend::dry_run`: the same loop
+//! runs against a display controller and input that only report what they
+//! would do (`DryRunCall`), so timing and mode logic can be checked in a
+//! user's log or in CI without pressing keys or changing power settings.
+//!
+//! ## Reasserting
+//! The same wait also wakes on a shared `reassert` notification, e.g. on
+//! unlocking the session (see `session_watch`). The display mode is applied
+//! again and the loop goes back to waiting out the rest of the interval, so
+//! a reassertion never adds a key press.
 
-use crate::core::ScreenMode;
-use crate::error::{AppError, Result};
-use crate::platform::DisplayControl;
-use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use crate::core::wake_interval::{jittered_interval, JitterRng};
+use crate::core::{ConditionEndAction, ScreenMode, WakeKey, WakeStrategy};
+use crate::error::Result;
+use crate::platform::{self, DisplayControl};
+use enigo::{Coordinate, Direction, Enigo, Keyboard, Mouse, Settings};
 use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::{watch, Notify};
+use tokio::task::AbortHandle;
+
+/// What a single wake cycle did
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CycleOutcome {
+    /// An F15 press was sent successfully
+    Pressed,
+    /// An F15 press was attempted and failed
+    PressFailed,
+    /// No press needed; the platform API keeps the system awake
+    ApiOnly,
+    /// Wake is paused, nothing was done
+    Paused,
+}
+
+impl CycleOutcome {
+    /// Whether the tray should blink for this cycle
+    ///
+    /// Only a press that actually happened counts as a heartbeat.
+    pub fn should_blink(self, blink_on_press: bool) -> bool {
+        blink_on_press && self == CycleOutcome::Pressed
+    }
+}
+
+/// Structured record of one wake cycle
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Heartbeat {
+    /// Cycle number within this wake session, starting at 1
+    pub iteration: u64,
+    pub outcome: CycleOutcome,
+    pub at: SystemTime,
+    /// When the next cycle is due
+    pub next_press_at: SystemTime,
+}
+
+impl std::fmt::Display for Heartbeat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let unix_secs = |time: SystemTime| {
+            time.duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or_default()
+        };
+        write!(
+            f,
+            "Wake heartbeat: iteration={} outcome={:?} time={} next_press_at={}",
+            self.iteration,
+            self.outcome,
+            unix_secs(self.at),
+            unix_secs(self.next_press_at)
+        )
+    }
+}
+
+/// Receiver of per-iteration heartbeats (the log, or a recorder in tests)
+pub type HeartbeatSink = Arc<dyn Fn(&Heartbeat) + Send + Sync>;
+
+/// Write a heartbeat to the application log at info level
+pub fn log_heartbeat(heartbeat: &Heartbeat) {
+    log::info!("{}", heartbeat);
+}
+
+/// Source of the periodic wake input
+///
+/// ## Design Intent
+/// Abstracts enigo so the wake loop can run against a mock keyboard in
+/// tests.
+pub trait WakeInput: Send {
+    /// Press and release `key` for one wake cycle
+    fn press(&mut self, key: WakeKey) -> std::result::Result<(), String>;
+
+    /// Move the pointer by one pixel and back for one wake cycle
+    fn jiggle(&mut self) -> std::result::Result<(), String>;
+}
+
+impl WakeInput for Enigo {
+    fn press(&mut self, key: WakeKey) -> std::result::Result<(), String> {
+        for _ in 0..key.clicks() {
+            self.key(key.to_enigo_key(), Direction::Click)
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+
+    fn jiggle(&mut self) -> std::result::Result<(), String> {
+        let origin = self.location().map_err(|e| e.to_string())?;
+        self.move_mouse(1, 0, Coordinate::Rel)
+            .map_err(|e| e.to_string())?;
+
+        // A failed move back, or one clamped at the screen edge, would
+        // leave the cursor off by a pixel; put it back where it was
+        let moved_back = self.move_mouse(-1, 0, Coordinate::Rel);
+        if moved_back.is_err() || self.location().ok() != Some(origin) {
+            log::debug!("Pointer not back at {:?}, restoring it", origin);
+            self.move_mouse(origin.0, origin.1, Coordinate::Abs)
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+/// Running flag of a wake loop that wakes the loop when cleared
+///
+/// ## Design Intent
+/// A plain flag can only be polled; pairing it with a `Notify` lets the
+/// loop wait out its interval and still return as soon as it is stopped.
+pub struct RunningFlag {
+    running: AtomicBool,
+    stopped: Notify,
+}
+
+impl RunningFlag {
+    /// A flag for a loop that is about to run
+    pub fn new() -> Self {
+        Self {
+            running: AtomicBool::new(true),
+            stopped: Notify::new(),
+        }
+    }
+
+    /// Whether the loop should keep going
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// Stop the loop, waking it if it is waiting for its next cycle
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+        self.stopped.notify_waiters();
+    }
+}
+
+impl Default for RunningFlag {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Pause flag of a wake loop that wakes the loop when it changes
+///
+/// ## Design Intent
+/// Like `RunningFlag`: pausing must release the display request as soon as
+/// e.g. the charger is unplugged, not after the rest of an interval that
+/// can be minutes long.
+#[derive(Default)]
+pub struct PauseFlag {
+    paused: AtomicBool,
+    changed: Notify,
+}
+
+impl PauseFlag {
+    /// Whether wake is suspended
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::SeqCst)
+    }
+
+    /// Pause or resume, waking the loop if that changes anything
+    ///
+    /// ## Returns
+    /// Whether wake was paused before
+    pub fn set(&self, paused: bool) -> bool {
+        let was_paused = self.paused.swap(paused, Ordering::SeqCst);
+        if was_paused != paused {
+            // A stored permit covers a loop that is mid-cycle right now
+            self.changed.notify_one();
+        }
+        was_paused
+    }
+}
+
+/// Callback receiving the outcome of every wake cycle
+pub type CycleListener = Arc<dyn Fn(CycleOutcome) + Send + Sync>;
+
+/// Listener registered by `set_cycle_listener`
+static CYCLE_LISTENER: OnceLock<CycleListener> = OnceLock::new();
+
+/// Register the process-wide wake cycle listener
+///
+/// ## Failure Modes
+/// - Already registered: Logs a warning, the first listener is kept
+pub fn set_cycle_listener(listener: CycleListener) {
+    if CYCLE_LISTENER.set(listener).is_err() {
+        log::warn!("Wake cycle listener already registered, ignoring");
+    }
+}
+
+/// Report a cycle outcome to the listener, if any
+fn notify_cycle(outcome: CycleOutcome) {
+    if let Some(listener) = CYCLE_LISTENER.get() {
+        listener(outcome);
+    }
+}
+
+/// Callback told when display control becomes unavailable or recovers
+pub type DisplayStatusListener = Arc<dyn Fn(bool) + Send + Sync>;
+
+/// Listener registered by `set_display_status_listener`
+static DISPLAY_STATUS_LISTENER: OnceLock<DisplayStatusListener> = OnceLock::new();
+
+/// Register the process-wide display status listener
+///
+/// ## Failure Modes
+/// - Already registered: Logs a warning, the first listener is kept
+pub fn set_display_status_listener(listener: DisplayStatusListener) {
+    if DISPLAY_STATUS_LISTENER.set(listener).is_err() {
+        log::warn!("Display status listener already registered, ignoring");
+    }
+}
+
+/// Report a display status change to the listener, if any
+fn notify_display_status(unavailable: bool) {
+    if let Some(listener) = DISPLAY_STATUS_LISTENER.get() {
+        listener(unavailable);
+    }
+}
 
 /// Service that keeps system awake via periodic input simulation
 ///
@@ -39,9 +306,26 @@ use std::time::Duration;
 /// for maximum compatibility and adds platform-specific display control.
 pub struct WakeService {
     /// Flag controlling whether wake loop continues
-    running: Arc<AtomicBool>,
+    running: Arc<RunningFlag>,
+    /// Flag temporarily suspending wake without stopping the loop
+    paused: Arc<PauseFlag>,
     /// Platform-specific display controller
     display_controller: Box<dyn DisplayControl + Send>,
+    /// Flag enabling a heartbeat on every cycle
+    log_each_iteration: Arc<AtomicBool>,
+    heartbeat_sink: HeartbeatSink,
+    /// Key to press, read every cycle
+    wake_key: Arc<Mutex<WakeKey>>,
+    /// Key press or mouse jiggle, read every cycle
+    wake_strategy: Arc<Mutex<WakeStrategy>>,
+    /// Set while the platform refuses the display request
+    display_unavailable: Arc<AtomicBool>,
+    /// Source of interval jitter, None to wait exactly the interval
+    jitter: Option<JitterRng>,
+    /// Notified to apply the display mode again mid-interval
+    reassert: Arc<Notify>,
+    /// Shorter interval in force for now, None outside a boost
+    boost: Option<watch::Receiver<Option<Duration>>>,
 }
 
 impl WakeService {
@@ -49,14 +333,130 @@ impl WakeService {
     ///
     /// ## Arguments
     /// * `running` - Shared flag to control service lifecycle
+    /// * `paused` - Shared flag to suspend wake while the loop keeps running
     /// * `display_controller` - Platform-specific display control implementation
     pub fn new(
-        running: Arc<AtomicBool>,
+        running: Arc<RunningFlag>,
+        paused: Arc<PauseFlag>,
         display_controller: Box<dyn DisplayControl + Send>,
     ) -> Self {
         Self {
             running,
+            paused,
             display_controller,
+            log_each_iteration: Arc::new(AtomicBool::new(false)),
+            heartbeat_sink: Arc::new(log_heartbeat),
+            wake_key: Arc::new(Mutex::new(WakeKey::default())),
+            wake_strategy: Arc::new(Mutex::new(WakeStrategy::default())),
+            display_unavailable: Arc::new(AtomicBool::new(false)),
+            jitter: None,
+            reassert: Arc::new(Notify::new()),
+            boost: None,
+        }
+    }
+
+    /// Send a heartbeat to `sink` on every cycle while `enabled` is set
+    ///
+    /// ## Arguments
+    /// * `enabled` - Shared flag, read each cycle so it can change at runtime
+    /// * `sink` - Receiver of heartbeats, normally `log_heartbeat`
+    pub fn with_heartbeat(mut self, enabled: Arc<AtomicBool>, sink: HeartbeatSink) -> Self {
+        self.log_each_iteration = enabled;
+        self.heartbeat_sink = sink;
+        self
+    }
+
+    /// Press the key in `wake_key`, read each cycle so it can change at runtime
+    pub fn with_wake_key(mut self, wake_key: Arc<Mutex<WakeKey>>) -> Self {
+        self.wake_key = wake_key;
+        self
+    }
+
+    /// Simulate the input in `wake_strategy`, read each cycle so it can
+    /// change at runtime
+    pub fn with_wake_strategy(mut self, wake_strategy: Arc<Mutex<WakeStrategy>>) -> Self {
+        self.wake_strategy = wake_strategy;
+        self
+    }
+
+    /// Report display control failures in `unavailable`, cleared again
+    /// once the platform accepts the request
+    pub fn with_display_status(mut self, unavailable: Arc<AtomicBool>) -> Self {
+        self.display_unavailable = unavailable;
+        self
+    }
+
+    /// Vary each wait within `JITTER_FRACTION` of the interval when
+    /// `enabled`, seeded per process (see `jitter_seed`)
+    pub fn with_jitter(mut self, enabled: bool) -> Self {
+        self.jitter = enabled.then(|| JitterRng::new(jitter_seed()));
+        self
+    }
+
+    /// Apply the display mode again whenever `reassert` is notified
+    pub fn with_reassert(mut self, reassert: Arc<Notify>) -> Self {
+        self.reassert = reassert;
+        self
+    }
+
+    /// Wait at most the interval in `boost` while it is set, re-timing the
+    /// current wait whenever it changes
+    pub fn with_boost(mut self, boost: watch::Receiver<Option<Duration>>) -> Self {
+        self.boost = Some(boost);
+        self
+    }
+
+    /// The interval in force: `interval`, or a shorter boost
+    fn effective_interval(&self, interval: Duration) -> Duration {
+        match self.boost.as_ref().and_then(|boost| *boost.borrow()) {
+            Some(boosted) => boosted.min(interval),
+            None => interval,
+        }
+    }
+
+    /// Time to wait after a cycle, jittered when enabled
+    fn next_wait(&mut self, interval: Duration) -> Duration {
+        let interval = self.effective_interval(interval);
+        match self.jitter.as_mut() {
+            Some(rng) => jittered_interval(interval, rng.next_unit()),
+            None => interval,
+        }
+    }
+
+    /// Release or retake the display request if the pause flag moved on
+    /// from `was_paused`
+    ///
+    /// ## Returns
+    /// Whether wake is paused now
+    fn follow_pause(&self, was_paused: bool, screen_mode: ScreenMode) -> bool {
+        let paused = self.paused.is_paused();
+        if paused != was_paused {
+            if paused {
+                log::info!("Wake service paused, restoring normal power behavior");
+                self.display_controller.restore_normal_mode();
+            } else {
+                log::info!("Wake service resumed");
+                self.apply_display_mode(screen_mode);
+            }
+        }
+        paused
+    }
+
+    /// Apply `screen_mode` to the display, recording whether it was refused
+    ///
+    /// ## Failure Modes
+    /// - Platform refuses: Logs a warning and sets `display_unavailable`;
+    ///   the loop keeps waking by input simulation
+    fn apply_display_mode(&self, screen_mode: ScreenMode) {
+        let unavailable = match self.display_controller.set_display_mode(screen_mode) {
+            Ok(()) => false,
+            Err(e) => {
+                log::warn!("Display control unavailable (continuing): {}", e);
+                true
+            }
+        };
+        if self.display_unavailable.swap(unavailable, Ordering::SeqCst) != unavailable {
+            notify_display_status(unavailable);
         }
     }
 
@@ -64,139 +464,843 @@ impl WakeService {
     ///
     /// ## Arguments
     /// * `screen_mode` - How to handle display power management
+    /// * `interval` - Time between wake cycles
+    /// * `input` - Key press source from `WakeBackend::input`, None when the
+    ///   platform API suffices
     ///
     /// ## Design Intent
     /// Main wake loop. Runs until `running` flag is set to false.
-    /// On Windows with AllowScreenOff, uses ES_CONTINUOUS API alone (no F15) to allow screen sleep.
-    /// On Windows with KeepScreenOn or non-Windows platforms, uses F15 simulation.
+    /// The display controller holds the platform request (execution state,
+    /// power assertion or logind inhibitor); key presses are added only
+    /// where `uses_input_simulation` says so: KeepScreenOn everywhere, and
+    /// AllowScreenOff where no native wake API is available.
     ///
     /// ## Side Effects
-    /// - On Windows AllowScreenOff: No F15 presses, screen can sleep normally
-    /// - On Windows KeepScreenOn: Presses F15 every 60 seconds + ES_DISPLAY_REQUIRED
-    /// - On non-Windows: Presses F15 every 60 seconds
+    /// - AllowScreenOff with native wake: No F15 presses, screen can sleep
+    /// - Otherwise: Presses F15 every `interval` on top of the display request
     /// - Sets platform display flags based on screen_mode
+    /// - While paused: no presses, normal display mode restored until resumed
     /// - Restores normal display mode on exit
     ///
     /// ## Failure Modes
-    /// - Input initialization fails: Returns InputSimulation error (when F15 needed)
     /// - Individual key press fails: Logs error, continues running
+    /// - Display request refused: Logs warning, sets `display_unavailable`,
+    ///   continues running
     ///
-    /// ## Returns
-    /// Ok(()) when stopped normally, AppError::InputSimulation if initialization fails
-    pub async fn run(self, screen_mode: ScreenMode) -> Result<()> {
+    /// Input initialization happens before the loop (see `create_wake_input`)
+    /// so callers can report it up front.
+    pub async fn run(
+        mut self,
+        screen_mode: ScreenMode,
+        interval: Duration,
+        mut input: Option<Box<dyn WakeInput>>,
+    ) {
         log::info!(
-            "Starting wake service with screen mode: {:?}",
-            screen_mode
+            "Starting wake service with screen mode: {:?}, interval: {:?}, jitter: {}",
+            screen_mode,
+            interval,
+            self.jitter.is_some()
         );
 
-        // Apply platform display settings
-        self.display_controller.set_display_mode(screen_mode);
-
-        // Determine if F15 simulation is needed
-        // On Windows with AllowScreenOff, ES_CONTINUOUS is sufficient - no F15 needed
-        // This allows the screen to sleep while keeping system awake
-        #[cfg(windows)]
-        let use_f15 = screen_mode.should_keep_display_on();
-        #[cfg(not(windows))]
-        let use_f15 = true;
+        // Apply platform display settings (deferred while paused)
+        let mut was_paused = self.paused.is_paused();
+        if was_paused {
+            log::info!("Wake service starting paused");
+        } else {
+            self.apply_display_mode(screen_mode);
+        }
 
         log::info!(
             "Wake strategy: F15 simulation={}, platform API=active",
-            use_f15
+            input.is_some()
         );
 
-        // Initialize input simulator only if needed
-        let mut enigo = if use_f15 {
-            let settings = Settings::default();
-            Some(
-                Enigo::new(&settings).map_err(|e| AppError::InputSimulation {
-                    message: "Failed to initialize input simulator".to_string(),
-                    cause: e.to_string(),
-                    recovery_hint:
-                        "Ensure the application has necessary permissions for input simulation.",
-                })?,
-            )
-        } else {
-            None
-        };
-
         // Main wake loop
-        while self.running.load(Ordering::SeqCst) {
-            if let Some(ref mut enigo) = enigo {
-                log::trace!("Simulating F15 key press (screen mode: {:?})", screen_mode);
-                
-                if let Err(e) = enigo.key(Key::F15, Direction::Click) {
-                    log::error!("F15 key press failed (continuing): {}", e);
+        let mut iteration = 0u64;
+        while self.running.is_running() {
+            iteration += 1;
+            let paused = self.follow_pause(was_paused, screen_mode);
+            was_paused = paused;
+
+            let outcome = if paused {
+                log::trace!("Wake service paused, skipping this cycle");
+                CycleOutcome::Paused
+            } else if let Some(ref mut input) = input {
+                let strategy = match self.wake_strategy.lock() {
+                    Ok(strategy) => *strategy,
+                    Err(e) => {
+                        log::error!("Mutex poisoned during wake strategy read: {}", e);
+                        WakeStrategy::default()
+                    }
+                };
+                let key = match self.wake_key.lock() {
+                    Ok(key) => *key,
+                    Err(e) => {
+                        log::error!("Mutex poisoned during wake key read: {}", e);
+                        WakeKey::default()
+                    }
+                };
+                let what = match strategy {
+                    WakeStrategy::KeyPress => format!("{} key press", key.label()),
+                    WakeStrategy::MouseJiggle => "Mouse jiggle".to_string(),
+                };
+                log::trace!("Simulating {} (screen mode: {:?})", what, screen_mode);
+                let result = match strategy {
+                    WakeStrategy::KeyPress => input.press(key),
+                    WakeStrategy::MouseJiggle => input.jiggle(),
+                };
+
+                if let Err(e) = result {
+                    log::error!("{} failed (continuing): {}", what, e);
+                    CycleOutcome::PressFailed
                 } else {
-                    log::trace!("F15 key press successful");
+                    log::trace!("{} successful", what);
+                    CycleOutcome::Pressed
                 }
             } else {
-                log::trace!("Keeping system awake via platform API only (screen mode: {:?})", screen_mode);
+                log::trace!(
+                    "Keeping system awake via platform API only (screen mode: {:?})",
+                    screen_mode
+                );
+                CycleOutcome::ApiOnly
+            };
+            notify_cycle(outcome);
+
+            let cycle_at = tokio::time::Instant::now();
+            let mut wait = self.next_wait(interval);
+            if self.log_each_iteration.load(Ordering::SeqCst) {
+                let at = SystemTime::now();
+                (self.heartbeat_sink)(&Heartbeat {
+                    iteration,
+                    outcome,
+                    at,
+                    next_press_at: at + wait,
+                });
             }
 
-            tokio::time::sleep(Duration::from_secs(60)).await;
+            loop {
+                let due = cycle_at + wait;
+                let wakeup = wait_for_cycle(
+                    &self.running,
+                    &self.paused,
+                    &self.reassert,
+                    &mut self.boost,
+                    due,
+                )
+                .await;
+                match wakeup {
+                    Wakeup::Due | Wakeup::Stopped => break,
+                    Wakeup::PauseChanged => {
+                        // The next cycle still comes when due: resuming
+                        // takes the display request now, presses follow
+                        was_paused = self.follow_pause(was_paused, screen_mode);
+                    }
+                    Wakeup::Reassert if self.paused.is_paused() => {
+                        log::debug!("Wake service paused, not reasserting display mode");
+                    }
+                    Wakeup::Reassert => {
+                        log::info!("Reasserting display mode: {:?}", screen_mode);
+                        self.apply_display_mode(screen_mode);
+                    }
+                    Wakeup::IntervalChanged => {
+                        wait = self.next_wait(interval);
+                        log::info!("Wake interval now {:?}", self.effective_interval(interval));
+                    }
+                }
+            }
         }
 
         // Restore normal display behavior
         self.display_controller.restore_normal_mode();
+        self.display_unavailable.store(false, Ordering::SeqCst);
         log::info!("Wake service stopped");
+    }
+}
+
+/// Factory for a wake session's display controller
+///
+/// Takes `use_power_requests` from the state file.
+pub type DisplayFactory = Arc<dyn Fn(bool) -> Box<dyn DisplayControl + Send> + Send + Sync>;
+
+/// Factory for a wake session's key press source
+pub type InputFactory = Arc<dyn Fn(ScreenMode) -> Result<Option<Box<dyn WakeInput>>> + Send + Sync>;
+
+/// Runs the system action after a watched condition ends
+pub type PowerAction =
+    Arc<dyn Fn(ConditionEndAction) -> std::result::Result<(), String> + Send + Sync>;
+
+/// Where wake sessions send display flags and key presses
+///
+/// ## Design Intent
+/// Held in the shared application state so every path that starts a wake
+/// session can be exercised in tests against a mock display and a dry-run
+/// keyboard instead of the real OS.
+#[derive(Clone)]
+pub struct WakeBackend {
+    pub display: DisplayFactory,
+    pub input: InputFactory,
+    /// The display controller keeps the system awake without input, so a
+    /// session can go on when `input` fails (see `ServiceManager::sync`)
+    pub native_wake: bool,
+    /// Sleep, hibernate or shut down when a watched condition ends
+    pub power_action: PowerAction,
+}
+
+impl WakeBackend {
+    /// The platform display controller and enigo F15 presses
+    pub fn platform() -> Self {
+        Self {
+            display: Arc::new(platform::get_display_controller),
+            input: Arc::new(create_wake_input),
+            native_wake: platform::native_wake_available(),
+            power_action: Arc::new(platform::perform_power_action),
+        }
+    }
+
+    /// Report every display, input and power call to `sink` instead of
+    /// making it
+    ///
+    /// Input is only created where `create_wake_input` would create it, so
+    /// a dry run makes the same calls, at the same times, as a real one.
+    pub fn dry_run(sink: DryRunSink) -> Self {
+        let display_sink = sink.clone();
+        let input_sink = sink.clone();
+        Self {
+            display: Arc::new(move |_| {
+                Box::new(DryRunDisplay {
+                    sink: display_sink.clone(),
+                })
+            }),
+            input: Arc::new(move |screen_mode| {
+                let input: Box<dyn WakeInput> = Box::new(DryRunInput {
+                    sink: input_sink.clone(),
+                });
+                Ok(uses_input_simulation(screen_mode).then_some(input))
+            }),
+            native_wake: platform::native_wake_available(),
+            power_action: Arc::new(move |action| {
+                sink(&DryRunCall::PowerAction(action));
+                Ok(())
+            }),
+        }
+    }
+}
+
+/// A system call a dry run would have made
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DryRunCall {
+    SetDisplayMode(ScreenMode),
+    RestoreNormalMode,
+    Press(WakeKey),
+    Jiggle,
+    PowerAction(ConditionEndAction),
+}
+
+impl std::fmt::Display for DryRunCall {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DryRunCall::SetDisplayMode(screen_mode) => {
+                write!(f, "would set display mode {:?}", screen_mode)
+            }
+            DryRunCall::RestoreNormalMode => write!(f, "would restore normal power behavior"),
+            DryRunCall::Press(key) => write!(f, "would press {}", key.label()),
+            DryRunCall::Jiggle => write!(f, "would jiggle the mouse"),
+            DryRunCall::PowerAction(action) => write!(f, "would run power action {:?}", action),
+        }
+    }
+}
+
+/// Receiver of dry-run calls (the log, or a recorder in tests)
+pub type DryRunSink = Arc<dyn Fn(&DryRunCall) + Send + Sync>;
+
+/// Write a dry-run call to the application log at info level
+pub fn log_dry_run(call: &DryRunCall) {
+    log::info!("Dry run: {}", call);
+}
+
+/// Display controller of a dry run
+struct DryRunDisplay {
+    sink: DryRunSink,
+}
+
+impl DisplayControl for DryRunDisplay {
+    fn set_display_mode(&self, screen_mode: ScreenMode) -> Result<()> {
+        (self.sink)(&DryRunCall::SetDisplayMode(screen_mode));
+        Ok(())
+    }
+
+    fn restore_normal_mode(&self) {
+        (self.sink)(&DryRunCall::RestoreNormalMode);
+    }
+}
+
+/// Wake input of a dry run
+struct DryRunInput {
+    sink: DryRunSink,
+}
+
+impl WakeInput for DryRunInput {
+    fn press(&mut self, key: WakeKey) -> std::result::Result<(), String> {
+        (self.sink)(&DryRunCall::Press(key));
+        Ok(())
+    }
 
+    fn jiggle(&mut self) -> std::result::Result<(), String> {
+        (self.sink)(&DryRunCall::Jiggle);
         Ok(())
     }
 }
 
+/// Create the key press source a wake session needs, if any
+///
+/// ## Platform Behavior
+/// With AllowScreenOff, where a platform API keeps the system awake
+/// (execution state, power assertion, logind inhibitor), no F15 is pressed,
+/// so the screen can sleep while the system stays awake. Everywhere else F15
+/// simulation is required.
+///
+/// ## Returns
+/// The input source (None if not needed), or AppError::InputSimulation
+pub fn create_wake_input(screen_mode: ScreenMode) -> Result<Option<Box<dyn WakeInput>>> {
+    if !uses_input_simulation(screen_mode) {
+        return Ok(None);
+    }
+    Ok(Some(Box::new(create_input_simulator()?)))
+}
+
+/// Whether wake sessions in `screen_mode` simulate input on this platform
+/// (see `create_wake_input`)
+pub fn uses_input_simulation(screen_mode: ScreenMode) -> bool {
+    screen_mode.should_keep_display_on() || !platform::native_wake_available()
+}
+
+/// Seed for interval jitter, different for every process
+///
+/// ## Design Intent
+/// Machines started from the same image at the same moment must still
+/// drift apart, so the clock is mixed with the process id.
+fn jitter_seed() -> u64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_nanos() as u64);
+    nanos ^ (u64::from(std::process::id()) << 32)
+}
+
+/// Why `wait_for_cycle` returned
+#[derive(Debug, PartialEq, Eq)]
+enum Wakeup {
+    /// The next cycle is due
+    Due,
+    /// The loop was stopped
+    Stopped,
+    /// Wake was paused or resumed
+    PauseChanged,
+    /// The display mode should be applied again
+    Reassert,
+    /// The boost interval changed; the wait should be re-timed
+    IntervalChanged,
+}
+
+/// Sleep until `due`, returning as soon as `running` is stopped
+///
+/// ## Design Intent
+/// Lets shutdown finish cleanup promptly instead of waiting out a full
+/// wake interval. The notification is registered before the flag is
+/// checked, so a stop between the two is not missed. A closed `boost`
+/// channel is never ready again, so it cannot spin the loop.
+///
+/// ## Returns
+/// Why it woke; on `PauseChanged`, `Reassert` and `IntervalChanged` the
+/// caller waits again
+async fn wait_for_cycle(
+    running: &RunningFlag,
+    paused: &PauseFlag,
+    reassert: &Notify,
+    boost: &mut Option<watch::Receiver<Option<Duration>>>,
+    due: tokio::time::Instant,
+) -> Wakeup {
+    let stopped = running.stopped.notified();
+    tokio::pin!(stopped);
+    stopped.as_mut().enable();
+    if !running.is_running() {
+        return Wakeup::Stopped;
+    }
+    let boost_changed = async {
+        let changed = match boost.as_mut() {
+            Some(boost) => boost.changed().await.is_ok(),
+            None => false,
+        };
+        if !changed {
+            std::future::pending::<()>().await;
+        }
+    };
+    tokio::select! {
+        _ = tokio::time::sleep_until(due) => Wakeup::Due,
+        _ = stopped => Wakeup::Stopped,
+        _ = paused.changed.notified() => {
+            if running.is_running() {
+                Wakeup::PauseChanged
+            } else {
+                Wakeup::Stopped
+            }
+        }
+        _ = reassert.notified() => {
+            if running.is_running() {
+                Wakeup::Reassert
+            } else {
+                Wakeup::Stopped
+            }
+        }
+        _ = boost_changed => Wakeup::IntervalChanged,
+    }
+}
+
+/// Stop a wake loop and wait, bounded, for its task to finish
+///
+/// ## Design Intent
+/// `run` restores the display before it returns, so a finished task means
+/// cleanup is done. Quit must stay responsive even if the loop is stuck
+/// (e.g. an input simulator deadlock), so waiting gives up after `timeout`.
+///
+/// ## Arguments
+/// * `running` - Running flag of the wake service
+/// * `task` - Handle of the task running the service
+/// * `timeout` - Upper bound on the wait
+///
+/// ## Side Effects
+/// Blocks the calling thread for at most `timeout`.
+///
+/// ## Returns
+/// True if the task finished, false on timeout
+pub fn stop_wake_service_blocking(
+    running: &RunningFlag,
+    task: &AbortHandle,
+    timeout: Duration,
+) -> bool {
+    running.stop();
+    wait_until(|| task.is_finished(), timeout)
+}
+
+/// Wait until `done` returns true or `timeout` elapses
+fn wait_until(done: impl Fn() -> bool, timeout: Duration) -> bool {
+    let deadline = std::time::Instant::now() + timeout;
+    while !done() {
+        if std::time::Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    true
+}
+
+/// Create the F15 input simulator
+///
+/// ## Design Intent
+/// Shared by the wake loop and the startup self-check so both report the
+/// same error when input simulation is unavailable.
+///
+/// ## Returns
+/// Initialized simulator, or AppError::InputSimulation
+pub fn create_input_simulator() -> Result<Enigo> {
+    Ok(Enigo::new(&Settings::default())?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::core::ScreenMode;
+    use crate::test_support::{RecordingDisplay, SilentInput};
+    use std::sync::atomic::AtomicUsize;
+
+    #[test]
+    fn test_wait_for_stuck_service_is_bounded() {
+        // A service that never finishes cleanup
+        let started = std::time::Instant::now();
+
+        assert!(!wait_until(|| false, Duration::from_millis(200)));
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn test_wait_for_stopped_service_returns_immediately() {
+        assert!(wait_until(|| true, Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_blink_only_after_successful_press() {
+        assert!(CycleOutcome::Pressed.should_blink(true));
+        assert!(!CycleOutcome::PressFailed.should_blink(true));
+        assert!(!CycleOutcome::ApiOnly.should_blink(true));
+        assert!(!CycleOutcome::Paused.should_blink(true));
+    }
+
+    #[test]
+    fn test_blink_disabled_by_preference() {
+        assert!(!CycleOutcome::Pressed.should_blink(false));
+    }
 
-    struct MockDisplayControl {
-        calls: Arc<std::sync::Mutex<Vec<String>>>,
+    /// Input recording each key press and counting mouse jiggles
+    struct RecordingInput {
+        keys: Arc<std::sync::Mutex<Vec<WakeKey>>>,
+        jiggles: Arc<AtomicUsize>,
     }
 
-    impl MockDisplayControl {
-        fn new() -> (Self, Arc<std::sync::Mutex<Vec<String>>>) {
-            let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
-            (
-                Self {
-                    calls: calls.clone(),
-                },
-                calls,
-            )
+    impl WakeInput for RecordingInput {
+        fn press(&mut self, key: WakeKey) -> std::result::Result<(), String> {
+            self.keys.lock().unwrap().push(key);
+            Ok(())
+        }
+
+        fn jiggle(&mut self) -> std::result::Result<(), String> {
+            self.jiggles.fetch_add(1, Ordering::SeqCst);
+            Ok(())
         }
     }
 
-    impl DisplayControl for MockDisplayControl {
-        fn set_display_mode(&self, screen_mode: ScreenMode) {
-            self.calls
-                .lock()
-                .unwrap()
-                .push(format!("set_display_mode({:?})", screen_mode));
+    #[tokio::test(start_paused = true)]
+    async fn test_wake_key_change_applies_next_cycle() {
+        let running = Arc::new(RunningFlag::new());
+        let wake_key = Arc::new(std::sync::Mutex::new(WakeKey::F15));
+        let keys = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let (mock_display, _calls) = RecordingDisplay::new();
+        let service = WakeService::new(
+            running.clone(),
+            Arc::new(PauseFlag::default()),
+            Box::new(mock_display),
+        )
+        .with_wake_key(wake_key.clone());
+
+        let handle = tokio::spawn(service.run(
+            ScreenMode::KeepScreenOn,
+            Duration::from_secs(60),
+            Some(Box::new(RecordingInput {
+                keys: keys.clone(),
+                jiggles: Arc::new(AtomicUsize::new(0)),
+            })),
+        ));
+        // Cycle at 0s uses F15, the one at 60s the new key
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        *wake_key.lock().unwrap() = WakeKey::ScrollLock;
+        tokio::time::sleep(Duration::from_secs(60)).await;
+
+        running.stop();
+        handle.await.unwrap();
+        assert_eq!(
+            *keys.lock().unwrap(),
+            vec![WakeKey::F15, WakeKey::ScrollLock]
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wake_strategy_change_applies_next_cycle() {
+        let running = Arc::new(RunningFlag::new());
+        let strategy = Arc::new(std::sync::Mutex::new(WakeStrategy::KeyPress));
+        let keys = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let jiggles = Arc::new(AtomicUsize::new(0));
+        let (mock_display, _calls) = RecordingDisplay::new();
+        let service = WakeService::new(
+            running.clone(),
+            Arc::new(PauseFlag::default()),
+            Box::new(mock_display),
+        )
+        .with_wake_strategy(strategy.clone());
+
+        let handle = tokio::spawn(service.run(
+            ScreenMode::KeepScreenOn,
+            Duration::from_secs(60),
+            Some(Box::new(RecordingInput {
+                keys: keys.clone(),
+                jiggles: jiggles.clone(),
+            })),
+        ));
+        // Cycle at 0s presses the key, the ones at 60s and 120s jiggle
+        tokio::time::sleep(Duration::from_secs(30)).await;
+        *strategy.lock().unwrap() = WakeStrategy::MouseJiggle;
+        tokio::time::sleep(Duration::from_secs(120)).await;
+
+        running.stop();
+        handle.await.unwrap();
+        assert_eq!(*keys.lock().unwrap(), vec![WakeKey::F15]);
+        assert_eq!(jiggles.load(Ordering::SeqCst), 2);
+    }
+
+    /// Display controller the platform always refuses, as
+    /// `SetThreadExecutionState` returning 0 does
+    struct RefusingDisplay;
+
+    impl DisplayControl for RefusingDisplay {
+        fn set_display_mode(&self, screen_mode: ScreenMode) -> Result<()> {
+            Err(crate::error::AppError::DisplayControl {
+                message: format!("SetThreadExecutionState rejected {:?}", screen_mode),
+                cause: "flags 0x80000041".to_string(),
+                recovery_hint: "hint",
+            })
         }
 
-        fn restore_normal_mode(&self) {
-            self.calls.lock().unwrap().push("restore_normal_mode".to_string());
+        fn restore_normal_mode(&self) {}
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_refused_display_marks_unavailable_and_keeps_waking() {
+        let running = Arc::new(RunningFlag::new());
+        let unavailable = Arc::new(AtomicBool::new(false));
+        let keys = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let service = WakeService::new(
+            running.clone(),
+            Arc::new(PauseFlag::default()),
+            Box::new(RefusingDisplay),
+        )
+        .with_display_status(unavailable.clone());
+
+        let handle = tokio::spawn(service.run(
+            ScreenMode::AwayMode,
+            Duration::from_secs(60),
+            Some(Box::new(RecordingInput {
+                keys: keys.clone(),
+                jiggles: Arc::new(AtomicUsize::new(0)),
+            })),
+        ));
+        tokio::time::sleep(Duration::from_secs(90)).await;
+        assert!(unavailable.load(Ordering::SeqCst));
+        assert_eq!(keys.lock().unwrap().len(), 2);
+
+        running.stop();
+        handle.await.unwrap();
+        assert!(!unavailable.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_heartbeat_per_iteration_when_enabled() {
+        let running = Arc::new(RunningFlag::new());
+        let enabled = Arc::new(AtomicBool::new(true));
+        let beats = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorder = beats.clone();
+        let (mock_display, _calls) = RecordingDisplay::new();
+        let service = WakeService::new(
+            running.clone(),
+            Arc::new(PauseFlag::default()),
+            Box::new(mock_display),
+        )
+        .with_heartbeat(
+            enabled.clone(),
+            Arc::new(move |beat: &Heartbeat| recorder.lock().unwrap().push(beat.clone())),
+        );
+
+        let handle = tokio::spawn(service.run(
+            ScreenMode::KeepScreenOn,
+            Duration::from_secs(60),
+            Some(Box::new(SilentInput)),
+        ));
+        // Cycles at 0s, 60s and 120s
+        tokio::time::sleep(Duration::from_secs(150)).await;
+        {
+            let beats = beats.lock().unwrap();
+            let iterations: Vec<u64> = beats.iter().map(|beat| beat.iteration).collect();
+            assert_eq!(iterations, vec![1, 2, 3]);
+            assert!(beats
+                .iter()
+                .all(|beat| beat.outcome == CycleOutcome::Pressed));
+            assert_eq!(
+                beats[0].next_press_at.duration_since(beats[0].at).unwrap(),
+                Duration::from_secs(60)
+            );
         }
+
+        // Turned off at runtime: the loop keeps going without heartbeats
+        enabled.store(false, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_secs(120)).await;
+        assert_eq!(beats.lock().unwrap().len(), 3);
+
+        running.stop();
+        handle.await.unwrap();
     }
 
     #[tokio::test]
-    #[ignore] // Requires input simulation which may fail in CI/test environment
+    async fn test_stop_wakes_sleeping_loop() {
+        let running = Arc::new(RunningFlag::new());
+        let (mock_display, calls) = RecordingDisplay::new();
+        let service = WakeService::new(
+            running.clone(),
+            Arc::new(PauseFlag::default()),
+            Box::new(mock_display),
+        );
+        let handle = tokio::spawn(service.run(
+            ScreenMode::KeepScreenOn,
+            Duration::from_secs(60),
+            Some(Box::new(SilentInput)),
+        ));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        // Well inside the 60s interval, the loop ends on the stop alone
+        running.stop();
+        let stopped = tokio::time::timeout(Duration::from_millis(200), handle).await;
+        assert!(stopped.is_ok(), "Service should stop within 200ms");
+        assert_eq!(
+            calls.lock().unwrap().last().map(String::as_str),
+            Some("restore_normal_mode")
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_reassert_reapplies_display_without_extra_press() {
+        let running = Arc::new(RunningFlag::new());
+        let paused = Arc::new(PauseFlag::default());
+        let reassert = Arc::new(Notify::new());
+        let (mock_display, calls) = RecordingDisplay::new();
+        let keys = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let service = WakeService::new(running.clone(), paused.clone(), Box::new(mock_display))
+            .with_reassert(reassert.clone());
+        let handle = tokio::spawn(service.run(
+            ScreenMode::KeepScreenOn,
+            Duration::from_secs(60),
+            Some(Box::new(RecordingInput {
+                keys: keys.clone(),
+                jiggles: Arc::new(AtomicUsize::new(0)),
+            })),
+        ));
+        let display_calls = || {
+            calls
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|call| call.starts_with("set_display_mode"))
+                .count()
+        };
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        assert_eq!(display_calls(), 1);
+
+        reassert.notify_one();
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(display_calls(), 2);
+        assert_eq!(keys.lock().unwrap().len(), 1);
+
+        // The interval is kept: the next press is still due at 60s
+        tokio::time::sleep(Duration::from_secs(45)).await;
+        assert_eq!(keys.lock().unwrap().len(), 1);
+        tokio::time::sleep(Duration::from_secs(5)).await;
+        assert_eq!(keys.lock().unwrap().len(), 2);
+
+        // Nothing is reasserted while paused
+        paused.set(true);
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        reassert.notify_one();
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        assert_eq!(display_calls(), 2);
+
+        running.stop();
+        handle.await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_pause_change_applies_mid_interval() {
+        let running = Arc::new(RunningFlag::new());
+        let paused = Arc::new(PauseFlag::default());
+        let (mock_display, calls) = RecordingDisplay::new();
+        let service = WakeService::new(running.clone(), paused.clone(), Box::new(mock_display));
+        let handle = tokio::spawn(service.run(
+            ScreenMode::KeepScreenOn,
+            Duration::from_secs(60),
+            Some(Box::new(SilentInput)),
+        ));
+        let last_call = || calls.lock().unwrap().last().cloned();
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        assert_eq!(
+            last_call().as_deref(),
+            Some("set_display_mode(KeepScreenOn)")
+        );
+
+        // Well before the next cycle is due at 60s
+        paused.set(true);
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        assert_eq!(last_call().as_deref(), Some("restore_normal_mode"));
+
+        paused.set(false);
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        assert_eq!(
+            last_call().as_deref(),
+            Some("set_display_mode(KeepScreenOn)")
+        );
+        assert_eq!(calls.lock().unwrap().len(), 3);
+
+        running.stop();
+        handle.await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_dry_run_records_calls_in_order() {
+        let calls = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorder = calls.clone();
+        let backend = WakeBackend::dry_run(Arc::new(move |call: &DryRunCall| {
+            recorder.lock().unwrap().push(call.clone())
+        }));
+        let running = Arc::new(RunningFlag::new());
+        let service = WakeService::new(
+            running.clone(),
+            Arc::new(PauseFlag::default()),
+            (backend.display)(false),
+        )
+        .with_wake_key(Arc::new(Mutex::new(WakeKey::F13)));
+        let input = (backend.input)(ScreenMode::KeepScreenOn).unwrap();
+
+        let handle =
+            tokio::spawn(service.run(ScreenMode::KeepScreenOn, Duration::from_secs(60), input));
+        // Cycles at 0s, 60s and 120s
+        tokio::time::sleep(Duration::from_secs(150)).await;
+        running.stop();
+        handle.await.unwrap();
+        (backend.power_action)(ConditionEndAction::Sleep).unwrap();
+
+        assert_eq!(
+            *calls.lock().unwrap(),
+            vec![
+                DryRunCall::SetDisplayMode(ScreenMode::KeepScreenOn),
+                DryRunCall::Press(WakeKey::F13),
+                DryRunCall::Press(WakeKey::F13),
+                DryRunCall::Press(WakeKey::F13),
+                DryRunCall::RestoreNormalMode,
+                DryRunCall::PowerAction(ConditionEndAction::Sleep),
+            ]
+        );
+        assert_eq!(
+            DryRunCall::Press(WakeKey::F13).to_string(),
+            "would press F13"
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
     async fn test_wake_service_lifecycle() {
-        let running = Arc::new(AtomicBool::new(true));
-        let (mock_display, calls) = MockDisplayControl::new();
-        let service = WakeService::new(running.clone(), Box::new(mock_display));
+        let running = Arc::new(RunningFlag::new());
+        let (mock_display, calls) = RecordingDisplay::new();
+        let paused = Arc::new(PauseFlag::default());
+        let service = WakeService::new(running.clone(), paused, Box::new(mock_display));
+        let keys = Arc::new(std::sync::Mutex::new(Vec::new()));
 
-        // Start service in background
+        // Start service in background against a mock keyboard
         let running_clone = running.clone();
+        let input = RecordingInput {
+            keys: keys.clone(),
+            jiggles: Arc::new(AtomicUsize::new(0)),
+        };
         let handle = tokio::spawn(async move {
-            service.run(ScreenMode::KeepScreenOn).await
+            service
+                .run(
+                    ScreenMode::KeepScreenOn,
+                    Duration::from_secs(60),
+                    Some(Box::new(input)),
+                )
+                .await
         });
 
-        // Let it initialize
+        // Presses are due at 0s, 60s and 120s
         tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(keys.lock().unwrap().len(), 1);
+        tokio::time::sleep(Duration::from_secs(59)).await;
+        assert_eq!(keys.lock().unwrap().len(), 1);
+        tokio::time::sleep(Duration::from_secs(61)).await;
+        assert_eq!(*keys.lock().unwrap(), vec![WakeKey::F15; 3]);
 
         // Stop service
-        running_clone.store(false, Ordering::SeqCst);
+        running_clone.stop();
 
         // Wait for completion - must complete for restore to be called
         let result = tokio::time::timeout(Duration::from_secs(3), handle).await;
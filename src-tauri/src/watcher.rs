@@ -0,0 +1,212 @@
+//! Shared watcher reaction
+//!
+//! Runs the automation supervisor and applies its edges to the shared wake
+//! state.
+//!
+//! ## Design Intent
+//! Every watcher (window title, device, process, network, remote session)
+//! is a `Condition` that only decides whether it holds. One supervisor loop
+//! samples them all, and what happens on an edge is the same for all of
+//! them and lives here:
+//! the edge becomes a request via `watcher_request`, goes through
+//! `resolve_desired_state`, and is applied with the same business logic as
+//! a manual toggle. When a watcher turns wake off it also schedules the
+//! configured `on_condition_end` follow-up.
+//!
+//! Watchers share `active_watch`, so an ending condition only clears the
+//! description it set itself and leaves another watcher's in place.
+//!
+//! ## Side Effects
+//! - Spawns the supervisor task for the application lifetime
+//! - May enable/disable wake, persist state and refresh the tray
+
+use crate::commands::{self, AppStateManager};
+use crate::core::automation::{
+    EvalContext, ManualCondition, MasterCondition, Supervisor, DEBOUNCE_SAMPLES,
+};
+use crate::core::{plan_condition_end, resolve_desired_state, watcher_request, AutomationInputs};
+use crate::device_watch::DeviceCondition;
+use crate::net_watch::NetworkCondition;
+use crate::notify;
+use crate::persistence::read_state;
+use crate::process_watch::ProcessCondition;
+use crate::remote_watch::RemoteSessionCondition;
+use crate::tray;
+use crate::window_watch::WindowTitleCondition;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// How often the supervisor samples every condition
+pub const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Supervisor over the user's controls and every built-in watcher
+///
+/// Watchers are added in description priority order.
+pub fn default_supervisor(state: &AppStateManager) -> Supervisor {
+    let mut supervisor = Supervisor::default();
+    supervisor.add(Box::new(ManualCondition), 1);
+    supervisor.add(Box::new(MasterCondition), 1);
+    supervisor.add(
+        Box::new(WindowTitleCondition::new(
+            state.automation.watch_window_title.clone(),
+        )),
+        DEBOUNCE_SAMPLES,
+    );
+    supervisor.add(
+        Box::new(DeviceCondition::new(state.automation.watch_device.clone())),
+        DEBOUNCE_SAMPLES,
+    );
+    supervisor.add(
+        Box::new(ProcessCondition::new(
+            state.automation.watch_process.clone(),
+        )),
+        DEBOUNCE_SAMPLES,
+    );
+    supervisor.add(
+        Box::new(NetworkCondition::new(
+            state.automation.watch_network.clone(),
+        )),
+        DEBOUNCE_SAMPLES,
+    );
+    supervisor.add(
+        Box::new(RemoteSessionCondition::new(
+            state.automation.keep_awake_on_remote_session.clone(),
+        )),
+        DEBOUNCE_SAMPLES,
+    );
+    supervisor
+}
+
+/// Context the conditions are evaluated against
+fn eval_context(state: &AppStateManager) -> EvalContext {
+    EvalContext {
+        manual_awake: state.is_awake.load(Ordering::SeqCst)
+            && !state.automation.enabled_by_watcher.load(Ordering::SeqCst),
+        master_off: state.automation.master_off.load(Ordering::SeqCst),
+        muted: state.automation.muted.load(Ordering::SeqCst),
+    }
+}
+
+/// Run `supervisor` in the background, applying every verdict
+///
+/// ## Design Intent
+/// Watchers act on edges: a verdict is applied when the watched condition
+/// keeping wake on starts, ends or changes, so a manual override of a
+/// watcher sticks until its condition ends.
+///
+/// ## Side Effects
+/// Spawns a Tokio task that runs for the lifetime of the application.
+pub fn spawn_supervisor(app: AppHandle, mut supervisor: Supervisor) {
+    tokio::spawn(async move {
+        // Watched condition of the last applied edge
+        let mut watch: Option<String> = None;
+
+        loop {
+            tokio::time::sleep(POLL_INTERVAL).await;
+
+            let ctx = eval_context(&app.state::<AppStateManager>());
+            let Some(verdict) = supervisor.poll(&ctx) else {
+                continue;
+            };
+            log::debug!(
+                "Conditions changed: awake={} reasons={:?} forced_by={:?}",
+                verdict.awake,
+                verdict.reasons,
+                verdict.forced_by
+            );
+
+            if verdict.watch == watch {
+                continue;
+            }
+            match (&verdict.watch, &watch) {
+                (Some(description), _) => apply_edge(&app, true, description),
+                (None, Some(ended)) => apply_edge(&app, false, ended),
+                (None, None) => {}
+            }
+            watch = verdict.watch;
+        }
+    });
+}
+
+/// React to a debounced watcher edge
+///
+/// ## Arguments
+/// * `app` - Application handle
+/// * `matched` - The watched condition now holds
+/// * `description` - Condition shown to the user (e.g. `device "Dock"`)
+pub fn apply_edge(app: &AppHandle, matched: bool, description: &str) {
+    let state = app.state::<AppStateManager>();
+
+    match state.automation.active_watch.lock() {
+        Ok(mut active) => {
+            if matched {
+                *active = Some(description.to_string());
+            } else if active.as_deref() == Some(description) {
+                *active = None;
+            }
+        }
+        Err(e) => log::error!("Mutex poisoned during watch edge: {}", e),
+    }
+
+    let awake = state.is_awake.load(Ordering::SeqCst);
+    let enabled_by_watcher = state.automation.enabled_by_watcher.load(Ordering::SeqCst);
+    let request = watcher_request(matched, awake, enabled_by_watcher);
+    if !matched {
+        state
+            .automation
+            .enabled_by_watcher
+            .store(false, Ordering::SeqCst);
+    }
+
+    let muted = state.automation.muted.load(Ordering::SeqCst);
+    let master_off = state.automation.master_off.load(Ordering::SeqCst);
+    let desired = resolve_desired_state(AutomationInputs {
+        master_off,
+        current_awake: awake,
+        muted,
+        watcher_request: request,
+        ..Default::default()
+    });
+    if desired.awake == awake {
+        if master_off && request.is_some() {
+            log::info!("Master switch off, ignoring change of {}", description);
+        } else if muted && request.is_some() {
+            log::info!("Automation muted, ignoring change of {}", description);
+        }
+        // The awake reasons in the tooltip may still have changed
+        tray::refresh(app);
+        return;
+    }
+
+    let target = desired.awake;
+    if target {
+        log::info!("Watched {} holds, enabling sleep prevention", description);
+        state
+            .automation
+            .enabled_by_watcher
+            .store(true, Ordering::SeqCst);
+    } else {
+        log::info!("Watched {} ended, disabling sleep prevention", description);
+    }
+
+    if let Err(e) = commands::set_awake_impl(&state, target) {
+        log::error!("Watcher failed to update wake state: {}", e);
+        return;
+    }
+    let reason = if target {
+        description.to_string()
+    } else {
+        format!("{} ended", description)
+    };
+    notify::notify_state_change(app, target, Some(&reason));
+    if !target {
+        let config = read_state();
+        let plan = plan_condition_end(config.on_condition_end, config.condition_end_delay_secs);
+        let perform = state.service.power_action();
+        commands::schedule_condition_end(&state.scheduled_actions, plan, move |action| {
+            perform(action)
+        });
+    }
+    tray::refresh(app);
+}
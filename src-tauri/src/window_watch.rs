@@ -0,0 +1,44 @@
+//! Window title watcher
+//!
+//! Keeps the system awake while a window whose title matches a pattern is
+//! open (e.g. a render progress dialog).
+//!
+//! ## Design Intent
+//! Covers jobs that show progress in a window but run no process with a
+//! predictable name. Applications often recreate such dialogs between
+//! stages, which debouncing (see `core::automation`) hides.
+//!
+//! The watcher only turns wake off again if it was the one that turned it
+//! on, so a user who enabled wake manually keeps it after the window closes.
+//! Its requests go through `resolve_desired_state`, so muted automation
+//! leaves the manual state alone and the master switch overrides both.
+//! When the watcher turns wake off it also schedules the configured
+//! `on_condition_end` follow-up (e.g. sleep after a render finishes).
+//!
+//! ## Platform Behavior
+//! - Windows: Enumerates top-level windows
+//! - Other: Enumeration unsupported, watcher never matches
+
+use crate::core::automation::{PatternCondition, PatternProbe};
+use crate::core::window_title::any_title_matches;
+use crate::platform;
+
+/// A visible window's title contains the configured pattern
+pub type WindowTitleCondition = PatternCondition<VisibleWindows>;
+
+/// Samples the titles of visible top-level windows
+#[derive(Default)]
+pub struct VisibleWindows;
+
+impl PatternProbe for VisibleWindows {
+    const NAME: &'static str = "window title";
+    const NOUN: &'static str = "window";
+
+    fn matches(&self, pattern: &str) -> Option<bool> {
+        let titles = platform::visible_window_titles()?;
+        Some(any_title_matches(
+            titles.iter().map(String::as_str),
+            pattern,
+        ))
+    }
+}
@@ -1,6 +1,129 @@
 // Tea - System Tray Sleep Prevention Utility
-// The UI for this application is the system tray icon.
-// This HTML window is not shown to users.
-// All functionality is accessed via the system tray menu.
+// The system tray is the primary UI. This page is the optional status
+// window opened from the tray's "Show Status" entry; it drives the same
+// commands as the tray and follows state changes made anywhere else.
 
-console.log("Tea application loaded - UI is system tray only");
+import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
+
+export type ScreenMode = "KeepScreenOn" | "AllowScreenOff" | "AwayMode";
+
+/** Payload of the `state-changed` event the backend emits to every window */
+export interface StateChanged {
+  is_awake: boolean;
+  screen_mode: ScreenMode;
+}
+
+/** Live settings returned by `get_state`; the wake state fields match `StateChanged` */
+export interface StateSnapshot extends StateChanged {
+  wake_key: string;
+  wake_strategy: "KeyPress" | "MouseJiggle";
+  wake_interval_secs: number;
+  boost_interval_secs: number | null;
+  interval_jitter: boolean;
+  remaining_secs: number | null;
+  pause_remaining_secs: number | null;
+  power_paused: boolean;
+  automation_muted: boolean;
+  master_enabled: boolean;
+  active_profile: string | null;
+  active_watch: string | null;
+}
+
+/** Result of `toggle_sleep`; a prompt means nothing changed until confirmed */
+export interface ToggleResponse extends StateChanged {
+  needs_confirmation: string | null;
+}
+
+/** Event the backend emits after every state change */
+export const STATE_CHANGED_EVENT = "state-changed";
+
+/** Text of the status line */
+export function statusText(status: StateChanged): string {
+  return status.is_awake ? "Sleep prevention is on" : "Sleep prevention is off";
+}
+
+/** Text of the toggle button, matching the tray toggle item */
+export function toggleText(isAwake: boolean): string {
+  return isAwake ? "Enable Sleep" : "Disable Sleep";
+}
+
+/** Show a state in the window */
+export function render(status: StateChanged, doc: Document = document): void {
+  const line = doc.getElementById("status");
+  if (line) {
+    line.textContent = statusText(status);
+  }
+
+  const toggle = doc.getElementById("toggle") as HTMLButtonElement | null;
+  if (toggle) {
+    toggle.textContent = toggleText(status.is_awake);
+    toggle.disabled = false;
+  }
+
+  doc
+    .querySelectorAll<HTMLInputElement>('input[name="screen-mode"]')
+    .forEach((input) => {
+      input.checked = input.value === status.screen_mode;
+    });
+}
+
+async function loadState(): Promise<void> {
+  render(await invoke<StateSnapshot>("get_state"));
+}
+
+async function toggleSleep(): Promise<void> {
+  const response = await invoke<ToggleResponse>("toggle_sleep");
+  // Disabling wake that a watcher holds on asks for confirmation; refusals
+  // (master off, battery) are errors and reach the caller
+  if (response.needs_confirmation === null) {
+    render(response);
+  } else if (window.confirm(response.needs_confirmation)) {
+    render(await invoke<ToggleResponse>("toggle_sleep", { confirm: true }));
+  }
+}
+
+async function changeScreenMode(mode: ScreenMode): Promise<void> {
+  await invoke<ScreenMode>("change_screen_mode", { newMode: mode });
+}
+
+/** Options the backend reports for this platform */
+export interface PlatformCapabilities {
+  allow_screen_off_supported: boolean;
+  away_mode_supported: boolean;
+  os: string;
+}
+
+async function init(): Promise<void> {
+  const capabilities = await invoke<PlatformCapabilities>("get_platform_capabilities");
+  // Only show the screen choice where Allow Screen Off actually works
+  const fieldset = document.getElementById("screen-mode");
+  if (fieldset && !capabilities.allow_screen_off_supported) {
+    fieldset.style.display = "none";
+  }
+  const awayMode = document.getElementById("away-mode");
+  if (awayMode && !capabilities.away_mode_supported) {
+    awayMode.style.display = "none";
+  }
+
+  document.getElementById("toggle")?.addEventListener("click", () => {
+    toggleSleep().catch((e) => console.error("Toggle sleep failed:", e));
+  });
+  document
+    .querySelectorAll<HTMLInputElement>('input[name="screen-mode"]')
+    .forEach((input) => {
+      input.addEventListener("change", () => {
+        changeScreenMode(input.value as ScreenMode).catch((e) =>
+          console.error("Change screen mode failed:", e)
+        );
+      });
+    });
+
+  await listen<StateChanged>(STATE_CHANGED_EVENT, (event) => render(event.payload));
+  await loadState();
+}
+
+// Only wire up inside Tauri; tests import the helpers above
+if ("__TAURI_INTERNALS__" in window) {
+  init().catch((e) => console.error("Status window failed to load:", e));
+}
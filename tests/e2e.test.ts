@@ -1,7 +1,7 @@
 /**
  * End-to-End Test Suite for Tea Application
- * Note: Tea is a system tray-only application. The HTML window is not shown to users.
- * These tests validate basic HTML structure exists but the real UI is the system tray.
+ * Note: The system tray is Tea's primary UI; the HTML page is the optional status window.
+ * These tests validate basic HTML structure exists.
  */
 
 import { describe, it, expect, beforeEach, afterEach } from "vitest";
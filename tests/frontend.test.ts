@@ -1,18 +1,28 @@
 /**
  * Frontend Test Suite for Tea Application
- * Note: Tea is a system tray-only application. The HTML window is not shown to users.
- * These tests validate the basic DOM structure for completeness.
+ * Note: The system tray is Tea's primary UI. The HTML page is the optional
+ * status window opened from the tray's "Show Status" entry.
+ * These tests validate its DOM structure and rendering of backend state.
  */
 
 import { describe, it, expect, beforeEach, afterEach } from "vitest";
+import { render, statusText, toggleText } from "../src/main";
 
 // DOM Setup Helper
 function setupDOM() {
   document.body.innerHTML = `
     <main class="container">
       <h1>Tea</h1>
-      <p>System Tray Sleep Prevention Utility</p>
-      <p>Access all features via the system tray icon.</p>
+      <p id="status" aria-live="polite">Loading…</p>
+      <div class="row">
+        <button id="toggle" type="button" disabled>Disable Sleep</button>
+      </div>
+      <fieldset id="screen-mode" class="row">
+        <legend>Screen</legend>
+        <label><input type="radio" name="screen-mode" value="KeepScreenOn" /> Keep Screen On</label>
+        <label><input type="radio" name="screen-mode" value="AllowScreenOff" /> Allow Screen Off</label>
+      </fieldset>
+      <p class="hint">Closing this window keeps Tea running in the system tray.</p>
     </main>
   `;
 }
@@ -21,6 +31,11 @@ function cleanupDOM() {
   document.body.innerHTML = "";
 }
 
+function checkedMode(): string | undefined {
+  return document.querySelector<HTMLInputElement>('input[name="screen-mode"]:checked')
+    ?.value;
+}
+
 describe("Frontend UI Tests", () => {
   beforeEach(() => {
     setupDOM();
@@ -43,18 +58,35 @@ describe("Frontend UI Tests", () => {
       expect(heading?.textContent).toBe("Tea");
     });
 
-    it("should have informational paragraphs", () => {
-      const paragraphs = document.querySelectorAll("p");
-      expect(paragraphs.length).toBe(2);
-      expect(paragraphs[0]?.textContent).toContain("System Tray");
-      expect(paragraphs[1]?.textContent).toContain("system tray icon");
+    it("should have a toggle button and both screen modes", () => {
+      expect(document.getElementById("toggle")?.tagName).toBe("BUTTON");
+      const modes = document.querySelectorAll('input[name="screen-mode"]');
+      expect(modes.length).toBe(2);
+    });
+  });
+
+  describe("Rendering Tests", () => {
+    it("should enable the toggle once state is known", () => {
+      render({ is_awake: false, screen_mode: "KeepScreenOn" });
+      const toggle = document.getElementById("toggle") as HTMLButtonElement;
+      expect(toggle.disabled).toBe(false);
+      expect(toggle.textContent).toBe("Disable Sleep");
+    });
+
+    it("should reflect an awake state", () => {
+      render({ is_awake: true, screen_mode: "AllowScreenOff" });
+      expect(document.getElementById("status")?.textContent).toBe(
+        statusText({ is_awake: true, screen_mode: "AllowScreenOff" })
+      );
+      expect(document.getElementById("toggle")?.textContent).toBe("Enable Sleep");
+      expect(checkedMode()).toBe("AllowScreenOff");
     });
 
-    it("should not have interactive elements", () => {
-      // System tray only - no form, input, or buttons
-      expect(document.querySelector("form")).toBeNull();
-      expect(document.querySelector("input")).toBeNull();
-      expect(document.querySelector("button")).toBeNull();
+    it("should follow later state changes", () => {
+      render({ is_awake: true, screen_mode: "AllowScreenOff" });
+      render({ is_awake: false, screen_mode: "KeepScreenOn" });
+      expect(document.getElementById("toggle")?.textContent).toBe(toggleText(false));
+      expect(checkedMode()).toBe("KeepScreenOn");
     });
   });
 
@@ -64,16 +96,13 @@ describe("Frontend UI Tests", () => {
       expect(main).toBeTruthy();
     });
 
-    it("should have a heading for screen readers", () => {
-      const heading = document.querySelector("h1");
-      expect(heading).toBeTruthy();
-      expect(heading?.textContent).not.toBe("");
+    it("should announce status changes", () => {
+      expect(document.getElementById("status")?.getAttribute("aria-live")).toBe("polite");
     });
 
-    it("should have text content for screen readers", () => {
-      const paragraphs = document.querySelectorAll("p");
-      paragraphs.forEach((p) => {
-        expect(p.textContent).not.toBe("");
+    it("should label every screen mode", () => {
+      document.querySelectorAll('input[name="screen-mode"]').forEach((input) => {
+        expect(input.closest("label")?.textContent?.trim()).not.toBe("");
       });
     });
   });
@@ -84,17 +113,9 @@ describe("Frontend UI Tests", () => {
       expect(content).toContain("Tea");
     });
 
-    it("should indicate system tray usage", () => {
-      const content = document.body.textContent || "";
-      expect(content.toLowerCase()).toContain("system tray");
-    });
-
-    it("should have clear messaging about UI location", () => {
-      const paragraphs = document.querySelectorAll("p");
-      const hasSystemTrayMention = Array.from(paragraphs).some((p) =>
-        p.textContent?.toLowerCase().includes("system tray")
-      );
-      expect(hasSystemTrayMention).toBe(true);
+    it("should explain that closing keeps the tray running", () => {
+      const hint = document.querySelector(".hint");
+      expect(hint?.textContent?.toLowerCase()).toContain("system tray");
     });
   });
 });
@@ -13,6 +13,11 @@ vi.mock('@tauri-apps/api/core', () => ({
   invoke: vi.fn(),
 }));
 
+// Mock the Tauri event API
+vi.mock('@tauri-apps/api/event', () => ({
+  listen: vi.fn(),
+}));
+
 // Mock Tauri plugin shell
 vi.mock('@tauri-apps/plugin-shell', () => ({
   open: vi.fn(),